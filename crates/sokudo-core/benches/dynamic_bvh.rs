@@ -0,0 +1,135 @@
+//! Compares the cost of building a new bounding volume hierarchy every frame against refitting it
+//! to a crowd of boxes milling around a floor, over a range of rebuild thresholds.
+//!
+//! Run with `cargo bench -p sokudo-core --bench dynamic_bvh`.
+
+use std::time::{Duration, Instant};
+
+use glam::Vec3;
+use sokudo_core::{aabb::Aabb, bvh::Bvh};
+
+/// The number of boxes in the crowd.
+const BOXES: usize = 4000;
+/// The number of frames the crowd is moved for.
+const FRAMES: u32 = 300;
+/// The half-width of the square floor the boxes are kept on.
+const FLOOR: f32 = 60.0;
+const DT: f32 = 1.0 / 60.0;
+
+/// A deterministic stream of pseudorandom numbers within `-1.0..1.0`.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+}
+
+struct Crowd {
+    positions: Vec<Vec3>,
+    velocities: Vec<Vec3>,
+}
+
+impl Crowd {
+    fn new() -> Crowd {
+        let mut random = Random(3);
+
+        let positions = (0..BOXES).map(|_| Vec3::new(FLOOR * random.next(), 0.5, FLOOR * random.next())).collect();
+        let velocities = (0..BOXES).map(|_| 3.0 * Vec3::new(random.next(), 0.0, random.next())).collect();
+
+        Crowd { positions, velocities }
+    }
+
+    /// Moves every box along its velocity, turning it around at the edges of the floor.
+    fn advance(&mut self) {
+        for (position, velocity) in self.positions.iter_mut().zip(self.velocities.iter_mut()) {
+            *position += *velocity * DT;
+
+            if position.x.abs() > FLOOR {
+                velocity.x = -velocity.x;
+            }
+
+            if position.z.abs() > FLOOR {
+                velocity.z = -velocity.z;
+            }
+        }
+    }
+
+    fn aabbs(&self) -> Vec<Aabb> {
+        self.positions.iter().map(|&p| Aabb::new(p - 0.5, p + 0.5)).collect()
+    }
+}
+
+/// The number of overlapping pairs of items in `bvh`.
+fn count_pairs(bvh: &Bvh) -> usize {
+    bvh.aabbs().iter()
+        .enumerate()
+        .map(|(i, &aabb)| bvh.aabb_query(aabb).into_iter().filter(|&j| j as usize > i).count())
+        .sum()
+}
+
+struct Measurement {
+    /// The mean time taken to update the hierarchy each frame, in microseconds.
+    update_time: f64,
+    /// The mean time taken to find the overlapping pairs each frame, in microseconds.
+    pair_time: f64,
+    /// The number of times the hierarchy was built.
+    rebuilds: u32,
+    /// The total number of pairs found, which is the same for every threshold.
+    pairs: usize,
+}
+
+/// Moves the crowd, refitting the hierarchy each frame and building it again once its cost has
+/// grown by more than a factor of `rebuild_threshold`, so that it is built every frame when the
+/// threshold is zero.
+fn measure(rebuild_threshold: f32) -> Measurement {
+    let mut crowd = Crowd::new();
+    let mut bvh = Bvh::new(&crowd.aabbs());
+    let mut built_cost = bvh.cost();
+
+    let mut update_time = Duration::ZERO;
+    let mut pair_time = Duration::ZERO;
+    let mut rebuilds = 1;
+    let mut pairs = 0;
+
+    for _ in 0..FRAMES {
+        crowd.advance();
+        let aabbs = crowd.aabbs();
+
+        let start = Instant::now();
+        bvh.refit(&aabbs);
+
+        if bvh.cost() > rebuild_threshold * built_cost {
+            bvh = Bvh::new(&aabbs);
+            built_cost = bvh.cost();
+            rebuilds += 1;
+        }
+
+        update_time += start.elapsed();
+
+        let start = Instant::now();
+        pairs += count_pairs(&bvh);
+        pair_time += start.elapsed();
+    }
+
+    Measurement {
+        update_time: update_time.as_secs_f64() * 1e6 / FRAMES as f64,
+        pair_time: pair_time.as_secs_f64() * 1e6 / FRAMES as f64,
+        rebuilds,
+        pairs,
+    }
+}
+
+fn main() {
+    println!("{:>10} {:>10} {:>12} {:>12} {:>12} {:>10}", "threshold", "rebuilds", "update µs", "pairs µs", "total µs", "pairs");
+
+    for rebuild_threshold in [0.0, 1.1, 1.25, 1.5, 2.0, f32::INFINITY] {
+        let Measurement { update_time, pair_time, rebuilds, pairs } = measure(rebuild_threshold);
+
+        println!(
+            "{rebuild_threshold:>10} {rebuilds:>10} {update_time:>12.1} {pair_time:>12.1} {:>12.1} {pairs:>10}",
+            update_time + pair_time,
+        );
+    }
+}
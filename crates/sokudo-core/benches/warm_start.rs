@@ -0,0 +1,61 @@
+//! Compares how far a stack of boxes sinks and how long it takes to simulate with and without
+//! warm starting its contacts, over a range of solver iterations.
+//!
+//! Run with `cargo bench -p sokudo-core --bench warm_start`.
+
+use std::time::Instant;
+
+use sokudo_core::world::World;
+use sokudo_io::read::ParsedWorld;
+
+const STACK: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/benches/tall-stack.ron");
+
+/// The number of steps the stack is given to settle before its sinking is measured.
+const SETTLE_STEPS: u32 = 100;
+
+struct Measurement {
+    /// The furthest the top box sank below its initial height after settling.
+    sink: f32,
+    /// The mean time taken by each step, in microseconds.
+    step_time: f64,
+}
+
+fn measure(warm_start: bool, substeps: u32, iterations: u32) -> Measurement {
+    let mut world: World = ParsedWorld::read(STACK).unwrap().into();
+    world.warm_start = warm_start;
+    world.substeps = substeps;
+    world.iterations = iterations;
+    world.initialize();
+
+    let top = world.colliders.len() - 1;
+    let height = world.colliders[top].position.y;
+    let mut sink = 0.0f32;
+
+    let start = Instant::now();
+
+    for step in 0..world.steps {
+        world.advance();
+
+        if step >= SETTLE_STEPS {
+            sink = sink.max(height - world.colliders[top].position.y);
+        }
+    }
+
+    Measurement { sink, step_time: start.elapsed().as_secs_f64() * 1e6 / world.steps as f64 }
+}
+
+fn main() {
+    println!("{:>8} {:>10} {:>12} {:>12} {:>12} {:>12}", "substeps", "iterations", "cold sink", "warm sink", "cold µs", "warm µs");
+
+    for substeps in [1, 2, 4] {
+        for iterations in [1, 2, 4, 8] {
+            let cold = measure(false, substeps, iterations);
+            let warm = measure(true, substeps, iterations);
+
+            println!(
+                "{substeps:>8} {iterations:>10} {:>12.4} {:>12.4} {:>12.1} {:>12.1}",
+                cold.sink, warm.sink, cold.step_time, warm.step_time,
+            );
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use glam::{Quat, Vec3};
+
+/// An axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    /// A bounding box containing all of space.
+    pub const INFINITE: Aabb = Aabb::new(Vec3::NEG_INFINITY, Vec3::INFINITY);
+
+    pub const fn new(min: Vec3, max: Vec3) -> Aabb {
+        Aabb { min, max }
+    }
+
+    /// A bounding box which contains only the given `point`.
+    pub const fn from_point(point: Vec3) -> Aabb {
+        Aabb { min: point, max: point }
+    }
+
+    /// The total area of the six faces of this bounding box.
+    pub fn surface_area(&self) -> f32 {
+        let size = self.max - self.min;
+        2.0 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    /// The eight corners of this bounding box.
+    pub fn corners(&self) -> [Vec3; 8] {
+        let Aabb { min, max } = *self;
+
+        [
+            Vec3::new(min.x, min.y, min.z),
+            Vec3::new(max.x, min.y, min.z),
+            Vec3::new(min.x, max.y, min.z),
+            Vec3::new(max.x, max.y, min.z),
+            Vec3::new(min.x, min.y, max.z),
+            Vec3::new(max.x, min.y, max.z),
+            Vec3::new(min.x, max.y, max.z),
+            Vec3::new(max.x, max.y, max.z),
+        ]
+    }
+
+    /// The bounding box of this box after it is scaled, rotated and then translated.
+    pub fn transform(&self, position: Vec3, rotation: Quat, scale: Vec3) -> Aabb {
+        let corners = self.corners().map(|corner| position + rotation * (scale * corner));
+
+        corners[1..].iter().fold(Aabb::from_point(corners[0]), |aabb, &p| aabb.include(p))
+    }
+
+    /// Whether or not this bounding box has finite bounds on every axis.
+    #[inline]
+    pub fn is_finite(&self) -> bool {
+        self.min.is_finite() && self.max.is_finite()
+    }
+
+    /// Grows this bounding box to contain `point`.
+    pub fn include(self, point: Vec3) -> Aabb {
+        Aabb {
+            min: self.min.min(point),
+            max: self.max.max(point),
+        }
+    }
+
+    /// The smallest bounding box containing both this box and `other`.
+    pub fn union(self, other: Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Whether or not this bounding box overlaps with `other`, including touching boundaries.
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+
+    /// The smallest `t` within `0..=max_t` at which the ray `origin + t * direction` lies inside
+    /// this bounding box, or `None` if the ray misses it.
+    pub fn ray_distance(&self, origin: Vec3, direction: Vec3, max_t: f32) -> Option<f32> {
+        let mut near = 0.0f32;
+        let mut far = max_t;
+
+        for axis in 0..3 {
+            // Rays parallel to a pair of faces only pass through the box between them.
+            if direction[axis] == 0.0 {
+                if origin[axis] < self.min[axis] || origin[axis] > self.max[axis] {
+                    return None;
+                }
+
+                continue;
+            }
+
+            let t0 = (self.min[axis] - origin[axis]) / direction[axis];
+            let t1 = (self.max[axis] - origin[axis]) / direction[axis];
+
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+        }
+
+        (near <= far).then_some(near)
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{aabb::Aabb, bvh::Bvh, collider::{Collider, ColliderId, ColliderMotion}};
+
+/// A [`Bvh`] over the colliders of a world which is kept between steps, refitting it to the
+/// colliders which moved and only building it again once it has degraded too far.
+#[derive(Debug, Clone, Default)]
+pub struct DynamicBvh {
+    bvh: Bvh,
+    /// The collider of each item of `bvh`, in ascending order.
+    ids: Vec<ColliderId>,
+    /// Colliders with infinite bounding boxes, which are paired with every other collider.
+    unbounded: Vec<ColliderId>,
+    /// The [`Bvh::cost`] of the hierarchy when it was last built.
+    built_cost: f32,
+    /// The number of times the hierarchy has been built.
+    pub rebuilds: u32,
+}
+
+impl DynamicBvh {
+    /// Updates the hierarchy to the current bounding boxes of `colliders`.
+    ///
+    /// The hierarchy is built again if colliders were added or removed since the last update, or
+    /// if refitting it made its cost exceed `rebuild_threshold` times its cost when it was built.
+    /// Otherwise only the bounding boxes of the colliders which can move are recomputed.
+    pub fn update(&mut self, colliders: &[Collider], rebuild_threshold: f32) {
+        let live = colliders.iter().enumerate().filter(|(_, c)| !c.removed).map(|(i, _)| ColliderId::new(i));
+
+        if !live.eq(merged(&self.ids, &self.unbounded)) {
+            self.rebuild(colliders);
+            return;
+        }
+
+        let mut aabbs = self.bvh.aabbs().to_vec();
+
+        for (aabb, id) in aabbs.iter_mut().zip(self.ids.iter()) {
+            let collider = &colliders[id.0 as usize];
+
+            if !matches!(collider.motion, ColliderMotion::Locked) && !collider.asleep {
+                *aabb = collider.aabb();
+            }
+        }
+
+        if !aabbs.iter().all(Aabb::is_finite) {
+            self.rebuild(colliders);
+            return;
+        }
+
+        self.bvh.refit(&aabbs);
+
+        if self.bvh.cost() > rebuild_threshold * self.built_cost {
+            self.rebuild(colliders);
+        }
+    }
+
+    fn rebuild(&mut self, colliders: &[Collider]) {
+        self.ids.clear();
+        self.unbounded.clear();
+
+        let mut aabbs = Vec::new();
+
+        for (i, collider) in colliders.iter().enumerate().filter(|(_, c)| !c.removed) {
+            let aabb = collider.aabb();
+
+            if aabb.is_finite() {
+                self.ids.push(ColliderId::new(i));
+                aabbs.push(aabb);
+            } else {
+                self.unbounded.push(ColliderId::new(i));
+            }
+        }
+
+        self.bvh = Bvh::new(&aabbs);
+        self.built_cost = self.bvh.cost();
+        self.rebuilds += 1;
+    }
+
+    /// Every pair of colliders whose bounding boxes overlapped at the last update.
+    ///
+    /// The returned pairs `(a, b)` have `a < b`, contain no duplicates and are sorted in ascending
+    /// order.
+    pub fn candidate_pairs(&self) -> Vec<(ColliderId, ColliderId)> {
+        let mut pairs = Vec::new();
+
+        for (i, &aabb) in self.bvh.aabbs().iter().enumerate() {
+            // The items are in the same order as their IDs, so only the later items need to be
+            // paired to find every pair once.
+            for j in self.bvh.aabb_query(aabb).into_iter().filter(|&j| j as usize > i) {
+                pairs.push((self.ids[i], self.ids[j as usize]));
+            }
+        }
+
+        for &a in self.unbounded.iter() {
+            for b in merged(&self.ids, &self.unbounded).filter(|&b| b != a) {
+                pairs.push((a.min(b), a.max(b)));
+            }
+        }
+
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+}
+
+/// The IDs of `a` and `b`, which are both in ascending order, merged into ascending order.
+fn merged<'a>(a: &'a [ColliderId], b: &'a [ColliderId]) -> impl Iterator<Item = ColliderId> + 'a {
+    let mut a = a.iter().copied().peekable();
+    let mut b = b.iter().copied().peekable();
+
+    std::iter::from_fn(move || match (a.peek(), b.peek()) {
+        (Some(x), Some(y)) if y < x => b.next(),
+        (Some(_), _) => a.next(),
+        (None, _) => b.next(),
+    })
+}
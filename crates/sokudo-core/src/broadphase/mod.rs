@@ -0,0 +1,138 @@
+use sokudo_io::read::broadphase::ParsedBroadphase;
+use dynamic_bvh::DynamicBvh;
+use spatial_hash::SpatialHash;
+
+use crate::{aabb::Aabb, collider::{Collider, ColliderId}};
+
+pub mod spatial_hash;
+pub mod dynamic_bvh;
+
+/// The method used to find candidate pairs of colliders before computing their contacts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Broadphase {
+    /// Tests every pair of colliders.
+    BruteForce,
+    /// See [`sweep_and_prune`].
+    SweepAndPrune,
+    /// Buckets colliders into a [`SpatialHash`] with cells of size `cell_size`.
+    SpatialHash {
+        cell_size: f32,
+    },
+    /// Keeps a [`DynamicBvh`] between steps, which is built again once refitting it has made its
+    /// cost grow by more than a factor of `rebuild_threshold`.
+    DynamicBvh {
+        rebuild_threshold: f32,
+    },
+}
+
+impl Broadphase {
+    /// Finds the candidate pairs of `colliders` which may be in contact.
+    ///
+    /// The returned pairs `(a, b)` always have `a < b` and are sorted in ascending order, so the
+    /// narrowphase sees the same pairs in the same order regardless of the method used.
+    ///
+    /// `tree` is the hierarchy kept between steps by [`Broadphase::DynamicBvh`], which the other
+    /// methods leave untouched.
+    pub fn candidate_pairs(&self, colliders: &[Collider], tree: &mut DynamicBvh) -> Vec<(ColliderId, ColliderId)> {
+        match *self {
+            Broadphase::BruteForce => brute_force(colliders),
+            Broadphase::SweepAndPrune => sweep_and_prune(colliders),
+            Broadphase::SpatialHash { cell_size } => {
+                let mut grid = SpatialHash::new(cell_size);
+
+                for (i, collider) in colliders.iter().enumerate().filter(|(_, c)| !c.removed) {
+                    grid.insert(ColliderId::new(i), collider.aabb());
+                }
+
+                grid.candidate_pairs()
+            },
+            Broadphase::DynamicBvh { rebuild_threshold } => {
+                tree.update(colliders, rebuild_threshold);
+                tree.candidate_pairs()
+            },
+        }
+    }
+
+    /// Finds the `colliders` whose bounding boxes overlap `aabb`, in ascending order.
+    pub fn query(&self, colliders: &[Collider], aabb: Aabb) -> Vec<ColliderId> {
+        let candidates: Vec<ColliderId> = match *self {
+            Broadphase::SpatialHash { cell_size } => {
+                let mut grid = SpatialHash::new(cell_size);
+
+                for (i, collider) in colliders.iter().enumerate().filter(|(_, c)| !c.removed) {
+                    grid.insert(ColliderId::new(i), collider.aabb());
+                }
+
+                grid.query(aabb)
+            },
+            _ => (0..colliders.len()).map(ColliderId::new).collect(),
+        };
+
+        candidates.into_iter()
+            .filter(|id| !colliders[id.0 as usize].removed && colliders[id.0 as usize].aabb().intersects(&aabb))
+            .collect()
+    }
+}
+
+/// Every pair of colliders.
+pub fn brute_force(colliders: &[Collider]) -> Vec<(ColliderId, ColliderId)> {
+    let live: Vec<usize> = (0..colliders.len()).filter(|&i| !colliders[i].removed).collect();
+
+    live.iter()
+        .enumerate()
+        .flat_map(|(k, &i)| live[k + 1..].iter().map(move |&j| (ColliderId::new(i), ColliderId::new(j))))
+        .collect()
+}
+
+/// Finds every pair of colliders whose bounding boxes overlap by sorting them along the x axis and
+/// only testing colliders whose intervals on it overlap.
+pub fn sweep_and_prune(colliders: &[Collider]) -> Vec<(ColliderId, ColliderId)> {
+    let aabbs: Vec<Aabb> = colliders.iter().map(Collider::aabb).collect();
+
+    let mut order: Vec<usize> = (0..colliders.len()).filter(|&i| !colliders[i].removed).collect();
+    order.sort_unstable_by(|&a, &b| aabbs[a].min.x.total_cmp(&aabbs[b].min.x));
+
+    let mut pairs = Vec::new();
+    let mut active: Vec<usize> = Vec::new();
+
+    for i in order {
+        let aabb = &aabbs[i];
+        active.retain(|&j| aabbs[j].max.x >= aabb.min.x);
+
+        for &j in active.iter() {
+            if aabb.intersects(&aabbs[j]) {
+                pairs.push((ColliderId::new(i.min(j)), ColliderId::new(i.max(j))));
+            }
+        }
+
+        active.push(i);
+    }
+
+    pairs.sort_unstable();
+    pairs
+}
+
+impl From<ParsedBroadphase> for Broadphase {
+    fn from(value: ParsedBroadphase) -> Self {
+        match value {
+            ParsedBroadphase::BruteForce => Broadphase::BruteForce,
+            ParsedBroadphase::SweepAndPrune => Broadphase::SweepAndPrune,
+            // A spatial hash without a positive and finite cell size would bucket colliders into
+            // infinitely many cells, which is warned about while parsing.
+            ParsedBroadphase::SpatialHash { .. } if !value.has_valid_cell_size() => Broadphase::SweepAndPrune,
+            ParsedBroadphase::SpatialHash { cell_size } => Broadphase::SpatialHash { cell_size },
+            ParsedBroadphase::DynamicBvh { rebuild_threshold } => Broadphase::DynamicBvh { rebuild_threshold },
+        }
+    }
+}
+
+impl From<Broadphase> for ParsedBroadphase {
+    fn from(value: Broadphase) -> Self {
+        match value {
+            Broadphase::BruteForce => ParsedBroadphase::BruteForce,
+            Broadphase::SweepAndPrune => ParsedBroadphase::SweepAndPrune,
+            Broadphase::SpatialHash { cell_size } => ParsedBroadphase::SpatialHash { cell_size },
+            Broadphase::DynamicBvh { rebuild_threshold } => ParsedBroadphase::DynamicBvh { rebuild_threshold },
+        }
+    }
+}
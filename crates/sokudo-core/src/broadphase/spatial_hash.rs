@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use glam::{IVec3, Vec3};
+
+use crate::{aabb::Aabb, collider::ColliderId};
+
+/// A uniform grid which buckets bounding boxes into the cells they overlap.
+#[derive(Debug)]
+pub struct SpatialHash {
+    /// The side length of each cell.
+    pub cell_size: f32,
+    cells: HashMap<IVec3, Vec<ColliderId>>,
+    /// Colliders with infinite bounding boxes, which are paired with every other collider.
+    unbounded: Vec<ColliderId>,
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> SpatialHash {
+        SpatialHash {
+            cell_size,
+            cells: HashMap::new(),
+            unbounded: Vec::new(),
+        }
+    }
+
+    /// The cell containing `point`.
+    #[inline]
+    fn cell(&self, point: Vec3) -> IVec3 {
+        (point / self.cell_size).floor().as_ivec3()
+    }
+
+    /// Inserts the collider `id` into every cell overlapped by `aabb`.
+    pub fn insert(&mut self, id: ColliderId, aabb: Aabb) {
+        if !aabb.is_finite() {
+            self.unbounded.push(id);
+            return;
+        }
+
+        let min = self.cell(aabb.min);
+        let max = self.cell(aabb.max);
+
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                for z in min.z..=max.z {
+                    self.cells.entry(IVec3::new(x, y, z)).or_default().push(id);
+                }
+            }
+        }
+    }
+
+    /// Every collider which shares at least one cell with `aabb`, in ascending order.
+    pub fn query(&self, aabb: Aabb) -> Vec<ColliderId> {
+        let mut ids = self.unbounded.clone();
+
+        if aabb.is_finite() {
+            let min = self.cell(aabb.min);
+            let max = self.cell(aabb.max);
+
+            for x in min.x..=max.x {
+                for y in min.y..=max.y {
+                    for z in min.z..=max.z {
+                        ids.extend(self.cells.get(&IVec3::new(x, y, z)).into_iter().flatten());
+                    }
+                }
+            }
+        } else {
+            ids.extend(self.cells.values().flatten());
+        }
+
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Every pair of colliders which share at least one cell.
+    ///
+    /// The returned pairs `(a, b)` have `a < b`, contain no duplicates and are sorted in ascending
+    /// order.
+    pub fn candidate_pairs(&self) -> Vec<(ColliderId, ColliderId)> {
+        let mut pairs = Vec::new();
+
+        for ids in self.cells.values() {
+            for (i, &a) in ids.iter().enumerate() {
+                for &b in ids[i + 1..].iter() {
+                    pairs.push((a.min(b), a.max(b)));
+                }
+            }
+        }
+
+        let bounded = self.cells.values().flatten();
+
+        for (i, &a) in self.unbounded.iter().enumerate() {
+            for &b in self.unbounded[i + 1..].iter().chain(bounded.clone()) {
+                pairs.push((a.min(b), a.max(b)));
+            }
+        }
+
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+}
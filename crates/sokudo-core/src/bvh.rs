@@ -0,0 +1,198 @@
+use glam::Vec3;
+
+use crate::aabb::Aabb;
+
+/// A bounding volume hierarchy over a fixed set of items, each of which is identified by its
+/// index and bounded by an [`Aabb`].
+#[derive(Debug, Clone, Default)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    /// The bounding box of each item.
+    aabbs: Vec<Aabb>,
+    /// The indices of the items, ordered so that the items of every leaf are contiguous.
+    items: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BvhNode {
+    aabb: Aabb,
+    /// The index of the first child of an interior node, whose second child directly follows it,
+    /// or of the first item of a leaf.
+    first: u32,
+    /// The number of items of a leaf, which is zero for interior nodes.
+    count: u32,
+}
+
+impl Bvh {
+    /// The largest number of items in a leaf.
+    pub const LEAF_SIZE: usize = 4;
+
+    /// Builds a hierarchy over the items bounded by `aabbs`, splitting each node at the median of
+    /// the centers of its items along the axis they are spread the furthest.
+    pub fn new(aabbs: &[Aabb]) -> Bvh {
+        let mut bvh = Bvh {
+            nodes: Vec::with_capacity(2 * aabbs.len()),
+            aabbs: aabbs.to_vec(),
+            items: (0..aabbs.len() as u32).collect(),
+        };
+
+        if !aabbs.is_empty() {
+            bvh.nodes.push(BvhNode { aabb: bounds(aabbs, &bvh.items), first: 0, count: aabbs.len() as u32 });
+            bvh.split(0);
+        }
+
+        bvh
+    }
+
+    fn split(&mut self, node: usize) {
+        let BvhNode { first, count, .. } = self.nodes[node];
+
+        if count as usize <= Self::LEAF_SIZE {
+            return;
+        }
+
+        let aabbs = &self.aabbs;
+        let items = &mut self.items[first as usize..(first + count) as usize];
+
+        let center = |i: u32| {
+            let aabb = aabbs[i as usize];
+            0.5 * (aabb.min + aabb.max)
+        };
+
+        let centers = items.iter().fold(Aabb::from_point(center(items[0])), |aabb, &i| aabb.include(center(i)));
+        let extent = centers.max - centers.min;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z { 0 } else if extent.y >= extent.z { 1 } else { 2 };
+
+        let half = items.len() / 2;
+        items.select_nth_unstable_by(half, |&a, &b| center(a)[axis].total_cmp(&center(b)[axis]));
+
+        let children = self.nodes.len() as u32;
+
+        for (start, len) in [(first, half as u32), (first + half as u32, count - half as u32)] {
+            let aabb = bounds(&self.aabbs, &self.items[start as usize..(start + len) as usize]);
+            self.nodes.push(BvhNode { aabb, first: start, count: len });
+        }
+
+        self.nodes[node].first = children;
+        self.nodes[node].count = 0;
+
+        self.split(children as usize);
+        self.split(children as usize + 1);
+    }
+
+    /// The number of items in this hierarchy.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.aabbs.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.aabbs.is_empty()
+    }
+
+    /// The bounding box of each item, by index.
+    #[inline]
+    pub fn aabbs(&self) -> &[Aabb] {
+        &self.aabbs
+    }
+
+    /// Replaces the bounding boxes of the items with `aabbs` and refits every node to them
+    /// bottom-up, keeping the structure of the hierarchy.
+    ///
+    /// Refitting is much cheaper than building a new hierarchy, but the nodes grow looser as the
+    /// items move away from where they were when it was built, which [`Bvh::cost`] measures.
+    ///
+    /// # Panics
+    ///
+    /// If `aabbs` does not have one bounding box for every item.
+    pub fn refit(&mut self, aabbs: &[Aabb]) {
+        self.aabbs.copy_from_slice(aabbs);
+
+        // The children of a node are always pushed after it, so visiting the nodes in reverse
+        // refits both children of every interior node before the node itself.
+        for node in (0..self.nodes.len()).rev() {
+            let BvhNode { first, count, .. } = self.nodes[node];
+            let first = first as usize;
+
+            self.nodes[node].aabb = if count > 0 {
+                bounds(&self.aabbs, &self.items[first..first + count as usize])
+            } else {
+                self.nodes[first].aabb.union(self.nodes[first + 1].aabb)
+            };
+        }
+    }
+
+    /// The total surface area of the nodes, which is proportional to the expected number of nodes
+    /// visited by a query and so grows as the hierarchy degrades.
+    pub fn cost(&self) -> f32 {
+        self.nodes.iter().map(|node| node.aabb.surface_area()).sum()
+    }
+
+    /// The indices of the items whose bounding boxes overlap `aabb`, in ascending order.
+    pub fn aabb_query(&self, aabb: Aabb) -> Vec<u32> {
+        let mut found = Vec::new();
+
+        if self.nodes.is_empty() {
+            return found;
+        }
+
+        let mut stack = vec![0];
+
+        while let Some(node) = stack.pop() {
+            let BvhNode { aabb: bounds, first, count } = self.nodes[node];
+
+            if !bounds.intersects(&aabb) {
+                continue;
+            }
+
+            if count > 0 {
+                let items = &self.items[first as usize..(first + count) as usize];
+                found.extend(items.iter().filter(|&&i| self.aabbs[i as usize].intersects(&aabb)));
+            } else {
+                stack.push(first as usize + 1);
+                stack.push(first as usize);
+            }
+        }
+
+        found.sort_unstable();
+        found
+    }
+
+    /// The indices of the items whose bounding boxes are hit by the ray `origin + t * direction`
+    /// for `t` within `0..=max_t`, in ascending order.
+    pub fn ray_query(&self, origin: Vec3, direction: Vec3, max_t: f32) -> Vec<u32> {
+        let mut found = Vec::new();
+
+        if self.nodes.is_empty() {
+            return found;
+        }
+
+        let hits = |aabb: &Aabb| aabb.ray_distance(origin, direction, max_t).is_some();
+        let mut stack = vec![0];
+
+        while let Some(node) = stack.pop() {
+            let BvhNode { aabb, first, count } = self.nodes[node];
+
+            if !hits(&aabb) {
+                continue;
+            }
+
+            if count > 0 {
+                let items = &self.items[first as usize..(first + count) as usize];
+                found.extend(items.iter().filter(|&&i| hits(&self.aabbs[i as usize])));
+            } else {
+                stack.push(first as usize + 1);
+                stack.push(first as usize);
+            }
+        }
+
+        found.sort_unstable();
+        found
+    }
+}
+
+/// The smallest bounding box containing the bounding boxes of `items`.
+fn bounds(aabbs: &[Aabb], items: &[u32]) -> Aabb {
+    items[1..].iter().fold(aabbs[items[0] as usize], |bounds, &i| bounds.union(aabbs[i as usize]))
+}
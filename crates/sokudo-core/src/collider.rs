@@ -1,9 +1,7 @@
-use std::{ops::{Index, IndexMut}, slice::SliceIndex};
+use glam::{Mat3, Quat, Vec3};
+use sokudo_io::{read::{collider::{ParsedCollider, ParsedColliderBody, ParsedColliderMotion}, defaults::DefaultOptions}, write::{collider::WriteCollider, snapshot::{SnapshotCollider, SnapshotColliderBody, SnapshotColliderMotion}, transform::WriteTransform}};
 
-use glam::Vec3;
-use sokudo_io::{read::collider::{ParsedCollider, ParsedColliderBody}, write::{collider::WriteCollider, inspect::InspectElements, transform::WriteTransform}};
-
-use crate::{particle::Particle, rigid_body::RigidBody};
+use crate::{aabb::Aabb, contact::Contact, particle::Particle, rigid_body::RigidBody, shape::{AbstractShape, Shape}, transform::Transform};
 
 #[derive(Debug)]
 pub struct Collider {
@@ -11,15 +9,36 @@ pub struct Collider {
     pub id: u32,
     /// The body of this collider.
     pub body: ColliderBody,
-    /// Whether or not this collider is locked. 
-    /// This turns off gravity and gives it infinite mass. 
-    pub locked: bool,
+    /// How this collider is moved. Locked and kinematic colliders are unaffected by gravity and
+    /// have infinite mass.
+    ///
+    /// Half-spaces are always locked.
+    pub motion: ColliderMotion,
+    /// Whether this collider only reports its overlaps with other colliders as collision events,
+    /// without resolving them.
+    pub is_sensor: bool,
+    /// The layers this collider belongs to, as a bitfield.
+    pub collision_layer: u32,
+    /// The layers this collider collides with, as a bitfield. Two colliders only collide when each
+    /// of them belongs to a layer in the mask of the other.
+    pub collision_mask: u32,
+
+    /// Whether this collider has been removed from the world. Removed colliders keep their place
+    /// so the IDs of the others never change, but are otherwise ignored by the simulation.
+    pub removed: bool,
 
     /// The position of the collider. For rigid bodies, this is located at its center of mass.
     pub position: Vec3,
     pub previous_position: Vec3,
     pub velocity: Vec3,
     pub previous_velocity: Vec3,
+
+    /// Whether or not this collider is asleep, in which case it is not integrated until it is
+    /// woken up.
+    pub asleep: bool,
+    /// The number of consecutive frames this collider has been moving slower than the sleep
+    /// thresholds.
+    pub sleep_frames: u32,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -31,6 +50,45 @@ impl ColliderId {
     }
 }
 
+/// How a [`Collider`] is moved.
+#[derive(Debug, Clone)]
+pub enum ColliderMotion {
+    /// The collider is moved by gravity, forces and collisions.
+    Dynamic,
+    /// The collider never moves.
+    Locked,
+    /// The collider follows a path of `(time, transform)` keyframes ordered by their times in
+    /// seconds, placing the origin of its shape at the interpolated translation. The scales of the
+    /// keyframes are ignored.
+    ///
+    /// The collider stays at the first keyframe before it and at the last keyframe after it.
+    Kinematic(Vec<(f32, Transform)>),
+}
+
+impl ColliderMotion {
+    /// The translation and rotation of a kinematic collider at `time`, or `None` if it is not
+    /// kinematic.
+    pub fn pose(&self, time: f32) -> Option<(Vec3, Quat)> {
+        let ColliderMotion::Kinematic(keyframes) = self else {
+            return None;
+        };
+
+        let next = keyframes.partition_point(|(t, _)| *t <= time);
+
+        match (next.checked_sub(1).map(|i| &keyframes[i]), keyframes.get(next)) {
+            (Some((t0, a)), Some((t1, b))) => {
+                let s = (time - t0) / (t1 - t0);
+                Some((a.translate.lerp(b.translate, s), a.rotate.slerp(b.rotate, s)))
+            },
+            (Some((_, keyframe)), None) | (None, Some((_, keyframe))) => Some((keyframe.translate, keyframe.rotate)),
+            (None, None) => None,
+        }
+    }
+}
+
+// Colliders are stored contiguously and most of them are rigid bodies, so boxing would only add
+// indirection.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum ColliderBody {
     Particle(Particle),
@@ -38,6 +96,24 @@ pub enum ColliderBody {
 }
 
 impl ColliderBody {
+    /// The bounding box of this body in global coordinates when its center of mass is located at
+    /// `position`.
+    pub fn aabb(&self, position: Vec3) -> Aabb {
+        match self {
+            ColliderBody::Particle(particle) => Aabb::new(position - particle.radius, position + particle.radius),
+            ColliderBody::Rigid(rb) => {
+                let aabb = rb.shape.aabb();
+
+                // Transforming an infinite box would mix infinities of opposite signs.
+                if aabb.is_finite() {
+                    aabb.transform(rb.origin(position), rb.rotation, rb.scale)
+                } else {
+                    Aabb::INFINITE
+                }
+            },
+        }
+    }
+
     #[inline]
     pub fn mass(&self) -> f32 {
         match self {
@@ -45,19 +121,362 @@ impl ColliderBody {
             ColliderBody::Rigid(rb) => rb.mass,
         }
     }
+
+    #[inline]
+    pub fn inverse_mass(&self) -> f32 {
+        match self {
+            ColliderBody::Particle(particle) => particle.inverse_mass(),
+            ColliderBody::Rigid(rb) => 1.0 / rb.mass,
+        }
+    }
+
+    #[inline]
+    pub fn static_friction(&self) -> f32 {
+        match self {
+            ColliderBody::Particle(particle) => particle.static_friction,
+            ColliderBody::Rigid(rb) => rb.static_friction,
+        }
+    }
+
+    #[inline]
+    pub fn dynamic_friction(&self) -> f32 {
+        match self {
+            ColliderBody::Particle(particle) => particle.dynamic_friction,
+            ColliderBody::Rigid(rb) => rb.dynamic_friction,
+        }
+    }
+
+    /// The rolling friction coefficient of this body, which is zero for particles since they
+    /// never rotate.
+    #[inline]
+    pub fn rolling_friction(&self) -> f32 {
+        match self {
+            ColliderBody::Particle(_) => 0.0,
+            ColliderBody::Rigid(rb) => rb.rolling_friction,
+        }
+    }
+
+    /// The spinning friction coefficient of this body, which is zero for particles since they
+    /// never rotate.
+    #[inline]
+    pub fn spinning_friction(&self) -> f32 {
+        match self {
+            ColliderBody::Particle(_) => 0.0,
+            ColliderBody::Rigid(rb) => rb.spinning_friction,
+        }
+    }
+
+    #[inline]
+    pub fn restitution(&self) -> f32 {
+        match self {
+            ColliderBody::Particle(particle) => particle.restitution,
+            ColliderBody::Rigid(rb) => rb.restitution,
+        }
+    }
+}
+
+impl Collider {
+    /// Creates a collider at rest with the origin of its body at `position`, which is dynamic
+    /// unless it is a half-space.
+    ///
+    /// Its ID is assigned once it is added to a world by [`World::add_collider`].
+    ///
+    /// [`World::add_collider`]: crate::world::World::add_collider
+    pub fn new(body: ColliderBody, position: Vec3) -> Collider {
+        let motion = match &body {
+            ColliderBody::Rigid(RigidBody { shape: Shape::HalfSpace(_), .. }) => ColliderMotion::Locked,
+            _ => ColliderMotion::Dynamic,
+        };
+
+        // Rigid bodies are placed by the origin of their shape, but positioned by their center of
+        // mass.
+        let position = match &body {
+            ColliderBody::Particle(_) => position,
+            ColliderBody::Rigid(rb) => position + rb.rotation * rb.center_of_mass,
+        };
+
+        Collider {
+            id: 0,
+            body,
+            motion,
+            is_sensor: false,
+            collision_layer: DefaultOptions::collision_layers(),
+            collision_mask: DefaultOptions::collision_layers(),
+            removed: false,
+
+            position,
+            previous_position: position,
+            velocity: Vec3::ZERO,
+            previous_velocity: Vec3::ZERO,
+
+            asleep: false,
+            sleep_frames: 0,
+        }
+    }
+
+    /// The bounding box of this collider in global coordinates.
+    #[inline]
+    pub fn aabb(&self) -> Aabb {
+        self.body.aabb(self.position)
+    }
+
+    /// Whether the layers of this collider and `other` allow them to collide.
+    #[inline]
+    pub fn can_collide(&self, other: &Collider) -> bool {
+        self.collision_layer & other.collision_mask != 0 && other.collision_layer & self.collision_mask != 0
+    }
+
+    /// Computes the contacts between this collider and `other`, where this collider is the first
+    /// body of each contact.
+    pub fn collide(&self, other: &Collider) -> Vec<Contact> {
+        match (&self.body, &other.body) {
+            (ColliderBody::Particle(_), ColliderBody::Particle(_)) => Contact::from_particles(self, other).into_iter().collect(),
+            (ColliderBody::Particle(particle), ColliderBody::Rigid(rb)) if particle.radius > 0.0 && matches!(rb.shape, Shape::TriangleMesh(_)) => {
+                Contact::from_particle_mesh(self, other)
+            },
+            (ColliderBody::Rigid(rb), ColliderBody::Particle(particle)) if particle.radius > 0.0 && matches!(rb.shape, Shape::TriangleMesh(_)) => {
+                Contact::from_particle_mesh(other, self).into_iter().map(Contact::flipped).collect()
+            },
+            (ColliderBody::Particle(_), ColliderBody::Rigid(_)) => {
+                Contact::from_particle_rigid_body(self, other).into_iter().collect()
+            },
+            (ColliderBody::Rigid(_), ColliderBody::Particle(_)) => {
+                Contact::from_particle_rigid_body(other, self).map(Contact::flipped).into_iter().collect()
+            },
+            (ColliderBody::Rigid(_), ColliderBody::Rigid(_)) => Contact::from_rigid_bodies(self, other),
+        }
+    }
+
+    /// Casts the ray `origin + t * direction` against this collider, returning the distance `t`
+    /// along the normalized `direction` at which it first hits its surface along with the outward
+    /// surface normal there.
+    ///
+    /// Rays starting inside the collider do not hit it.
+    pub fn raycast(&self, origin: Vec3, direction: Vec3) -> Option<(f32, Vec3)> {
+        let direction = direction.normalize_or_zero();
+
+        match &self.body {
+            ColliderBody::Particle(particle) => {
+                // Solve `|origin + t * direction - position| = radius` for the nearest `t`.
+                let offset = origin - self.position;
+                let b = offset.dot(direction);
+                let c = offset.length_squared() - particle.radius * particle.radius;
+                let discriminant = b * b - c;
+
+                if c <= 0.0 || discriminant < 0.0 || direction == Vec3::ZERO {
+                    return None;
+                }
+
+                let t = -b - discriminant.sqrt();
+                (t >= 0.0).then(|| (t, (origin + t * direction - self.position).normalize_or_zero()))
+            },
+            ColliderBody::Rigid(rb) => {
+                // The ray is parameterized identically in the rigid body's local coordinates, so
+                // the distance along it can be found there directly.
+                let to_local = rb.rotation.inverse();
+                let start = (to_local * (origin - rb.origin(self.position))) / rb.scale;
+                let local_direction = (to_local * direction) / rb.scale;
+
+                let t = rb.shape.raycast(start, local_direction, f32::INFINITY)?;
+
+                let gradient = rb.shape.sd_gradient(start + t * local_direction);
+                let normal = (rb.rotation * (gradient / rb.scale)).normalize_or_zero();
+
+                Some((t, normal))
+            },
+        }
+    }
+
+    /// Whether or not this collider is moved by gravity, forces and collisions.
+    #[inline]
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self.motion, ColliderMotion::Dynamic)
+    }
+
+    /// The inverse mass of this collider, which is zero if it is not dynamic.
+    #[inline]
+    pub fn inverse_mass(&self) -> f32 {
+        if !self.is_dynamic() {
+            0.0
+        } else {
+            self.body.inverse_mass()
+        }
+    }
+
+    /// The inverse inertia tensor of this collider in global coordinates, which is zero if it is
+    /// not dynamic or a particle.
+    #[inline]
+    pub fn global_inverse_inertia(&self) -> Mat3 {
+        match &self.body {
+            ColliderBody::Rigid(rb) if self.is_dynamic() => rb.global_inverse_inertia(),
+            _ => Mat3::ZERO,
+        }
+    }
+
+    /// Compute the generalized inverse mass of this collider at point `r` when applying positional
+    /// correction along the vector `n`, which is zero if it is not dynamic.
+    ///
+    /// See [`RigidBody::positional_inverse_mass`].
+    #[inline]
+    pub fn positional_inverse_mass(&self, r: Vec3, n: Vec3) -> f32 {
+        match &self.body {
+            _ if !self.is_dynamic() => 0.0,
+            ColliderBody::Particle(particle) => particle.inverse_mass(),
+            ColliderBody::Rigid(rb) => rb.positional_inverse_mass(r, n),
+        }
+    }
+
+    /// The rotation of this collider, which is always the identity for particles.
+    #[inline]
+    pub fn rotation(&self) -> Quat {
+        match &self.body {
+            ColliderBody::Particle(_) => Quat::IDENTITY,
+            ColliderBody::Rigid(rb) => rb.rotation,
+        }
+    }
+
+    /// The rotation of this collider at the start of the substep, which is always the identity
+    /// for particles.
+    #[inline]
+    pub fn previous_rotation(&self) -> Quat {
+        match &self.body {
+            ColliderBody::Particle(_) => Quat::IDENTITY,
+            ColliderBody::Rigid(rb) => rb.previous_rotation,
+        }
+    }
+
+    /// The velocity of the point `r` on this collider, where `r` is relative to its center of
+    /// mass in global coordinates.
+    #[inline]
+    pub fn velocity_at(&self, r: Vec3) -> Vec3 {
+        match &self.body {
+            ColliderBody::Particle(_) => self.velocity,
+            ColliderBody::Rigid(rb) => self.velocity + rb.angular_velocity.cross(r),
+        }
+    }
+
+    /// The velocity of the point `r` on this collider at the start of the substep.
+    ///
+    /// See [`Collider::velocity_at`].
+    #[inline]
+    pub fn previous_velocity_at(&self, r: Vec3) -> Vec3 {
+        match &self.body {
+            ColliderBody::Particle(_) => self.previous_velocity,
+            ColliderBody::Rigid(rb) => self.previous_velocity + rb.previous_angular_velocity.cross(r),
+        }
+    }
+
+    /// The angular velocity of this collider, which is always zero for particles.
+    #[inline]
+    pub fn angular_velocity(&self) -> Vec3 {
+        match &self.body {
+            ColliderBody::Particle(_) => Vec3::ZERO,
+            ColliderBody::Rigid(rb) => rb.angular_velocity,
+        }
+    }
+
+    /// Wakes this collider up if it is asleep.
+    #[inline]
+    pub fn wake(&mut self) {
+        self.asleep = false;
+        self.sleep_frames = 0;
+    }
+
+    /// Instantly changes the momentum of this collider by `impulse` at its center of mass, waking
+    /// it up if it is asleep. Colliders which are not dynamic are unaffected.
+    ///
+    /// Unlike a force, which is integrated over the next step, the velocity changes immediately,
+    /// which suits instantaneous effects such as explosions or clicks.
+    pub fn apply_impulse(&mut self, impulse: Vec3) {
+        self.apply_impulse_at_point(impulse, self.position);
+    }
+
+    /// Instantly changes the momentum of this collider by `impulse` at `point` in global
+    /// coordinates, which also changes the angular velocity of a rigid body unless the impulse is
+    /// directed through its center of mass. Asleep colliders are woken up, while colliders which
+    /// are not dynamic are unaffected.
+    pub fn apply_impulse_at_point(&mut self, impulse: Vec3, point: Vec3) {
+        if !self.is_dynamic() {
+            return;
+        }
+
+        self.wake();
+        self.apply_offset_impulse(impulse, point - self.position);
+    }
+
+    /// Applies the velocity `impulse` to this collider at point `r`, where `r` is relative to its
+    /// center of mass in global coordinates. Colliders which are not dynamic are unaffected.
+    pub fn apply_offset_impulse(&mut self, impulse: Vec3, r: Vec3) {
+        if !self.is_dynamic() {
+            return;
+        }
+
+        self.velocity += impulse * self.body.inverse_mass();
+
+        if let ColliderBody::Rigid(rb) = &mut self.body {
+            rb.angular_velocity += rb.global_inverse_inertia() * r.cross(impulse);
+        }
+    }
+
+    /// Applies the angular velocity `impulse` to this collider, in global coordinates. Particles
+    /// and colliders which are not dynamic are unaffected.
+    pub fn apply_angular_impulse(&mut self, impulse: Vec3) {
+        if !self.is_dynamic() {
+            return;
+        }
+
+        if let ColliderBody::Rigid(rb) = &mut self.body {
+            rb.angular_velocity += rb.global_inverse_inertia() * impulse;
+        }
+    }
+
+    /// Undoes the movement of this rigid body since the start of the substep along its locked
+    /// axes, and removes its velocity along them.
+    pub fn lock_axes(&mut self) {
+        let ColliderBody::Rigid(rb) = &mut self.body else {
+            return;
+        };
+
+        if rb.lock_translation.any() {
+            let locked = Vec3::select(rb.lock_translation, Vec3::ONE, Vec3::ZERO);
+            self.position -= locked * (self.position - self.previous_position);
+            self.velocity -= locked * self.velocity;
+        }
+
+        if rb.lock_rotation.any() {
+            let locked = Vec3::select(rb.lock_rotation, Vec3::ONE, Vec3::ZERO);
+
+            // The rotation since the start of the substep about the local axes of the body.
+            let delta = (rb.previous_rotation.inverse() * rb.rotation).to_scaled_axis();
+            rb.rotation = (rb.previous_rotation * Quat::from_scaled_axis(delta - locked * delta)).normalize();
+
+            let local_angular_velocity = rb.rotation.inverse() * rb.angular_velocity;
+            rb.angular_velocity = rb.rotation * (local_angular_velocity - locked * local_angular_velocity);
+        }
+    }
 }
 
 impl From<ParsedCollider> for Collider {
     fn from(value: ParsedCollider) -> Self {
+        let collider = Collider::new(value.body.into(), value.position);
+        let motion = match value.motion {
+            _ if matches!(collider.motion, ColliderMotion::Locked) => ColliderMotion::Locked,
+            ParsedColliderMotion::Dynamic => ColliderMotion::Dynamic,
+            ParsedColliderMotion::Locked => ColliderMotion::Locked,
+            ParsedColliderMotion::Kinematic(keyframes) => ColliderMotion::Kinematic(
+                keyframes.into_iter().map(|(time, transform)| (time, transform.into())).collect(),
+            ),
+        };
+
         Collider {
             id: value.id,
-            locked: value.locked,
-            body: value.body.into(),
-
-            position: value.position,
-            previous_position: value.position,
+            motion,
+            is_sensor: value.is_sensor,
+            collision_layer: value.collision_layer,
+            collision_mask: value.collision_mask,
             velocity: value.velocity,
             previous_velocity: value.velocity,
+            ..collider
         }
     }
 }
@@ -71,19 +490,85 @@ impl From<ParsedColliderBody> for ColliderBody {
     }
 }
 
+impl From<&Collider> for SnapshotCollider {
+    fn from(value: &Collider) -> Self {
+        SnapshotCollider {
+            id: value.id,
+            body: match &value.body {
+                ColliderBody::Particle(particle) => SnapshotColliderBody::Particle(particle.into()),
+                ColliderBody::Rigid(rb) => SnapshotColliderBody::RigidBody(rb.into()),
+            },
+            motion: match &value.motion {
+                ColliderMotion::Dynamic => SnapshotColliderMotion::Dynamic,
+                ColliderMotion::Locked => SnapshotColliderMotion::Locked,
+                ColliderMotion::Kinematic(keyframes) => SnapshotColliderMotion::Kinematic(
+                    keyframes.iter().map(|(time, transform)| (*time, transform.into())).collect(),
+                ),
+            },
+            is_sensor: value.is_sensor,
+            collision_layer: value.collision_layer,
+            collision_mask: value.collision_mask,
+            removed: value.removed,
+
+            position: value.position,
+            previous_position: value.previous_position,
+            velocity: value.velocity,
+            previous_velocity: value.previous_velocity,
+
+            asleep: value.asleep,
+            sleep_frames: value.sleep_frames,
+        }
+    }
+}
+
+impl From<SnapshotCollider> for Collider {
+    fn from(value: SnapshotCollider) -> Self {
+        Collider {
+            id: value.id,
+            body: match value.body {
+                SnapshotColliderBody::Particle(particle) => ColliderBody::Particle(particle.into()),
+                SnapshotColliderBody::RigidBody(rb) => ColliderBody::Rigid(rb.into()),
+            },
+            motion: match value.motion {
+                SnapshotColliderMotion::Dynamic => ColliderMotion::Dynamic,
+                SnapshotColliderMotion::Locked => ColliderMotion::Locked,
+                SnapshotColliderMotion::Kinematic(keyframes) => ColliderMotion::Kinematic(
+                    keyframes.into_iter().map(|(time, transform)| (time, transform.into())).collect(),
+                ),
+            },
+            is_sensor: value.is_sensor,
+            collision_layer: value.collision_layer,
+            collision_mask: value.collision_mask,
+            removed: value.removed,
+
+            position: value.position,
+            previous_position: value.previous_position,
+            velocity: value.velocity,
+            previous_velocity: value.previous_velocity,
+
+            asleep: value.asleep,
+            sleep_frames: value.sleep_frames,
+        }
+    }
+}
+
 impl From<&Collider> for WriteCollider {
     fn from(value: &Collider) -> Self {
         let transform = match &value.body {
             ColliderBody::Particle(_) => WriteTransform::from_translate(value.position),
             ColliderBody::Rigid(rb) => WriteTransform {
-                translate: value.position,
+                translate: rb.origin(value.position),
                 rotate: rb.rotation,
+                scale: rb.scale,
             }
         };
         
         WriteCollider {
             id: value.id,
             transform,
+            velocity: value.velocity,
+            angular_velocity: value.angular_velocity(),
+            contacts: Vec::new(),
         }
     }
 }
@@ -0,0 +1,104 @@
+use glam::Vec3;
+use sokudo_io::read::constraint::ParsedConstraint;
+
+use crate::collider::{Collider, ColliderId};
+
+use super::{twist_angle, Constraint};
+
+/// Keeps the twist of the second body relative to the first about an axis within a range,
+/// without otherwise coupling the two bodies.
+///
+/// The constraint is inactive while the twist is within the range. Beyond it, [`Constraint::c`]
+/// is the signed angle by which the range is exceeded and [`Constraint::c_gradients`] are the
+/// axes each body would be rotated about to increase the twist. Since a rotation cannot be
+/// applied as a positional impulse without also translating the bodies, the inverse masses are
+/// zero and the correction is applied through [`Constraint::angular_error`] instead.
+pub struct AngularLimitConstraint {
+    pub a: ColliderId,
+    pub b: ColliderId,
+
+    /// The twist axis in the local coordinates of the first body.
+    pub axis: Vec3,
+
+    /// The lower limit of the twist angle, in radians.
+    pub min_angle: f32,
+    /// The upper limit of the twist angle, in radians.
+    pub max_angle: f32,
+
+    pub compliance: f32,
+}
+
+impl AngularLimitConstraint {
+    /// The signed angle by which the twist exceeds the range, or zero if it is within it.
+    fn violation(&self, a: &Collider, b: &Collider) -> f32 {
+        let angle = twist_angle(a, b, self.axis);
+
+        if angle < self.min_angle {
+            angle - self.min_angle
+        } else if angle > self.max_angle {
+            angle - self.max_angle
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Constraint for AngularLimitConstraint {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        vec![self.a, self.b]
+    }
+
+    fn c(&self, bodies: &[&Collider]) -> f32 {
+        let [a, b] = *bodies else { return 0.0 };
+        self.violation(a, b)
+    }
+
+    fn c_gradients(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b] = *bodies else { return vec![] };
+
+        if self.violation(a, b) == 0.0 {
+            return vec![Vec3::ZERO, Vec3::ZERO];
+        }
+
+        let axis = (a.rotation() * self.axis).normalize();
+        vec![-axis, axis]
+    }
+
+    fn inverse_masses(&self, bodies: &[&Collider]) -> Vec<f32> {
+        vec![0.0; bodies.len()]
+    }
+
+    #[inline]
+    fn compliance(&self) -> f32 {
+        self.compliance
+    }
+
+    fn to_parsed(&self) -> Option<ParsedConstraint> {
+        Some(self.into())
+    }
+
+    fn angular_error(&self, bodies: &[&Collider]) -> Option<Vec3> {
+        let [a, b] = *bodies else { return None };
+        let violation = self.violation(a, b);
+
+        if violation == 0.0 {
+            return None;
+        }
+
+        Some(violation * (a.rotation() * self.axis).normalize())
+    }
+}
+
+impl From<&AngularLimitConstraint> for ParsedConstraint {
+    fn from(value: &AngularLimitConstraint) -> Self {
+        ParsedConstraint::AngularLimit {
+            a: value.a.0,
+            b: value.b.0,
+            axis: value.axis,
+            min_angle: value.min_angle,
+            max_angle: value.max_angle,
+            compliance: value.compliance,
+        }
+    }
+}
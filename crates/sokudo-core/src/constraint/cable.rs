@@ -0,0 +1,69 @@
+use glam::Vec3;
+use sokudo_io::read::constraint::ParsedConstraint;
+
+use crate::collider::{Collider, ColliderId};
+
+use super::Constraint;
+
+/// Keeps the centers of mass of two colliders at most a given distance from each other, like a
+/// cable which goes taut but never pushes.
+///
+/// Unlike a [`DistanceConstraint`](super::distance::DistanceConstraint), this is an inequality
+/// constraint which is inactive while the cable is slack.
+pub struct CableConstraint {
+    pub a: ColliderId,
+    pub b: ColliderId,
+
+    /// The length of the cable, beyond which the colliders are pulled back together.
+    pub max_length: f32,
+    pub compliance: f32,
+}
+
+impl Constraint for CableConstraint {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        vec![self.a, self.b]
+    }
+
+    fn c(&self, bodies: &[&Collider]) -> f32 {
+        let [a, b] = *bodies else { return 0.0 };
+
+        ((a.position - b.position).length() - self.max_length).max(0.0)
+    }
+
+    fn c_gradients(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b] = *bodies else { return vec![] };
+        let offset = a.position - b.position;
+
+        if offset.length() <= self.max_length {
+            return vec![Vec3::ZERO, Vec3::ZERO];
+        }
+
+        let n = offset.normalize_or_zero();
+        vec![n, -n]
+    }
+
+    fn inverse_masses(&self, bodies: &[&Collider]) -> Vec<f32> {
+        bodies.iter().map(|body| body.inverse_mass()).collect()
+    }
+
+    #[inline]
+    fn compliance(&self) -> f32 {
+        self.compliance
+    }
+
+    fn to_parsed(&self) -> Option<ParsedConstraint> {
+        Some(self.into())
+    }
+}
+
+impl From<&CableConstraint> for ParsedConstraint {
+    fn from(value: &CableConstraint) -> Self {
+        ParsedConstraint::Cable {
+            a: value.a.0,
+            b: value.b.0,
+            max_length: value.max_length,
+            compliance: value.compliance,
+        }
+    }
+}
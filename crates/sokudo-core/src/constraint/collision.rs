@@ -2,7 +2,7 @@ use glam::Vec3;
 
 use crate::{collider::{Collider, ColliderBody, ColliderId}, contact::Contact};
 
-use super::Constraint;
+use super::{Constraint, ContactConstraint};
 
 pub struct ParticleCollisionConstraint {
     pub particle: ColliderId,
@@ -10,6 +10,7 @@ pub struct ParticleCollisionConstraint {
 
     pub contact: Contact,
     pub compliance: f32,
+    pub static_friction: f32,
 }
 
 impl Constraint for ParticleCollisionConstraint {
@@ -18,8 +19,10 @@ impl Constraint for ParticleCollisionConstraint {
         vec![self.particle, self.rb]
     }
 
-    fn c(&self, _bodies: &[&Collider]) -> f32 {
-        self.contact.depth
+    fn c(&self, bodies: &[&Collider]) -> f32 {
+        let [particle, rb] = *bodies else { return 0.0 };
+
+        self.contact.current_depth(particle, rb)
     }
 
     fn c_gradients(&self, _bodies: &[&Collider]) -> Vec<Vec3> {
@@ -38,14 +41,19 @@ impl Constraint for ParticleCollisionConstraint {
             return vec![];
         };
 
-        let w1 = if particle.locked { 0.0 } else { particle_body.inverse_mass() };
-        let w2 = if rb.locked { 0.0 } else { rb_body.positional_inverse_mass(self.contact.anchor2, self.contact.normal) };
+        let (_, anchor2) = self.contact.current_anchors(particle, rb);
+
+        let w1 = if !particle.is_dynamic() { 0.0 } else { particle_body.inverse_mass() };
+        let w2 = if !rb.is_dynamic() { 0.0 } else { rb_body.positional_inverse_mass(anchor2, self.contact.normal) };
 
         vec![w1, w2]
     }
 
-    fn anchors(&self) -> Vec<Vec3> {
-        vec![self.contact.anchor1, self.contact.anchor2]
+    fn anchors(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [particle, rb] = *bodies else { return vec![] };
+        let (anchor1, anchor2) = self.contact.current_anchors(particle, rb);
+
+        vec![anchor1, anchor2]
     }
 
     #[inline]
@@ -54,9 +62,178 @@ impl Constraint for ParticleCollisionConstraint {
     }
 }
 
+impl ContactConstraint for ParticleCollisionConstraint {
+    #[inline]
+    fn contact(&self) -> &Contact {
+        &self.contact
+    }
+
+    #[inline]
+    fn static_friction(&self) -> f32 {
+        self.static_friction
+    }
+}
+
+pub struct ParticleParticleCollisionConstraint {
+    pub a: ColliderId,
+    pub b: ColliderId,
+
+    pub contact: Contact,
+    pub compliance: f32,
+    pub static_friction: f32,
+}
+
+impl Constraint for ParticleParticleCollisionConstraint {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        vec![self.a, self.b]
+    }
+
+    fn c(&self, bodies: &[&Collider]) -> f32 {
+        let [a, b] = *bodies else { return 0.0 };
+
+        self.contact.current_depth(a, b)
+    }
+
+    fn c_gradients(&self, _bodies: &[&Collider]) -> Vec<Vec3> {
+        let n = self.contact.normal;
+        vec![-n, n]
+    }
+
+    fn inverse_masses(&self, bodies: &[&Collider]) -> Vec<f32> {
+        let [a, b] = *bodies else { return vec![] };
+
+        vec![a.inverse_mass(), b.inverse_mass()]
+    }
+
+    fn anchors(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b] = *bodies else { return vec![] };
+        let (anchor1, anchor2) = self.contact.current_anchors(a, b);
+
+        vec![anchor1, anchor2]
+    }
+
+    #[inline]
+    fn compliance(&self) -> f32 {
+        self.compliance
+    }
+}
+
+impl ContactConstraint for ParticleParticleCollisionConstraint {
+    #[inline]
+    fn contact(&self) -> &Contact {
+        &self.contact
+    }
+
+    #[inline]
+    fn static_friction(&self) -> f32 {
+        self.static_friction
+    }
+}
+
 pub struct RigidBodyCollisionConstraint {
     pub a: ColliderId,
     pub b: ColliderId,
 
+    pub contact: Contact,
     pub compliance: f32,
+    pub static_friction: f32,
+}
+
+impl Constraint for RigidBodyCollisionConstraint {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        vec![self.a, self.b]
+    }
+
+    fn c(&self, bodies: &[&Collider]) -> f32 {
+        let [a, b] = *bodies else { return 0.0 };
+
+        self.contact.current_depth(a, b)
+    }
+
+    fn c_gradients(&self, _bodies: &[&Collider]) -> Vec<Vec3> {
+        let n = self.contact.normal;
+        vec![-n, n]
+    }
+
+    fn inverse_masses(&self, bodies: &[&Collider]) -> Vec<f32> {
+        let [a, b] = *bodies else { return vec![] };
+        let (anchor1, anchor2) = self.contact.current_anchors(a, b);
+
+        let w1 = a.positional_inverse_mass(anchor1, self.contact.normal);
+        let w2 = b.positional_inverse_mass(anchor2, self.contact.normal);
+
+        vec![w1, w2]
+    }
+
+    fn anchors(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b] = *bodies else { return vec![] };
+        let (anchor1, anchor2) = self.contact.current_anchors(a, b);
+
+        vec![anchor1, anchor2]
+    }
+
+    #[inline]
+    fn compliance(&self) -> f32 {
+        self.compliance
+    }
+}
+
+impl ContactConstraint for RigidBodyCollisionConstraint {
+    #[inline]
+    fn contact(&self) -> &Contact {
+        &self.contact
+    }
+
+    #[inline]
+    fn static_friction(&self) -> f32 {
+        self.static_friction
+    }
+}
+
+/// Resolves the [`Contact`] of a [`ContactConstraint`] while letting it pull its bodies back
+/// together once they have been separated too far, by measuring the signed depth of the contact
+/// rather than clamping it to zero.
+///
+/// The solver never lets the accumulated multiplier of this constraint become positive, so it can
+/// only undo corrections already applied in the same substep, such as a warm-started impulse
+/// which turns out larger than needed, and never attracts the bodies.
+pub struct SeparatingCollisionConstraint<'a> {
+    pub collision: &'a dyn ContactConstraint,
+    /// The depth the contact is resolved to, below which the bodies are not pushed apart.
+    pub target_depth: f32,
+}
+
+impl Constraint for SeparatingCollisionConstraint<'_> {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        self.collision.bodies()
+    }
+
+    fn c(&self, bodies: &[&Collider]) -> f32 {
+        let [a, b, ..] = *bodies else { return 0.0 };
+
+        self.collision.contact().current_signed_depth(a, b) - self.target_depth
+    }
+
+    #[inline]
+    fn c_gradients(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        self.collision.c_gradients(bodies)
+    }
+
+    #[inline]
+    fn inverse_masses(&self, bodies: &[&Collider]) -> Vec<f32> {
+        self.collision.inverse_masses(bodies)
+    }
+
+    #[inline]
+    fn anchors(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        self.collision.anchors(bodies)
+    }
+
+    #[inline]
+    fn compliance(&self) -> f32 {
+        self.collision.compliance()
+    }
 }
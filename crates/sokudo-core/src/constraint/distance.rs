@@ -0,0 +1,60 @@
+use glam::Vec3;
+use sokudo_io::read::constraint::ParsedConstraint;
+
+use crate::collider::{Collider, ColliderId};
+
+use super::Constraint;
+
+/// Keeps the centers of mass of two colliders at a fixed distance from each other.
+pub struct DistanceConstraint {
+    pub a: ColliderId,
+    pub b: ColliderId,
+
+    /// The distance the two colliders are kept at.
+    pub rest_length: f32,
+    pub compliance: f32,
+}
+
+impl Constraint for DistanceConstraint {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        vec![self.a, self.b]
+    }
+
+    fn c(&self, bodies: &[&Collider]) -> f32 {
+        let [a, b] = *bodies else { return 0.0 };
+
+        (a.position - b.position).length() - self.rest_length
+    }
+
+    fn c_gradients(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b] = *bodies else { return vec![] };
+
+        let n = (a.position - b.position).normalize_or_zero();
+        vec![n, -n]
+    }
+
+    fn inverse_masses(&self, bodies: &[&Collider]) -> Vec<f32> {
+        bodies.iter().map(|body| body.inverse_mass()).collect()
+    }
+
+    #[inline]
+    fn compliance(&self) -> f32 {
+        self.compliance
+    }
+
+    fn to_parsed(&self) -> Option<ParsedConstraint> {
+        Some(self.into())
+    }
+}
+
+impl From<&DistanceConstraint> for ParsedConstraint {
+    fn from(value: &DistanceConstraint) -> Self {
+        ParsedConstraint::Distance {
+            a: value.a.0,
+            b: value.b.0,
+            rest_length: value.rest_length,
+            compliance: value.compliance,
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use glam::Vec3;
+
+use crate::{collider::{Collider, ColliderId}, contact::Contact};
+
+use super::{Constraint, ContactConstraint, VelocityConstraint};
+
+/// Prevents the contact points of a [`ContactConstraint`] from sliding relative to each other.
+///
+/// The correction is only applied while the tangential multiplier stays below the static friction
+/// coefficient times the normal multiplier of the contact.
+pub struct StaticFrictionConstraint<'a> {
+    pub contact: &'a dyn ContactConstraint,
+}
+
+impl Constraint for StaticFrictionConstraint<'_> {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        self.contact.bodies()
+    }
+
+    fn c(&self, bodies: &[&Collider]) -> f32 {
+        let [a, b, ..] = *bodies else { return 0.0 };
+
+        self.contact.contact().tangential_displacement(a, b).length()
+    }
+
+    fn c_gradients(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b, ..] = *bodies else { return vec![] };
+
+        let t = self.contact.contact().tangential_displacement(a, b).normalize_or_zero();
+        vec![t, -t]
+    }
+
+    fn inverse_masses(&self, bodies: &[&Collider]) -> Vec<f32> {
+        let [a, b, ..] = *bodies else { return vec![] };
+
+        let contact = self.contact.contact();
+        let t = contact.tangential_displacement(a, b).normalize_or_zero();
+        let (anchor1, anchor2) = contact.current_anchors(a, b);
+
+        vec![a.positional_inverse_mass(anchor1, t), b.positional_inverse_mass(anchor2, t)]
+    }
+
+    fn anchors(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b, ..] = *bodies else { return vec![] };
+        let (anchor1, anchor2) = self.contact.contact().current_anchors(a, b);
+
+        vec![anchor1, anchor2]
+    }
+
+    #[inline]
+    fn compliance(&self) -> f32 {
+        0.0
+    }
+}
+
+/// Slows down the tangential velocity of two bodies in contact, bounded by the change in normal
+/// velocity caused by the collision response.
+pub struct DynamicFrictionConstraint {
+    pub a: ColliderId,
+    pub b: ColliderId,
+
+    pub contact: Contact,
+    pub coefficient: f32,
+}
+
+impl VelocityConstraint for DynamicFrictionConstraint {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        vec![self.a, self.b]
+    }
+
+    fn solve(&self, mut bodies: std::vec::IntoIter<&mut Collider>, _h: f32) {
+        let a = bodies.next().unwrap();
+        let b = bodies.next().unwrap();
+
+        let n = self.contact.normal;
+        let (anchor1, anchor2) = self.contact.current_anchors(a, b);
+
+        let vdiff = a.velocity_at(anchor1) - b.velocity_at(anchor2);
+        let vn = n.dot(vdiff);
+        let vt = vdiff - n * vn;
+        let vt_length = vt.length();
+
+        if vt_length <= f32::EPSILON {
+            return;
+        }
+
+        let vn_prev = n.dot(a.previous_velocity_at(anchor1) - b.previous_velocity_at(anchor2));
+        let delta_vn = (vn - vn_prev).max(0.0);
+
+        let t = vt / vt_length;
+        let w_sum = a.positional_inverse_mass(anchor1, t) + b.positional_inverse_mass(anchor2, t);
+
+        if w_sum <= f32::EPSILON {
+            return;
+        }
+
+        let delta_v = -t * (self.coefficient * delta_vn).min(vt_length);
+        let p = delta_v / w_sum;
+
+        a.apply_offset_impulse(p, anchor1);
+        b.apply_offset_impulse(-p, anchor2);
+    }
+}
@@ -0,0 +1,215 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::Vec3;
+use sokudo_io::read::constraint::{ParsedConstraint, ParsedHingeMotor};
+
+use crate::collider::{Collider, ColliderId};
+
+use super::{twist_angle, Constraint, VelocityConstraint};
+
+/// Couples two bodies at a shared anchor point and only allows them to rotate relative to each
+/// other about a single axis.
+///
+/// The positional part of the constraint ([`Constraint::c`]) is the distance between the two
+/// anchors, while the misalignment of the two axes and any violated angle limits are corrected
+/// through [`Constraint::angular_error`].
+///
+/// A hinge with a [`HingeMotor`] is also a [`VelocityConstraint`], which drives the bodies about
+/// the hinge axis each substep.
+pub struct HingeConstraint {
+    pub a: ColliderId,
+    pub b: ColliderId,
+
+    /// The anchor on the first body in its local coordinates, relative to its center of mass.
+    pub anchor_a: Vec3,
+    /// The anchor on the second body in its local coordinates, relative to its center of mass.
+    pub anchor_b: Vec3,
+    /// The hinge axis in the local coordinates of the first body.
+    pub axis_a: Vec3,
+    /// The hinge axis in the local coordinates of the second body.
+    pub axis_b: Vec3,
+
+    /// The lower limit of the hinge angle, in radians.
+    pub min_angle: Option<f32>,
+    /// The upper limit of the hinge angle, in radians.
+    pub max_angle: Option<f32>,
+
+    pub compliance: f32,
+
+    /// Drives the hinge about its axis, if set.
+    pub motor: Option<HingeMotor>,
+}
+
+/// How a [`HingeConstraint`] is driven about its axis.
+#[derive(Debug, Clone, Copy)]
+pub enum HingeMotor {
+    /// Drives the angular velocity of the second body relative to the first about the axis
+    /// towards `target_velocity`, in radians per second.
+    Velocity {
+        target_velocity: f32,
+        /// The largest torque the motor applies.
+        max_force: f32,
+    },
+    /// Drives the hinge angle towards `target_angle`, in radians, by driving the relative angular
+    /// velocity towards `stiffness` times the remaining angle.
+    Angle {
+        target_angle: f32,
+        stiffness: f32,
+        /// The largest torque the motor applies.
+        max_force: f32,
+    },
+}
+
+impl From<ParsedHingeMotor> for HingeMotor {
+    fn from(value: ParsedHingeMotor) -> Self {
+        match value {
+            ParsedHingeMotor::Velocity { target_velocity, max_force } => HingeMotor::Velocity { target_velocity, max_force },
+            ParsedHingeMotor::Angle { target_angle, stiffness, max_force } => HingeMotor::Angle { target_angle, stiffness, max_force },
+        }
+    }
+}
+
+impl From<HingeMotor> for ParsedHingeMotor {
+    fn from(value: HingeMotor) -> Self {
+        match value {
+            HingeMotor::Velocity { target_velocity, max_force } => ParsedHingeMotor::Velocity { target_velocity, max_force },
+            HingeMotor::Angle { target_angle, stiffness, max_force } => ParsedHingeMotor::Angle { target_angle, stiffness, max_force },
+        }
+    }
+}
+
+impl HingeConstraint {
+    /// The world space offsets of the two anchors from their bodies' centers of mass.
+    fn world_anchors(&self, a: &Collider, b: &Collider) -> (Vec3, Vec3) {
+        (a.rotation() * self.anchor_a, b.rotation() * self.anchor_b)
+    }
+
+    /// The angle of the second body relative to the first about the hinge axis, where zero is
+    /// the angle at which both bodies have the same orientation.
+    pub fn angle(&self, a: &Collider, b: &Collider) -> f32 {
+        twist_angle(a, b, self.axis_a)
+    }
+}
+
+impl Constraint for HingeConstraint {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        vec![self.a, self.b]
+    }
+
+    fn c(&self, bodies: &[&Collider]) -> f32 {
+        let [a, b] = *bodies else { return 0.0 };
+        let (ra, rb) = self.world_anchors(a, b);
+
+        ((a.position + ra) - (b.position + rb)).length()
+    }
+
+    fn c_gradients(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b] = *bodies else { return vec![] };
+        let (ra, rb) = self.world_anchors(a, b);
+
+        let n = ((a.position + ra) - (b.position + rb)).normalize_or_zero();
+        vec![n, -n]
+    }
+
+    fn inverse_masses(&self, bodies: &[&Collider]) -> Vec<f32> {
+        let [a, b] = *bodies else { return vec![] };
+        let (ra, rb) = self.world_anchors(a, b);
+
+        let n = ((a.position + ra) - (b.position + rb)).normalize_or_zero();
+        vec![a.positional_inverse_mass(ra, n), b.positional_inverse_mass(rb, n)]
+    }
+
+    fn anchors(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b] = *bodies else { return vec![] };
+        let (ra, rb) = self.world_anchors(a, b);
+
+        vec![ra, rb]
+    }
+
+    #[inline]
+    fn compliance(&self) -> f32 {
+        self.compliance
+    }
+
+    fn to_parsed(&self) -> Option<ParsedConstraint> {
+        Some(self.into())
+    }
+
+    fn angular_error(&self, bodies: &[&Collider]) -> Option<Vec3> {
+        let [a, b] = *bodies else { return None };
+
+        let axis_a = (a.rotation() * self.axis_a).normalize();
+        let axis_b = (b.rotation() * self.axis_b).normalize();
+
+        // Rotating the first body about this axis brings the two hinge axes into alignment.
+        let alignment = axis_a.cross(axis_b);
+
+        let angle = self.angle(a, b);
+        let limit = match (self.min_angle, self.max_angle) {
+            (Some(min), _) if angle < min => angle - min,
+            (_, Some(max)) if angle > max => angle - max,
+            _ => 0.0,
+        };
+
+        Some(alignment + limit * axis_a)
+    }
+}
+
+impl VelocityConstraint for HingeConstraint {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        vec![self.a, self.b]
+    }
+
+    fn to_parsed(&self) -> Option<ParsedConstraint> {
+        Some(self.into())
+    }
+
+    fn solve(&self, mut bodies: std::vec::IntoIter<&mut Collider>, h: f32) {
+        let a = bodies.next().unwrap();
+        let b = bodies.next().unwrap();
+
+        let Some(motor) = self.motor else { return };
+
+        let (target_velocity, max_force) = match motor {
+            HingeMotor::Velocity { target_velocity, max_force } => (target_velocity, max_force),
+            HingeMotor::Angle { target_angle, stiffness, max_force } => {
+                // The shorter way around is always taken towards the target.
+                let error = (target_angle - self.angle(a, b) + PI).rem_euclid(TAU) - PI;
+                (stiffness * error, max_force)
+            },
+        };
+
+        let axis = (a.rotation() * self.axis_a).normalize();
+        let w_sum = axis.dot(a.global_inverse_inertia() * axis) + axis.dot(b.global_inverse_inertia() * axis);
+
+        if w_sum <= f32::EPSILON {
+            return;
+        }
+
+        let velocity = axis.dot(b.angular_velocity() - a.angular_velocity());
+        let max_impulse = max_force * h;
+        let impulse = ((target_velocity - velocity) / w_sum).clamp(-max_impulse, max_impulse);
+
+        a.apply_angular_impulse(-impulse * axis);
+        b.apply_angular_impulse(impulse * axis);
+    }
+}
+
+impl From<&HingeConstraint> for ParsedConstraint {
+    fn from(value: &HingeConstraint) -> Self {
+        ParsedConstraint::Hinge {
+            a: value.a.0,
+            b: value.b.0,
+            anchor_a: value.anchor_a,
+            anchor_b: value.anchor_b,
+            axis_a: value.axis_a,
+            axis_b: value.axis_b,
+            min_angle: value.min_angle,
+            max_angle: value.max_angle,
+            compliance: value.compliance,
+            motor: value.motor.map(ParsedHingeMotor::from),
+        }
+    }
+}
@@ -1,9 +1,29 @@
+use std::f32::consts::{PI, TAU};
+
+use angular_limit::AngularLimitConstraint;
+use cable::CableConstraint;
+use distance::DistanceConstraint;
 use glam::Vec3;
+use hinge::{HingeConstraint, HingeMotor};
+use prismatic::PrismaticJoint;
+use sokudo_io::read::constraint::ParsedConstraint;
+use spherical::SphericalJoint;
+use spring::SpringConstraint;
+use volume::VolumeConstraint;
 
-use crate::collider::{Collider, ColliderId};
+use crate::{collider::{Collider, ColliderId}, contact::Contact};
 
+pub mod angular_limit;
+pub mod cable;
 pub mod collision;
+pub mod distance;
+pub mod friction;
+pub mod hinge;
+pub mod prismatic;
 pub mod restitution;
+pub mod spherical;
+pub mod spring;
+pub mod volume;
 
 pub trait Constraint {
     /// The participating bodies of this constraint.
@@ -12,7 +32,9 @@ pub trait Constraint {
 
     /// Computes the constraint error (C).
     ///
-    /// This value should be exactly zero when the constraint is satisfied.
+    /// This value should be exactly zero when the constraint is satisfied. Inequality constraints,
+    /// which only act in one direction, are inactive while they are satisfied and then return
+    /// zero along with zero gradients, so they apply no correction at all.
     fn c(&self, bodies: &[&Collider]) -> f32;
 
     /// The gradient of the constraint (∇C) for each of the bodies.
@@ -25,17 +47,212 @@ pub trait Constraint {
     /// The inverse masses of the participating bodies.
     fn inverse_masses(&self, bodies: &[&Collider]) -> Vec<f32>;
 
-    /// The anchors where positional impulses should be applied.
-    fn anchors(&self) -> Vec<Vec3>;
+    /// The anchors where positional impulses should be applied, relative to each body's center
+    /// of mass in global coordinates.
+    ///
+    /// By default, impulses are applied at the center of mass of every body, so they never
+    /// rotate it.
+    fn anchors(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        vec![Vec3::ZERO; bodies.len()]
+    }
 
     /// The inverse stiffness of this constraint.
     fn compliance(&self) -> f32;
+
+    /// The rotation, as a scaled axis in global coordinates, which should be applied to the first
+    /// body relative to the second to satisfy this constraint.
+    ///
+    /// This is solved after the positional correction and only affects the first two bodies.
+    /// Constraints which do not restrict the relative rotation of their bodies return `None`.
+    fn angular_error(&self, _bodies: &[&Collider]) -> Option<Vec3> {
+        None
+    }
+
+    /// This constraint as it is given in a world file, which is how it is stored in a
+    /// [`Snapshot`](sokudo_io::write::snapshot::Snapshot).
+    ///
+    /// Constraints which only exist for a single substep, such as those between contacts, return
+    /// `None`.
+    fn to_parsed(&self) -> Option<ParsedConstraint> {
+        None
+    }
+}
+
+/// A [`Constraint`] which resolves a [`Contact`] between its first two bodies.
+pub trait ContactConstraint: Constraint {
+    /// The contact resolved by this constraint.
+    fn contact(&self) -> &Contact;
+
+    /// The combined coefficient of static friction of the bodies in contact.
+    fn static_friction(&self) -> f32;
 }
 
 pub trait VelocityConstraint {
     /// The participating bodies of this constraint.
     fn bodies(&self) -> Vec<ColliderId>;
 
-    /// Solve the velocity constraint, applying the required impulses.
-    fn solve(&self, bodies: std::vec::IntoIter<&mut Collider>);
+    /// Solve the velocity constraint over a substep of length `h`, applying the required impulses.
+    fn solve(&self, bodies: std::vec::IntoIter<&mut Collider>, h: f32);
+
+    /// This constraint as it is given in a world file, or `None` for constraints which only exist
+    /// for a single substep. See [`Constraint::to_parsed`].
+    fn to_parsed(&self) -> Option<ParsedConstraint> {
+        None
+    }
+}
+
+impl From<ParsedConstraint> for Box<dyn Constraint> {
+    fn from(value: ParsedConstraint) -> Self {
+        match value {
+            ParsedConstraint::Distance { a, b, rest_length, compliance } => Box::new(DistanceConstraint {
+                a: ColliderId(a),
+                b: ColliderId(b),
+                rest_length,
+                compliance,
+            }),
+            ParsedConstraint::Cable { a, b, max_length, compliance } => Box::new(CableConstraint {
+                a: ColliderId(a),
+                b: ColliderId(b),
+                max_length,
+                compliance,
+            }),
+            ParsedConstraint::Hinge {
+                a,
+                b,
+                anchor_a,
+                anchor_b,
+                axis_a,
+                axis_b,
+                min_angle,
+                max_angle,
+                compliance,
+                motor,
+            } => Box::new(HingeConstraint {
+                a: ColliderId(a),
+                b: ColliderId(b),
+                anchor_a,
+                anchor_b,
+                axis_a,
+                axis_b,
+                min_angle,
+                max_angle,
+                compliance,
+                motor: motor.map(HingeMotor::from),
+            }),
+            ParsedConstraint::Spherical {
+                a,
+                b,
+                anchor_a,
+                anchor_b,
+                cone_axis_a,
+                cone_axis_b,
+                max_swing_angle,
+                compliance,
+            } => Box::new(SphericalJoint {
+                a: ColliderId(a),
+                b: ColliderId(b),
+                anchor_a,
+                anchor_b,
+                cone_axis_a,
+                cone_axis_b,
+                max_swing_angle,
+                compliance,
+            }),
+            ParsedConstraint::Prismatic {
+                a,
+                b,
+                anchor_a,
+                anchor_b,
+                axis,
+                min_distance,
+                max_distance,
+                compliance,
+            } => Box::new(PrismaticJoint {
+                a: ColliderId(a),
+                b: ColliderId(b),
+                anchor_a,
+                anchor_b,
+                axis,
+                min_distance,
+                max_distance,
+                compliance,
+            }),
+            ParsedConstraint::Spring { a, b, rest_length, stiffness, damping } => Box::new(SpringConstraint {
+                a: ColliderId(a),
+                b: ColliderId(b),
+                rest_length,
+                stiffness,
+                damping,
+            }),
+            ParsedConstraint::Volume { a, b, c, d, rest_volume, compliance } => Box::new(VolumeConstraint {
+                a: ColliderId(a),
+                b: ColliderId(b),
+                c: ColliderId(c),
+                d: ColliderId(d),
+                rest_volume,
+                compliance,
+            }),
+            ParsedConstraint::AngularLimit { a, b, axis, min_angle, max_angle, compliance } => Box::new(AngularLimitConstraint {
+                a: ColliderId(a),
+                b: ColliderId(b),
+                axis,
+                min_angle,
+                max_angle,
+                compliance,
+            }),
+        }
+    }
+}
+
+/// The angle, in radians from -π to π, by which `b` is twisted relative to `a` about `axis` in the
+/// local coordinates of `a`, where zero is the angle at which both bodies have the same
+/// orientation.
+pub fn twist_angle(a: &Collider, b: &Collider, axis: Vec3) -> f32 {
+    let relative = a.rotation().inverse() * b.rotation();
+    let twist = 2.0 * relative.xyz().dot(axis.normalize()).atan2(relative.w);
+
+    if twist > PI {
+        twist - TAU
+    } else if twist < -PI {
+        twist + TAU
+    } else {
+        twist
+    }
+}
+
+/// The part of a parsed constraint which is solved on velocities, if any.
+pub fn velocity_constraint(value: &ParsedConstraint) -> Option<Box<dyn VelocityConstraint>> {
+    match *value {
+        ParsedConstraint::Spring { a, b, rest_length, stiffness, damping } if damping > 0.0 => Some(Box::new(SpringConstraint {
+            a: ColliderId(a),
+            b: ColliderId(b),
+            rest_length,
+            stiffness,
+            damping,
+        })),
+        ParsedConstraint::Hinge {
+            a,
+            b,
+            anchor_a,
+            anchor_b,
+            axis_a,
+            axis_b,
+            min_angle,
+            max_angle,
+            compliance,
+            motor: Some(motor),
+        } => Some(Box::new(HingeConstraint {
+            a: ColliderId(a),
+            b: ColliderId(b),
+            anchor_a,
+            anchor_b,
+            axis_a,
+            axis_b,
+            min_angle,
+            max_angle,
+            compliance,
+            motor: Some(motor.into()),
+        })),
+        _ => None,
+    }
 }
@@ -0,0 +1,135 @@
+use glam::Vec3;
+use sokudo_io::read::constraint::ParsedConstraint;
+
+use crate::collider::{Collider, ColliderId};
+
+use super::Constraint;
+
+/// Couples two bodies so that they can only slide relative to each other along a single axis,
+/// while keeping the same orientation.
+///
+/// The positional part of the constraint ([`Constraint::c`]) is the offset between the two anchors
+/// perpendicular to the slide axis, together with any violated travel limits along it. The
+/// relative rotation of the bodies is corrected through [`Constraint::angular_error`].
+pub struct PrismaticJoint {
+    pub a: ColliderId,
+    pub b: ColliderId,
+
+    /// The anchor on the first body in its local coordinates, relative to its center of mass.
+    pub anchor_a: Vec3,
+    /// The anchor on the second body in its local coordinates, relative to its center of mass.
+    pub anchor_b: Vec3,
+    /// The slide axis in the local coordinates of the first body.
+    pub axis: Vec3,
+
+    /// The lower limit of the distance travelled by the second anchor along the slide axis.
+    pub min_distance: Option<f32>,
+    /// The upper limit of the distance travelled by the second anchor along the slide axis.
+    pub max_distance: Option<f32>,
+
+    pub compliance: f32,
+}
+
+impl PrismaticJoint {
+    /// The world space offsets of the two anchors from their bodies' centers of mass.
+    fn world_anchors(&self, a: &Collider, b: &Collider) -> (Vec3, Vec3) {
+        (a.rotation() * self.anchor_a, b.rotation() * self.anchor_b)
+    }
+
+    /// The signed distance of the second anchor from the first along the slide axis.
+    pub fn distance(&self, a: &Collider, b: &Collider) -> f32 {
+        let (ra, rb) = self.world_anchors(a, b);
+        let axis = (a.rotation() * self.axis).normalize();
+
+        ((b.position + rb) - (a.position + ra)).dot(axis)
+    }
+
+    /// The displacement of the second anchor which violates the constraint.
+    fn error(&self, a: &Collider, b: &Collider) -> Vec3 {
+        let (ra, rb) = self.world_anchors(a, b);
+        let axis = (a.rotation() * self.axis).normalize();
+
+        let offset = (b.position + rb) - (a.position + ra);
+        let distance = self.distance(a, b);
+
+        let limit = match (self.min_distance, self.max_distance) {
+            (Some(min), _) if distance < min => distance - min,
+            (_, Some(max)) if distance > max => distance - max,
+            _ => 0.0,
+        };
+
+        offset - distance * axis + limit * axis
+    }
+}
+
+impl Constraint for PrismaticJoint {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        vec![self.a, self.b]
+    }
+
+    fn c(&self, bodies: &[&Collider]) -> f32 {
+        let [a, b] = *bodies else { return 0.0 };
+
+        self.error(a, b).length()
+    }
+
+    fn c_gradients(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b] = *bodies else { return vec![] };
+
+        let n = self.error(a, b).normalize_or_zero();
+        vec![-n, n]
+    }
+
+    fn inverse_masses(&self, bodies: &[&Collider]) -> Vec<f32> {
+        let [a, b] = *bodies else { return vec![] };
+        let (ra, rb) = self.world_anchors(a, b);
+
+        let n = self.error(a, b).normalize_or_zero();
+        vec![a.positional_inverse_mass(ra, n), b.positional_inverse_mass(rb, n)]
+    }
+
+    fn anchors(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b] = *bodies else { return vec![] };
+        let (ra, rb) = self.world_anchors(a, b);
+
+        vec![ra, rb]
+    }
+
+    #[inline]
+    fn compliance(&self) -> f32 {
+        self.compliance
+    }
+
+    fn to_parsed(&self) -> Option<ParsedConstraint> {
+        Some(self.into())
+    }
+
+    fn angular_error(&self, bodies: &[&Collider]) -> Option<Vec3> {
+        let [a, b] = *bodies else { return None };
+
+        // Rotating the first body by this rotation brings it to the orientation of the second.
+        let mut relative = b.rotation() * a.rotation().inverse();
+
+        if relative.w < 0.0 {
+            relative = -relative;
+        }
+
+        Some(2.0 * relative.xyz())
+    }
+}
+
+impl From<&PrismaticJoint> for ParsedConstraint {
+    fn from(value: &PrismaticJoint) -> Self {
+        ParsedConstraint::Prismatic {
+            a: value.a.0,
+            b: value.b.0,
+            anchor_a: value.anchor_a,
+            anchor_b: value.anchor_b,
+            axis: value.axis,
+            min_distance: value.min_distance,
+            max_distance: value.max_distance,
+            compliance: value.compliance,
+        }
+    }
+}
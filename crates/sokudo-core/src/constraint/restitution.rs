@@ -1,48 +1,71 @@
-use crate::{collider::{Collider, ColliderBody, ColliderId}, contact::Contact};
+use crate::{collider::{Collider, ColliderId}, contact::Contact};
 
 use super::VelocityConstraint;
 
-pub struct ParticleRestitutionConstraint {
-    pub particle: ColliderId,
-    pub rb: ColliderId,
+pub struct RestitutionConstraint {
+    pub a: ColliderId,
+    pub b: ColliderId,
 
     pub contact: Contact,
     pub coefficient: f32,
+    /// The relative velocity of the contact points along the contact normal before the position
+    /// solve.
+    pub normal_velocity: f32,
 }
 
-impl VelocityConstraint for ParticleRestitutionConstraint {
+impl RestitutionConstraint {
+    /// Creates a restitution constraint for `contact`, storing the current relative normal
+    /// velocity of the bodies `a` and `b`.
+    ///
+    /// Bodies approaching each other slower than `threshold` do not bounce at all, so resting
+    /// contacts are not kept vibrating by the small velocities left over from the position solve.
+    pub fn new(
+        a: (ColliderId, &Collider),
+        b: (ColliderId, &Collider),
+        contact: Contact,
+        coefficient: f32,
+        threshold: f32,
+    ) -> RestitutionConstraint {
+        let (anchor1, anchor2) = contact.current_anchors(a.1, b.1);
+        let normal_velocity = contact.normal.dot(a.1.velocity_at(anchor1) - b.1.velocity_at(anchor2));
+
+        RestitutionConstraint {
+            a: a.0,
+            b: b.0,
+            contact,
+            coefficient: if -normal_velocity < threshold { 0.0 } else { coefficient },
+            normal_velocity,
+        }
+    }
+}
+
+impl VelocityConstraint for RestitutionConstraint {
     #[inline]
     fn bodies(&self) -> Vec<ColliderId> {
-        vec![self.particle, self.rb]
+        vec![self.a, self.b]
     }
 
-    fn solve(&self, mut bodies: std::vec::IntoIter<&mut Collider>) {
-        let particle = bodies.next().unwrap();
-        let rb = bodies.next().unwrap();
-
-        let ColliderBody::Particle(ref particle_body) = particle.body else {
-            return;
-        };
-        
-        let ColliderBody::Rigid(ref rb_body) = rb.body else {
-            return;
-        };
+    fn solve(&self, mut bodies: std::vec::IntoIter<&mut Collider>, _h: f32) {
+        let a = bodies.next().unwrap();
+        let b = bodies.next().unwrap();
 
         let n = self.contact.normal;
+        let (anchor1, anchor2) = self.contact.current_anchors(a, b);
+
+        let vn = n.dot(a.velocity_at(anchor1) - b.velocity_at(anchor2));
 
-        let vdiff_prev = particle.previous_velocity - rb.previous_velocity;
-        let vn_prev = n.dot(vdiff_prev);
+        let w_sum = a.positional_inverse_mass(anchor1, n) + b.positional_inverse_mass(anchor2, n);
+
+        if w_sum <= f32::EPSILON {
+            return;
+        }
 
-        let vdiff = particle.velocity - rb.velocity;
-        let vn = n.dot(vdiff);
-        
-        // TODO: Correct generalized inverse mass for rigid body
-        let w1 = if particle.locked { 0.0 } else { 1.0 / particle_body.mass };
-        let w2 = if rb.locked { 0.0 } else { 1.0 / rb_body.mass };
-        let w_sum = w1 + w2;
+        // The bodies should separate with the pre-solve approach velocity scaled by the
+        // coefficient, but never be pulled back together.
+        let target = (-self.coefficient * self.normal_velocity).max(0.0);
+        let p = n * (target - vn) / w_sum;
 
-        // TODO: Correct impulse for rigid body that accounts for angular velocity
-        particle.velocity += n * (-vn - self.coefficient * vn_prev) * w1 / w_sum;
-        rb.velocity -= n * (-vn - self.coefficient * vn_prev) * w2 / w_sum;
+        a.apply_offset_impulse(p, anchor1);
+        b.apply_offset_impulse(-p, anchor2);
     }
 }
@@ -0,0 +1,123 @@
+use glam::Vec3;
+use sokudo_io::read::constraint::ParsedConstraint;
+
+use crate::collider::{Collider, ColliderId};
+
+use super::Constraint;
+
+/// Couples two bodies at a shared anchor point while leaving them free to rotate relative to each
+/// other, like a ball-and-socket joint.
+///
+/// The swing of the second body can optionally be limited to a cone around an axis on the first
+/// body, which is corrected through [`Constraint::angular_error`].
+pub struct SphericalJoint {
+    pub a: ColliderId,
+    pub b: ColliderId,
+
+    /// The anchor on the first body in its local coordinates, relative to its center of mass.
+    pub anchor_a: Vec3,
+    /// The anchor on the second body in its local coordinates, relative to its center of mass.
+    pub anchor_b: Vec3,
+    /// The axis of the swing cone in the local coordinates of the first body.
+    pub cone_axis_a: Vec3,
+    /// The axis in the local coordinates of the second body which is kept inside the swing cone.
+    pub cone_axis_b: Vec3,
+
+    /// The largest angle between the two cone axes, in radians.
+    pub max_swing_angle: Option<f32>,
+
+    pub compliance: f32,
+}
+
+impl SphericalJoint {
+    /// The world space offsets of the two anchors from their bodies' centers of mass.
+    fn world_anchors(&self, a: &Collider, b: &Collider) -> (Vec3, Vec3) {
+        (a.rotation() * self.anchor_a, b.rotation() * self.anchor_b)
+    }
+
+    /// The angle between the two cone axes, in radians.
+    pub fn swing_angle(&self, a: &Collider, b: &Collider) -> f32 {
+        let axis_a = (a.rotation() * self.cone_axis_a).normalize();
+        let axis_b = (b.rotation() * self.cone_axis_b).normalize();
+
+        axis_a.angle_between(axis_b)
+    }
+}
+
+impl Constraint for SphericalJoint {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        vec![self.a, self.b]
+    }
+
+    fn c(&self, bodies: &[&Collider]) -> f32 {
+        let [a, b] = *bodies else { return 0.0 };
+        let (ra, rb) = self.world_anchors(a, b);
+
+        ((a.position + ra) - (b.position + rb)).length()
+    }
+
+    fn c_gradients(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b] = *bodies else { return vec![] };
+        let (ra, rb) = self.world_anchors(a, b);
+
+        let n = ((a.position + ra) - (b.position + rb)).normalize_or_zero();
+        vec![n, -n]
+    }
+
+    fn inverse_masses(&self, bodies: &[&Collider]) -> Vec<f32> {
+        let [a, b] = *bodies else { return vec![] };
+        let (ra, rb) = self.world_anchors(a, b);
+
+        let n = ((a.position + ra) - (b.position + rb)).normalize_or_zero();
+        vec![a.positional_inverse_mass(ra, n), b.positional_inverse_mass(rb, n)]
+    }
+
+    fn anchors(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b] = *bodies else { return vec![] };
+        let (ra, rb) = self.world_anchors(a, b);
+
+        vec![ra, rb]
+    }
+
+    #[inline]
+    fn compliance(&self) -> f32 {
+        self.compliance
+    }
+
+    fn to_parsed(&self) -> Option<ParsedConstraint> {
+        Some(self.into())
+    }
+
+    fn angular_error(&self, bodies: &[&Collider]) -> Option<Vec3> {
+        let [a, b] = *bodies else { return None };
+        let max = self.max_swing_angle?;
+
+        let angle = self.swing_angle(a, b);
+
+        if angle <= max {
+            return None;
+        }
+
+        let axis_a = (a.rotation() * self.cone_axis_a).normalize();
+        let axis_b = (b.rotation() * self.cone_axis_b).normalize();
+
+        // Rotating the first body about this axis swings its cone towards the second body's axis.
+        Some(axis_a.cross(axis_b).normalize_or_zero() * (angle - max))
+    }
+}
+
+impl From<&SphericalJoint> for ParsedConstraint {
+    fn from(value: &SphericalJoint) -> Self {
+        ParsedConstraint::Spherical {
+            a: value.a.0,
+            b: value.b.0,
+            anchor_a: value.anchor_a,
+            anchor_b: value.anchor_b,
+            cone_axis_a: value.cone_axis_a,
+            cone_axis_b: value.cone_axis_b,
+            max_swing_angle: value.max_swing_angle,
+            compliance: value.compliance,
+        }
+    }
+}
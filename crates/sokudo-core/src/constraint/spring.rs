@@ -0,0 +1,101 @@
+use glam::Vec3;
+use sokudo_io::read::constraint::ParsedConstraint;
+
+use crate::collider::{Collider, ColliderId};
+
+use super::{Constraint, VelocityConstraint};
+
+/// A damped spring between the centers of mass of two colliders.
+///
+/// This behaves like a [`DistanceConstraint`](super::distance::DistanceConstraint) whose
+/// compliance is the inverse of `stiffness`, so that it takes a force of `stiffness` newtons to
+/// stretch the spring by one unit. As a [`VelocityConstraint`], it additionally removes part of
+/// the relative velocity of the two colliders along the spring.
+pub struct SpringConstraint {
+    pub a: ColliderId,
+    pub b: ColliderId,
+
+    /// The length of the spring when it is at rest.
+    pub rest_length: f32,
+    /// The force per unit of extension of the spring.
+    pub stiffness: f32,
+    /// The rate at which the relative velocity along the spring is removed, per second.
+    pub damping: f32,
+}
+
+impl Constraint for SpringConstraint {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        vec![self.a, self.b]
+    }
+
+    fn c(&self, bodies: &[&Collider]) -> f32 {
+        let [a, b] = *bodies else { return 0.0 };
+
+        (a.position - b.position).length() - self.rest_length
+    }
+
+    fn c_gradients(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b] = *bodies else { return vec![] };
+
+        let n = (a.position - b.position).normalize_or_zero();
+        vec![n, -n]
+    }
+
+    fn inverse_masses(&self, bodies: &[&Collider]) -> Vec<f32> {
+        bodies.iter().map(|body| body.inverse_mass()).collect()
+    }
+
+    /// The solver divides this by the square of the substep, giving an effective compliance of
+    /// `1 / (stiffness * h^2)`.
+    #[inline]
+    fn compliance(&self) -> f32 {
+        self.stiffness.recip()
+    }
+
+    fn to_parsed(&self) -> Option<ParsedConstraint> {
+        Some(self.into())
+    }
+}
+
+impl VelocityConstraint for SpringConstraint {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        vec![self.a, self.b]
+    }
+
+    fn to_parsed(&self) -> Option<ParsedConstraint> {
+        Some(self.into())
+    }
+
+    fn solve(&self, mut bodies: std::vec::IntoIter<&mut Collider>, h: f32) {
+        let a = bodies.next().unwrap();
+        let b = bodies.next().unwrap();
+
+        let n = (a.position - b.position).normalize_or_zero();
+        let vn = n.dot(a.velocity - b.velocity);
+
+        let w_sum = a.inverse_mass() + b.inverse_mass();
+
+        if w_sum <= f32::EPSILON {
+            return;
+        }
+
+        let p = -n * vn * (self.damping * h).min(1.0) / w_sum;
+
+        a.apply_offset_impulse(p, Vec3::ZERO);
+        b.apply_offset_impulse(-p, Vec3::ZERO);
+    }
+}
+
+impl From<&SpringConstraint> for ParsedConstraint {
+    fn from(value: &SpringConstraint) -> Self {
+        ParsedConstraint::Spring {
+            a: value.a.0,
+            b: value.b.0,
+            rest_length: value.rest_length,
+            stiffness: value.stiffness,
+            damping: value.damping,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use glam::Vec3;
+use sokudo_io::read::constraint::ParsedConstraint;
+
+use crate::collider::{Collider, ColliderId};
+
+use super::Constraint;
+
+/// Keeps the signed volume of the tetrahedron between the centers of mass of four colliders
+/// fixed, which together with [`DistanceConstraint`](super::distance::DistanceConstraint)s along
+/// its edges makes a soft body which preserves its volume.
+///
+/// The volume is positive when `d` lies on the side of the triangle `a`, `b`, `c` which it faces
+/// counter-clockwise.
+pub struct VolumeConstraint {
+    pub a: ColliderId,
+    pub b: ColliderId,
+    pub c: ColliderId,
+    pub d: ColliderId,
+
+    /// The signed volume the tetrahedron is kept at.
+    pub rest_volume: f32,
+    pub compliance: f32,
+}
+
+impl Constraint for VolumeConstraint {
+    #[inline]
+    fn bodies(&self) -> Vec<ColliderId> {
+        vec![self.a, self.b, self.c, self.d]
+    }
+
+    fn c(&self, bodies: &[&Collider]) -> f32 {
+        let [a, b, c, d] = *bodies else { return 0.0 };
+
+        (b.position - a.position).cross(c.position - a.position).dot(d.position - a.position) / 6.0
+            - self.rest_volume
+    }
+
+    fn c_gradients(&self, bodies: &[&Collider]) -> Vec<Vec3> {
+        let [a, b, c, d] = *bodies else { return vec![] };
+        let (ab, ac, ad) = (b.position - a.position, c.position - a.position, d.position - a.position);
+
+        // Moving a vertex changes the volume by the area of the opposite face along its normal.
+        let gradient_b = ac.cross(ad) / 6.0;
+        let gradient_c = ad.cross(ab) / 6.0;
+        let gradient_d = ab.cross(ac) / 6.0;
+
+        vec![-(gradient_b + gradient_c + gradient_d), gradient_b, gradient_c, gradient_d]
+    }
+
+    fn inverse_masses(&self, bodies: &[&Collider]) -> Vec<f32> {
+        bodies.iter().map(|body| body.inverse_mass()).collect()
+    }
+
+    #[inline]
+    fn compliance(&self) -> f32 {
+        self.compliance
+    }
+
+    fn to_parsed(&self) -> Option<ParsedConstraint> {
+        Some(self.into())
+    }
+}
+
+impl From<&VolumeConstraint> for ParsedConstraint {
+    fn from(value: &VolumeConstraint) -> Self {
+        ParsedConstraint::Volume {
+            a: value.a.0,
+            b: value.b.0,
+            c: value.c.0,
+            d: value.d.0,
+            rest_volume: value.rest_volume,
+            compliance: value.compliance,
+        }
+    }
+}
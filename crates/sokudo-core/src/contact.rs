@@ -1,54 +1,555 @@
-use glam::Vec3;
+use glam::{Mat3, Vec3};
 
-use crate::{collider::{Collider, ColliderBody}, shape::AbstractShape};
+use crate::{
+    collider::{Collider, ColliderBody},
+    narrowphase::{self, manifold::{self, OrientedBox}, ConvexShape, SupportPoint, TransformedShape},
+    aabb::Aabb,
+    rigid_body::RigidBody,
+    shape::{triangle_mesh::closest_point_on_triangle, AbstractShape, Shape},
+};
 
-// TODO: generalize for multiple contact points
 #[derive(Clone, Debug, PartialEq)]
 pub struct Contact {
     /// Contact point in global coordinates relative to the first body's center of mass.
     pub anchor1: Vec3,
     /// Contact point in global coordinates relative to the second body's center of mass.
     pub anchor2: Vec3,
-    /// Contact normal in global coordinates.
+    /// Contact point in the first body's local coordinates relative to its center of mass.
+    pub local_anchor1: Vec3,
+    /// Contact point in the second body's local coordinates relative to its center of mass.
+    pub local_anchor2: Vec3,
+    /// Contact normal in global coordinates, pointing from the second body towards the first.
     pub normal: Vec3,
-    /// Penetration depth.
+    /// Penetration depth along the normal at the time the contact was generated.
     pub depth: f32,
+    /// Identifies the features of the bodies which generated this contact when it is part of a
+    /// contact manifold, so it can be matched with the same contact in later substeps.
+    pub feature: Option<u32>,
+}
+
+/// A contact of a manifold kept between substeps, so the contact with the same feature in the next
+/// substep can be generated in the same place.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PersistentContact {
+    pub feature: u32,
+    pub local_anchor1: Vec3,
+    pub local_anchor2: Vec3,
+    /// The Lagrange multiplier accumulated by the collision constraint of the contact, from which
+    /// the constraint of the same contact starts in the next substep.
+    pub lagrange: f32,
 }
 
 impl Contact {
+    /// The furthest the anchors of a contact may drift between substeps for it to be kept in
+    /// place by [`Contact::persist`].
+    pub const PERSISTENCE_DISTANCE: f32 = 0.005;
+
+    fn new(anchor1: Vec3, anchor2: Vec3, rb1: &RigidBody, rb2: &RigidBody, normal: Vec3, depth: f32) -> Contact {
+        Contact {
+            anchor1,
+            anchor2,
+            local_anchor1: rb1.rotation.inverse() * anchor1,
+            local_anchor2: rb2.rotation.inverse() * anchor2,
+            normal,
+            depth: depth.max(0.0),
+            feature: None,
+        }
+    }
+
+    /// Computes the contact between two particles, which touch when the distance between their
+    /// centers is less than the sum of their radii.
+    pub fn from_particles(
+        particle1: &Collider,
+        particle2: &Collider,
+    ) -> Option<Contact> {
+        let (ColliderBody::Particle(body1), ColliderBody::Particle(body2)) = (&particle1.body, &particle2.body) else {
+            return None;
+        };
+
+        let offset = particle1.position - particle2.position;
+        let distance = offset.length();
+        let radii = body1.radius + body2.radius;
+
+        if distance >= radii {
+            return None;
+        }
+
+        // Coincident particles have no center line, so they are pushed apart vertically.
+        let normal = if distance > f32::EPSILON { offset / distance } else { Vec3::Y };
+
+        // Particles never rotate, so their local anchors are the same as their global ones.
+        let anchor1 = -normal * body1.radius;
+        let anchor2 = normal * body2.radius;
+
+        Some(Contact {
+            anchor1,
+            anchor2,
+            local_anchor1: anchor1,
+            local_anchor2: anchor2,
+            normal,
+            depth: radii - distance,
+            feature: None,
+        })
+    }
+
+    /// Computes the contact between a particle and a rigid body, which touch when the center of
+    /// the particle is closer to the surface of the rigid body than its radius or inside it.
     pub fn from_particle_rigid_body(
         particle: &Collider,
         rb: &Collider,
     ) -> Option<Contact> {
-        let ColliderBody::Rigid(rb_body) = &rb.body else {
+        let (ColliderBody::Particle(particle_body), ColliderBody::Rigid(rb_body)) = (&particle.body, &rb.body) else {
             return None;
         };
 
-        let p_local_rb = (rb_body.rotation.inverse() * (particle.position - rb.position)) / rb_body.scale;
-        let depth = rb_body.shape.sd(p_local_rb);
+        let radius = particle_body.radius;
+        let (point, normal) = project_onto_surface(particle.position, rb, rb_body, local_radius(radius, rb_body))?;
+        let signed_distance = (particle.position - point).dot(normal);
 
-        if depth > 0.0 {
+        if signed_distance > radius {
             return None;
         }
 
-        let normal = rb_body.rotation * rb_body.shape.sd_gradient(p_local_rb);
-        let point = particle.position;
-
-        let anchor1 = Vec3::ZERO; // point - particle.position
+        let anchor1 = -normal * radius;
         let anchor2 = point - rb.position;
-        
+
         Some(Contact {
             anchor1,
             anchor2,
+            local_anchor1: anchor1,
+            local_anchor2: rb_body.rotation.inverse() * anchor2,
             normal,
-            depth: depth.abs(),
+            depth: (radius - signed_distance).max(0.0),
+            feature: None,
         })
     }
 
+    /// Computes the contacts between a particle with a radius and the surface of a rigid body
+    /// with a triangle mesh shape, which touch wherever a triangle is closer to the center of the
+    /// particle than its radius.
+    ///
+    /// Only the triangles near the particle are found through the hierarchy of the mesh. Unlike
+    /// [`Contact::from_particle_rigid_body`], the inside of the mesh is never considered, so open
+    /// meshes such as bowls collide just as closed ones do.
+    pub fn from_particle_mesh(
+        particle: &Collider,
+        rb: &Collider,
+    ) -> Vec<Contact> {
+        let (ColliderBody::Particle(particle_body), ColliderBody::Rigid(rb_body)) = (&particle.body, &rb.body) else {
+            return Vec::new();
+        };
+
+        let Shape::TriangleMesh(mesh) = &rb_body.shape else {
+            return Vec::new();
+        };
+
+        let origin = rb_body.origin(rb.position);
+        let to_global = |v: Vec3| origin + rb_body.rotation * (rb_body.scale * v);
+
+        let radius = particle_body.radius;
+        let center = (rb_body.rotation.inverse() * (particle.position - origin)) / rb_body.scale;
+        let extent = Vec3::splat(radius) / rb_body.scale.abs();
+
+        let mut contacts: Vec<Contact> = Vec::new();
+
+        for triangle in mesh.triangles_in(Aabb::new(center - extent, center + extent)) {
+            let point = closest_point_on_triangle(particle.position, triangle.map(to_global));
+            let offset = particle.position - point;
+            let distance = offset.length();
+
+            if distance >= radius || distance <= f32::EPSILON {
+                continue;
+            }
+
+            // Triangles sharing the closest edge or vertex would otherwise push the particle out
+            // of it more than once.
+            let anchor2 = point - rb.position;
+
+            if contacts.iter().any(|contact| contact.anchor2.abs_diff_eq(anchor2, 1e-5)) {
+                continue;
+            }
+
+            let normal = offset / distance;
+            let anchor1 = -normal * radius;
+
+            contacts.push(Contact {
+                anchor1,
+                anchor2,
+                local_anchor1: anchor1,
+                local_anchor2: rb_body.rotation.inverse() * anchor2,
+                normal,
+                depth: radius - distance,
+                feature: None,
+            });
+        }
+
+        contacts
+    }
+
+    /// Sweeps `particle` from its previous position to its current one against the rigid body
+    /// `rb`, returning the position at which its surface first touches that of the rigid body
+    /// along with a contact there.
+    pub fn from_particle_sweep(
+        particle: &Collider,
+        rb: &Collider,
+    ) -> Option<(Vec3, Contact)> {
+        let (ColliderBody::Particle(particle_body), ColliderBody::Rigid(rb_body)) = (&particle.body, &rb.body) else {
+            return None;
+        };
+
+        // The sweep is parameterized identically in the rigid body's local coordinates, so the
+        // time of impact can be found there directly.
+        let origin = rb_body.origin(rb.position);
+        let to_local = |p: Vec3| (rb_body.rotation.inverse() * (p - origin)) / rb_body.scale;
+        let start = to_local(particle.previous_position);
+        let end = to_local(particle.position);
+
+        let radius = particle_body.radius;
+        let toi = if radius > 0.0 {
+            sphere_cast(&rb_body.shape, start, end - start, local_radius(radius, rb_body))?
+        } else {
+            rb_body.shape.raycast(start, end - start, 1.0)?
+        };
+        let position = particle.previous_position.lerp(particle.position, toi);
+
+        let gradient = rb_body.shape.sd_gradient(start.lerp(end, toi));
+        let normal = (rb_body.rotation * (gradient / rb_body.scale)).normalize_or_zero();
+        let anchor1 = -normal * radius;
+        let anchor2 = position + anchor1 - rb.position;
+
+        Some((position, Contact {
+            anchor1,
+            anchor2,
+            local_anchor1: anchor1,
+            local_anchor2: rb_body.rotation.inverse() * anchor2,
+            normal,
+            depth: 0.0,
+            feature: None,
+        }))
+    }
+
+    /// Computes the contacts between two rigid bodies.
+    ///
+    /// Pairs of convex shapes are first collided through the [`narrowphase`], piece by piece for
+    /// compound shapes of convex parts, which rejects separated pairs and finds the deepest
+    /// contact even when no vertex penetrates, such as between two edges. Every pair then
+    /// generates a contact for every vertex of either rigid body which lies inside the other, so
+    /// resting faces are supported at more than one point. Pairs of boxes instead generate a
+    /// manifold clipped from their touching faces.
     pub fn from_rigid_bodies(
         rb1: &Collider,
         rb2: &Collider,
-    ) -> Option<Contact> {
-        None
+    ) -> Vec<Contact> {
+        let (ColliderBody::Rigid(rb1_body), ColliderBody::Rigid(rb2_body)) = (&rb1.body, &rb2.body) else {
+            return Vec::new();
+        };
+
+        if let (Shape::Cuboid(_), Shape::Cuboid(_)) = (&rb1_body.shape, &rb2_body.shape) {
+            return Contact::from_boxes(rb1, rb1_body, rb2, rb2_body);
+        }
+
+        let mut contacts = Vec::new();
+
+        if let (Some(pieces1), Some(pieces2)) = (convex_pieces(rb1, rb1_body), convex_pieces(rb2, rb2_body)) {
+            for piece1 in pieces1.iter() {
+                for piece2 in pieces2.iter() {
+                    let Some(penetration) = narrowphase::penetration(piece1, piece2) else {
+                        continue;
+                    };
+
+                    contacts.push(Contact::new(
+                        penetration.point_a - rb1.position,
+                        penetration.point_b - rb2.position,
+                        rb1_body,
+                        rb2_body,
+                        -penetration.normal,
+                        penetration.depth,
+                    ));
+                }
+            }
+
+            if contacts.is_empty() {
+                return contacts;
+            }
+        }
+
+        let origin1 = rb1_body.origin(rb1.position);
+        let origin2 = rb2_body.origin(rb2.position);
+
+        for vertex in rb1_body.vertices.iter() {
+            let point1 = origin1 + rb1_body.rotation * (rb1_body.scale * *vertex);
+
+            let Some((point2, normal)) = project_onto_surface(point1, rb2, rb2_body, 0.0) else {
+                continue;
+            };
+
+            contacts.push(Contact::new(
+                point1 - rb1.position,
+                point2 - rb2.position,
+                rb1_body,
+                rb2_body,
+                normal,
+                (point2 - point1).dot(normal),
+            ));
+        }
+
+        for vertex in rb2_body.vertices.iter() {
+            let point2 = origin2 + rb2_body.rotation * (rb2_body.scale * *vertex);
+
+            let Some((point1, normal)) = project_onto_surface(point2, rb1, rb1_body, 0.0) else {
+                continue;
+            };
+
+            contacts.push(Contact::new(
+                point1 - rb1.position,
+                point2 - rb2.position,
+                rb1_body,
+                rb2_body,
+                -normal,
+                (point1 - point2).dot(normal),
+            ));
+        }
+
+        contacts
+    }
+
+    /// Computes the contacts between two rigid bodies with cuboid shapes, which form a manifold
+    /// of up to four contacts found by [`manifold::collide_boxes`]. Each contact carries the ID of
+    /// the features it came from, so a box resting on another is supported at its corners and its
+    /// contacts can be matched between substeps.
+    fn from_boxes(
+        rb1: &Collider,
+        rb1_body: &RigidBody,
+        rb2: &Collider,
+        rb2_body: &RigidBody,
+    ) -> Vec<Contact> {
+        let box1 = OrientedBox {
+            center: rb1_body.origin(rb1.position),
+            rotation: rb1_body.rotation,
+            half_extents: rb1_body.scale.abs() / 2.0,
+        };
+        let box2 = OrientedBox {
+            center: rb2_body.origin(rb2.position),
+            rotation: rb2_body.rotation,
+            half_extents: rb2_body.scale.abs() / 2.0,
+        };
+
+        let Some(manifold) = manifold::collide_boxes(&box1, &box2) else {
+            return Vec::new();
+        };
+
+        manifold.points.iter().map(|point| Contact {
+            feature: Some(point.feature),
+            ..Contact::new(
+                point.point_a - rb1.position,
+                point.point_b - rb2.position,
+                rb1_body,
+                rb2_body,
+                -manifold.normal,
+                point.depth,
+            )
+        }).collect()
+    }
+
+    /// Keeps this contact where the contact with the same feature in the `previous` manifold of
+    /// its bodies was generated, if neither of its points has drifted further than
+    /// [`Contact::PERSISTENCE_DISTANCE`] since. Otherwise contacts resting in place would jitter
+    /// slightly as they are generated again each substep.
+    pub fn persist(&mut self, previous: &[PersistentContact], body1: &Collider, body2: &Collider) {
+        let Some(feature) = self.feature else {
+            return;
+        };
+
+        let Some(previous) = previous.iter().find(|previous| previous.feature == feature) else {
+            return;
+        };
+
+        let anchor1 = body1.rotation() * previous.local_anchor1;
+        let anchor2 = body2.rotation() * previous.local_anchor2;
+
+        if anchor1.distance(self.anchor1) > Self::PERSISTENCE_DISTANCE
+            || anchor2.distance(self.anchor2) > Self::PERSISTENCE_DISTANCE {
+            return;
+        }
+
+        self.anchor1 = anchor1;
+        self.anchor2 = anchor2;
+        self.local_anchor1 = previous.local_anchor1;
+        self.local_anchor2 = previous.local_anchor2;
+        self.depth = self.current_depth(body1, body2);
+    }
+
+    /// The part of this contact kept between substeps, if it is part of a manifold, which carries
+    /// over the Lagrange multiplier of the contact with the same feature in the `previous`
+    /// manifold.
+    pub fn persistent(&self, previous: &[PersistentContact]) -> Option<PersistentContact> {
+        let feature = self.feature?;
+        let lagrange = previous.iter()
+            .find(|previous| previous.feature == feature)
+            .map_or(0.0, |previous| previous.lagrange);
+
+        Some(PersistentContact {
+            feature,
+            local_anchor1: self.local_anchor1,
+            local_anchor2: self.local_anchor2,
+            lagrange,
+        })
+    }
+
+    /// This contact with the roles of the two bodies swapped.
+    pub fn flipped(self) -> Contact {
+        Contact {
+            anchor1: self.anchor2,
+            anchor2: self.anchor1,
+            local_anchor1: self.local_anchor2,
+            local_anchor2: self.local_anchor1,
+            normal: -self.normal,
+            depth: self.depth,
+            feature: self.feature,
+        }
+    }
+
+    /// The contact points in global coordinates relative to each body's center of mass, given
+    /// the current rotations of the bodies.
+    #[inline]
+    pub fn current_anchors(&self, body1: &Collider, body2: &Collider) -> (Vec3, Vec3) {
+        (body1.rotation() * self.local_anchor1, body2.rotation() * self.local_anchor2)
+    }
+
+    /// The penetration depth of this contact given the current positions and rotations of its
+    /// bodies, which is zero once they have separated.
+    #[inline]
+    pub fn current_depth(&self, body1: &Collider, body2: &Collider) -> f32 {
+        self.current_signed_depth(body1, body2).max(0.0)
+    }
+
+    /// The penetration depth of this contact given the current positions and rotations of its
+    /// bodies, which is the negative distance between its points once they have separated.
+    #[inline]
+    pub fn current_signed_depth(&self, body1: &Collider, body2: &Collider) -> f32 {
+        let (anchor1, anchor2) = self.current_anchors(body1, body2);
+        ((body2.position + anchor2) - (body1.position + anchor1)).dot(self.normal)
+    }
+
+    /// The signed penetration depth of this contact given the positions and rotations of its
+    /// bodies at the start of the substep, before they were integrated.
+    pub fn previous_signed_depth(&self, body1: &Collider, body2: &Collider) -> f32 {
+        let point1 = body1.previous_position + body1.previous_rotation() * self.local_anchor1;
+        let point2 = body2.previous_position + body2.previous_rotation() * self.local_anchor2;
+
+        (point2 - point1).dot(self.normal)
+    }
+
+    /// The relative displacement of the contact points perpendicular to the normal since the
+    /// start of the substep.
+    pub fn tangential_displacement(&self, body1: &Collider, body2: &Collider) -> Vec3 {
+        let delta1 = (body1.position + body1.rotation() * self.local_anchor1)
+            - (body1.previous_position + body1.previous_rotation() * self.local_anchor1);
+        let delta2 = (body2.position + body2.rotation() * self.local_anchor2)
+            - (body2.previous_position + body2.previous_rotation() * self.local_anchor2);
+
+        let delta = delta1 - delta2;
+        delta - self.normal * delta.dot(self.normal)
+    }
+}
+
+/// Projects a `point` in global coordinates which lies inside the rigid body `rb`, or within
+/// `margin` of its surface in its local coordinates, onto its surface, returning the projected
+/// point and the outward surface normal there.
+fn project_onto_surface(point: Vec3, rb: &Collider, rb_body: &RigidBody, margin: f32) -> Option<(Vec3, Vec3)> {
+    let origin = rb_body.origin(rb.position);
+    let p_local_rb = (rb_body.rotation.inverse() * (point - origin)) / rb_body.scale;
+    let sd = rb_body.shape.sd(p_local_rb);
+
+    if sd > margin {
+        return None;
+    }
+
+    let gradient = rb_body.shape.sd_gradient(p_local_rb);
+    let normal = (rb_body.rotation * (gradient / rb_body.scale)).normalize_or_zero();
+
+    let surface_local_rb = p_local_rb - gradient * sd;
+    let surface = origin + rb_body.rotation * (rb_body.scale * surface_local_rb);
+
+    Some((surface, normal))
+}
+
+/// The largest distance in the local coordinates of `rb_body` which may lie within `radius` in
+/// global coordinates, along the axis it is scaled the least.
+fn local_radius(radius: f32, rb_body: &RigidBody) -> f32 {
+    radius / rb_body.scale.abs().min_element()
+}
+
+/// Sphere traces the segment `origin + t * direction` for `t` within `0..=1` against `shape`,
+/// returning the first `t` at which the segment comes within `radius` of its surface, or `None`
+/// if it never does or already starts there.
+fn sphere_cast(shape: &Shape, origin: Vec3, direction: Vec3, radius: f32) -> Option<f32> {
+    const MAX_STEPS: usize = 64;
+    const SURFACE_EPSILON: f32 = 1e-4;
+
+    let speed = direction.length();
+
+    if speed <= f32::EPSILON || shape.sd(origin) <= radius {
+        return None;
+    }
+
+    let mut t = 0.0;
+
+    for _ in 0..MAX_STEPS {
+        let sd = shape.sd(origin + t * direction) - radius;
+
+        if sd <= SURFACE_EPSILON {
+            return Some(t);
+        }
+
+        t += sd / speed;
+
+        if t > 1.0 {
+            return None;
+        }
+    }
+
+    None
+}
+
+/// A convex piece of a rigid body, which is either the whole body or a part of its compound
+/// shape.
+enum ConvexPiece<'a> {
+    Body(ConvexShape<'a>),
+    Part(TransformedShape<'a>),
+}
+
+impl SupportPoint for ConvexPiece<'_> {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        match self {
+            ConvexPiece::Body(shape) => shape.support(direction),
+            ConvexPiece::Part(shape) => shape.support(direction),
+        }
+    }
+}
+
+/// The convex pieces of a rigid body in global coordinates, which is the body itself for convex
+/// shapes and every part of a compound shape, or `None` if any piece is not convex.
+fn convex_pieces<'a>(collider: &Collider, body: &'a RigidBody) -> Option<Vec<ConvexPiece<'a>>> {
+    let position = body.origin(collider.position);
+
+    match &body.shape {
+        Shape::Compound(compound) => {
+            let linear = Mat3::from_quat(body.rotation) * Mat3::from_diagonal(body.scale);
+
+            compound.parts.iter().map(|(transform, shape)| {
+                shape.support(Vec3::X).map(|_| ConvexPiece::Part(TransformedShape {
+                    shape,
+                    position: position + linear * transform.translate,
+                    linear: linear * Mat3::from_quat(transform.rotate) * Mat3::from_diagonal(transform.scale),
+                }))
+            }).collect()
+        },
+        shape => shape.support(Vec3::X).map(|_| vec![ConvexPiece::Body(ConvexShape {
+            shape,
+            position,
+            rotation: body.rotation,
+            scale: body.scale,
+        })]),
     }
 }
@@ -0,0 +1,168 @@
+use std::collections::VecDeque;
+
+use glam::Vec3;
+use sokudo_io::{read::{defaults::DefaultOptions, emitter::ParsedEmitter}, write::snapshot::SnapshotEmitter};
+
+use crate::{collider::{Collider, ColliderBody, ColliderId}, particle::Particle};
+
+/// A source which spawns particles into a world at a steady rate and removes each of them once
+/// it has existed for its lifetime.
+///
+/// The initial velocities are drawn from a generator seeded by the world file, so the same world
+/// always spawns the same particles.
+#[derive(Debug, Clone)]
+pub struct Emitter {
+    /// The position every particle is spawned at.
+    pub position: Vec3,
+    /// The number of particles spawned per second.
+    pub rate: f32,
+    /// The mean initial velocity of the particles.
+    pub velocity: Vec3,
+    /// The largest amount by which each component of the initial velocity of a particle differs
+    /// from `velocity`.
+    pub velocity_spread: Vec3,
+    /// The number of seconds each particle exists for.
+    pub lifetime: f32,
+    /// The radius of the particles.
+    pub radius: f32,
+    /// The mass of the particles.
+    pub mass: f32,
+    /// The largest number of particles of this emitter which exist at once.
+    pub max_particles: u32,
+
+    /// The state of the generator the initial velocities are drawn from.
+    pub random: u64,
+    /// The fraction of a particle accumulated towards the next one to be spawned.
+    pub accumulated: f32,
+    /// The particles of this emitter which still exist, oldest first, along with the time in
+    /// seconds at which each of them is removed.
+    pub particles: VecDeque<(ColliderId, f32)>,
+}
+
+impl Emitter {
+    /// The next number drawn uniformly from `-1.0..1.0`, using SplitMix64.
+    fn next_random(&mut self) -> f32 {
+        self.random = self.random.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = self.random;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^= z >> 31;
+
+        (z >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+
+    /// Advances this emitter to the step starting at `time`, returning the particles which have
+    /// outlived their lifetime and the new particles due within the step of `dt` seconds, which
+    /// are given consecutive IDs from `next_id`.
+    ///
+    /// The particles only depend on the steps this emitter has been advanced through, so replaying
+    /// them reproduces the same IDs.
+    pub fn update(&mut self, time: f32, dt: f32, next_id: &mut u32) -> (Vec<ColliderId>, Vec<Collider>) {
+        let mut expired = Vec::new();
+
+        while let Some(&(id, expiry)) = self.particles.front() {
+            if expiry > time {
+                break;
+            }
+
+            self.particles.pop_front();
+            expired.push(id);
+        }
+
+        let mut spawned = Vec::new();
+        self.accumulated += self.rate * dt;
+
+        while self.accumulated >= 1.0 {
+            self.accumulated -= 1.0;
+
+            if self.particles.len() >= self.max_particles as usize {
+                continue;
+            }
+
+            let mut particle = self.spawn();
+            particle.id = *next_id;
+            *next_id += 1;
+
+            self.particles.push_back((ColliderId(particle.id), time + self.lifetime));
+            spawned.push(particle);
+        }
+
+        (expired, spawned)
+    }
+
+    /// Creates the next particle of this emitter, without adding it to the world.
+    fn spawn(&mut self) -> Collider {
+        let spread = Vec3::new(self.next_random(), self.next_random(), self.next_random());
+
+        let particle = Particle {
+            mass: self.mass,
+            radius: self.radius,
+            static_friction: DefaultOptions::static_friction(),
+            dynamic_friction: DefaultOptions::dynamic_friction(),
+            restitution: DefaultOptions::restitution(),
+        };
+
+        let mut collider = Collider::new(ColliderBody::Particle(particle), self.position);
+        collider.velocity = self.velocity + spread * self.velocity_spread;
+        collider.previous_velocity = collider.velocity;
+        collider
+    }
+}
+
+impl From<ParsedEmitter> for Emitter {
+    fn from(value: ParsedEmitter) -> Self {
+        Emitter {
+            position: value.position,
+            rate: value.rate,
+            velocity: value.velocity,
+            velocity_spread: value.velocity_spread,
+            lifetime: value.lifetime,
+            radius: value.radius,
+            mass: value.mass,
+            max_particles: value.max_particles,
+
+            random: value.seed,
+            accumulated: 0.0,
+            particles: VecDeque::new(),
+        }
+    }
+}
+
+impl From<&Emitter> for SnapshotEmitter {
+    fn from(value: &Emitter) -> Self {
+        SnapshotEmitter {
+            position: value.position,
+            rate: value.rate,
+            velocity: value.velocity,
+            velocity_spread: value.velocity_spread,
+            lifetime: value.lifetime,
+            radius: value.radius,
+            mass: value.mass,
+            max_particles: value.max_particles,
+
+            random: value.random,
+            accumulated: value.accumulated,
+            particles: value.particles.iter().map(|&(id, expiry)| (id.0, expiry)).collect(),
+        }
+    }
+}
+
+impl From<SnapshotEmitter> for Emitter {
+    fn from(value: SnapshotEmitter) -> Self {
+        Emitter {
+            position: value.position,
+            rate: value.rate,
+            velocity: value.velocity,
+            velocity_spread: value.velocity_spread,
+            lifetime: value.lifetime,
+            radius: value.radius,
+            mass: value.mass,
+            max_particles: value.max_particles,
+
+            random: value.random,
+            accumulated: value.accumulated,
+            particles: value.particles.into_iter().map(|(id, expiry)| (ColliderId(id), expiry)).collect(),
+        }
+    }
+}
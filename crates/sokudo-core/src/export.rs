@@ -0,0 +1,569 @@
+use std::{collections::{BTreeMap, HashMap, HashSet}, f32::consts::TAU, fs, io, path};
+
+use glam::{Mat4, Quat, Vec3};
+use gltf_json::{accessor::{ComponentType, GenericComponentType, Type}, animation::{Channel, Interpolation, Property, Sampler, Target}, buffer::{self, View}, mesh::{Mode, Primitive, Semantic}, scene::UnitQuaternion, validation::{Checked, USize64}, Accessor, Animation, Buffer, Index, Mesh, Node, Root, Scene, Value};
+use sokudo_io::{read::{collider::{ParsedColliderBody, ParsedShape}, ParseError, ParsedWorld}, write::{ReadStateError, ReadWorldStateHistory}};
+use thiserror::Error;
+
+use crate::{emitter::Emitter, shape::{convex_hull::ConvexHullShape, sdf::SdfShape}};
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    /// A parse error.
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// A state reading error.
+    #[error(transparent)]
+    ReadState(#[from] ReadStateError),
+    /// An error writing the exported files.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// An error serializing the glTF document.
+    #[error(transparent)]
+    Json(#[from] gltf_json::Error),
+}
+
+/// The number of segments around the circumference of spheres, cylinders, and cones.
+const SEGMENTS: u32 = 32;
+/// The number of rings from pole to pole of spheres.
+const RINGS: u32 = 16;
+/// Half the side length of the quad used to export half-spaces.
+const HALF_SPACE_HALF_SIZE: f32 = 100.0;
+
+/// The positions of a collider over the history, sampled once per frame.
+#[derive(Default)]
+struct Samples {
+    times: Vec<f32>,
+    translations: Vec<Vec3>,
+    rotations: Vec<Quat>,
+    scales: Vec<Vec3>,
+    /// Whether the last sample hides the collider, which is scaled to zero while it is not in the
+    /// world.
+    hidden: bool,
+}
+
+impl Samples {
+    fn push(&mut self, time: f32, translation: Vec3, rotation: Quat, scale: Vec3) {
+        self.times.push(time);
+        self.translations.push(translation);
+        self.rotations.push(rotation);
+        self.scales.push(scale);
+        self.hidden = scale == Vec3::ZERO;
+    }
+
+    /// Hides the collider from `time` on, at its last position.
+    fn hide(&mut self, time: f32) {
+        let (Some(&translation), Some(&rotation)) = (self.translations.last(), self.rotations.last()) else {
+            return;
+        };
+
+        self.push(time, translation, rotation, Vec3::ZERO);
+    }
+}
+
+/// Exports the history at `history_path` of the world at `world_path` to a glTF file at
+/// `output_path`, with one animated node per collider, including the particles spawned by
+/// emitters.
+///
+/// The binary data is written next to `output_path`, with the same name and a `.bin` extension.
+/// Colliders are scaled to zero while they are not in the world, before they are spawned and once
+/// they are removed. Scale is only animated if at least one collider changes scale over the
+/// history.
+pub fn export_gltf<P>(world_path: P, history_path: P, output_path: P) -> Result<(), ExportError>
+where
+    P: AsRef<path::Path>,
+{
+    let world = ParsedWorld::read(world_path)?;
+    let history = ReadWorldStateHistory::open(history_path)?;
+
+    let mut samples: HashMap<u32, Samples> = HashMap::new();
+
+    for step in 0..history.len() {
+        let state = history.try_get(step)?;
+        let time = step as f32 * history.dt();
+        let mut present = HashSet::new();
+
+        for collider in state.colliders {
+            let transform = collider.transform;
+            let samples = samples.entry(collider.id).or_insert_with(|| {
+                let mut samples = Samples::default();
+
+                // Colliders spawned part way through stay hidden until the frame they appear in.
+                if step > 0 {
+                    samples.push((step - 1) as f32 * history.dt(), transform.translate, transform.rotate, Vec3::ZERO);
+                }
+
+                samples
+            });
+
+            samples.push(time, transform.translate, transform.rotate, transform.scale);
+            present.insert(collider.id);
+        }
+
+        for (id, samples) in samples.iter_mut() {
+            if !samples.hidden && !present.contains(id) {
+                samples.hide(time);
+            }
+        }
+    }
+
+    // Each node is given a mesh, along with the position it is placed at if it is never sampled.
+    let mut meshes = Vec::new();
+    let mut bodies = Vec::new();
+
+    for collider in world.colliders.iter() {
+        bodies.push((collider.id, meshes.len(), collider.position));
+        meshes.push(match &collider.body {
+            ParsedColliderBody::Particle(particle) => particle_triangles(particle.radius),
+            ParsedColliderBody::RigidBody(rb) => shape_triangles(&rb.shape),
+        });
+    }
+
+    // The particles spawned by emitters only depend on the step, so replaying the emitters over
+    // the history finds the IDs of all of them, which share the mesh of their emitter.
+    let mut emitters: Vec<_> = world.emitters.iter().map(|&emitter| Emitter::from(emitter)).collect();
+    let emitter_meshes: Vec<_> = emitters.iter()
+        .map(|emitter| {
+            meshes.push(particle_triangles(emitter.radius));
+            meshes.len() - 1
+        })
+        .collect();
+    let mut next_id = world.colliders.len() as u32;
+
+    // The world may have been simulated with a different time step than its file specifies.
+    for step in 0..history.len() {
+        let time = step as f32 * history.dt();
+
+        for (emitter, &mesh) in emitters.iter_mut().zip(emitter_meshes.iter()) {
+            let (_, spawned) = emitter.update(time, history.dt(), &mut next_id);
+            bodies.extend(spawned.into_iter().map(|particle| (particle.id, mesh, particle.position)));
+        }
+    }
+
+    let animate_scale = samples.values().any(|samples| samples.scales.windows(2).any(|w| w[0] != w[1]));
+
+    let mut root = Root::default();
+    let mut data = Vec::new();
+    let mut nodes = Vec::new();
+    let mut animation = Animation {
+        extensions: None,
+        extras: Default::default(),
+        channels: Vec::new(),
+        name: Some(String::from("history")),
+        samplers: Vec::new(),
+    };
+
+    let meshes: Vec<_> = meshes.into_iter().map(|(vertices, indices)| {
+        let positions: Vec<f32> = vertices.iter().flat_map(|v| v.to_array()).collect();
+        let indices: Vec<u32> = indices.into_iter().flatten().collect();
+
+        let position_accessor = push_accessor(
+            &mut root, &mut data, float_bytes(&positions), vertices.len(),
+            ComponentType::F32, Type::Vec3, Some(bounds(&positions, 3)), Some(buffer::Target::ArrayBuffer),
+        );
+        let index_accessor = push_accessor(
+            &mut root, &mut data, index_bytes(&indices), indices.len(),
+            ComponentType::U32, Type::Scalar, None, Some(buffer::Target::ElementArrayBuffer),
+        );
+
+        root.push(Mesh {
+            extensions: None,
+            extras: Default::default(),
+            name: None,
+            primitives: vec![Primitive {
+                attributes: BTreeMap::from([(Checked::Valid(Semantic::Positions), position_accessor)]),
+                extensions: None,
+                extras: Default::default(),
+                indices: Some(index_accessor),
+                material: None,
+                mode: Checked::Valid(Mode::Triangles),
+                targets: None,
+            }],
+            weights: None,
+        })
+    }).collect();
+
+    for (id, mesh, position) in bodies {
+        let samples = samples.remove(&id).unwrap_or_default();
+
+        let node = root.push(Node {
+            camera: None,
+            children: None,
+            extensions: None,
+            extras: Default::default(),
+            matrix: None,
+            mesh: Some(meshes[mesh]),
+            name: Some(format!("collider {id}")),
+            rotation: samples.rotations.first().map(|r| UnitQuaternion(r.to_array())),
+            scale: samples.scales.first().map(|s| s.to_array()),
+            translation: Some(samples.translations.first().copied().unwrap_or(position).to_array()),
+            skin: None,
+            weights: None,
+        });
+
+        nodes.push(node);
+
+        if samples.times.is_empty() {
+            continue;
+        }
+
+        let input = push_accessor(
+            &mut root, &mut data, float_bytes(&samples.times), samples.times.len(),
+            ComponentType::F32, Type::Scalar, Some(bounds(&samples.times, 1)), None,
+        );
+
+        let translations: Vec<f32> = samples.translations.iter().flat_map(|v| v.to_array()).collect();
+        let rotations: Vec<f32> = samples.rotations.iter().flat_map(|r| r.to_array()).collect();
+        let scales: Vec<f32> = samples.scales.iter().flat_map(|v| v.to_array()).collect();
+
+        let mut channels = vec![(Property::Translation, Type::Vec3, translations), (Property::Rotation, Type::Vec4, rotations)];
+
+        if animate_scale {
+            channels.push((Property::Scale, Type::Vec3, scales));
+        }
+
+        for (property, type_, values) in channels {
+            let output = push_accessor(
+                &mut root, &mut data, float_bytes(&values), samples.times.len(),
+                ComponentType::F32, type_, None, None,
+            );
+
+            let sampler = Index::push(&mut animation.samplers, Sampler {
+                extensions: None,
+                extras: Default::default(),
+                input,
+                interpolation: Checked::Valid(Interpolation::Linear),
+                output,
+            });
+
+            animation.channels.push(Channel {
+                sampler,
+                target: Target {
+                    extensions: None,
+                    extras: Default::default(),
+                    node,
+                    path: Checked::Valid(property),
+                },
+                extensions: None,
+                extras: Default::default(),
+            });
+        }
+    }
+
+    if !animation.channels.is_empty() {
+        root.push(animation);
+    }
+
+    let scene = root.push(Scene {
+        extensions: None,
+        extras: Default::default(),
+        name: None,
+        nodes,
+    });
+    root.scene = Some(scene);
+
+    let output_path = output_path.as_ref();
+    let buffer_path = output_path.with_extension("bin");
+
+    root.push(Buffer {
+        byte_length: USize64::from(data.len()),
+        name: None,
+        uri: buffer_path.file_name().map(|name| name.to_string_lossy().into_owned()),
+        extensions: None,
+        extras: Default::default(),
+    });
+
+    fs::write(&buffer_path, &data)?;
+    fs::write(output_path, root.to_vec_pretty()?)?;
+
+    Ok(())
+}
+
+/// Appends `bytes` to `data` in a new view of the only buffer, returning an accessor to it.
+#[allow(clippy::too_many_arguments)]
+fn push_accessor(
+    root: &mut Root,
+    data: &mut Vec<u8>,
+    bytes: Vec<u8>,
+    count: usize,
+    component_type: ComponentType,
+    type_: Type,
+    bounds: Option<(Value, Value)>,
+    target: Option<buffer::Target>,
+) -> Index<Accessor> {
+    let view = root.push(View {
+        buffer: Index::new(0),
+        byte_length: USize64::from(bytes.len()),
+        byte_offset: Some(USize64::from(data.len())),
+        byte_stride: None,
+        name: None,
+        target: target.map(Checked::Valid),
+        extensions: None,
+        extras: Default::default(),
+    });
+
+    data.extend(bytes);
+
+    let (min, max) = bounds.unzip();
+
+    root.push(Accessor {
+        buffer_view: Some(view),
+        byte_offset: None,
+        count: USize64::from(count),
+        component_type: Checked::Valid(GenericComponentType(component_type)),
+        extensions: None,
+        extras: Default::default(),
+        type_: Checked::Valid(type_),
+        min,
+        max,
+        name: None,
+        normalized: false,
+        sparse: None,
+    })
+}
+
+/// The little-endian bytes of `values`, which glTF stores components as.
+fn float_bytes(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+/// The little-endian bytes of `values`, which glTF stores components as.
+fn index_bytes(values: &[u32]) -> Vec<u8> {
+    values.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+/// The componentwise minimum and maximum of `values`, which hold `components` values per element.
+fn bounds(values: &[f32], components: usize) -> (Value, Value) {
+    let mut min = vec![f32::INFINITY; components];
+    let mut max = vec![f32::NEG_INFINITY; components];
+
+    for element in values.chunks(components) {
+        for (i, &value) in element.iter().enumerate() {
+            min[i] = min[i].min(value);
+            max[i] = max[i].max(value);
+        }
+    }
+
+    (Value::from(min), Value::from(max))
+}
+
+/// Triangulates a particle of `radius`.
+fn particle_triangles(radius: f32) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+    // Point particles are still given a small size to keep them visible.
+    let radius = if radius > 0.0 { radius } else { 0.1 };
+    let (vertices, indices) = sphere();
+
+    (vertices.into_iter().map(|v| v * radius).collect(), indices)
+}
+
+/// Triangulates a shape in its local coordinates.
+fn shape_triangles(shape: &ParsedShape) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+    match shape {
+        ParsedShape::Cuboid => cuboid(),
+        ParsedShape::Cylinder { radius, half_height } => cylinder(*radius, *half_height),
+        ParsedShape::Cone { radius, height } => cone(*radius, *height),
+        ParsedShape::Torus { major_radius, minor_radius } => torus(*major_radius, *minor_radius),
+        ParsedShape::Mesh { mesh, .. } => (mesh.vertices.clone(), mesh.indices.clone()),
+        ParsedShape::ConvexHull { points } => {
+            let hull = ConvexHullShape::new(points);
+            (hull.vertices, hull.indices)
+        },
+        ParsedShape::Sdf { grid, .. } => SdfShape::from((**grid).clone()).triangulate(glam::UVec3::ONE),
+        ParsedShape::HalfSpace { normal } => half_space(*normal),
+        ParsedShape::Compound { parts } => {
+            let mut vertices = Vec::new();
+            let mut indices = Vec::new();
+
+            for part in parts.iter() {
+                let transform = Mat4::from_scale_rotation_translation(
+                    part.transform.scale,
+                    part.transform.rotate,
+                    part.transform.translate,
+                );
+
+                let (part_vertices, part_indices) = shape_triangles(&part.shape);
+                let offset = vertices.len() as u32;
+
+                vertices.extend(part_vertices.into_iter().map(|v| transform.transform_point3(v)));
+                indices.extend(part_indices.into_iter().map(|tri| tri.map(|i| i + offset)));
+            }
+
+            (vertices, indices)
+        },
+    }
+}
+
+/// Flips the triangles which face towards `center`, so all of them face away from it.
+fn orient_away_from(vertices: &[Vec3], indices: &mut [[u32; 3]], center: Vec3) {
+    for tri in indices.iter_mut() {
+        let [a, b, c] = tri.map(|i| vertices[i as usize]);
+        let normal = (b - a).cross(c - a);
+
+        if normal.dot((a + b + c) / 3.0 - center) < 0.0 {
+            tri.swap(1, 2);
+        }
+    }
+}
+
+/// A unit cube centered at the origin.
+fn cuboid() -> (Vec<Vec3>, Vec<[u32; 3]>) {
+    let vertices: Vec<Vec3> = (0..8)
+        .map(|i| Vec3::new((i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32) - 0.5)
+        .collect();
+
+    let mut indices = vec![
+        [0, 2, 6], [0, 6, 4], [1, 3, 7], [1, 7, 5],
+        [0, 1, 5], [0, 5, 4], [2, 3, 7], [2, 7, 6],
+        [0, 1, 3], [0, 3, 2], [4, 5, 7], [4, 7, 6],
+    ];
+
+    orient_away_from(&vertices, &mut indices, Vec3::ZERO);
+    (vertices, indices)
+}
+
+/// A unit sphere centered at the origin.
+fn sphere() -> (Vec<Vec3>, Vec<[u32; 3]>) {
+    let mut vertices = vec![Vec3::Y];
+
+    for ring in 1..RINGS {
+        let (sin_theta, cos_theta) = (ring as f32 / RINGS as f32 * TAU / 2.0).sin_cos();
+
+        for segment in 0..SEGMENTS {
+            let (sin_phi, cos_phi) = (segment as f32 / SEGMENTS as f32 * TAU).sin_cos();
+            vertices.push(Vec3::new(sin_theta * cos_phi, cos_theta, sin_theta * sin_phi));
+        }
+    }
+
+    vertices.push(Vec3::NEG_Y);
+
+    let ring_vertex = |ring: u32, segment: u32| 1 + (ring - 1) * SEGMENTS + segment % SEGMENTS;
+    let bottom = vertices.len() as u32 - 1;
+    let mut indices = Vec::new();
+
+    for segment in 0..SEGMENTS {
+        indices.push([0, ring_vertex(1, segment), ring_vertex(1, segment + 1)]);
+        indices.push([bottom, ring_vertex(RINGS - 1, segment), ring_vertex(RINGS - 1, segment + 1)]);
+
+        for ring in 1..RINGS - 1 {
+            let [a, b] = [ring_vertex(ring, segment), ring_vertex(ring, segment + 1)];
+            let [c, d] = [ring_vertex(ring + 1, segment), ring_vertex(ring + 1, segment + 1)];
+
+            indices.push([a, b, d]);
+            indices.push([a, d, c]);
+        }
+    }
+
+    orient_away_from(&vertices, &mut indices, Vec3::ZERO);
+    (vertices, indices)
+}
+
+/// A ring of vertices at the height `y` around the y-axis.
+fn ring(radius: f32, y: f32) -> impl Iterator<Item = Vec3> {
+    (0..SEGMENTS).map(move |segment| {
+        let (sin, cos) = (segment as f32 / SEGMENTS as f32 * TAU).sin_cos();
+        Vec3::new(radius * cos, y, radius * sin)
+    })
+}
+
+/// A cylinder centered at the origin along the y-axis.
+fn cylinder(radius: f32, half_height: f32) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+    let mut vertices: Vec<Vec3> = ring(radius, -half_height).chain(ring(radius, half_height)).collect();
+    vertices.push(Vec3::new(0.0, -half_height, 0.0));
+    vertices.push(Vec3::new(0.0, half_height, 0.0));
+
+    let [bottom, top] = [2 * SEGMENTS, 2 * SEGMENTS + 1];
+    let mut indices = Vec::new();
+
+    for a in 0..SEGMENTS {
+        let b = (a + 1) % SEGMENTS;
+
+        indices.push([a, b, b + SEGMENTS]);
+        indices.push([a, b + SEGMENTS, a + SEGMENTS]);
+        indices.push([bottom, a, b]);
+        indices.push([top, a + SEGMENTS, b + SEGMENTS]);
+    }
+
+    orient_away_from(&vertices, &mut indices, Vec3::ZERO);
+    (vertices, indices)
+}
+
+/// A cone along the y-axis, centered at its centroid a quarter of the way up from its base.
+fn cone(radius: f32, height: f32) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+    let mut vertices: Vec<Vec3> = ring(radius, -0.25 * height).collect();
+    vertices.push(Vec3::new(0.0, -0.25 * height, 0.0));
+    vertices.push(Vec3::new(0.0, 0.75 * height, 0.0));
+
+    let [base, apex] = [SEGMENTS, SEGMENTS + 1];
+    let mut indices = Vec::new();
+
+    for a in 0..SEGMENTS {
+        let b = (a + 1) % SEGMENTS;
+
+        indices.push([base, a, b]);
+        indices.push([apex, a, b]);
+    }
+
+    orient_away_from(&vertices, &mut indices, Vec3::ZERO);
+    (vertices, indices)
+}
+
+/// A torus centered at the origin whose tube circles the y-axis.
+fn torus(major_radius: f32, minor_radius: f32) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+    let tube_segments = SEGMENTS / 2;
+    let mut vertices = Vec::new();
+
+    for segment in 0..SEGMENTS {
+        let (sin_u, cos_u) = (segment as f32 / SEGMENTS as f32 * TAU).sin_cos();
+
+        for tube_segment in 0..tube_segments {
+            let (sin_v, cos_v) = (tube_segment as f32 / tube_segments as f32 * TAU).sin_cos();
+            let radius = major_radius + minor_radius * cos_v;
+
+            vertices.push(Vec3::new(radius * cos_u, minor_radius * sin_v, radius * sin_u));
+        }
+    }
+
+    let vertex = |segment: u32, tube_segment: u32| (segment % SEGMENTS) * tube_segments + tube_segment % tube_segments;
+    let mut indices = Vec::new();
+
+    for segment in 0..SEGMENTS {
+        for tube_segment in 0..tube_segments {
+            let [a, b] = [vertex(segment, tube_segment), vertex(segment + 1, tube_segment)];
+            let [c, d] = [vertex(segment, tube_segment + 1), vertex(segment + 1, tube_segment + 1)];
+
+            indices.push([a, b, d]);
+            indices.push([a, d, c]);
+        }
+    }
+
+    // Each triangle faces away from the middle of the tube it wraps around.
+    for tri in indices.iter_mut() {
+        let [a, b, c] = tri.map(|i| vertices[i as usize]);
+        let centroid = (a + b + c) / 3.0;
+        let spine = major_radius * Vec3::new(centroid.x, 0.0, centroid.z).normalize_or_zero();
+
+        if (b - a).cross(c - a).dot(centroid - spine) < 0.0 {
+            tri.swap(1, 2);
+        }
+    }
+
+    (vertices, indices)
+}
+
+/// A large quad through the origin facing along `normal`.
+fn half_space(normal: Vec3) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+    let normal = normal.try_normalize().unwrap_or(Vec3::Y);
+    let (u, v) = normal.any_orthonormal_pair();
+
+    let vertices = vec![
+        (-u - v) * HALF_SPACE_HALF_SIZE,
+        (u - v) * HALF_SPACE_HALF_SIZE,
+        (u + v) * HALF_SPACE_HALF_SIZE,
+        (-u + v) * HALF_SPACE_HALF_SIZE,
+    ];
+
+    let mut indices = vec![[0, 1, 2], [0, 2, 3]];
+
+    orient_away_from(&vertices, &mut indices, -normal);
+    (vertices, indices)
+}
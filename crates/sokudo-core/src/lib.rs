@@ -1,10 +1,18 @@
 pub mod run;
-mod world;
-mod transform;
-mod shape;
-mod collider;
-mod rigid_body;
-mod particle;
-mod constraint;
+pub mod world;
+pub mod transform;
+pub mod shape;
+pub mod collider;
+pub mod rigid_body;
+pub mod particle;
+pub mod constraint;
 mod contact;
+pub mod aabb;
+pub mod bvh;
+pub mod material;
+pub mod water;
+pub mod emitter;
+pub mod export;
+mod broadphase;
+pub mod narrowphase;
 mod math;
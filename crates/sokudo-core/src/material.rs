@@ -0,0 +1,51 @@
+use sokudo_io::read::material::ParsedMaterialCombine;
+
+/// How the material coefficients of two colliders are combined into the coefficient of a contact
+/// between them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MaterialCombine {
+    /// The smaller of the two coefficients.
+    Min,
+    /// The larger of the two coefficients.
+    Max,
+    /// The product of the two coefficients.
+    Multiply,
+    /// The mean of the two coefficients.
+    #[default]
+    Average,
+}
+
+impl MaterialCombine {
+    /// Combines the coefficients `a` and `b` of two colliders.
+    #[inline]
+    pub fn combine(self, a: f32, b: f32) -> f32 {
+        match self {
+            MaterialCombine::Min => a.min(b),
+            MaterialCombine::Max => a.max(b),
+            MaterialCombine::Multiply => a * b,
+            MaterialCombine::Average => 0.5 * (a + b),
+        }
+    }
+}
+
+impl From<ParsedMaterialCombine> for MaterialCombine {
+    fn from(value: ParsedMaterialCombine) -> Self {
+        match value {
+            ParsedMaterialCombine::Min => MaterialCombine::Min,
+            ParsedMaterialCombine::Max => MaterialCombine::Max,
+            ParsedMaterialCombine::Multiply => MaterialCombine::Multiply,
+            ParsedMaterialCombine::Average => MaterialCombine::Average,
+        }
+    }
+}
+
+impl From<MaterialCombine> for ParsedMaterialCombine {
+    fn from(value: MaterialCombine) -> Self {
+        match value {
+            MaterialCombine::Min => ParsedMaterialCombine::Min,
+            MaterialCombine::Max => ParsedMaterialCombine::Max,
+            MaterialCombine::Multiply => ParsedMaterialCombine::Multiply,
+            MaterialCombine::Average => ParsedMaterialCombine::Average,
+        }
+    }
+}
@@ -14,3 +14,8 @@ pub fn skew_symmetric_mat3(v: Vec3) -> Mat3 {
         v.y, -v.x, 0.0,
     ])
 }
+
+/// Computes the outer product `a * b^T` of two vectors.
+pub fn outer_product(a: Vec3, b: Vec3) -> Mat3 {
+    Mat3::from_cols(a * b.x, a * b.y, a * b.z)
+}
@@ -0,0 +1,157 @@
+use glam::Vec3;
+
+use super::{manifold::OrientedBox, penetration, Penetration, SupportPoint};
+
+/// A capsule in global coordinates, made up of every point within `radius` of the segment from
+/// `start` to `end`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Capsule {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub radius: f32,
+}
+
+impl SupportPoint for Capsule {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let end = if (self.end - self.start).dot(direction) > 0.0 { self.end } else { self.start };
+        end + self.radius * direction.normalize_or_zero()
+    }
+}
+
+/// The number of golden-section iterations used by [`collide_capsule_box`] to find the point of
+/// the segment of the capsule closest to the box, which narrows it down to within `0.618^n` of
+/// the length of the segment.
+const CLOSEST_POINT_ITERATIONS: usize = 40;
+
+/// Lengths below which a segment is treated as a point.
+const EPSILON: f32 = 1e-12;
+
+/// The closest points between the segment from `start_a` to `end_a` and the segment from
+/// `start_b` to `end_b`, on each segment respectively.
+pub fn closest_points_on_segments(start_a: Vec3, end_a: Vec3, start_b: Vec3, end_b: Vec3) -> (Vec3, Vec3) {
+    let d_a = end_a - start_a;
+    let d_b = end_b - start_b;
+    let r = start_a - start_b;
+
+    let length_a = d_a.length_squared();
+    let length_b = d_b.length_squared();
+    let f = d_b.dot(r);
+
+    let (s, t) = if length_a <= EPSILON && length_b <= EPSILON {
+        (0.0, 0.0)
+    } else if length_a <= EPSILON {
+        (0.0, (f / length_b).clamp(0.0, 1.0))
+    } else {
+        let c = d_a.dot(r);
+
+        if length_b <= EPSILON {
+            ((-c / length_a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = d_a.dot(d_b);
+            let denominator = length_a * length_b - b * b;
+
+            // Parallel segments have no unique closest points, so any point of `a` will do.
+            let s = if denominator > EPSILON { ((b * f - c * length_b) / denominator).clamp(0.0, 1.0) } else { 0.0 };
+            let t = (b * s + f) / length_b;
+
+            // Clamping `t` moves the closest point of `b` to one of its ends, so the closest point
+            // of `a` to that end is found again.
+            if t < 0.0 {
+                ((-c / length_a).clamp(0.0, 1.0), 0.0)
+            } else if t > 1.0 {
+                (((b - c) / length_a).clamp(0.0, 1.0), 1.0)
+            } else {
+                (s, t)
+            }
+        }
+    };
+
+    (start_a + d_a * s, start_b + d_b * t)
+}
+
+/// Computes the penetration of two capsules from the closest points of their segments, as the
+/// penetration of two spheres centered there, or `None` if they do not overlap.
+pub fn collide_capsules(a: &Capsule, b: &Capsule) -> Option<Penetration> {
+    let (center_a, center_b) = closest_points_on_segments(a.start, a.end, b.start, b.end);
+    let offset = center_b - center_a;
+    let distance = offset.length();
+    let radius = a.radius + b.radius;
+
+    if distance >= radius {
+        return None;
+    }
+
+    // Segments which intersect have no direction between them, so they are pushed apart along
+    // the direction perpendicular to both.
+    let normal = if distance > 0.0 {
+        offset / distance
+    } else {
+        let normal = (a.end - a.start).cross(b.end - b.start).normalize_or_zero();
+
+        if normal == Vec3::ZERO {
+            (a.end - a.start).normalize_or(Vec3::X).any_orthonormal_vector()
+        } else {
+            normal
+        }
+    };
+
+    Some(Penetration {
+        normal,
+        depth: radius - distance,
+        point_a: center_a + normal * a.radius,
+        point_b: center_b - normal * b.radius,
+    })
+}
+
+/// Computes the penetration of a capsule `a` and a box `b` from the point of the segment of the
+/// capsule closest to the box, or `None` if they do not overlap.
+///
+/// If the segment passes through the box, there is no closest point to push the shapes apart
+/// from, so the penetration is found through the [`super::gjk`] and [`super::epa`] instead.
+pub fn collide_capsule_box(a: &Capsule, b: &OrientedBox) -> Option<Penetration> {
+    let closest_on_box = |point: Vec3| {
+        let local = b.rotation.inverse() * (point - b.center);
+        b.center + b.rotation * local.clamp(-b.half_extents, b.half_extents)
+    };
+
+    let segment = |s: f32| a.start.lerp(a.end, s);
+    let distance = |s: f32| segment(s).distance(closest_on_box(segment(s)));
+
+    // The distance from a convex box is convex along the segment, so a golden-section search
+    // finds its minimum.
+    let ratio = 0.5 * (5.0f32.sqrt() - 1.0);
+    let (mut low, mut high) = (0.0f32, 1.0f32);
+
+    for _ in 0..CLOSEST_POINT_ITERATIONS {
+        let left = high - ratio * (high - low);
+        let right = low + ratio * (high - low);
+
+        if distance(left) <= distance(right) {
+            high = right;
+        } else {
+            low = left;
+        }
+    }
+
+    let center = segment(0.5 * (low + high));
+    let point_b = closest_on_box(center);
+    let offset = point_b - center;
+    let distance = offset.length();
+
+    if distance >= a.radius {
+        return None;
+    }
+
+    if distance <= f32::EPSILON {
+        return penetration(a, b);
+    }
+
+    let normal = offset / distance;
+
+    Some(Penetration {
+        normal,
+        depth: a.radius - distance,
+        point_a: center + normal * a.radius,
+        point_b,
+    })
+}
@@ -0,0 +1,110 @@
+use glam::Vec3;
+
+use super::{MinkowskiPoint, Penetration, SupportPoint};
+
+const MAX_ITERATIONS: usize = 64;
+const TOLERANCE: f32 = 1e-4;
+
+struct Face {
+    indices: [usize; 3],
+    normal: Vec3,
+    distance: f32,
+}
+
+impl Face {
+    fn new(points: &[MinkowskiPoint], indices: [usize; 3]) -> Option<Face> {
+        let [a, b, c] = indices.map(|i| points[i].point);
+        let normal = (b - a).cross(c - a).try_normalize()?;
+        let distance = normal.dot(a);
+
+        // Keep the face normals pointing away from the origin.
+        if distance < 0.0 {
+            Some(Face { indices: [indices[0], indices[2], indices[1]], normal: -normal, distance: -distance })
+        } else {
+            Some(Face { indices, normal, distance })
+        }
+    }
+}
+
+/// Computes the penetration of the overlapping convex objects `a` and `b` using the Expanding
+/// Polytope Algorithm, starting from the tetrahedron found by [`super::gjk::intersect`].
+pub fn penetration(a: &impl SupportPoint, b: &impl SupportPoint, simplex: [MinkowskiPoint; 4]) -> Option<Penetration> {
+    let mut points = simplex.to_vec();
+    let mut faces: Vec<Face> = [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]]
+        .into_iter()
+        .filter_map(|indices| Face::new(&points, indices))
+        .collect();
+
+    for _ in 0..MAX_ITERATIONS {
+        let closest = faces.iter().min_by(|f1, f2| f1.distance.total_cmp(&f2.distance))?;
+        let support = MinkowskiPoint::support(a, b, closest.normal);
+
+        if support.point.dot(closest.normal) - closest.distance < TOLERANCE {
+            return Some(witness(&points, closest));
+        }
+
+        let index = points.len();
+        points.push(support);
+
+        // Remove every face visible from the new point, keeping track of the edges on the horizon.
+        let mut horizon: Vec<[usize; 2]> = Vec::new();
+        faces.retain(|face| {
+            if face.normal.dot(support.point - points[face.indices[0]].point) <= 0.0 {
+                return true;
+            }
+
+            let [i, j, k] = face.indices;
+
+            for edge in [[i, j], [j, k], [k, i]] {
+                if let Some(shared) = horizon.iter().position(|e| *e == [edge[1], edge[0]]) {
+                    horizon.swap_remove(shared);
+                } else {
+                    horizon.push(edge);
+                }
+            }
+
+            false
+        });
+
+        faces.extend(horizon.into_iter().filter_map(|[i, j]| Face::new(&points, [i, j, index])));
+    }
+
+    let closest = faces.iter().min_by(|f1, f2| f1.distance.total_cmp(&f2.distance))?;
+    Some(witness(&points, closest))
+}
+
+/// Computes the penetration described by the projection of the origin onto `face`.
+fn witness(points: &[MinkowskiPoint], face: &Face) -> Penetration {
+    let [p1, p2, p3] = face.indices.map(|i| points[i]);
+    let projection = face.normal * face.distance;
+    let [u, v, w] = barycentric(projection, p1.point, p2.point, p3.point);
+
+    Penetration {
+        normal: face.normal,
+        depth: face.distance,
+        point_a: u * p1.a + v * p2.a + w * p3.a,
+        point_b: u * p1.b + v * p2.b + w * p3.b,
+    }
+}
+
+fn barycentric(p: Vec3, a: Vec3, b: Vec3, c: Vec3) -> [f32; 3] {
+    let v0 = b - a;
+    let v1 = c - a;
+    let v2 = p - a;
+
+    let d00 = v0.dot(v0);
+    let d01 = v0.dot(v1);
+    let d11 = v1.dot(v1);
+    let d20 = v2.dot(v0);
+    let d21 = v2.dot(v1);
+    let denom = d00 * d11 - d01 * d01;
+
+    if denom.abs() <= f32::EPSILON {
+        return [1.0, 0.0, 0.0];
+    }
+
+    let v = (d11 * d20 - d01 * d21) / denom;
+    let w = (d00 * d21 - d01 * d20) / denom;
+
+    [1.0 - v - w, v, w]
+}
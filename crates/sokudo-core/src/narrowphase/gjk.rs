@@ -0,0 +1,164 @@
+use glam::Vec3;
+
+use super::{MinkowskiPoint, SupportPoint};
+
+const MAX_ITERATIONS: usize = 64;
+
+/// Determines whether the convex objects `a` and `b` overlap using the
+/// Gilbert-Johnson-Keerthi algorithm.
+///
+/// If they do, the tetrahedron enclosing the origin within their Minkowski difference is returned.
+pub fn intersect(a: &impl SupportPoint, b: &impl SupportPoint) -> Option<[MinkowskiPoint; 4]> {
+    let mut direction = Vec3::X;
+    let first = MinkowskiPoint::support(a, b, direction);
+
+    let mut simplex = vec![first];
+    direction = -first.point;
+
+    for _ in 0..MAX_ITERATIONS {
+        if direction.length_squared() <= f32::EPSILON {
+            // The origin lies on the simplex, so the objects are touching.
+            return complete_tetrahedron(a, b, simplex);
+        }
+
+        let point = MinkowskiPoint::support(a, b, direction);
+
+        if point.point.dot(direction) < 0.0 {
+            return None;
+        }
+
+        simplex.push(point);
+
+        if next_simplex(&mut simplex, &mut direction) {
+            return simplex.try_into().ok();
+        }
+    }
+
+    None
+}
+
+/// Reduces `simplex` to the feature closest to the origin and updates `direction` to point from
+/// it towards the origin, returning `true` once the simplex is a tetrahedron enclosing the
+/// origin.
+///
+/// The most recently added point is always last.
+fn next_simplex(simplex: &mut Vec<MinkowskiPoint>, direction: &mut Vec3) -> bool {
+    match simplex.len() {
+        2 => {
+            line(simplex, direction);
+            false
+        },
+        3 => {
+            triangle(simplex, direction);
+            false
+        },
+        4 => tetrahedron(simplex, direction),
+        _ => unreachable!(),
+    }
+}
+
+fn line(simplex: &mut Vec<MinkowskiPoint>, direction: &mut Vec3) {
+    let (b, a) = (simplex[0], simplex[1]);
+    let ab = b.point - a.point;
+    let ao = -a.point;
+
+    if ab.dot(ao) > 0.0 {
+        *direction = ab.cross(ao).cross(ab);
+
+        // The origin lies on the line itself.
+        if direction.length_squared() <= f32::EPSILON {
+            *direction = ab.any_orthonormal_vector();
+        }
+    } else {
+        *simplex = vec![a];
+        *direction = ao;
+    }
+}
+
+fn triangle(simplex: &mut Vec<MinkowskiPoint>, direction: &mut Vec3) {
+    let (c, b, a) = (simplex[0], simplex[1], simplex[2]);
+    let ab = b.point - a.point;
+    let ac = c.point - a.point;
+    let ao = -a.point;
+    let abc = ab.cross(ac);
+
+    if abc.cross(ac).dot(ao) > 0.0 {
+        if ac.dot(ao) > 0.0 {
+            *simplex = vec![c, a];
+            *direction = ac.cross(ao).cross(ac);
+        } else {
+            *simplex = vec![b, a];
+            line(simplex, direction);
+        }
+    } else if ab.cross(abc).dot(ao) > 0.0 {
+        *simplex = vec![b, a];
+        line(simplex, direction);
+    } else if abc.dot(ao) > 0.0 {
+        *direction = abc;
+    } else {
+        *simplex = vec![b, c, a];
+        *direction = -abc;
+    }
+}
+
+fn tetrahedron(simplex: &mut Vec<MinkowskiPoint>, direction: &mut Vec3) -> bool {
+    let (d, c, b, a) = (simplex[0], simplex[1], simplex[2], simplex[3]);
+    let ab = b.point - a.point;
+    let ac = c.point - a.point;
+    let ad = d.point - a.point;
+    let ao = -a.point;
+
+    let abc = ab.cross(ac);
+    let acd = ac.cross(ad);
+    let adb = ad.cross(ab);
+
+    if abc.dot(ao) > 0.0 {
+        *simplex = vec![c, b, a];
+        triangle(simplex, direction);
+        false
+    } else if acd.dot(ao) > 0.0 {
+        *simplex = vec![d, c, a];
+        triangle(simplex, direction);
+        false
+    } else if adb.dot(ao) > 0.0 {
+        *simplex = vec![b, d, a];
+        triangle(simplex, direction);
+        false
+    } else {
+        true
+    }
+}
+
+/// Grows a degenerate simplex containing the origin into a tetrahedron by adding support points
+/// along directions orthogonal to it.
+fn complete_tetrahedron(a: &impl SupportPoint, b: &impl SupportPoint, mut simplex: Vec<MinkowskiPoint>) -> Option<[MinkowskiPoint; 4]> {
+    let axes = [Vec3::X, Vec3::Y, Vec3::Z, Vec3::NEG_X, Vec3::NEG_Y, Vec3::NEG_Z];
+    let mut axes = axes.into_iter();
+
+    while simplex.len() < 4 {
+        let direction = match simplex.len() {
+            1 => axes.next()?,
+            2 => (simplex[1].point - simplex[0].point).any_orthonormal_vector(),
+            _ => (simplex[1].point - simplex[0].point).cross(simplex[2].point - simplex[0].point),
+        };
+
+        let mut point = MinkowskiPoint::support(a, b, direction);
+
+        if simplex.iter().any(|p| p.point.distance_squared(point.point) <= f32::EPSILON) {
+            point = MinkowskiPoint::support(a, b, -direction);
+        }
+
+        if simplex.iter().any(|p| p.point.distance_squared(point.point) <= f32::EPSILON) {
+            // The Minkowski difference is flat along this direction, so try another one.
+            if simplex.len() == 1 {
+                continue;
+            }
+
+            return None;
+        }
+
+        simplex.push(point);
+    }
+
+    simplex.try_into().ok()
+}
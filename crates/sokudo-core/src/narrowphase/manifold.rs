@@ -0,0 +1,343 @@
+use glam::{Quat, Vec3};
+
+use super::SupportPoint;
+
+/// A box placed in the world.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrientedBox {
+    pub center: Vec3,
+    pub rotation: Quat,
+    /// Half the side lengths of the box along each of its local axes.
+    pub half_extents: Vec3,
+}
+
+/// A point of contact between two boxes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ManifoldPoint {
+    /// The point on the surface of `a`, in global coordinates.
+    pub point_a: Vec3,
+    /// The point on the surface of `b`, in global coordinates.
+    pub point_b: Vec3,
+    /// The penetration depth along the normal of the manifold.
+    pub depth: f32,
+    /// Identifies the faces and edges of the boxes which generated this point, which stays the
+    /// same for as long as the boxes touch in the same way.
+    pub feature: u32,
+}
+
+/// The points of contact between two boxes touching along a face.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifold {
+    /// The direction in which `b` should be moved to separate the boxes.
+    pub normal: Vec3,
+    pub points: Vec<ManifoldPoint>,
+}
+
+/// The maximum number of points in a manifold.
+pub const MAX_POINTS: usize = 4;
+
+/// The factors by which the penetration along an axis must be smaller than along the best axis so
+/// far to replace it. Face axes of `a` are preferred over those of `b`, and both over edge axes,
+/// so the chosen axis does not flip back and forth between frames while several are similar.
+const FACE_TOLERANCE: f32 = 0.98;
+const EDGE_TOLERANCE: f32 = 0.95;
+/// The distance by which the penetration along an axis must be smaller than along the best axis
+/// so far to replace it, on top of [`FACE_TOLERANCE`] or [`EDGE_TOLERANCE`].
+const ABSOLUTE_TOLERANCE: f32 = 0.001;
+
+/// The feature ID of a contact between two edges, combined with the IDs of the edges.
+const EDGE_FEATURE: u32 = 1 << 13;
+/// The feature ID of a contact at the deepest corner of `b`, combined with the reference face.
+const CORNER_FEATURE: u32 = 1 << 14;
+
+impl OrientedBox {
+    fn axis(&self, axis: usize) -> Vec3 {
+        self.rotation * Vec3::AXES[axis]
+    }
+
+    /// The outward normal of a face, where faces `2i` and `2i + 1` are perpendicular to the
+    /// positive and negative local axis `i` respectively.
+    fn face_normal(&self, face: usize) -> Vec3 {
+        let sign = if face.is_multiple_of(2) { 1.0 } else { -1.0 };
+        sign * self.axis(face / 2)
+    }
+
+    /// The face whose outward normal is most aligned with `direction`, along with the cosine
+    /// between them.
+    fn most_aligned_face(&self, direction: Vec3) -> (usize, f32) {
+        (0..6).map(|face| (face, self.face_normal(face).dot(direction)))
+            .fold((0, f32::NEG_INFINITY), |best, face| if face.1 > best.1 { face } else { best })
+    }
+
+    /// The distance of a face from the center of the box.
+    fn face_offset(&self, face: usize) -> f32 {
+        self.face_normal(face).dot(self.center) + self.half_extents[face / 2]
+    }
+
+    /// The corner of the box furthest along `direction`.
+    fn support(&self, direction: Vec3) -> Vec3 {
+        (0..3).fold(self.center, |corner, i| {
+            let axis = self.axis(i);
+            corner + axis * self.half_extents[i].copysign(axis.dot(direction))
+        })
+    }
+
+    /// The center of the edge parallel to local axis `axis` which is furthest along `direction`,
+    /// along with an ID distinguishing it from the other edges parallel to it.
+    fn support_edge(&self, axis: usize, direction: Vec3) -> (Vec3, u32) {
+        let mut center = self.center;
+        let mut corner = 0;
+
+        for (bit, i) in [(axis + 1) % 3, (axis + 2) % 3].into_iter().enumerate() {
+            let side = self.axis(i);
+
+            if side.dot(direction) < 0.0 {
+                center -= side * self.half_extents[i];
+                corner |= 1 << bit;
+            } else {
+                center += side * self.half_extents[i];
+            }
+        }
+
+        (center, corner)
+    }
+
+    /// The corners of a face in order around it.
+    fn face_vertices(&self, face: usize) -> [Vec3; 4] {
+        let axis = face / 2;
+        let u = self.axis((axis + 1) % 3) * self.half_extents[(axis + 1) % 3];
+        let v = self.axis((axis + 2) % 3) * self.half_extents[(axis + 2) % 3];
+        let center = self.center + self.face_normal(face) * self.half_extents[axis];
+
+        [center + u + v, center - u + v, center - u - v, center + u - v]
+    }
+}
+
+impl SupportPoint for OrientedBox {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        OrientedBox::support(self, direction)
+    }
+}
+
+/// The axis along which two boxes penetrate least.
+enum SeparatingAxis {
+    /// The normal of a face of `a`, or of `b` if flipped.
+    Face { face: usize, flip: bool },
+    /// The cross product of an edge of `a` and an edge of `b` along the given local axes.
+    Edge { axis_a: usize, axis_b: usize },
+}
+
+/// Computes the contact manifold of the boxes `a` and `b`, or `None` if they do not overlap.
+///
+/// The boxes are first tested for separation along each of the fifteen candidate axes, which are
+/// the face normals of either box and the cross products of their edges. If they penetrate least
+/// along a face normal, that face is the reference face and the face of the other box most
+/// opposed to it the incident face. The incident face is clipped against the sides of the
+/// reference face, and every remaining corner below the reference face becomes a point of the
+/// manifold, keeping at most [`MAX_POINTS`] of them. If they penetrate least along the cross
+/// product of two edges, the manifold is the single pair of closest points on the edges.
+pub fn collide_boxes(a: &OrientedBox, b: &OrientedBox) -> Option<Manifold> {
+    let offset = b.center - a.center;
+
+    // The penetration along an axis, along with the axis pointing from `a` towards `b`.
+    let penetration = |axis: Vec3| {
+        let radius = |r#box: &OrientedBox| (0..3)
+            .map(|i| r#box.half_extents[i] * r#box.axis(i).dot(axis).abs())
+            .sum::<f32>();
+
+        let distance = offset.dot(axis);
+        let normal = if distance < 0.0 { -axis } else { axis };
+
+        (radius(a) + radius(b) - distance.abs(), normal)
+    };
+
+    let mut best: Option<(SeparatingAxis, f32, Vec3)> = None;
+
+    for (flip, r#box, tolerance) in [(false, a, 1.0), (true, b, FACE_TOLERANCE)] {
+        for axis in 0..3 {
+            let (depth, normal) = penetration(r#box.axis(axis));
+
+            if depth < 0.0 {
+                return None;
+            }
+
+            if best.as_ref().is_none_or(|best| depth < best.1 * tolerance - ABSOLUTE_TOLERANCE) {
+                // The reference face of `b` faces `a`, against the normal.
+                let outward = if flip { -normal } else { normal };
+                let face = 2 * axis + (r#box.axis(axis).dot(outward) < 0.0) as usize;
+
+                best = Some((SeparatingAxis::Face { face, flip }, depth, normal));
+            }
+        }
+    }
+
+    for axis_a in 0..3 {
+        for axis_b in 0..3 {
+            let axis = a.axis(axis_a).cross(b.axis(axis_b));
+
+            // Parallel edges are already covered by the face normals.
+            if axis.length_squared() < 1e-6 {
+                continue;
+            }
+
+            let (depth, normal) = penetration(axis.normalize());
+
+            if depth < 0.0 {
+                return None;
+            }
+
+            if best.as_ref().is_some_and(|best| depth < best.1 * EDGE_TOLERANCE - ABSOLUTE_TOLERANCE) {
+                best = Some((SeparatingAxis::Edge { axis_a, axis_b }, depth, normal));
+            }
+        }
+    }
+
+    let (axis, depth, normal) = best?;
+
+    let points = match axis {
+        SeparatingAxis::Face { face, flip } => {
+            let (reference, incident) = if flip { (b, a) } else { (a, b) };
+            let points = clip_faces(reference, incident, face, flip);
+
+            if points.is_empty() && depth <= ABSOLUTE_TOLERANCE {
+                // The faces only just touch, with every clipped corner above the reference face.
+                return None;
+            } else if points.is_empty() {
+                // The deepest corner of `b` lies beyond the sides of the reference face, so it is
+                // pushed out along the normal instead.
+                let point_b = b.support(-normal);
+                vec![ManifoldPoint { point_a: point_b + normal * depth, point_b, depth, feature: CORNER_FEATURE | face as u32 }]
+            } else {
+                reduce(points, normal)
+            }
+        },
+        SeparatingAxis::Edge { axis_a, axis_b } => {
+            let (point_a, corner_a) = a.support_edge(axis_a, normal);
+            let (point_b, corner_b) = b.support_edge(axis_b, -normal);
+            let (point_a, point_b) = closest_points(
+                (point_a, a.axis(axis_a), a.half_extents[axis_a]),
+                (point_b, b.axis(axis_b), b.half_extents[axis_b]),
+            );
+
+            let feature = EDGE_FEATURE | ((3 * axis_a + axis_b) as u32) << 4 | corner_a << 2 | corner_b;
+            vec![ManifoldPoint { point_a, point_b, depth, feature }]
+        },
+    };
+
+    Some(Manifold { normal, points })
+}
+
+/// Clips the `incident` box's face most opposed to face `reference_face` of the `reference` box
+/// against the sides of the reference face, returning the clipped corners which lie below it.
+/// The `reference` box is `b` if `flip` is set.
+fn clip_faces(reference: &OrientedBox, incident: &OrientedBox, reference_face: usize, flip: bool) -> Vec<ManifoldPoint> {
+    let reference_normal = reference.face_normal(reference_face);
+    let (incident_face, _) = incident.most_aligned_face(-reference_normal);
+
+    // Each corner is stored with the edge leaving it, where edges 0 to 3 are those of the
+    // incident face and 4 to 7 lie along the sides of the reference face.
+    let mut polygon: Vec<(Vec3, u32)> = incident.face_vertices(incident_face).into_iter().zip(0..).collect();
+
+    let axis = reference_face / 2;
+    let sides = [(axis + 1) % 3, (axis + 2) % 3].into_iter()
+        .flat_map(|side| [2 * side, 2 * side + 1]);
+
+    for (side, edge) in sides.zip(4..) {
+        polygon = clip(&polygon, reference.face_normal(side), reference.face_offset(side), edge);
+    }
+
+    let offset = reference.face_offset(reference_face);
+    let mut points = Vec::with_capacity(polygon.len());
+
+    for (i, &(point, leaving)) in polygon.iter().enumerate() {
+        let separation = reference_normal.dot(point) - offset;
+
+        if separation > 0.0 {
+            continue;
+        }
+
+        let entering = polygon[(i + polygon.len() - 1) % polygon.len()].1;
+        let feature = (flip as u32) << 12
+            | (reference_face as u32) << 9
+            | (incident_face as u32) << 6
+            | entering << 3
+            | leaving;
+
+        let projected = point - separation * reference_normal;
+        let (point_a, point_b) = if flip { (point, projected) } else { (projected, point) };
+
+        points.push(ManifoldPoint {
+            point_a,
+            point_b,
+            depth: -separation,
+            feature,
+        });
+    }
+
+    points
+}
+
+/// The closest points between two edges, each given by its center, direction and half length.
+fn closest_points((center_a, direction_a, extent_a): (Vec3, Vec3, f32), (center_b, direction_b, extent_b): (Vec3, Vec3, f32)) -> (Vec3, Vec3) {
+    let offset = center_a - center_b;
+    let cosine = direction_a.dot(direction_b);
+    let denominator = 1.0 - cosine * cosine;
+
+    let (a, b) = (direction_a.dot(offset), direction_b.dot(offset));
+    let s = ((cosine * b - a) / denominator).clamp(-extent_a, extent_a);
+    let t = (b + cosine * s).clamp(-extent_b, extent_b);
+
+    (center_a + direction_a * s, center_b + direction_b * t)
+}
+
+/// Clips a polygon against the plane with the given `normal` and `offset`, keeping the part
+/// behind it. Corners created where an edge leaves the plane are followed by `edge`.
+fn clip(polygon: &[(Vec3, u32)], normal: Vec3, offset: f32, edge: u32) -> Vec<(Vec3, u32)> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+
+    for (i, &(start, leaving)) in polygon.iter().enumerate() {
+        let (end, _) = polygon[(i + 1) % polygon.len()];
+        let start_distance = normal.dot(start) - offset;
+        let end_distance = normal.dot(end) - offset;
+
+        if start_distance <= 0.0 {
+            clipped.push((start, leaving));
+        }
+
+        if (start_distance <= 0.0) != (end_distance <= 0.0) {
+            let t = start_distance / (start_distance - end_distance);
+            let crossing = start.lerp(end, t);
+
+            clipped.push((crossing, if start_distance <= 0.0 { edge } else { leaving }));
+        }
+    }
+
+    clipped
+}
+
+/// Reduces a manifold to at most [`MAX_POINTS`] points, keeping the deepest point, the point
+/// furthest from it, and the points furthest to either side of the line between them, which
+/// together cover as much of the contact area as possible.
+fn reduce(points: Vec<ManifoldPoint>, normal: Vec3) -> Vec<ManifoldPoint> {
+    if points.len() <= MAX_POINTS {
+        return points;
+    }
+
+    let max_by = |key: &dyn Fn(&ManifoldPoint) -> f32| {
+        points.iter().copied().fold(None, |best: Option<ManifoldPoint>, point| match best {
+            Some(best) if key(&best) >= key(&point) => Some(best),
+            _ => Some(point),
+        }).unwrap()
+    };
+
+    let deepest = max_by(&|p| p.depth);
+    let furthest = max_by(&|p| p.point_a.distance_squared(deepest.point_a));
+
+    let side = |p: &ManifoldPoint| {
+        (furthest.point_a - deepest.point_a).cross(p.point_a - deepest.point_a).dot(normal)
+    };
+    let left = max_by(&side);
+    let right = max_by(&|p| -side(p));
+
+    vec![deepest, furthest, left, right]
+}
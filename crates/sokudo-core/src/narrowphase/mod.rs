@@ -0,0 +1,103 @@
+use glam::{Mat3, Quat, Vec3};
+
+use crate::shape::{AbstractShape, Shape};
+
+pub mod capsule;
+pub mod epa;
+pub mod gjk;
+pub mod manifold;
+
+/// A convex object which can be queried for its furthest point along a direction.
+pub trait SupportPoint {
+    /// The furthest point of this object along `direction` in global coordinates.
+    fn support(&self, direction: Vec3) -> Vec3;
+}
+
+/// A point on the Minkowski difference `a - b` of two objects, along with the points on each
+/// object it was computed from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MinkowskiPoint {
+    pub point: Vec3,
+    pub a: Vec3,
+    pub b: Vec3,
+}
+
+impl MinkowskiPoint {
+    /// The support point of the Minkowski difference `a - b` along `direction`.
+    pub fn support(a: &impl SupportPoint, b: &impl SupportPoint, direction: Vec3) -> MinkowskiPoint {
+        let a = a.support(direction);
+        let b = b.support(-direction);
+
+        MinkowskiPoint {
+            point: a - b,
+            a,
+            b,
+        }
+    }
+}
+
+/// The penetration of two overlapping objects `a` and `b`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Penetration {
+    /// The direction in which `b` should be moved to separate the objects.
+    pub normal: Vec3,
+    /// The distance `b` must be moved along the normal to separate the objects.
+    pub depth: f32,
+    /// The deepest point of `a` inside `b`, in global coordinates.
+    pub point_a: Vec3,
+    /// The deepest point of `b` inside `a`, in global coordinates.
+    pub point_b: Vec3,
+}
+
+/// Computes the penetration of the convex objects `a` and `b`, or `None` if they do not overlap.
+pub fn penetration(a: &impl SupportPoint, b: &impl SupportPoint) -> Option<Penetration> {
+    let simplex = gjk::intersect(a, b)?;
+    epa::penetration(a, b, simplex)
+}
+
+/// A convex [`Shape`] placed in the world.
+pub struct ConvexShape<'a> {
+    pub shape: &'a Shape,
+    pub position: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl SupportPoint for ConvexShape<'_> {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        // The scale is symmetric, so the support of the scaled shape is the scaled support of the
+        // shape along the scaled direction.
+        let local_direction = self.scale * (self.rotation.inverse() * direction);
+        let local = self.shape.support(local_direction).unwrap_or(Vec3::ZERO);
+
+        self.position + self.rotation * (self.scale * local)
+    }
+}
+
+/// A convex [`Shape`] placed in the world by an arbitrary linear map, such as a part of a
+/// compound shape whose rotation does not commute with the scale of its body.
+pub struct TransformedShape<'a> {
+    pub shape: &'a Shape,
+    pub position: Vec3,
+    /// The map from the local coordinates of `shape` to global coordinates.
+    pub linear: Mat3,
+}
+
+impl SupportPoint for TransformedShape<'_> {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let local = self.shape.support(self.linear.transpose() * direction).unwrap_or(Vec3::ZERO);
+        self.position + self.linear * local
+    }
+}
+
+/// A sphere in global coordinates.
+pub struct Sphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl SupportPoint for Sphere {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        self.center + self.radius * direction.normalize_or_zero()
+    }
+}
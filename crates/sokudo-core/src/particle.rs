@@ -1,9 +1,18 @@
-use sokudo_io::read::collider::ParsedParticle;
+use sokudo_io::{read::collider::ParsedParticle, write::snapshot::SnapshotParticle};
 
 #[derive(Debug)]
 pub struct Particle {
     /// The mass of this particle.
     pub mass: f32,
+    /// The radius of this particle, used when colliding with other particles.
+    pub radius: f32,
+    /// The coefficient of static friction of this particle.
+    pub static_friction: f32,
+    /// The coefficient of dynamic friction of this particle.
+    pub dynamic_friction: f32,
+    /// The coefficient of restitution of this particle, where zero is perfectly inelastic and one is
+    /// perfectly elastic.
+    pub restitution: f32,
 }
 
 impl Particle {
@@ -16,6 +25,34 @@ impl From<ParsedParticle> for Particle {
     fn from(value: ParsedParticle) -> Self {
         Particle {
             mass: value.mass,
+            radius: value.radius,
+            static_friction: value.static_friction,
+            dynamic_friction: value.dynamic_friction,
+            restitution: value.restitution,
+        }
+    }
+}
+
+impl From<&Particle> for SnapshotParticle {
+    fn from(value: &Particle) -> Self {
+        SnapshotParticle {
+            mass: value.mass,
+            radius: value.radius,
+            static_friction: value.static_friction,
+            dynamic_friction: value.dynamic_friction,
+            restitution: value.restitution,
+        }
+    }
+}
+
+impl From<SnapshotParticle> for Particle {
+    fn from(value: SnapshotParticle) -> Self {
+        Particle {
+            mass: value.mass,
+            radius: value.radius,
+            static_friction: value.static_friction,
+            dynamic_friction: value.dynamic_friction,
+            restitution: value.restitution,
         }
     }
 }
@@ -1,7 +1,7 @@
-use glam::{Mat3, Quat, UVec3, Vec3};
-use sokudo_io::read::collider::ParsedRigidBody;
+use glam::{BVec3, Mat3, Quat, UVec3, Vec3};
+use sokudo_io::{read::{collider::ParsedRigidBody, defaults::DefaultOptions}, write::snapshot::SnapshotRigidBody};
 
-use crate::{shape::{AbstractShape, Shape}, transform::Transform};
+use crate::{math::{outer_product, skew_symmetric_mat3}, shape::{AbstractShape, Shape}};
 
 #[derive(Debug)]
 pub struct RigidBody {
@@ -11,10 +11,47 @@ pub struct RigidBody {
     pub scale: Vec3,
     /// The mass of this rigid body.
     pub mass: f32,
+    /// The center of mass of this rigid body in its local coordinates, relative to the origin of
+    /// its shape.
+    pub center_of_mass: Vec3,
     /// The resolution of the vertices, in all three dimensions.
     pub vertex_resolution: UVec3,
     /// The precomputed vertices to test for intersections on this rigid body.
     pub vertices: Vec<Vec3>,
+    /// The coefficient of static friction of this rigid body.
+    pub static_friction: f32,
+    /// The coefficient of dynamic friction of this rigid body.
+    pub dynamic_friction: f32,
+    /// The coefficient of restitution of this rigid body, where zero is perfectly inelastic and one is
+    /// perfectly elastic.
+    pub restitution: f32,
+    /// The fraction of linear velocity this rigid body loses per second.
+    pub linear_damping: f32,
+    /// The fraction of angular velocity this rigid body loses per second.
+    pub angular_damping: f32,
+    /// The drag coefficient of this rigid body, which opposes its motion with a force proportional
+    /// to the square of its speed and its cross-section along its velocity.
+    pub drag_coefficient: f32,
+    /// The torque opposing the rolling of this rigid body at each contact per unit of normal
+    /// force, which acts as a distance by which the normal force is offset from the contact.
+    pub rolling_friction: f32,
+    /// The torque opposing the spinning of this rigid body about the normal of each contact per
+    /// unit of normal force.
+    pub spinning_friction: f32,
+    /// The largest linear speed this rigid body can move at, which is infinite when unlimited.
+    pub max_linear_velocity: f32,
+    /// The largest angular speed this rigid body can rotate at, which is infinite when unlimited.
+    pub max_angular_velocity: f32,
+    /// The global axes along which this rigid body cannot move.
+    pub lock_translation: BVec3,
+    /// The axes in the local coordinates of this rigid body about which it cannot rotate.
+    pub lock_rotation: BVec3,
+
+    /// The external force accumulated on this rigid body for the next step, in global coordinates.
+    pub force: Vec3,
+    /// The external torque accumulated on this rigid body for the next step, in global
+    /// coordinates.
+    pub torque: Vec3,
 
     /// The inverse of the inertia tensor of this rigid body, in local coordinates.
     pub inertia_tensor: InertiaTensor, 
@@ -26,14 +63,106 @@ pub struct RigidBody {
 }
 
 impl RigidBody {
-    pub fn compute_vertices(&mut self) {
+    /// The number of Newton iterations used to solve for the gyroscopic torque in
+    /// [`RigidBody::gyroscopic_angular_velocity`].
+    pub const GYROSCOPIC_ITERATIONS: usize = 2;
+
+    /// Creates an unscaled, unrotated rigid body of `shape` with its center of mass at the origin
+    /// of the shape, and the same material as a rigid body in a world file which omits it.
+    ///
+    /// The vertices and inertia tensor are computed once the rigid body is added to a world.
+    pub fn new(shape: Shape, mass: f32) -> RigidBody {
+        RigidBody {
+            shape,
+            scale: Vec3::ONE,
+            mass,
+            center_of_mass: Vec3::ZERO,
+            vertex_resolution: DefaultOptions::vertex_resolution(),
+            vertices: Vec::new(),
+            static_friction: DefaultOptions::static_friction(),
+            dynamic_friction: DefaultOptions::dynamic_friction(),
+            restitution: DefaultOptions::restitution(),
+            linear_damping: 0.0,
+            angular_damping: 0.0,
+            drag_coefficient: 0.0,
+            rolling_friction: 0.0,
+            spinning_friction: 0.0,
+            max_linear_velocity: DefaultOptions::max_velocity(),
+            max_angular_velocity: DefaultOptions::max_velocity(),
+            lock_translation: BVec3::FALSE,
+            lock_rotation: BVec3::FALSE,
+            force: Vec3::ZERO,
+            torque: Vec3::ZERO,
+
+            inertia_tensor: InertiaTensor::INFINITY,
+            rotation: Quat::IDENTITY,
+            previous_rotation: Quat::IDENTITY,
+            angular_velocity: Vec3::ZERO,
+            previous_angular_velocity: Vec3::ZERO,
+        }
+    }
+
+    /// Computes the vertices of this rigid body unless they were given explicitly, first clamping
+    /// its vertex resolution along each axis to `max_resolution`.
+    pub fn compute_vertices(&mut self, max_resolution: u32) {
+        self.vertex_resolution = self.vertex_resolution.min(UVec3::splat(max_resolution.max(1)));
+
         if self.vertices.is_empty() {
             self.vertices = self.shape.vertices(self.vertex_resolution);
         }
     }
 
     pub fn compute_inertia_tensor(&mut self) {
-        self.inertia_tensor = InertiaTensor::new(self.shape.moments(self.scale));
+        let moments = self.mass * self.shape.moments(self.scale);
+
+        self.inertia_tensor = if self.center_of_mass == Vec3::ZERO {
+            InertiaTensor::new(moments)
+        } else {
+            // Shift the inertia about the origin of the shape to the center of mass using the
+            // parallel axis theorem.
+            let d = self.center_of_mass;
+            let shift = self.mass * (Mat3::from_diagonal(Vec3::splat(d.length_squared())) - outer_product(d, d));
+            let tensor = Mat3::from_diagonal(moments) + shift;
+
+            if tensor.is_finite() {
+                InertiaTensor::from_tensor(tensor)
+            } else {
+                InertiaTensor::INFINITY
+            }
+        };
+    }
+
+    /// The angular velocity of this rigid body after rotating freely for `h` seconds, in global
+    /// coordinates.
+    ///
+    /// This solves the implicit Euler update `I (w' - w) + h w' × (I w') = 0` in local
+    /// coordinates with Newton's method, which keeps spinning bodies stable at large timesteps
+    /// while letting asymmetric ones precess.
+    pub fn gyroscopic_angular_velocity(&self, h: f32) -> Vec3 {
+        let inertia = self.inertia_tensor.tensor();
+
+        let initial = self.rotation.inverse() * self.angular_velocity;
+        let initial_momentum = inertia * initial;
+        let mut angular_velocity = initial;
+
+        for _ in 0..Self::GYROSCOPIC_ITERATIONS {
+            let momentum = inertia * angular_velocity;
+            let residual = momentum - initial_momentum + h * angular_velocity.cross(momentum);
+
+            let jacobian = inertia + h
+                * (skew_symmetric_mat3(angular_velocity) * inertia - skew_symmetric_mat3(momentum));
+
+            angular_velocity -= jacobian.inverse() * residual;
+        }
+
+        self.rotation * angular_velocity
+    }
+
+    /// The origin of the shape of this rigid body in global coordinates when its center of mass
+    /// is located at `position`.
+    #[inline]
+    pub fn origin(&self, position: Vec3) -> Vec3 {
+        position - self.rotation * self.center_of_mass
     }
 
     // TODO: Maybe store global inverse inertia tensor as well + update per frame?
@@ -41,6 +170,49 @@ impl RigidBody {
         self.inertia_tensor.rotate(self.rotation).inverse()
     }
 
+    /// Rotates this rigid body by the small rotation `delta`, given as a scaled axis in global
+    /// coordinates.
+    pub fn rotate(&mut self, delta: Vec3) {
+        self.rotation = (self.rotation + Quat::from_vec4(0.5 * delta.extend(0.0)) * self.rotation).normalize();
+    }
+
+    /// Applies `force` at the center of mass of this rigid body during the next step.
+    pub fn apply_force(&mut self, force: Vec3) {
+        self.force += force;
+    }
+
+    /// Applies `torque` to this rigid body during the next step.
+    pub fn apply_torque(&mut self, torque: Vec3) {
+        self.torque += torque;
+    }
+
+    /// Applies `force` at point `r` during the next step, where `r` is relative to the body's
+    /// center of mass in global coordinates.
+    pub fn apply_force_at_point(&mut self, force: Vec3, r: Vec3) {
+        self.force += force;
+        self.torque += r.cross(force);
+    }
+
+    /// The quadratic drag force on this rigid body while moving at `velocity`, which is
+    /// `-0.5 * drag_coefficient * area * |velocity| * velocity` for the cross-sectional area of its
+    /// shape along the velocity and a fluid of unit density.
+    pub fn drag_force(&self, velocity: Vec3) -> Vec3 {
+        if self.drag_coefficient == 0.0 || velocity == Vec3::ZERO {
+            return Vec3::ZERO;
+        }
+
+        let direction = self.rotation.inverse() * velocity.normalize();
+        let area = self.shape.cross_section(self.scale, direction);
+
+        -0.5 * self.drag_coefficient * area * velocity.length() * velocity
+    }
+
+    /// Clears the forces and torques accumulated on this rigid body.
+    pub fn clear_forces(&mut self) {
+        self.force = Vec3::ZERO;
+        self.torque = Vec3::ZERO;
+    }
+
     /// Compute the generalized inverse mass of this rigid body at point `r` when applying
     /// positional correction along the vector `n` where `r` is relative to the body's center of
     /// mass in global coordinates.
@@ -52,15 +224,36 @@ impl RigidBody {
 
 impl From<ParsedRigidBody> for RigidBody {
     fn from(value: ParsedRigidBody) -> Self {
+        let shape: Shape = value.shape.into();
+        let mass = match value.density {
+            Some(density) => density * shape.volume(value.transform.scale),
+            None => value.mass,
+        };
+
         RigidBody {
-            shape: value.shape.into(),
-            mass: value.mass,
+            shape,
+            mass,
+            center_of_mass: value.center_of_mass,
             vertex_resolution: if value.vertex_resolution == UVec3::ZERO {
                 UVec3::ONE
             } else {
                 value.vertex_resolution
             },
             vertices: value.vertices,
+            static_friction: value.static_friction,
+            dynamic_friction: value.dynamic_friction,
+            restitution: value.restitution,
+            linear_damping: value.linear_damping,
+            angular_damping: value.angular_damping,
+            drag_coefficient: value.drag_coefficient,
+            rolling_friction: value.rolling_friction,
+            spinning_friction: value.spinning_friction,
+            max_linear_velocity: value.max_linear_velocity,
+            max_angular_velocity: value.max_angular_velocity,
+            lock_translation: value.lock_translation,
+            lock_rotation: value.lock_rotation,
+            force: Vec3::ZERO,
+            torque: Vec3::ZERO,
 
             inertia_tensor: InertiaTensor::INFINITY,
             previous_rotation: value.transform.rotate,
@@ -72,6 +265,72 @@ impl From<ParsedRigidBody> for RigidBody {
     }
 }
 
+impl From<&RigidBody> for SnapshotRigidBody {
+    fn from(value: &RigidBody) -> Self {
+        SnapshotRigidBody {
+            shape: (&value.shape).into(),
+            scale: value.scale,
+            mass: value.mass,
+            center_of_mass: value.center_of_mass,
+            vertex_resolution: value.vertex_resolution,
+            vertices: value.vertices.clone(),
+            static_friction: value.static_friction,
+            dynamic_friction: value.dynamic_friction,
+            restitution: value.restitution,
+            linear_damping: value.linear_damping,
+            angular_damping: value.angular_damping,
+            drag_coefficient: value.drag_coefficient,
+            rolling_friction: value.rolling_friction,
+            spinning_friction: value.spinning_friction,
+            max_linear_velocity: value.max_linear_velocity,
+            max_angular_velocity: value.max_angular_velocity,
+            lock_translation: value.lock_translation,
+            lock_rotation: value.lock_rotation,
+            force: value.force,
+            torque: value.torque,
+
+            inverse_inertia_tensor: value.inertia_tensor.inverse(),
+            rotation: value.rotation,
+            previous_rotation: value.previous_rotation,
+            angular_velocity: value.angular_velocity,
+            previous_angular_velocity: value.previous_angular_velocity,
+        }
+    }
+}
+
+impl From<SnapshotRigidBody> for RigidBody {
+    fn from(value: SnapshotRigidBody) -> Self {
+        RigidBody {
+            shape: value.shape.into(),
+            scale: value.scale,
+            mass: value.mass,
+            center_of_mass: value.center_of_mass,
+            vertex_resolution: value.vertex_resolution,
+            vertices: value.vertices,
+            static_friction: value.static_friction,
+            dynamic_friction: value.dynamic_friction,
+            restitution: value.restitution,
+            linear_damping: value.linear_damping,
+            angular_damping: value.angular_damping,
+            drag_coefficient: value.drag_coefficient,
+            rolling_friction: value.rolling_friction,
+            spinning_friction: value.spinning_friction,
+            max_linear_velocity: value.max_linear_velocity,
+            max_angular_velocity: value.max_angular_velocity,
+            lock_translation: value.lock_translation,
+            lock_rotation: value.lock_rotation,
+            force: value.force,
+            torque: value.torque,
+
+            inertia_tensor: InertiaTensor::from_inverse_tensor(value.inverse_inertia_tensor),
+            rotation: value.rotation,
+            previous_rotation: value.previous_rotation,
+            angular_velocity: value.angular_velocity,
+            previous_angular_velocity: value.previous_angular_velocity,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct InertiaTensor {
     inverse: Mat3
@@ -1,6 +1,7 @@
-use std::path;
+use std::{fs, io, path, time::Instant};
 
-use sokudo_io::{read::{ParseError, ParsedWorld}, write::{WriteWorldStateHistory, WriteStateError}};
+use indicatif::{ProgressBar, ProgressStyle};
+use sokudo_io::{read::{script::ParsedScript, ParseError, ParsedWorld}, write::{bench::BenchReport, checkpoint::Checkpoint, events::CollisionEventLog, HistoryOptions, HistoryWriter, ReadStateError, WriteStateError}};
 use thiserror::Error;
 
 use crate::world::World;
@@ -13,24 +14,303 @@ pub enum RunSimulationError {
     /// A state writing error.
     #[error(transparent)]
     WriteState(#[from] WriteStateError),
+    /// A checkpoint reading error.
+    #[error(transparent)]
+    ReadState(#[from] ReadStateError),
+    /// The time step given in place of that of the world file is not positive.
+    #[error("the time step cannot be overridden with {0}, which is not positive")]
+    NonPositiveDt(f32),
+    /// The number of steps given in place of that of the world file is zero.
+    #[error("the number of steps cannot be overridden with zero")]
+    ZeroSteps,
 }
 
+/// The number of steps between each checkpoint written while simulating.
+pub const CHECKPOINT_INTERVAL: u32 = 100;
+
+/// How a simulation is run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunOptions {
+    /// How the history is written.
+    pub history: HistoryOptions,
+    /// Whether or not to show the progress of the simulation on the terminal.
+    pub progress: bool,
+    /// The file to write the collision events of the simulation to, if any.
+    pub events: Option<path::PathBuf>,
+    /// The number of frames the history is padded to, if any. The simulation stops early once
+    /// the world is at rest or the history has this many frames, after which the last frame is
+    /// repeated until it has exactly this many. See [`World::is_at_rest`].
+    pub pad_to: Option<u64>,
+    /// The script of impulses and forces applied to the colliders while simulating, if any.
+    pub script: Option<path::PathBuf>,
+    /// The time step to simulate the world with in place of that of its file, if any.
+    pub dt: Option<f32>,
+    /// The number of steps to simulate the world for in place of that of its file, if any.
+    pub steps: Option<u32>,
+}
+
+/// Simulates the world at `world_path`, writing its history to `state_path` with the default
+/// [`RunOptions`].
 pub fn run_simulation<P>(world_path: P, state_path: P) -> Result<(), RunSimulationError>
 where
     P: AsRef<path::Path>
 {
-    let mut world: World = ParsedWorld::read(world_path)?.into();
-    let mut history = WriteWorldStateHistory::default();
+    run_simulation_with_options(world_path, state_path, RunOptions::default())
+}
+
+/// Simulates the world at `world_path`, writing its history to `state_path` with `options`.
+///
+/// Each state is appended to the file as soon as it is computed rather than being held in memory.
+/// Every [`CHECKPOINT_INTERVAL`] steps, a checkpoint is written to [`checkpoint_path`] from which
+/// an interrupted simulation can be continued with [`resume_simulation`]. It is removed once the
+/// simulation finishes.
+///
+/// If [`RunOptions::events`] is set, the collision events are written there whenever a
+/// checkpoint is written and once the simulation finishes.
+pub fn run_simulation_with_options<P>(world_path: P, state_path: P, options: RunOptions) -> Result<(), RunSimulationError>
+where
+    P: AsRef<path::Path>
+{
+    let world = read_world(world_path, &options)?;
+    let mut history = HistoryWriter::create(&state_path, world.dt, options.history)?;
+
+    history.push(&if history.is_verbose() { world.verbose_state() } else { world.state() })?;
+
+    let events = options.events.map(|path| (path, CollisionEventLog::default()));
+
+    simulate(world, history, state_path.as_ref(), options.progress, events, options.pad_to)
+}
+
+/// Continues simulating the world at `world_path` from the `checkpoint` written while simulating
+/// it earlier, appending to the history at `state_path`.
+///
+/// The states in the history after the checkpoint are discarded, and the rest of the simulation
+/// is identical to one which was never interrupted, as are the collision events written to
+/// [`RunOptions::events`]. The [`RunOptions::history`] is ignored, since the format of the
+/// existing history is used instead.
+pub fn resume_simulation<P>(world_path: P, state_path: P, checkpoint: P, options: RunOptions) -> Result<(), RunSimulationError>
+where
+    P: AsRef<path::Path>
+{
+    let mut world = read_world(world_path, &options)?;
+    world.restore(&Checkpoint::read(checkpoint)?);
+
+    // The history holds the initial state followed by one state per step.
+    let history = HistoryWriter::resume(&state_path, world.step as u64 + 1)?;
+
+    // Events after the checkpoint are discarded along with the states.
+    let events = match options.events {
+        Some(path) if path.exists() => {
+            let mut log = CollisionEventLog::read(&path)?;
+            log.events.retain(|event| event.step <= world.step);
+            Some((path, log))
+        },
+        Some(path) => Some((path, CollisionEventLog::default())),
+        None => None,
+    };
+
+    simulate(world, history, state_path.as_ref(), options.progress, events, options.pad_to)
+}
+
+/// Simulates the world at `world_path` for `steps` steps, or as many as it specifies if `None`,
+/// without writing its history, and measures how long it took.
+///
+/// The world is stepped exactly as by [`run_simulation`], so the report of the same world and
+/// build is only affected by the speed of the machine.
+pub fn bench_simulation<P>(world_path: P, steps: Option<u32>) -> Result<BenchReport, RunSimulationError>
+where
+    P: AsRef<path::Path>
+{
+    let parsed = ParsedWorld::read(world_path)?;
+
+    // The report is usually read from standard output, so warnings are kept out of it.
+    for warning in parsed.warnings.iter() {
+        eprintln!("warning: {}", warning);
+    }
+
+    let mut world: World = parsed.into();
+    world.initialize();
+
+    let steps = steps.unwrap_or(world.steps);
+
+    let mut constraints = 0;
+    let mut collision_constraints = 0;
+    let mut max_residual = 0.0f32;
+    let mut residual = 0.0;
+
+    let start = Instant::now();
+
+    for _ in 0..steps {
+        world.advance();
+
+        constraints += world.constraints.len() + world.collision_constraints.len();
+        collision_constraints += world.collision_constraints.len();
+
+        let step_residual = world.last_residual();
+        max_residual = max_residual.max(step_residual.max);
+        residual += step_residual.rms as f64;
+    }
+
+    let wall_time = start.elapsed().as_secs_f64();
+    let average = |total: f64| if steps == 0 { 0.0 } else { total / steps as f64 };
+
+    Ok(BenchReport {
+        steps,
+        wall_time,
+        steps_per_second: if wall_time > 0.0 { steps as f64 / wall_time } else { 0.0 },
+        average_constraints: average(constraints as f64),
+        average_collision_constraints: average(collision_constraints as f64),
+        max_residual,
+        average_residual: average(residual),
+    })
+}
+
+/// The checkpoint written while simulating the history at `state_path`.
+pub fn checkpoint_path<P>(state_path: P) -> path::PathBuf
+where
+    P: AsRef<path::Path>
+{
+    let mut path = state_path.as_ref().as_os_str().to_owned();
+    path.push(".checkpoint");
+    path.into()
+}
+
+/// Reads and initializes the world at `world_path` with the time step and number of steps of
+/// `options` in place of its own, along with the script of `options`, if any, printing any
+/// warnings found while parsing it.
+fn read_world<P>(world_path: P, options: &RunOptions) -> Result<World, RunSimulationError>
+where
+    P: AsRef<path::Path>
+{
+    let mut parsed = ParsedWorld::read(world_path)?;
+
+    match options.dt {
+        Some(dt) if dt <= 0.0 || dt.is_nan() => return Err(RunSimulationError::NonPositiveDt(dt)),
+        Some(dt) => parsed.dt = dt,
+        None => (),
+    }
+
+    match options.steps {
+        Some(0) => return Err(RunSimulationError::ZeroSteps),
+        Some(steps) => parsed.steps = steps,
+        None => (),
+    }
+
+    for warning in parsed.warnings.iter() {
+        println!("warning: {}", warning);
+    }
 
+    let mut world: World = parsed.into();
     world.initialize();
-    history.push(world.state());
 
-    for _ in 0..world.steps {
-        world.step();
-        history.push(world.state());
+    if let Some(script_path) = &options.script {
+        world.script = ParsedScript::read(script_path)?.entries;
     }
 
-    history.write(state_path)?;
+    Ok(world)
+}
+
+/// Steps `world` until it has simulated all of its steps, appending each state to `history` and
+/// each collision event to the log in `events`, and shows a progress bar while doing so if
+/// `progress` is set.
+///
+/// If `pad_to` is set, the history is padded to that many frames as described by
+/// [`RunOptions::pad_to`].
+fn simulate(
+    world: World,
+    history: HistoryWriter,
+    state_path: &path::Path,
+    progress: bool,
+    events: Option<(path::PathBuf, CollisionEventLog)>,
+    pad_to: Option<u64>,
+) -> Result<(), RunSimulationError> {
+    let bar = if progress {
+        ProgressBar::new(world.steps as u64).with_style(
+            ProgressStyle::with_template("{wide_bar} {pos}/{len} steps [{elapsed_precise} elapsed, {eta_precise} remaining]")
+                .unwrap()
+        )
+    } else {
+        ProgressBar::hidden()
+    };
+
+    bar.set_position(world.step as u64);
+    bar.reset_eta();
 
-    Ok(())
+    let result = simulate_steps(world, history, state_path, &bar, events, pad_to);
+
+    // The bar is removed on failure so that it does not run into the error printed afterwards.
+    if result.is_ok() {
+        bar.finish();
+    } else {
+        bar.finish_and_clear();
+    }
+
+    result
+}
+
+fn simulate_steps(
+    mut world: World,
+    mut history: HistoryWriter,
+    state_path: &path::Path,
+    bar: &ProgressBar,
+    mut events: Option<(path::PathBuf, CollisionEventLog)>,
+    pad_to: Option<u64>,
+) -> Result<(), RunSimulationError> {
+    let checkpoint = checkpoint_path(state_path);
+    let mut checkpoint_due = false;
+
+    while world.step < world.steps {
+        if pad_to.is_some_and(|frames| history.len() >= frames || world.is_at_rest()) {
+            break;
+        }
+
+        let state = if history.is_verbose() {
+            world.advance();
+            world.verbose_state()
+        } else {
+            world.step()
+        };
+
+        history.push(&state)?;
+        bar.inc(1);
+
+        if let Some((_, log)) = &mut events {
+            log.events.extend_from_slice(&world.events);
+        }
+
+        checkpoint_due |= world.step.is_multiple_of(CHECKPOINT_INTERVAL);
+
+        // A checkpoint is only useful once every state before it is in the history, which for
+        // compressed histories is not until the current block is full.
+        if checkpoint_due && history.flush()? == history.len() {
+            // The events are written first, so that they always cover the checkpoint.
+            if let Some((path, log)) = &events {
+                log.write(path)?;
+            }
+
+            world.checkpoint().write(&checkpoint)?;
+            checkpoint_due = false;
+        }
+    }
+
+    if let Some(frames) = pad_to {
+        // The world has not changed since its last state was pushed, so its state is that frame.
+        let mut state = if history.is_verbose() { world.verbose_state() } else { world.state() };
+
+        while history.len() < frames {
+            state.step += 1;
+            history.push(&state)?;
+        }
+    }
+
+    history.finish()?;
+
+    if let Some((path, log)) = &events {
+        log.write(path)?;
+    }
+
+    match fs::remove_file(&checkpoint) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(WriteStateError::from(err).into()),
+        _ => Ok(()),
+    }
 }
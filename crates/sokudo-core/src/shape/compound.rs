@@ -0,0 +1,90 @@
+use glam::{Mat3, UVec3, Vec3};
+
+use crate::{aabb::Aabb, transform::Transform};
+
+use super::{AbstractShape, Shape};
+
+/// The union of several shapes, each placed relative to the origin of the compound.
+///
+/// Parts must be bounded and may not be compounds themselves.
+#[derive(Debug)]
+pub struct CompoundShape {
+    pub parts: Vec<(Transform, Shape)>,
+}
+
+impl CompoundShape {
+    /// The centroid of this compound when scaled by `scale`, weighting every part by its volume.
+    ///
+    /// Every part is assumed to be centered at its own origin.
+    pub fn centroid(&self, scale: Vec3) -> Vec3 {
+        let volume = self.volume(Vec3::ONE);
+
+        if volume <= f32::EPSILON {
+            return Vec3::ZERO;
+        }
+
+        self.parts.iter()
+            .map(|(transform, shape)| shape.volume(transform.scale) * transform.translate)
+            .sum::<Vec3>() * scale / volume
+    }
+}
+
+impl AbstractShape for CompoundShape {
+    /// The distance to the nearest part, which is exact outside of the compound and only bounds
+    /// the distance for parts with non-uniform scale.
+    fn sd(&self, point: Vec3) -> f32 {
+        self.parts.iter().map(|(transform, shape)| {
+            let local = transform.rotate.inverse() * (point - transform.translate) / transform.scale;
+            shape.sd(local) * transform.scale.abs().min_element()
+        }).fold(f32::INFINITY, f32::min)
+    }
+
+    fn vertices(&self, resolution: UVec3) -> Vec<Vec3> {
+        self.parts.iter().flat_map(|(transform, shape)| {
+            shape.vertices(resolution).into_iter()
+                .map(|v| transform.translate + transform.rotate * (transform.scale * v))
+        }).collect()
+    }
+
+    /// Applies the parallel axis theorem to every part about the centroid of the compound.
+    ///
+    /// Every part is assumed to be centered at its own origin.
+    fn moments(&self, scale: Vec3) -> Vec3 {
+        let volume = self.volume(Vec3::ONE);
+
+        if volume <= f32::EPSILON {
+            return Vec3::ZERO;
+        }
+
+        let outer = |v: Vec3| Mat3::from_cols(v * v.x, v * v.y, v * v.z);
+
+        // Accumulate the covariance of the unscaled compound about its origin, since the
+        // rotated parts only have principal moments in their own frames.
+        let covariance = self.parts.iter().fold(Mat3::ZERO, |acc, (transform, shape)| {
+            let moments = shape.moments(transform.scale);
+            let part_covariance = Mat3::from_diagonal(Vec3::splat(moments.element_sum() / 2.0) - moments);
+            let rotation = Mat3::from_quat(transform.rotate);
+            let weight = shape.volume(transform.scale) / volume;
+
+            acc + (rotation * part_covariance * rotation.transpose() + outer(transform.translate)) * weight
+        });
+
+        let scale_matrix = Mat3::from_diagonal(scale);
+        let covariance = scale_matrix * covariance * scale_matrix - outer(self.centroid(scale));
+        let (xx, yy, zz) = (covariance.x_axis.x, covariance.y_axis.y, covariance.z_axis.z);
+
+        Vec3::new(yy + zz, xx + zz, xx + yy)
+    }
+
+    fn volume(&self, scale: Vec3) -> f32 {
+        let volume: f32 = self.parts.iter().map(|(transform, shape)| shape.volume(transform.scale)).sum();
+        (volume * scale.x * scale.y * scale.z).abs()
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.parts.iter()
+            .map(|(transform, shape)| shape.aabb().transform(transform.translate, transform.rotate, transform.scale))
+            .reduce(Aabb::union)
+            .unwrap_or(Aabb::from_point(Vec3::ZERO))
+    }
+}
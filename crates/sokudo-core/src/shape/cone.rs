@@ -0,0 +1,114 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::{UVec3, Vec2, Vec3, Vec3Swizzles};
+
+use crate::aabb::Aabb;
+
+use super::AbstractShape;
+
+/// A solid cone whose axis lies along y with its apex pointing up.
+///
+/// The origin lies at the centroid of the cone, a quarter of its height above the center of its
+/// base, so that its center of mass is at the origin like every other shape.
+#[derive(Debug)]
+pub struct ConeShape {
+    /// The radius of the base.
+    pub radius: f32,
+    /// The distance from the base to the apex.
+    pub height: f32,
+}
+
+impl ConeShape {
+    /// The number of vertices around each circle per unit of `vertex_resolution.x`.
+    pub const SEGMENTS: u32 = 16;
+
+    /// The height of the base relative to the origin.
+    #[inline]
+    pub fn base(&self) -> f32 {
+        -0.25 * self.height
+    }
+
+    /// The height of the apex relative to the origin.
+    #[inline]
+    pub fn apex(&self) -> f32 {
+        0.75 * self.height
+    }
+}
+
+impl AbstractShape for ConeShape {
+    fn sd(&self, point: Vec3) -> f32 {
+        // Work in the plane through the axis relative to the apex, where the cone is the triangle
+        // between the apex, the axis and the rim at `q`.
+        let q = Vec2::new(self.radius, -self.height);
+        let w = Vec2::new(point.xz().length(), point.y - self.apex());
+
+        let to_side = w - q * (w.dot(q) / q.dot(q)).clamp(0.0, 1.0);
+        let to_base = w - q * Vec2::new((w.x / q.x).clamp(0.0, 1.0), 1.0);
+        let distance = to_side.length_squared().min(to_base.length_squared()).sqrt();
+
+        // Points are outside when they lie beyond either the slanted side or the base.
+        let outside = (w.y * q.x - w.x * q.y).max(q.y - w.y);
+        distance.copysign(outside)
+    }
+
+    /// Samples `resolution.x` times [`ConeShape::SEGMENTS`] points around every circle,
+    /// `resolution.z` concentric circles on the base and `resolution.y` rows of circles along
+    /// the lateral surface, as well as the apex.
+    fn vertices(&self, resolution: UVec3) -> Vec<Vec3> {
+        let segments = (Self::SEGMENTS * resolution.x).max(3);
+        let rings = resolution.z.max(1);
+        let rows = resolution.y.max(1);
+
+        let circle = |radius: f32, y: f32| (0..segments).map(move |i| {
+            let (sin, cos) = (TAU * i as f32 / segments as f32).sin_cos();
+            Vec3::new(radius * cos, y, radius * sin)
+        });
+
+        let mut vertices = vec![Vec3::new(0.0, self.apex(), 0.0), Vec3::new(0.0, self.base(), 0.0)];
+
+        for ring in 1..=rings {
+            vertices.extend(circle(self.radius * ring as f32 / rings as f32, self.base()));
+        }
+
+        // The rim was already sampled with the base.
+        for row in 1..rows {
+            let t = row as f32 / rows as f32;
+            vertices.extend(circle(self.radius * (1.0 - t), self.base() + t * self.height));
+        }
+
+        vertices
+    }
+
+    fn support(&self, direction: Vec3) -> Option<Vec3> {
+        let apex = Vec3::new(0.0, self.apex(), 0.0);
+
+        let radial = self.radius * direction.xz().normalize_or_zero();
+        let rim = Vec3::new(radial.x, self.base(), radial.y);
+
+        Some(if apex.dot(direction) >= rim.dot(direction) { apex } else { rim })
+    }
+
+    fn moments(&self, scale: Vec3) -> Vec3 {
+        // The base becomes an ellipse under non-uniform scaling.
+        let a = self.radius * scale.x;
+        let c = self.radius * scale.z;
+        let h = self.height * scale.y;
+
+        Vec3::new(
+            3.0 / 20.0 * c * c + 3.0 / 80.0 * h * h,
+            3.0 / 20.0 * (a * a + c * c),
+            3.0 / 20.0 * a * a + 3.0 / 80.0 * h * h,
+        )
+    }
+
+    fn volume(&self, scale: Vec3) -> f32 {
+        (PI * self.radius * self.radius * self.height * scale.x * scale.y * scale.z / 3.0).abs()
+    }
+
+    fn aabb(&self) -> Aabb {
+        Aabb::new(
+            Vec3::new(-self.radius, self.base(), -self.radius),
+            Vec3::new(self.radius, self.apex(), self.radius),
+        )
+    }
+}
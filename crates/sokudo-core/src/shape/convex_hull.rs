@@ -0,0 +1,237 @@
+use glam::{UVec3, Vec3};
+
+use crate::aabb::Aabb;
+
+use super::{triangle_mesh::{closest_point_on_triangle, polyhedron_moments, polyhedron_volume}, AbstractShape};
+
+/// The convex hull of a set of points.
+#[derive(Debug)]
+pub struct ConvexHullShape {
+    /// The vertices of the hull.
+    pub vertices: Vec<Vec3>,
+    /// The vertex indices of each face, wound counter-clockwise when viewed from outside.
+    pub indices: Vec<[u32; 3]>,
+    /// The outward unit normal and offset from the origin of the plane of each face.
+    pub planes: Vec<(Vec3, f32)>,
+}
+
+impl ConvexHullShape {
+    /// The minimum thickness of the hull along any direction, used when the points are coplanar,
+    /// collinear or coincident.
+    pub const MIN_THICKNESS: f32 = 1e-3;
+
+    /// Computes the convex hull of `points` using quickhull.
+    ///
+    /// If the points do not span a volume, the hull of their bounding box grown to
+    /// [`ConvexHullShape::MIN_THICKNESS`] along every axis is used instead.
+    pub fn new(points: &[Vec3]) -> ConvexHullShape {
+        let (vertices, indices) = quickhull(points).unwrap_or_else(|| {
+            let aabb = points.iter().fold(Aabb::from_point(points.first().copied().unwrap_or(Vec3::ZERO)), |aabb, &p| aabb.include(p));
+            let center = 0.5 * (aabb.min + aabb.max);
+            let half_extents = (0.5 * (aabb.max - aabb.min)).max(Vec3::splat(0.5 * Self::MIN_THICKNESS));
+
+            let corners = Aabb::new(center - half_extents, center + half_extents).corners();
+            quickhull(&corners).expect("bounding box has volume")
+        });
+
+        let planes = indices.iter()
+            .map(|&[a, b, c]| {
+                let [a, b, c] = [a, b, c].map(|i| vertices[i as usize]);
+                let normal = (b - a).cross(c - a).normalize();
+                (normal, normal.dot(a))
+            })
+            .collect();
+
+        ConvexHullShape {
+            vertices,
+            indices,
+            planes,
+        }
+    }
+}
+
+impl AbstractShape for ConvexHullShape {
+    fn sd(&self, point: Vec3) -> f32 {
+        let plane_distance = self.planes.iter()
+            .map(|&(normal, offset)| normal.dot(point) - offset)
+            .fold(f32::NEG_INFINITY, f32::max);
+
+        // Inside, the distance to the closest face plane is exact. Outside, it is only a lower
+        // bound, so the closest point on the surface is found instead.
+        if plane_distance <= 0.0 {
+            return plane_distance;
+        }
+
+        self.indices.iter()
+            .map(|&[a, b, c]| {
+                let triangle = [a, b, c].map(|i| self.vertices[i as usize]);
+                closest_point_on_triangle(point, triangle).distance(point)
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    fn vertices(&self, _resolution: UVec3) -> Vec<Vec3> {
+        self.vertices.clone()
+    }
+
+    fn support(&self, direction: Vec3) -> Option<Vec3> {
+        self.vertices.iter().copied().max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+    }
+
+    fn moments(&self, scale: Vec3) -> Vec3 {
+        polyhedron_moments(&self.vertices, &self.indices, scale).unwrap_or(Vec3::ZERO)
+    }
+
+    fn volume(&self, scale: Vec3) -> f32 {
+        polyhedron_volume(&self.vertices, &self.indices, scale)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.vertices.iter().fold(Aabb::from_point(self.vertices[0]), |aabb, &v| aabb.include(v))
+    }
+}
+
+struct Face {
+    indices: [usize; 3],
+    normal: Vec3,
+    offset: f32,
+    /// The points which lie above this face and have not yet been added to the hull.
+    outside: Vec<usize>,
+}
+
+impl Face {
+    fn new(points: &[Vec3], indices: [usize; 3]) -> Face {
+        let [a, b, c] = indices.map(|i| points[i]);
+        let normal = (b - a).cross(c - a).normalize_or_zero();
+
+        Face {
+            indices,
+            normal,
+            offset: normal.dot(a),
+            outside: Vec::new(),
+        }
+    }
+
+    #[inline]
+    fn distance(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) - self.offset
+    }
+}
+
+/// Computes the vertices and triangles of the convex hull of `points`, or `None` if the points do
+/// not span a volume.
+fn quickhull(points: &[Vec3]) -> Option<(Vec<Vec3>, Vec<[u32; 3]>)> {
+    let extent = points.iter().fold(0.0f32, |acc, p| acc.max(p.abs().max_element()));
+    let epsilon = 1e-5 * extent.max(1.0);
+
+    // Start with a tetrahedron between four extreme points.
+    let (p0, p1) = {
+        let extremes: Vec<usize> = (0..3)
+            .flat_map(|axis| {
+                let by_axis = |&a: &usize, &b: &usize| points[a][axis].total_cmp(&points[b][axis]);
+                let indices = 0..points.len();
+                [indices.clone().min_by(by_axis), indices.max_by(by_axis)]
+            })
+            .flatten()
+            .collect();
+
+        let mut best = (*extremes.first()?, *extremes.first()?);
+
+        for &a in extremes.iter() {
+            for &b in extremes.iter() {
+                if points[a].distance_squared(points[b]) > points[best.0].distance_squared(points[best.1]) {
+                    best = (a, b);
+                }
+            }
+        }
+
+        best
+    };
+
+    if points[p0].distance(points[p1]) <= epsilon {
+        return None;
+    }
+
+    let line = (points[p1] - points[p0]).normalize();
+    let p2 = (0..points.len()).max_by(|&a, &b| {
+        let distance = |i: usize| (points[i] - points[p0]).cross(line).length_squared();
+        distance(a).total_cmp(&distance(b))
+    })?;
+
+    if (points[p2] - points[p0]).cross(line).length() <= epsilon {
+        return None;
+    }
+
+    let base = Face::new(points, [p0, p1, p2]);
+    let p3 = (0..points.len()).max_by(|&a, &b| base.distance(points[a]).abs().total_cmp(&base.distance(points[b]).abs()))?;
+
+    if base.distance(points[p3]).abs() <= epsilon {
+        return None;
+    }
+
+    let mut faces: Vec<Face> = if base.distance(points[p3]) < 0.0 {
+        vec![[p0, p1, p2], [p0, p3, p1], [p1, p3, p2], [p2, p3, p0]]
+    } else {
+        vec![[p0, p2, p1], [p0, p1, p3], [p1, p2, p3], [p2, p0, p3]]
+    }
+    .into_iter()
+    .map(|indices| Face::new(points, indices))
+    .collect();
+
+    let assign = |faces: &mut [Face], candidates: &mut dyn Iterator<Item = usize>| {
+        for i in candidates {
+            if let Some(face) = faces.iter_mut().find(|face| face.distance(points[i]) > epsilon) {
+                face.outside.push(i);
+            }
+        }
+    };
+
+    assign(&mut faces, &mut (0..points.len()).filter(|i| ![p0, p1, p2, p3].contains(i)));
+
+    while let Some(face_index) = faces.iter().position(|face| !face.outside.is_empty()) {
+        let face = &faces[face_index];
+        let eye = *face.outside.iter().max_by(|&&a, &&b| face.distance(points[a]).total_cmp(&face.distance(points[b])))?;
+
+        let (visible, remaining): (Vec<Face>, Vec<Face>) = faces
+            .into_iter()
+            .partition(|face| face.distance(points[eye]) > epsilon);
+
+        // The horizon is made of the edges of visible faces whose neighbouring face is not
+        // visible, which appear only once among the visible faces.
+        let edges: Vec<(usize, usize)> = visible.iter()
+            .flat_map(|face| {
+                let [a, b, c] = face.indices;
+                [(a, b), (b, c), (c, a)]
+            })
+            .collect();
+
+        let horizon = edges.iter().filter(|&&(a, b)| !edges.contains(&(b, a)));
+
+        faces = remaining;
+        let new_faces = faces.len();
+        faces.extend(horizon.map(|&(a, b)| Face::new(points, [a, b, eye])));
+
+        let orphans = visible.into_iter()
+            .flat_map(|face| face.outside)
+            .filter(|&i| i != eye);
+
+        assign(&mut faces[new_faces..], &mut orphans.collect::<Vec<_>>().into_iter());
+    }
+
+    // Compact the vertices down to only those used by the hull.
+    let mut remap = vec![u32::MAX; points.len()];
+    let mut vertices = Vec::new();
+
+    let indices = faces.iter()
+        .map(|face| face.indices.map(|i| {
+            if remap[i] == u32::MAX {
+                remap[i] = vertices.len() as u32;
+                vertices.push(points[i]);
+            }
+
+            remap[i]
+        }))
+        .collect();
+
+    Some((vertices, indices))
+}
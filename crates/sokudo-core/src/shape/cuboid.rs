@@ -1,5 +1,7 @@
 use glam::{UVec3, Vec2, Vec3, Vec3Swizzles};
 
+use crate::aabb::Aabb;
+
 use super::AbstractShape;
 
 /// A cube shape with unit side lengths centered at the origin.
@@ -57,7 +59,20 @@ impl AbstractShape for CuboidShape {
         vertices
     }
 
+    fn support(&self, direction: Vec3) -> Option<Vec3> {
+        Some(0.5 * direction.signum())
+    }
+
     fn moments(&self, scale: Vec3) -> Vec3 {
-        scale.normalize()
+        let sq = scale * scale;
+        Vec3::new(sq.y + sq.z, sq.x + sq.z, sq.x + sq.y) / 12.0
+    }
+
+    fn volume(&self, scale: Vec3) -> f32 {
+        (scale.x * scale.y * scale.z).abs()
+    }
+
+    fn aabb(&self) -> Aabb {
+        Aabb::new(Vec3::splat(-0.5), Vec3::splat(0.5))
     }
 }
@@ -0,0 +1,87 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::{UVec3, Vec2, Vec3, Vec3Swizzles};
+
+use crate::aabb::Aabb;
+
+use super::AbstractShape;
+
+/// A solid cylinder centered at the origin whose axis lies along y.
+#[derive(Debug)]
+pub struct CylinderShape {
+    /// The radius of the circular caps.
+    pub radius: f32,
+    /// Half of the distance between the two caps.
+    pub half_height: f32,
+}
+
+impl CylinderShape {
+    /// The number of vertices around each circle per unit of `vertex_resolution.x`.
+    pub const SEGMENTS: u32 = 16;
+}
+
+impl AbstractShape for CylinderShape {
+    fn sd(&self, point: Vec3) -> f32 {
+        let d = Vec2::new(point.xz().length() - self.radius, point.y.abs() - self.half_height);
+        d.max_element().min(0.0) + d.max(Vec2::ZERO).length()
+    }
+
+    /// Samples `resolution.x` times [`CylinderShape::SEGMENTS`] points around every circle,
+    /// `resolution.z` concentric circles on each cap and `resolution.y` rows of circles along the
+    /// side wall.
+    fn vertices(&self, resolution: UVec3) -> Vec<Vec3> {
+        let segments = (Self::SEGMENTS * resolution.x).max(3);
+        let rings = resolution.z.max(1);
+        let rows = resolution.y.max(1);
+
+        let circle = |radius: f32, y: f32| (0..segments).map(move |i| {
+            let (sin, cos) = (TAU * i as f32 / segments as f32).sin_cos();
+            Vec3::new(radius * cos, y, radius * sin)
+        });
+
+        let mut vertices = Vec::new();
+
+        for y in [self.half_height, -self.half_height] {
+            vertices.push(Vec3::new(0.0, y, 0.0));
+
+            for ring in 1..=rings {
+                vertices.extend(circle(self.radius * ring as f32 / rings as f32, y));
+            }
+        }
+
+        // The rims were already sampled with the caps.
+        for row in 1..rows {
+            let y = self.half_height * (2.0 * row as f32 / rows as f32 - 1.0);
+            vertices.extend(circle(self.radius, y));
+        }
+
+        vertices
+    }
+
+    fn support(&self, direction: Vec3) -> Option<Vec3> {
+        let radial = self.radius * direction.xz().normalize_or_zero();
+        Some(Vec3::new(radial.x, self.half_height.copysign(direction.y), radial.y))
+    }
+
+    fn moments(&self, scale: Vec3) -> Vec3 {
+        // The caps become ellipses under non-uniform scaling.
+        let a = self.radius * scale.x;
+        let c = self.radius * scale.z;
+        let h = 2.0 * self.half_height * scale.y;
+
+        Vec3::new(
+            c * c / 4.0 + h * h / 12.0,
+            (a * a + c * c) / 4.0,
+            a * a / 4.0 + h * h / 12.0,
+        )
+    }
+
+    fn volume(&self, scale: Vec3) -> f32 {
+        (PI * self.radius * self.radius * 2.0 * self.half_height * scale.x * scale.y * scale.z).abs()
+    }
+
+    fn aabb(&self) -> Aabb {
+        let extent = Vec3::new(self.radius, self.half_height, self.radius);
+        Aabb::new(-extent, extent)
+    }
+}
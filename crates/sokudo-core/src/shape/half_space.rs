@@ -0,0 +1,47 @@
+use glam::{UVec3, Vec3};
+
+use crate::aabb::Aabb;
+
+use super::AbstractShape;
+
+/// The infinite region below a plane through the origin.
+#[derive(Debug)]
+pub struct HalfSpaceShape {
+    /// The outward unit normal of the plane.
+    pub normal: Vec3,
+}
+
+impl AbstractShape for HalfSpaceShape {
+    fn sd(&self, point: Vec3) -> f32 {
+        self.normal.dot(point)
+    }
+
+    fn sd_gradient(&self, _point: Vec3) -> Vec3 {
+        self.normal
+    }
+
+    /// Half-spaces have no vertices, since every other shape is tested against their plane
+    /// instead.
+    fn vertices(&self, _resolution: UVec3) -> Vec<Vec3> {
+        Vec::new()
+    }
+
+    /// Half-spaces always have infinite mass.
+    fn moments(&self, _scale: Vec3) -> Vec3 {
+        Vec3::INFINITY
+    }
+
+    fn volume(&self, _scale: Vec3) -> f32 {
+        f32::INFINITY
+    }
+
+    fn aabb(&self) -> Aabb {
+        Aabb::INFINITE
+    }
+
+    /// Half-spaces are infinitely wide in every direction but along their normal, and are never
+    /// moved by drag anyway.
+    fn cross_section(&self, _scale: Vec3, _direction: Vec3) -> f32 {
+        f32::INFINITY
+    }
+}
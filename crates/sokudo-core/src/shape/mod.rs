@@ -1,8 +1,26 @@
+use compound::CompoundShape;
+use convex_hull::ConvexHullShape;
+use cone::ConeShape;
 use cuboid::CuboidShape;
+use cylinder::CylinderShape;
+use half_space::HalfSpaceShape;
+use sdf::SdfShape;
+use torus::TorusShape;
 use glam::{UVec3, Vec3};
-use sokudo_io::read::collider::ParsedShape;
+use sokudo_io::{read::collider::ParsedShape, write::snapshot::SnapshotShape};
+use triangle_mesh::TriangleMeshShape;
 
+use crate::aabb::Aabb;
+
+pub mod compound;
+pub mod cone;
+pub mod convex_hull;
 pub mod cuboid;
+pub mod cylinder;
+pub mod half_space;
+pub mod sdf;
+pub mod torus;
+pub mod triangle_mesh;
 
 pub trait AbstractShape {
     const GRADIENT_EPSILON: f32 = 0.01;
@@ -33,38 +51,208 @@ pub trait AbstractShape {
         Vec3::new(d_sd_dx, d_sd_dy, d_sd_dz).normalize_or_zero()
     }
 
+    /// Marches along the ray `origin + t * direction` until it hits this [`Shape`], returning the
+    /// smallest `t` no greater than `max_t` at which it reaches the surface.
+    ///
+    /// `direction` need not be normalized. Rays starting inside the shape return `None`.
+    fn raycast(&self, origin: Vec3, direction: Vec3, max_t: f32) -> Option<f32> {
+        const MAX_STEPS: usize = 64;
+        const SURFACE_EPSILON: f32 = 1e-4;
+
+        let speed = direction.length();
+
+        if speed <= f32::EPSILON || self.sd(origin) <= 0.0 {
+            return None;
+        }
+
+        let mut t = 0.0;
+
+        for _ in 0..MAX_STEPS {
+            let sd = self.sd(origin + t * direction);
+
+            if sd <= SURFACE_EPSILON {
+                return Some(t);
+            }
+
+            t += sd / speed;
+
+            if t > max_t {
+                return None;
+            }
+        }
+
+        None
+    }
+
     fn vertices(&self, resolution: UVec3) -> Vec<Vec3>;
 
+    /// The furthest point of this [`Shape`] along `direction` in local coordinates, or `None` if
+    /// it is not a bounded convex shape.
+    ///
+    /// Shapes which return `None` are collided by sampling their vertices instead of through the
+    /// [`crate::narrowphase`].
+    fn support(&self, _direction: Vec3) -> Option<Vec3> {
+        None
+    }
+
+    /// The principal moments of inertia of this [`Shape`] with unit mass, scaled by `scale`.
     fn moments(&self, scale: Vec3) -> Vec3;
+
+    /// The volume of this [`Shape`] when scaled by `scale`.
+    fn volume(&self, scale: Vec3) -> f32;
+
+    /// The bounding box of this [`Shape`] in local coordinates with unit scale.
+    fn aabb(&self) -> Aabb;
+
+    /// The area of the silhouette of this [`Shape`] when scaled by `scale` and seen along the unit
+    /// `direction` in local coordinates, which sets how much drag it experiences.
+    ///
+    /// Default implementation uses the silhouette of the bounding box, which is exact for cuboids.
+    fn cross_section(&self, scale: Vec3, direction: Vec3) -> f32 {
+        let aabb = self.aabb();
+        let size = (aabb.max - aabb.min) * scale.abs();
+
+        (direction.abs() * Vec3::new(size.y * size.z, size.x * size.z, size.x * size.y)).element_sum()
+    }
 }
 
 #[derive(Debug)]
 pub enum Shape {
     Cuboid(CuboidShape),
+    Cylinder(CylinderShape),
+    Cone(ConeShape),
+    Torus(TorusShape),
+    TriangleMesh(TriangleMeshShape),
+    ConvexHull(ConvexHullShape),
+    Sdf(SdfShape),
+    HalfSpace(HalfSpaceShape),
+    Compound(CompoundShape),
 }
 
 impl AbstractShape for Shape {
     fn sd(&self, point: Vec3) -> f32 {
         match self {
             Shape::Cuboid(c) => c.sd(point),
+            Shape::Cylinder(c) => c.sd(point),
+            Shape::Cone(c) => c.sd(point),
+            Shape::Torus(t) => t.sd(point),
+            Shape::TriangleMesh(m) => m.sd(point),
+            Shape::ConvexHull(h) => h.sd(point),
+            Shape::Sdf(f) => f.sd(point),
+            Shape::HalfSpace(p) => p.sd(point),
+            Shape::Compound(c) => c.sd(point),
         }
     }
 
     fn sd_gradient(&self, point: Vec3) -> Vec3 {
         match self {
             Shape::Cuboid(c) => c.sd_gradient(point),
+            Shape::Cylinder(c) => c.sd_gradient(point),
+            Shape::Cone(c) => c.sd_gradient(point),
+            Shape::Torus(t) => t.sd_gradient(point),
+            Shape::TriangleMesh(m) => m.sd_gradient(point),
+            Shape::ConvexHull(h) => h.sd_gradient(point),
+            Shape::Sdf(f) => f.sd_gradient(point),
+            Shape::HalfSpace(p) => p.sd_gradient(point),
+            Shape::Compound(c) => c.sd_gradient(point),
+        }
+    }
+
+    fn raycast(&self, origin: Vec3, direction: Vec3, max_t: f32) -> Option<f32> {
+        match self {
+            Shape::Cuboid(c) => c.raycast(origin, direction, max_t),
+            Shape::Cylinder(c) => c.raycast(origin, direction, max_t),
+            Shape::Cone(c) => c.raycast(origin, direction, max_t),
+            Shape::Torus(t) => t.raycast(origin, direction, max_t),
+            Shape::TriangleMesh(m) => m.raycast(origin, direction, max_t),
+            Shape::ConvexHull(h) => h.raycast(origin, direction, max_t),
+            Shape::Sdf(f) => f.raycast(origin, direction, max_t),
+            Shape::HalfSpace(p) => p.raycast(origin, direction, max_t),
+            Shape::Compound(c) => c.raycast(origin, direction, max_t),
         }
     }
 
     fn vertices(&self, resolution: UVec3) -> Vec<Vec3> {
         match self {
             Shape::Cuboid(c) => c.vertices(resolution),
+            Shape::Cylinder(c) => c.vertices(resolution),
+            Shape::Cone(c) => c.vertices(resolution),
+            Shape::Torus(t) => t.vertices(resolution),
+            Shape::TriangleMesh(m) => m.vertices(resolution),
+            Shape::ConvexHull(h) => h.vertices(resolution),
+            Shape::Sdf(f) => f.vertices(resolution),
+            Shape::HalfSpace(p) => p.vertices(resolution),
+            Shape::Compound(c) => c.vertices(resolution),
+        }
+    }
+
+    fn support(&self, direction: Vec3) -> Option<Vec3> {
+        match self {
+            Shape::Cuboid(c) => c.support(direction),
+            Shape::Cylinder(c) => c.support(direction),
+            Shape::Cone(c) => c.support(direction),
+            Shape::Torus(t) => t.support(direction),
+            Shape::TriangleMesh(m) => m.support(direction),
+            Shape::ConvexHull(h) => h.support(direction),
+            Shape::Sdf(f) => f.support(direction),
+            Shape::HalfSpace(p) => p.support(direction),
+            Shape::Compound(c) => c.support(direction),
         }
     }
 
     fn moments(&self, scale: Vec3) -> Vec3 {
         match self {
             Shape::Cuboid(c) => c.moments(scale),
+            Shape::Cylinder(c) => c.moments(scale),
+            Shape::Cone(c) => c.moments(scale),
+            Shape::Torus(t) => t.moments(scale),
+            Shape::TriangleMesh(m) => m.moments(scale),
+            Shape::ConvexHull(h) => h.moments(scale),
+            Shape::Sdf(f) => f.moments(scale),
+            Shape::HalfSpace(p) => p.moments(scale),
+            Shape::Compound(c) => c.moments(scale),
+        }
+    }
+
+    fn volume(&self, scale: Vec3) -> f32 {
+        match self {
+            Shape::Cuboid(c) => c.volume(scale),
+            Shape::Cylinder(c) => c.volume(scale),
+            Shape::Cone(c) => c.volume(scale),
+            Shape::Torus(t) => t.volume(scale),
+            Shape::TriangleMesh(m) => m.volume(scale),
+            Shape::ConvexHull(h) => h.volume(scale),
+            Shape::Sdf(f) => f.volume(scale),
+            Shape::HalfSpace(p) => p.volume(scale),
+            Shape::Compound(c) => c.volume(scale),
+        }
+    }
+
+    fn aabb(&self) -> Aabb {
+        match self {
+            Shape::Cuboid(c) => c.aabb(),
+            Shape::Cylinder(c) => c.aabb(),
+            Shape::Cone(c) => c.aabb(),
+            Shape::Torus(t) => t.aabb(),
+            Shape::TriangleMesh(m) => m.aabb(),
+            Shape::ConvexHull(h) => h.aabb(),
+            Shape::Sdf(f) => f.aabb(),
+            Shape::HalfSpace(p) => p.aabb(),
+            Shape::Compound(c) => c.aabb(),
+        }
+    }
+
+    fn cross_section(&self, scale: Vec3, direction: Vec3) -> f32 {
+        match self {
+            Shape::Cuboid(c) => c.cross_section(scale, direction),
+            Shape::Cylinder(c) => c.cross_section(scale, direction),
+            Shape::Cone(c) => c.cross_section(scale, direction),
+            Shape::Torus(t) => t.cross_section(scale, direction),
+            Shape::TriangleMesh(m) => m.cross_section(scale, direction),
+            Shape::ConvexHull(h) => h.cross_section(scale, direction),
+            Shape::Sdf(f) => f.cross_section(scale, direction),
+            Shape::HalfSpace(p) => p.cross_section(scale, direction),
+            Shape::Compound(c) => c.cross_section(scale, direction),
         }
     }
 }
@@ -73,6 +261,60 @@ impl From<ParsedShape> for Shape {
     fn from(value: ParsedShape) -> Self {
         match value {
             ParsedShape::Cuboid => Shape::Cuboid(CuboidShape),
+            ParsedShape::Cylinder { radius, half_height } => Shape::Cylinder(CylinderShape { radius, half_height }),
+            ParsedShape::Cone { radius, height } => Shape::Cone(ConeShape { radius, height }),
+            ParsedShape::Torus { major_radius, minor_radius } => Shape::Torus(TorusShape { major_radius, minor_radius }),
+            ParsedShape::Mesh { mesh, .. } => Shape::TriangleMesh(TriangleMeshShape::new(mesh.vertices, mesh.indices)),
+            ParsedShape::ConvexHull { points } => Shape::ConvexHull(ConvexHullShape::new(&points)),
+            ParsedShape::Sdf { grid, .. } => Shape::Sdf((*grid).into()),
+            ParsedShape::HalfSpace { normal } => Shape::HalfSpace(HalfSpaceShape {
+                normal: normal.normalize(),
+            }),
+            ParsedShape::Compound { parts } => Shape::Compound(CompoundShape {
+                parts: parts.into_iter().map(|part| (part.transform.into(), part.shape.into())).collect(),
+            }),
+        }
+    }
+}
+
+impl From<&Shape> for SnapshotShape {
+    fn from(value: &Shape) -> Self {
+        match value {
+            Shape::Cuboid(_) => SnapshotShape::Cuboid,
+            Shape::Cylinder(c) => SnapshotShape::Cylinder { radius: c.radius, half_height: c.half_height },
+            Shape::Cone(c) => SnapshotShape::Cone { radius: c.radius, height: c.height },
+            Shape::Torus(t) => SnapshotShape::Torus { major_radius: t.major_radius, minor_radius: t.minor_radius },
+            Shape::TriangleMesh(m) => SnapshotShape::TriangleMesh { vertices: m.vertices.clone(), indices: m.indices.clone() },
+            Shape::ConvexHull(h) => SnapshotShape::ConvexHull {
+                vertices: h.vertices.clone(),
+                indices: h.indices.clone(),
+                planes: h.planes.clone(),
+            },
+            Shape::Sdf(s) => SnapshotShape::Sdf { size: s.size, min: s.min, max: s.max, values: s.values.clone() },
+            Shape::HalfSpace(h) => SnapshotShape::HalfSpace { normal: h.normal },
+            Shape::Compound(c) => SnapshotShape::Compound {
+                parts: c.parts.iter().map(|(transform, shape)| (transform.into(), shape.into())).collect(),
+            },
+        }
+    }
+}
+
+impl From<SnapshotShape> for Shape {
+    fn from(value: SnapshotShape) -> Self {
+        match value {
+            SnapshotShape::Cuboid => Shape::Cuboid(CuboidShape),
+            SnapshotShape::Cylinder { radius, half_height } => Shape::Cylinder(CylinderShape { radius, half_height }),
+            SnapshotShape::Cone { radius, height } => Shape::Cone(ConeShape { radius, height }),
+            SnapshotShape::Torus { major_radius, minor_radius } => Shape::Torus(TorusShape { major_radius, minor_radius }),
+            SnapshotShape::TriangleMesh { vertices, indices } => Shape::TriangleMesh(TriangleMeshShape::new(vertices, indices)),
+            // The hull is kept as it was rather than built again from its vertices, which could
+            // order its faces differently.
+            SnapshotShape::ConvexHull { vertices, indices, planes } => Shape::ConvexHull(ConvexHullShape { vertices, indices, planes }),
+            SnapshotShape::Sdf { size, min, max, values } => Shape::Sdf(SdfShape { size, min, max, values }),
+            SnapshotShape::HalfSpace { normal } => Shape::HalfSpace(HalfSpaceShape { normal }),
+            SnapshotShape::Compound { parts } => Shape::Compound(CompoundShape {
+                parts: parts.into_iter().map(|(transform, shape)| (transform.into(), shape.into())).collect(),
+            }),
         }
     }
 }
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use glam::{Mat3, UVec3, Vec3};
+use sokudo_io::read::sdf::ParsedSdf;
+
+use crate::aabb::Aabb;
+
+use super::AbstractShape;
+
+/// A shape whose surface is the zero level set of a signed distance field sampled on a regular
+/// grid, which is interpolated trilinearly between samples.
+#[derive(Debug)]
+pub struct SdfShape {
+    /// The number of samples along each axis, which is at least two.
+    pub size: UVec3,
+    /// The position of the first sample.
+    pub min: Vec3,
+    /// The position of the last sample.
+    pub max: Vec3,
+    /// The samples ordered with x varying fastest, then y, then z.
+    pub values: Vec<f32>,
+}
+
+/// The six tetrahedra sharing the diagonal from the first to the last corner of a cube, whose
+/// corners are numbered by their x, y and z offsets in the first three bits.
+const TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 1, 3, 7],
+    [0, 3, 2, 7],
+    [0, 2, 6, 7],
+    [0, 6, 4, 7],
+    [0, 4, 5, 7],
+    [0, 5, 1, 7],
+];
+
+impl SdfShape {
+    /// The distance between neighbouring samples along each axis.
+    pub fn spacing(&self) -> Vec3 {
+        (self.max - self.min) / (self.size - UVec3::ONE).as_vec3()
+    }
+
+    fn sample(&self, index: UVec3) -> f32 {
+        self.values[(index.x + self.size.x * (index.y + self.size.y * index.z)) as usize]
+    }
+
+    /// Trilinearly interpolates the samples at a `point` within the bounds of the grid.
+    fn interpolate(&self, point: Vec3) -> f32 {
+        let last = self.size - UVec3::ONE;
+        let grid = ((point - self.min) / self.spacing()).clamp(Vec3::ZERO, last.as_vec3());
+        let cell = grid.floor().as_uvec3().min(last - UVec3::ONE);
+        let t = grid - cell.as_vec3();
+
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        let x = |y: u32, z: u32| lerp(
+            self.sample(cell + UVec3::new(0, y, z)),
+            self.sample(cell + UVec3::new(1, y, z)),
+            t.x,
+        );
+
+        lerp(lerp(x(0, 0), x(1, 0), t.y), lerp(x(0, 1), x(1, 1), t.y), t.z)
+    }
+
+    /// Extracts a triangle mesh of the zero level set by marching tetrahedra through the grid
+    /// with every cell divided `resolution` times along each axis, returning its vertices and the
+    /// vertex indices of each triangle wound counterclockwise when viewed from outside.
+    pub fn triangulate(&self, resolution: UVec3) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+        let cells = (self.size - UVec3::ONE) * resolution.max(UVec3::ONE);
+        let points = cells + UVec3::ONE;
+        let spacing = (self.max - self.min) / cells.as_vec3();
+
+        let index = |p: UVec3| p.x + points.x * (p.y + points.y * p.z);
+        let position = |p: UVec3| self.min + spacing * p.as_vec3();
+
+        let mut values = Vec::with_capacity((points.x * points.y * points.z) as usize);
+        for z in 0..points.z {
+            for y in 0..points.y {
+                for x in 0..points.x {
+                    values.push(self.interpolate(position(UVec3::new(x, y, z))));
+                }
+            }
+        }
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let mut edges: HashMap<(u32, u32), u32> = HashMap::new();
+
+        // The vertex where the surface crosses the edge from a grid point `a` inside the shape to
+        // a grid point `b` outside of it, shared by every triangle touching that edge. Crossings
+        // exactly at `b` are shared by every edge meeting there.
+        let mut crossing = |a: UVec3, b: UVec3, vertices: &mut Vec<Vec3>| {
+            let (ia, ib) = (index(a), index(b));
+            let (va, vb) = (values[ia as usize], values[ib as usize]);
+            let key = if vb == 0.0 { (ib, ib) } else { (ia, ib) };
+
+            *edges.entry(key).or_insert_with(|| {
+                vertices.push(position(a).lerp(position(b), va / (va - vb)));
+                vertices.len() as u32 - 1
+            })
+        };
+
+        for z in 0..cells.z {
+            for y in 0..cells.y {
+                for x in 0..cells.x {
+                    let corners: [UVec3; 8] = std::array::from_fn(|i| {
+                        UVec3::new(x, y, z) + UVec3::new(i as u32 & 1, (i as u32 >> 1) & 1, (i as u32 >> 2) & 1)
+                    });
+
+                    for tetrahedron in TETRAHEDRA {
+                        let corners = tetrahedron.map(|i| corners[i]);
+                        let (inside, outside): (Vec<UVec3>, Vec<UVec3>) = corners
+                            .into_iter()
+                            .partition(|&p| values[index(p) as usize] < 0.0);
+
+                        let triangles = match (inside.len(), outside.len()) {
+                            (1, 3) => vec![outside.iter().map(|&o| crossing(inside[0], o, &mut vertices)).collect::<Vec<_>>()],
+                            (3, 1) => vec![inside.iter().map(|&i| crossing(i, outside[0], &mut vertices)).collect()],
+                            (2, 2) => {
+                                let quad = [
+                                    crossing(inside[0], outside[0], &mut vertices),
+                                    crossing(inside[0], outside[1], &mut vertices),
+                                    crossing(inside[1], outside[1], &mut vertices),
+                                    crossing(inside[1], outside[0], &mut vertices),
+                                ];
+                                vec![vec![quad[0], quad[1], quad[2]], vec![quad[0], quad[2], quad[3]]]
+                            },
+                            _ => continue,
+                        };
+
+                        let centroid = |points: &[UVec3]| points.iter().map(|&p| position(p)).sum::<Vec3>() / points.len() as f32;
+                        let outward = centroid(&outside) - centroid(&inside);
+
+                        for triangle in triangles {
+                            let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+                            let [pa, pb, pc] = [a, b, c].map(|i| vertices[i as usize]);
+                            let normal = (pb - pa).cross(pc - pa);
+
+                            // Surfaces passing exactly through grid points collapse triangles.
+                            if normal.length_squared() <= f32::EPSILON * spacing.length_squared() * spacing.length_squared() {
+                                continue;
+                            }
+
+                            if normal.dot(outward) > 0.0 {
+                                indices.push([a, b, c]);
+                            } else {
+                                indices.push([a, c, b]);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        (vertices, indices)
+    }
+
+    /// The centers of the cells of the grid which lie inside the shape.
+    fn inside_cells(&self) -> impl Iterator<Item = Vec3> + '_ {
+        let cells = self.size - UVec3::ONE;
+        let spacing = self.spacing();
+
+        (0..cells.z).flat_map(move |z| (0..cells.y).flat_map(move |y| (0..cells.x).map(move |x| {
+            self.min + spacing * (UVec3::new(x, y, z).as_vec3() + 0.5)
+        }))).filter(|&center| self.interpolate(center) < 0.0)
+    }
+}
+
+impl AbstractShape for SdfShape {
+    /// Interpolates the samples inside the bounds of the grid, and adds the distance to the bounds
+    /// outside of them.
+    fn sd(&self, point: Vec3) -> f32 {
+        let clamped = point.clamp(self.min, self.max);
+        self.interpolate(clamped) + (point - clamped).length()
+    }
+
+    /// Samples the vertices of the mesh extracted by [`SdfShape::triangulate`].
+    fn vertices(&self, resolution: UVec3) -> Vec<Vec3> {
+        self.triangulate(resolution).0
+    }
+
+    /// Treats every cell of the grid whose center lies inside the shape as a solid box.
+    fn moments(&self, scale: Vec3) -> Vec3 {
+        let centers: Vec<Vec3> = self.inside_cells().map(|center| scale * center).collect();
+
+        if centers.is_empty() {
+            return Vec3::ZERO;
+        }
+
+        let count = centers.len() as f32;
+        let outer = |v: Vec3| Mat3::from_cols(v * v.x, v * v.y, v * v.z);
+        let centroid = centers.iter().sum::<Vec3>() / count;
+        let cell = scale * self.spacing();
+
+        let covariance = centers.iter().fold(Mat3::ZERO, |acc, &center| acc + outer(center)) * (1.0 / count)
+            - outer(centroid)
+            + Mat3::from_diagonal(cell * cell / 12.0);
+        let (xx, yy, zz) = (covariance.x_axis.x, covariance.y_axis.y, covariance.z_axis.z);
+
+        Vec3::new(yy + zz, xx + zz, xx + yy)
+    }
+
+    fn volume(&self, scale: Vec3) -> f32 {
+        let spacing = scale * self.spacing();
+        (self.inside_cells().count() as f32 * spacing.x * spacing.y * spacing.z).abs()
+    }
+
+    fn aabb(&self) -> Aabb {
+        Aabb::new(self.min, self.max)
+    }
+}
+
+impl From<ParsedSdf> for SdfShape {
+    fn from(value: ParsedSdf) -> Self {
+        SdfShape {
+            size: value.size,
+            min: value.min,
+            max: value.max,
+            values: value.values,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::{UVec3, Vec2, Vec3, Vec3Swizzles};
+
+use crate::aabb::Aabb;
+
+use super::AbstractShape;
+
+/// A solid torus centered at the origin whose tube circles the y-axis.
+///
+/// Tori are not convex, so they are always collided by sampling their vertices.
+#[derive(Debug)]
+pub struct TorusShape {
+    /// The distance from the center to the middle of the tube.
+    pub major_radius: f32,
+    /// The radius of the tube.
+    pub minor_radius: f32,
+}
+
+impl TorusShape {
+    /// The number of points around the major circle per unit of `vertex_resolution.x`.
+    pub const SEGMENTS: u32 = 16;
+    /// The number of points around the tube per unit of `vertex_resolution.y`.
+    pub const TUBE_SEGMENTS: u32 = 8;
+}
+
+impl AbstractShape for TorusShape {
+    fn sd(&self, point: Vec3) -> f32 {
+        Vec2::new(point.xz().length() - self.major_radius, point.y).length() - self.minor_radius
+    }
+
+    /// Samples `resolution.x` times [`TorusShape::SEGMENTS`] circles around the major circle, each
+    /// with `resolution.y` times [`TorusShape::TUBE_SEGMENTS`] points around the tube.
+    fn vertices(&self, resolution: UVec3) -> Vec<Vec3> {
+        let segments = (Self::SEGMENTS * resolution.x).max(3);
+        let tube_segments = (Self::TUBE_SEGMENTS * resolution.y).max(3);
+
+        let mut vertices = Vec::with_capacity((segments * tube_segments) as usize);
+
+        for u in 0..segments {
+            let (sin_u, cos_u) = (TAU * u as f32 / segments as f32).sin_cos();
+
+            for v in 0..tube_segments {
+                let (sin_v, cos_v) = (TAU * v as f32 / tube_segments as f32).sin_cos();
+                let radius = self.major_radius + self.minor_radius * cos_v;
+
+                vertices.push(Vec3::new(radius * cos_u, self.minor_radius * sin_v, radius * sin_u));
+            }
+        }
+
+        vertices
+    }
+
+    fn moments(&self, scale: Vec3) -> Vec3 {
+        let r2 = self.major_radius * self.major_radius;
+        let a2 = self.minor_radius * self.minor_radius;
+
+        // The mean squared distances from each plane through the center, which stretch with the
+        // scale along their axis.
+        let xx = (r2 / 2.0 + 3.0 * a2 / 8.0) * scale.x * scale.x;
+        let yy = a2 / 4.0 * scale.y * scale.y;
+        let zz = (r2 / 2.0 + 3.0 * a2 / 8.0) * scale.z * scale.z;
+
+        Vec3::new(yy + zz, xx + zz, xx + yy)
+    }
+
+    fn volume(&self, scale: Vec3) -> f32 {
+        (2.0 * PI * PI * self.major_radius * self.minor_radius * self.minor_radius * scale.x * scale.y * scale.z).abs()
+    }
+
+    fn aabb(&self) -> Aabb {
+        let outer = self.major_radius + self.minor_radius;
+        let extent = Vec3::new(outer, self.minor_radius, outer);
+        Aabb::new(-extent, extent)
+    }
+}
@@ -0,0 +1,241 @@
+use std::f32::consts::PI;
+
+use glam::{Mat3, UVec3, Vec3};
+
+use crate::{aabb::Aabb, bvh::Bvh};
+
+use super::{convex_hull::ConvexHullShape, AbstractShape};
+
+/// An arbitrary closed triangle mesh.
+#[derive(Debug)]
+pub struct TriangleMeshShape {
+    pub vertices: Vec<Vec3>,
+    /// The vertex indices of each triangle, wound counter-clockwise when viewed from outside.
+    pub indices: Vec<[u32; 3]>,
+    /// The hierarchy over the bounding boxes of the triangles, in the same order as `indices`.
+    /// It is built once in local coordinates when the mesh is loaded, so it is reused however the
+    /// rigid body moves.
+    bvh: Bvh,
+}
+
+impl TriangleMeshShape {
+    pub fn new(vertices: Vec<Vec3>, indices: Vec<[u32; 3]>) -> TriangleMeshShape {
+        let aabbs: Vec<_> = indices.iter()
+            .map(|&[a, b, c]| Aabb::from_point(vertices[a as usize]).include(vertices[b as usize]).include(vertices[c as usize]))
+            .collect();
+
+        TriangleMeshShape { bvh: Bvh::new(&aabbs), vertices, indices }
+    }
+
+    #[inline]
+    fn triangle(&self, i: usize) -> [Vec3; 3] {
+        self.indices[i].map(|v| self.vertices[v as usize])
+    }
+
+    #[inline]
+    fn triangles(&self) -> impl Iterator<Item = [Vec3; 3]> + '_ {
+        (0..self.indices.len()).map(|i| self.triangle(i))
+    }
+
+    /// The triangles whose bounding boxes overlap `aabb`, in local coordinates.
+    pub fn triangles_in(&self, aabb: Aabb) -> impl Iterator<Item = [Vec3; 3]> + '_ {
+        self.bvh.aabb_query(aabb).into_iter().map(|i| self.triangle(i as usize))
+    }
+
+    /// The generalized winding number of this mesh around `point`, which is one inside a closed
+    /// mesh (or negative one if its triangles are wound inwards) and zero outside of it.
+    fn winding_number(&self, point: Vec3) -> f32 {
+        let solid_angle: f32 = self.triangles()
+            .map(|[a, b, c]| {
+                let (a, b, c) = (a - point, b - point, c - point);
+                let (la, lb, lc) = (a.length(), b.length(), c.length());
+
+                let numerator = a.dot(b.cross(c));
+                let denominator = la * lb * lc + a.dot(b) * lc + b.dot(c) * la + c.dot(a) * lb;
+
+                2.0 * numerator.atan2(denominator)
+            })
+            .sum();
+
+        solid_angle / (4.0 * PI)
+    }
+}
+
+impl AbstractShape for TriangleMeshShape {
+    fn sd(&self, point: Vec3) -> f32 {
+        let distance = self.triangles()
+            .map(|triangle| closest_point_on_triangle(point, triangle).distance(point))
+            .fold(f32::INFINITY, f32::min);
+
+        if self.winding_number(point).abs() > 0.5 {
+            -distance
+        } else {
+            distance
+        }
+    }
+
+    /// Intersects the ray with the triangles whose bounding boxes it passes through, rather than
+    /// marching along it.
+    fn raycast(&self, origin: Vec3, direction: Vec3, max_t: f32) -> Option<f32> {
+        if direction.length_squared() <= f32::EPSILON * f32::EPSILON || self.sd(origin) <= 0.0 {
+            return None;
+        }
+
+        self.bvh.ray_query(origin, direction, max_t)
+            .into_iter()
+            .filter_map(|i| ray_triangle_intersection(origin, direction, self.triangle(i as usize)))
+            .filter(|&t| t <= max_t)
+            .min_by(f32::total_cmp)
+    }
+
+    fn vertices(&self, _resolution: UVec3) -> Vec<Vec3> {
+        self.vertices.clone()
+    }
+
+    fn moments(&self, scale: Vec3) -> Vec3 {
+        // Treating the mesh as a uniform density convex hull avoids relying on it being closed.
+        ConvexHullShape::new(&self.vertices).moments(scale)
+    }
+
+    fn volume(&self, scale: Vec3) -> f32 {
+        // Matches the convex hull used for the moments of inertia.
+        ConvexHullShape::new(&self.vertices).volume(scale)
+    }
+
+    fn aabb(&self) -> Aabb {
+        let Some(&first) = self.vertices.first() else {
+            return Aabb::from_point(Vec3::ZERO);
+        };
+
+        self.vertices.iter().fold(Aabb::from_point(first), |aabb, &v| aabb.include(v))
+    }
+}
+
+/// Computes the principal moments of inertia with unit mass of the closed solid bounded by the
+/// given triangles after scaling its vertices by `scale`, about its center of mass.
+///
+/// The solid is split into tetrahedra between each triangle and the centroid of the vertices.
+/// Off-diagonal products of inertia are ignored. Returns `None` if the solid has no volume.
+pub fn polyhedron_moments(vertices: &[Vec3], indices: &[[u32; 3]], scale: Vec3) -> Option<Vec3> {
+    let origin = vertices.iter().fold(Vec3::ZERO, |acc, &v| acc + scale * v) / vertices.len().max(1) as f32;
+
+    let mut volume = 0.0;
+    let mut first_moment = Vec3::ZERO;
+    let mut covariance = Mat3::ZERO;
+
+    for &[a, b, c] in indices {
+        let [a, b, c] = [a, b, c].map(|i| scale * vertices[i as usize] - origin);
+
+        let tetrahedron_volume = a.dot(b.cross(c)) / 6.0;
+        let sum = a + b + c;
+
+        volume += tetrahedron_volume;
+        first_moment += tetrahedron_volume * sum / 4.0;
+
+        // The covariance of a tetrahedron with one vertex at the origin.
+        let outer = |v: Vec3| Mat3::from_cols(v * v.x, v * v.y, v * v.z);
+        covariance += (tetrahedron_volume / 20.0) * (outer(a) + outer(b) + outer(c) + outer(sum));
+    }
+
+    if volume.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    let center_of_mass = first_moment / volume;
+    let covariance = covariance * (1.0 / volume) - Mat3::from_cols(
+        center_of_mass * center_of_mass.x,
+        center_of_mass * center_of_mass.y,
+        center_of_mass * center_of_mass.z,
+    );
+
+    let (xx, yy, zz) = (covariance.x_axis.x, covariance.y_axis.y, covariance.z_axis.z);
+
+    Some(Vec3::new(yy + zz, xx + zz, xx + yy))
+}
+
+/// Computes the volume of the closed solid bounded by the given triangles after scaling its
+/// vertices by `scale`.
+pub fn polyhedron_volume(vertices: &[Vec3], indices: &[[u32; 3]], scale: Vec3) -> f32 {
+    let volume: f32 = indices.iter().map(|&[a, b, c]| {
+        let [a, b, c] = [a, b, c].map(|i| scale * vertices[i as usize]);
+        a.dot(b.cross(c)) / 6.0
+    }).sum();
+
+    volume.abs()
+}
+
+/// The `t` at which the ray `origin + t * direction` passes through the triangle `[a, b, c]` from
+/// either side, if it does for a positive `t`.
+pub fn ray_triangle_intersection(origin: Vec3, direction: Vec3, [a, b, c]: [Vec3; 3]) -> Option<f32> {
+    let ab = b - a;
+    let ac = c - a;
+
+    let p = direction.cross(ac);
+    let determinant = ab.dot(p);
+
+    if determinant.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    let inverse = 1.0 / determinant;
+    let ao = origin - a;
+
+    let u = ao.dot(p) * inverse;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = ao.cross(ab);
+    let v = direction.dot(q) * inverse;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = ac.dot(q) * inverse;
+    (t > 0.0).then_some(t)
+}
+
+/// The closest point to `p` on the triangle `[a, b, c]`.
+pub fn closest_point_on_triangle(p: Vec3, [a, b, c]: [Vec3; 3]) -> Vec3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+
+    let bp = p - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = p - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+
+    let denominator = 1.0 / (va + vb + vc);
+    a + ab * (vb * denominator) + ac * (vc * denominator)
+}
@@ -240,6 +240,17 @@ impl From<&Transform> for WriteTransform {
         WriteTransform {
             translate: value.translate,
             rotate: value.rotate,
+            scale: value.scale,
+        }
+    }
+}
+
+impl From<WriteTransform> for Transform {
+    fn from(value: WriteTransform) -> Self {
+        Transform {
+            translate: value.translate,
+            rotate: value.rotate,
+            scale: value.scale,
         }
     }
 }
@@ -0,0 +1,79 @@
+use glam::Vec3;
+use sokudo_io::read::water::ParsedWater;
+
+use crate::{collider::ColliderBody, shape::AbstractShape};
+
+/// A body of water filling everything below a horizontal plane, which pushes the rigid bodies
+/// submerged in it up against gravity and slows them down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Water {
+    /// The height of the surface of the water.
+    pub height: f32,
+    /// The density of the water.
+    pub density: f32,
+    /// The fraction of linear velocity a fully submerged body loses per second.
+    pub drag: f32,
+}
+
+/// The buoyancy on a body partially or fully submerged in [`Water`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Buoyancy {
+    /// The buoyant force, in global coordinates.
+    pub force: Vec3,
+    /// The center of buoyancy the force is applied at, in global coordinates.
+    pub center: Vec3,
+    /// The fraction of the body below the surface.
+    pub submerged: f32,
+}
+
+impl Water {
+    /// The buoyancy on `body` when its center of mass is at `position`, or `None` if it is not in
+    /// the water.
+    ///
+    /// The submerged part of the body is approximated by clipping its bounding box at the surface,
+    /// so the force is `density * submerged_volume * -gravity` and acts at the center of the
+    /// clipped box. Particles are too small to float.
+    pub fn buoyancy(&self, body: &ColliderBody, position: Vec3, gravity: Vec3) -> Option<Buoyancy> {
+        let ColliderBody::Rigid(rb) = body else {
+            return None;
+        };
+
+        let aabb = body.aabb(position);
+
+        if !aabb.is_finite() || aabb.min.y >= self.height {
+            return None;
+        }
+
+        let top = aabb.max.y.min(self.height);
+        let submerged = (top - aabb.min.y) / (aabb.max.y - aabb.min.y);
+        let volume = submerged * rb.shape.volume(rb.scale);
+
+        let center = 0.5 * (aabb.min + aabb.max);
+
+        Some(Buoyancy {
+            force: -self.density * volume * gravity,
+            center: Vec3::new(center.x, 0.5 * (aabb.min.y + top), center.z),
+            submerged,
+        })
+    }
+}
+
+impl From<ParsedWater> for Water {
+    fn from(value: ParsedWater) -> Self {
+        Water {
+            height: value.height,
+            density: value.density,
+            drag: value.drag,
+        }
+    }
+}
+
+impl From<Water> for ParsedWater {
+    fn from(value: Water) -> Self {
+        ParsedWater {
+            height: value.height,
+            density: value.density,
+            drag: value.drag,
+        }
+    }
+}
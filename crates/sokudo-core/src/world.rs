@@ -1,167 +1,833 @@
+use std::{collections::{BTreeMap, BTreeSet}, path};
+
 use glam::{Quat, Vec3};
-use sokudo_io::{read::ParsedWorld, write::{collider::WriteCollider, inspect::InspectElements, WriteWorldState}};
+use sokudo_io::{read::{defaults::DefaultOptions, script::{ScriptAction, ScriptEntry}, ParsedWorld}, write::{checkpoint::{Checkpoint, CheckpointCollider, CheckpointContact, CheckpointManifold}, collider::{WriteCollider, WriteContact}, events::{CollisionEvent, CollisionEventKind}, inspect::InspectElements, snapshot::{Snapshot, SnapshotCollider, SnapshotEmitter, SNAPSHOT_VERSION}, ReadStateError, WriteStateError, WriteWorldState}};
+use thiserror::Error;
 
-use crate::{collider::{Collider, ColliderBody, ColliderId}, constraint::{collision::ParticleCollisionConstraint, restitution::ParticleRestitutionConstraint, Constraint, VelocityConstraint}, contact::Contact, math::skew_symmetric_mat3, rigid_body::RigidBody};
+use crate::{aabb::Aabb, broadphase::{dynamic_bvh::DynamicBvh, Broadphase}, material::MaterialCombine, collider::{Collider, ColliderBody, ColliderId, ColliderMotion}, constraint::{collision::{ParticleCollisionConstraint, ParticleParticleCollisionConstraint, RigidBodyCollisionConstraint, SeparatingCollisionConstraint}, friction::{DynamicFrictionConstraint, StaticFrictionConstraint}, restitution::RestitutionConstraint, velocity_constraint, Constraint, ContactConstraint, VelocityConstraint}, contact::{Contact, PersistentContact}, emitter::Emitter, water::Water};
 
+/// A world of colliders and the constraints between them.
+///
+/// Stepping a world is deterministic: colliders, candidate pairs, contacts and constraints are
+/// always processed in the order of their [`ColliderId`]s and never depend on hashing, so two
+/// identical worlds produce bit-identical states on the same platform. Baking the same world
+/// twice therefore produces byte-identical histories.
 pub struct World {
     pub steps: u32,
     pub dt: f32,
+    /// The number of steps simulated so far.
+    pub step: u32,
+    /// The number of substeps `dt` is divided into each step.
+    pub substeps: u32,
+    /// The number of times the constraints are projected each substep. Unlike substeps, extra
+    /// iterations do not integrate again, so they usually improve accuracy less for the same cost.
+    pub iterations: u32,
+    /// How the static and dynamic friction coefficients of two colliders are combined into those
+    /// of a contact between them.
+    pub friction_combine: MaterialCombine,
+    /// How the restitution coefficients of two colliders are combined into that of a contact
+    /// between them.
+    pub restitution_combine: MaterialCombine,
+    /// The acceleration due to gravity, applied to every dynamic collider.
     pub gravity: Vec3,
+    /// The method used to find pairs of colliders which may be in contact.
+    pub broadphase: Broadphase,
+    /// The water the rigid bodies float in, if any.
+    pub water: Option<Water>,
+    /// The linear speed below which a collider is considered to be at rest.
+    pub sleep_linear_velocity: f32,
+    /// The angular speed below which a collider is considered to be at rest.
+    pub sleep_angular_velocity: f32,
+    /// The number of consecutive frames a collider must be at rest before it falls asleep.
+    /// Colliders never fall asleep when this is zero.
+    pub sleep_window: u32,
+    /// The distance a particle must travel within a single substep before it is swept against
+    /// the rigid bodies to keep it from tunnelling through them. Particles with a larger radius
+    /// are only swept once they travel further than it.
+    pub ccd_threshold: f32,
+    /// The relative normal speed below which a contact is treated as perfectly inelastic,
+    /// whatever the restitution coefficients of its colliders.
+    pub restitution_velocity_threshold: f32,
+    /// Whether the collision constraints of contacts which persist between substeps start from the
+    /// Lagrange multipliers they accumulated in the previous substep, which lets tall stacks
+    /// settle with fewer iterations.
+    pub warm_start: bool,
+    /// The largest vertex resolution of a rigid body along each axis, to which the resolutions of
+    /// the rigid bodies are clamped when their vertices are computed.
+    pub max_vertex_resolution: u32,
+    /// The largest fraction of the penetration each contact already had at the start of a step
+    /// which is resolved during it, spread over its substeps. That penetration is resolved without
+    /// changing velocities, so bodies which start out deeply overlapping are eased apart over
+    /// several steps rather than launched apart at once.
+    pub max_correction_per_step: f32,
     pub colliders: Vec<Collider>,
+    /// The emitters which spawn particles into this world at the start of each step.
+    pub emitters: Vec<Emitter>,
+    /// The impulses and forces applied to colliders at the start of given steps, ordered by
+    /// their steps.
+    pub script: Vec<ScriptEntry>,
 
     pub constraints: Vec<Box<dyn Constraint>>,
-    pub collision_constraints: Vec<Box<dyn Constraint>>,
+    pub collision_constraints: Vec<Box<dyn ContactConstraint>>,
     pub velocity_constraints: Vec<Box<dyn VelocityConstraint>>,
     pub velocity_collision_constraints: Vec<Box<dyn VelocityConstraint>>,
     pub lagrange: Vec<f32>,
+    pub angular_lagrange: Vec<f32>,
+    pub friction_lagrange: Vec<f32>,
+    /// Whether each collision constraint started from the Lagrange multiplier of a persistent
+    /// contact this substep.
+    pub warm_started: Vec<bool>,
 
     pub inspector: InspectElements,
+    /// The collision events of the last step, ordered by the IDs of their colliders.
+    pub events: Vec<CollisionEvent>,
+    /// The pairs of colliders which were touching in the last step, with the smaller ID first.
+    pub touching: BTreeSet<(ColliderId, ColliderId)>,
+    /// The pairs of colliders involving a sensor which overlapped in any substep of the current
+    /// step, with the smaller ID first.
+    pub overlapping: BTreeSet<(ColliderId, ColliderId)>,
+    /// The contact manifolds generated by each pair of colliders in the last substep, from which
+    /// the contacts of the next substep are kept in place.
+    pub manifolds: BTreeMap<(ColliderId, ColliderId), Vec<PersistentContact>>,
+    residual: SolverResidual,
+    /// The hierarchy kept between steps when the broadphase is [`Broadphase::DynamicBvh`].
+    broadphase_tree: DynamicBvh,
+}
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    /// A snapshot writing error.
+    #[error(transparent)]
+    WriteState(#[from] WriteStateError),
+    /// A snapshot reading error.
+    #[error(transparent)]
+    ReadState(#[from] ReadStateError),
+    /// A constraint which cannot be given in a world file, and so cannot be stored in a snapshot.
+    #[error("constraint {index} cannot be stored in a snapshot")]
+    UnsupportedConstraint {
+        index: usize,
+    },
+    /// A velocity constraint which cannot be given in a world file, and so cannot be stored in a
+    /// snapshot.
+    #[error("velocity constraint {index} cannot be stored in a snapshot")]
+    UnsupportedVelocityConstraint {
+        index: usize,
+    },
+}
+
+/// The error left in the active constraints of a world once the positions of the last substep of
+/// a step have been solved, which shrinks as the numbers of substeps and iterations grow.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SolverResidual {
+    /// The largest `|c|` of the active constraints.
+    pub max: f32,
+    /// The root mean square of `|c|` over the active constraints.
+    pub rms: f32,
+    /// The number of active constraints, which are every joint and the collision constraints of
+    /// the contacts which were still penetrating.
+    pub constraints: usize,
 }
 
 impl World {
+    pub const DEFAULT_SLEEP_LINEAR_VELOCITY: f32 = 0.05;
+    pub const DEFAULT_SLEEP_ANGULAR_VELOCITY: f32 = 0.05;
+    pub const DEFAULT_SLEEP_WINDOW: u32 = 30;
+    pub const DEFAULT_CCD_THRESHOLD: f32 = 0.05;
+
+    /// Creates an empty world stepped by `dt` seconds under `gravity`, with the same options as a
+    /// world file which omits them.
+    ///
+    /// ```
+    /// use glam::Vec3;
+    /// use sokudo_core::{
+    ///     collider::{Collider, ColliderBody},
+    ///     rigid_body::RigidBody,
+    ///     shape::{cuboid::CuboidShape, half_space::HalfSpaceShape, Shape},
+    ///     world::World,
+    /// };
+    ///
+    /// let mut world = World::new(1.0 / 60.0, Vec3::new(0.0, -9.81, 0.0));
+    ///
+    /// let floor = RigidBody::new(Shape::HalfSpace(HalfSpaceShape { normal: Vec3::Y }), 1.0);
+    /// world.add_collider(Collider::new(ColliderBody::Rigid(floor), Vec3::ZERO));
+    ///
+    /// let boxes = [0.5, 1.5].map(|height| {
+    ///     let cube = RigidBody::new(Shape::Cuboid(CuboidShape), 1.0);
+    ///     let position = Vec3::new(0.0, height, 0.0);
+    ///     world.add_collider(Collider::new(ColliderBody::Rigid(cube), position))
+    /// });
+    ///
+    /// let mut state = world.state();
+    ///
+    /// for _ in 0..100 {
+    ///     state = world.step();
+    /// }
+    ///
+    /// assert_eq!(state.step, 100);
+    ///
+    /// for (id, height) in boxes.into_iter().zip([0.5, 1.5]) {
+    ///     let translate = state.colliders[id.0 as usize].transform.translate;
+    ///     assert!((translate.y - height).abs() < 0.05, "box rested at {translate}");
+    /// }
+    /// ```
+    pub fn new(dt: f32, gravity: Vec3) -> World {
+        World {
+            steps: 0,
+            dt,
+            step: 0,
+            substeps: DefaultOptions::substeps(),
+            iterations: DefaultOptions::iterations(),
+            friction_combine: MaterialCombine::default(),
+            restitution_combine: MaterialCombine::default(),
+            gravity,
+            broadphase: Broadphase::SweepAndPrune,
+            water: None,
+            sleep_linear_velocity: Self::DEFAULT_SLEEP_LINEAR_VELOCITY,
+            sleep_angular_velocity: Self::DEFAULT_SLEEP_ANGULAR_VELOCITY,
+            sleep_window: Self::DEFAULT_SLEEP_WINDOW,
+            ccd_threshold: Self::DEFAULT_CCD_THRESHOLD,
+            restitution_velocity_threshold: DefaultOptions::restitution_velocity_threshold(),
+            max_vertex_resolution: DefaultOptions::max_vertex_resolution(),
+            max_correction_per_step: DefaultOptions::max_correction_per_step(),
+            warm_start: true,
+            colliders: Vec::new(),
+            emitters: Vec::new(),
+            script: Vec::new(),
+
+            constraints: Vec::new(),
+            velocity_constraints: Vec::new(),
+            collision_constraints: Vec::new(),
+            velocity_collision_constraints: Vec::new(),
+            lagrange: Vec::new(),
+            angular_lagrange: Vec::new(),
+            friction_lagrange: Vec::new(),
+            warm_started: Vec::new(),
+
+            inspector: InspectElements::default(),
+            events: Vec::new(),
+            touching: BTreeSet::new(),
+            overlapping: BTreeSet::new(),
+            manifolds: BTreeMap::new(),
+            residual: SolverResidual::default(),
+            broadphase_tree: DynamicBvh::default(),
+        }
+    }
+
+    /// Adds `collider` to this world, assigning it the next ID and computing the vertices and
+    /// inertia tensor of its rigid body.
+    pub fn add_collider(&mut self, mut collider: Collider) -> ColliderId {
+        let id = ColliderId::new(self.colliders.len());
+        collider.id = id.0;
+
+        if let ColliderBody::Rigid(rb) = &mut collider.body {
+            rb.compute_vertices(self.max_vertex_resolution);
+            rb.compute_inertia_tensor();
+        }
+
+        self.colliders.push(collider);
+        id
+    }
+
+    /// Whether every dynamic collider of this world is asleep, so that stepping it further changes
+    /// nothing. This is never the case while colliders cannot fall asleep, or while the world has
+    /// kinematic colliders or emitters, which keep moving and spawning on their own.
+    pub fn is_at_rest(&self) -> bool {
+        self.sleep_window > 0
+            && self.emitters.is_empty()
+            && self.colliders.iter().all(|collider| match collider.motion {
+                ColliderMotion::Dynamic => collider.asleep,
+                ColliderMotion::Kinematic(_) => false,
+                ColliderMotion::Locked => true,
+            })
+    }
+
+    /// Removes the collider `id` from this world. Its ID is never reused, and the collider is
+    /// kept in place so that the IDs of the other colliders do not change, but it no longer moves,
+    /// collides or appears in the states of this world.
+    pub fn remove_collider(&mut self, id: ColliderId) {
+        let collider = &mut self.colliders[id.0 as usize];
+
+        collider.removed = true;
+        collider.motion = ColliderMotion::Locked;
+        collider.asleep = false;
+        collider.velocity = Vec3::ZERO;
+        collider.previous_velocity = Vec3::ZERO;
+
+        if let ColliderBody::Rigid(rb) = &mut collider.body {
+            rb.angular_velocity = Vec3::ZERO;
+            rb.previous_angular_velocity = Vec3::ZERO;
+        }
+    }
+
+    /// Adds `constraint` between colliders of this world, which is solved from the next step.
+    pub fn add_constraint(&mut self, constraint: Box<dyn Constraint>) {
+        self.constraints.push(constraint);
+    }
+
     pub fn initialize(&mut self) {
         for collider in self.colliders.iter_mut() {
             if let ColliderBody::Rigid(rb) = &mut collider.body {
-                rb.compute_vertices();
+                rb.compute_vertices(self.max_vertex_resolution);
                 rb.compute_inertia_tensor();
             }
         }
+
+        // Kinematic colliders start on their paths instead of jumping onto them.
+        self.move_kinematic(self.step as f32 * self.dt);
+
+        for collider in self.colliders.iter_mut().filter(|c| matches!(c.motion, ColliderMotion::Kinematic(_))) {
+            collider.previous_position = collider.position;
+
+            if let ColliderBody::Rigid(rb) = &mut collider.body {
+                rb.previous_rotation = rb.rotation;
+            }
+        }
     }
 
-    pub fn step(&mut self) {
+    /// Steps this world by `dt` and returns its new state.
+    pub fn step(&mut self) -> WriteWorldState {
+        self.advance();
+        self.state()
+    }
+
+    /// Steps this world by `dt` without collecting its new state, for when it is not needed or a
+    /// [`World::verbose_state`] is collected instead.
+    pub fn advance(&mut self) {
         self.inspector.reset();
+        self.overlapping.clear();
+        self.emit();
+        self.apply_script();
+
+        let h = self.dt / self.substeps as f32;
+        let mut impulses = BTreeMap::new();
+
+        for substep in 0..self.substeps {
+            self.integrate(h);
+            self.lock_axes();
+            self.move_kinematic((self.step * self.substeps + substep + 1) as f32 * h);
+
+            // TODO: Collect collision pairs once per step and narrow phase per substep
+            self.create_collisions();
+            self.lagrange = vec![0.0; self.constraints.len() + self.collision_constraints.len()];
+            self.angular_lagrange = vec![0.0; self.lagrange.len()];
+            self.friction_lagrange = vec![0.0; self.collision_constraints.len()];
+            self.warm_started = vec![false; self.collision_constraints.len()];
+
+            self.depenetrate(h);
+
+            if self.warm_start {
+                self.warm_start_collisions();
+            }
+
+            for _ in 0..self.iterations {
+                self.solve_constraints(h);
+            }
+
+            if substep + 1 == self.substeps {
+                self.residual = self.solver_residual();
+            }
+
+            self.store_contact_lagrange();
+            self.lock_axes();
+
+            self.accumulate_contact_impulses(&mut impulses, h);
+
+            self.update_velocities(h);
+            self.solve_velocities(h);
+            self.apply_rolling_friction(h);
+            self.lock_axes();
+            self.clamp_velocities();
+        }
+
+        for collider in self.colliders.iter_mut() {
+            if let ColliderBody::Rigid(rb) = &mut collider.body {
+                rb.clear_forces();
+            }
+        }
+
+        self.update_sleeping();
+        self.inspect_contacts(h);
+        self.step += 1;
+        self.record_collision_events(impulses);
+    }
+
+    /// Removes the particles of each emitter which have outlived their lifetime and spawns those
+    /// which are due this step.
+    fn emit(&mut self) {
+        let time = self.step as f32 * self.dt;
+        let mut next_id = self.colliders.len() as u32;
+
+        for i in 0..self.emitters.len() {
+            let (expired, spawned) = self.emitters[i].update(time, self.dt, &mut next_id);
+
+            for id in expired {
+                self.remove_collider(id);
+            }
+
+            for particle in spawned {
+                self.add_collider(particle);
+            }
+        }
+    }
+
+    /// The constraint error left after the last step. See [`SolverResidual`].
+    pub fn last_residual(&self) -> SolverResidual {
+        self.residual
+    }
+
+    fn solver_residual(&self) -> SolverResidual {
+        let c = |constraint: &dyn Constraint| {
+            let bodies: Vec<_> = constraint.bodies().iter().map(|id| &self.colliders[id.0 as usize]).collect();
+            constraint.c(&bodies)
+        };
+
+        let errors: Vec<f32> = self.constraints.iter()
+            .map(|constraint| c(constraint.as_ref()).abs())
+            .chain(self.collision_constraints.iter()
+                .map(|constraint| c(constraint.as_ref()))
+                .filter(|&penetration| penetration > 0.0))
+            .collect();
+
+        if errors.is_empty() {
+            return SolverResidual::default();
+        }
+
+        SolverResidual {
+            max: errors.iter().copied().fold(0.0, f32::max),
+            rms: (errors.iter().map(|error| error * error).sum::<f32>() / errors.len() as f32).sqrt(),
+            constraints: errors.len(),
+        }
+    }
+
+    /// Applies the entries of the script for the current step, skipping those of colliders which
+    /// do not exist during it.
+    fn apply_script(&mut self) {
+        let start = self.script.partition_point(|entry| entry.step < self.step);
+
+        for entry in self.script[start..].iter().take_while(|entry| entry.step == self.step) {
+            let Some(collider) = self.colliders.get_mut(entry.collider as usize).filter(|c| !c.removed) else {
+                continue;
+            };
+
+            match (entry.action, &mut collider.body) {
+                (ScriptAction::Impulse(impulse), _) => collider.apply_impulse(impulse),
+                (ScriptAction::Force(force), ColliderBody::Rigid(rb)) => rb.apply_force(force),
+                // Particles do not accumulate forces, but a force held for a step changes their
+                // momentum exactly as much as the equivalent impulse.
+                (ScriptAction::Force(force), ColliderBody::Particle(_)) => collider.apply_impulse(force * self.dt),
+            }
+        }
+    }
+
+    /// Pushes apart the bodies of each contact which already penetrated at the start of the substep
+    /// by its share of `max_correction_per_step` of that penetration, moving their previous
+    /// positions and rotations along with them so that the correction does not change their
+    /// velocities.
+    ///
+    /// Bodies which start out deeply overlapping are thereby eased apart over several steps
+    /// instead of being launched apart by the velocity a full correction would give them.
+    fn depenetrate(&mut self, h: f32) {
+        // The fraction is compounded over the substeps, so each of them resolves the share which
+        // adds up to `max_correction_per_step` over the whole step.
+        let fraction = 1.0 - (1.0 - self.max_correction_per_step).powf(1.0 / self.substeps as f32);
+
+        for constraint in self.collision_constraints.iter() {
+            let ids = constraint.bodies();
+            let &[a, b, ..] = ids.as_slice() else {
+                continue;
+            };
+
+            let existing_depth = existing_depth(&self.colliders, constraint.as_ref());
+
+            if existing_depth <= 0.0 {
+                continue;
+            }
+
+            let depth = constraint.contact().current_signed_depth(&self.colliders[a.0 as usize], &self.colliders[b.0 as usize]);
+            let separating = SeparatingCollisionConstraint {
+                collision: constraint.as_ref(),
+                target_depth: depth - fraction * existing_depth,
+            };
 
-        // TODO: Collect collision pairs
+            let delta_lagrange = positional_delta_lagrange(&self.colliders, &separating, 0.0, h);
+            let before: Vec<_> = ids.iter().map(|id| (self.colliders[id.0 as usize].position, self.colliders[id.0 as usize].rotation())).collect();
+
+            apply_positional_correction(&mut self.colliders, &separating, delta_lagrange);
+
+            for (id, (position, rotation)) in ids.into_iter().zip(before) {
+                let collider = &mut self.colliders[id.0 as usize];
+                collider.previous_position += collider.position - position;
+
+                if let ColliderBody::Rigid(rb) = &mut collider.body {
+                    rb.previous_rotation = (rb.rotation * rotation.inverse() * rb.previous_rotation).normalize();
+                }
+            }
+        }
+    }
+
+    /// Applies the Lagrange multiplier each collision constraint of a persistent contact
+    /// accumulated in the previous substep before solving, so the solver only has to correct the
+    /// change in the contact since then rather than resolving it from scratch.
+    fn warm_start_collisions(&mut self) {
+        let collisions = self.collision_constraints.iter()
+            .zip(self.lagrange[self.constraints.len()..].iter_mut())
+            .zip(self.warm_started.iter_mut());
+
+        for ((constraint, lagrange), warm_started) in collisions {
+            let Some(previous) = persistent_contact(&self.manifolds, constraint.as_ref()) else {
+                continue;
+            };
+
+            if previous.lagrange != 0.0 {
+                *lagrange = previous.lagrange;
+                *warm_started = true;
+                apply_positional_correction(&mut self.colliders, constraint.as_ref(), previous.lagrange);
+            }
+        }
+    }
+
+    /// Stores the Lagrange multiplier accumulated by the collision constraint of each persistent
+    /// contact this substep in its manifold, for [`World::warm_start_collisions`] to start from.
+    fn store_contact_lagrange(&mut self) {
+        let collisions = self.collision_constraints.iter().zip(self.lagrange[self.constraints.len()..].iter());
+
+        for (constraint, &lagrange) in collisions {
+            let Some(feature) = constraint.contact().feature else {
+                continue;
+            };
+
+            let &[a, b] = constraint.bodies().as_slice() else {
+                continue;
+            };
+
+            let contact = self.manifolds.get_mut(&(a, b))
+                .and_then(|manifold| manifold.iter_mut().find(|contact| contact.feature == feature));
+
+            if let Some(contact) = contact {
+                contact.lagrange = lagrange;
+            }
+        }
+    }
+
+    /// Adds the normal impulse applied by each collision constraint this substep to the total of
+    /// its pair of colliders.
+    fn accumulate_contact_impulses(&self, impulses: &mut BTreeMap<(ColliderId, ColliderId), f32>, h: f32) {
+        let collision_lagrange = &self.lagrange[self.constraints.len()..];
+
+        for (constraint, lagrange) in self.collision_constraints.iter().zip(collision_lagrange) {
+            let &[a, b] = constraint.bodies().as_slice() else {
+                continue;
+            };
+
+            *impulses.entry((a.min(b), a.max(b))).or_insert(0.0) += lagrange.abs() / h;
+        }
+    }
+
+    /// Classifies the pairs of colliders which touched during the last step against those which
+    /// touched in the step before it, replacing `events` and `touching`.
+    fn record_collision_events(&mut self, mut impulses: BTreeMap<(ColliderId, ColliderId), f32>) {
+        let event = |(a, b): (ColliderId, ColliderId), kind, impulse| CollisionEvent {
+            step: self.step,
+            a: a.0,
+            b: b.0,
+            kind,
+            impulse,
+        };
+
+        // Overlaps with sensors are reported like contacts which never push the colliders apart.
+        for &pair in self.overlapping.iter() {
+            impulses.entry(pair).or_insert(0.0);
+        }
+
+        let mut events: Vec<_> = impulses.iter()
+            .map(|(&pair, &impulse)| {
+                let kind = if self.touching.contains(&pair) { CollisionEventKind::Stay } else { CollisionEventKind::Begin };
+                event(pair, kind, impulse)
+            })
+            .chain(self.touching.iter()
+                .filter(|pair| !impulses.contains_key(pair))
+                .map(|&pair| event(pair, CollisionEventKind::End, 0.0)))
+            .collect();
+
+        events.sort_by_key(|event| (event.a, event.b));
+
+        self.events = events;
+        self.touching = impulses.into_keys().collect();
+    }
+
+    /// Records the contacts found in the final substep of length `h` in the inspector, along with
+    /// the normal impulses their collision constraints applied.
+    fn inspect_contacts(&mut self, h: f32) {
+        let collision_lagrange = &self.lagrange[self.constraints.len()..];
+
+        for (i, (constraint, lagrange)) in self.collision_constraints.iter().zip(collision_lagrange).enumerate() {
+            let &[_, body2] = constraint.bodies().as_slice() else {
+                continue;
+            };
+
+            let body2 = &self.colliders[body2.0 as usize];
+            let contact = constraint.contact();
+            let point = body2.position + body2.rotation() * contact.local_anchor2;
+
+            self.inspector.add_contact(format!("contact {i}"), point, contact.normal, lagrange.abs() / h);
+        }
+    }
+
+    fn integrate(&mut self, h: f32) {
+        for collider in self.colliders.iter_mut().filter(|c| c.is_dynamic()) {
+            // Forces applied to a sleeping rigid body wake it up.
+            if let ColliderBody::Rigid(rb) = &collider.body {
+                if collider.asleep && (rb.force != Vec3::ZERO || rb.torque != Vec3::ZERO) {
+                    collider.wake();
+                }
+            }
+
+            if collider.asleep {
+                // Sleeping colliders stay where they are unless they are moved by a constraint.
+                collider.previous_position = collider.position;
+                collider.previous_velocity = collider.velocity;
+
+                if let ColliderBody::Rigid(rb) = &mut collider.body {
+                    rb.previous_rotation = rb.rotation;
+                    rb.previous_angular_velocity = rb.angular_velocity;
+                }
+
+                continue;
+            }
 
-        // Integrate
-        for collider in self.colliders.iter_mut().filter(|c| !c.locked) {
             let mass = collider.body.mass();
-            let external_forces = self.gravity * mass;
+            let buoyancy = self.water.and_then(|water| water.buoyancy(&collider.body, collider.position, self.gravity));
+            let external_forces = self.gravity * mass + match &collider.body {
+                ColliderBody::Rigid(rb) => rb.force,
+                ColliderBody::Particle(_) => Vec3::ZERO,
+            } + buoyancy.map_or(Vec3::ZERO, |buoyancy| buoyancy.force);
 
             collider.previous_position = collider.position;
-            collider.velocity += self.dt * external_forces / mass;
-            collider.position += self.dt * collider.velocity;
+            collider.velocity += h * external_forces / mass;
+            if let ColliderBody::Rigid(rb) = &collider.body {
+                // Drag never does more than bring the body to a stop within a substep.
+                let drag = h * rb.drag_force(collider.velocity) / mass;
+                collider.velocity += drag.clamp_length_max(collider.velocity.length());
+                collider.velocity *= (1.0 - rb.linear_damping * h).max(0.0);
+            }
+            if let (Some(water), Some(buoyancy)) = (self.water, buoyancy) {
+                collider.velocity *= (1.0 - water.drag * buoyancy.submerged * h).max(0.0);
+            }
+            collider.position += h * collider.velocity;
             collider.previous_velocity = collider.velocity;
 
             if let ColliderBody::Rigid(rb) = &mut collider.body {
-                let external_torque = Vec3::ZERO;
-
                 rb.previous_rotation = rb.rotation;
 
-                let effective_angular_inertia = rb.global_inverse_inertia();
-                let mut delta_ang_vel = self.dt * if effective_angular_inertia.is_finite() {
-                    effective_angular_inertia.inverse() * external_torque
-                } else {
-                    Vec3::ZERO
-                };
+                // The buoyant force acts at the center of buoyancy rather than the center of mass.
+                let torque = rb.torque + buoyancy.map_or(Vec3::ZERO, |buoyancy| {
+                    (buoyancy.center - collider.previous_position).cross(buoyancy.force)
+                });
+
+                let mut delta_ang_vel = h * (rb.global_inverse_inertia() * torque);
 
                 // Solve for gyroscopic torque using a more stable and accurate implicit Euler
                 // method.
-                delta_ang_vel += {
-                    let local_inertia = rb.inertia_tensor.tensor();
+                delta_ang_vel += rb.gyroscopic_angular_velocity(h) - rb.angular_velocity;
+
+                rb.angular_velocity += delta_ang_vel;
+                rb.angular_velocity *= (1.0 - rb.angular_damping * h).max(0.0);
+
+                let delta_rot = Quat::from_scaled_axis(h * rb.angular_velocity);
+                rb.rotation = (delta_rot * rb.rotation).normalize();
+
+                rb.previous_angular_velocity = rb.angular_velocity;
+            }
+        }
+    }
 
-                    let local_ang_vel = rb.rotation.inverse() * rb.angular_velocity;
-                    let angular_momentum = local_inertia * local_ang_vel;
+    /// Moves kinematic colliders to where their paths are at `time`. Their velocities follow
+    /// from the distance they moved once the substep is over, like those of every other collider.
+    fn move_kinematic(&mut self, time: f32) {
+        for collider in self.colliders.iter_mut() {
+            let Some((translate, rotate)) = collider.motion.pose(time) else {
+                continue;
+            };
 
-                    let jacobian = local_inertia + self.dt
-                        * (skew_symmetric_mat3(local_ang_vel) * local_inertia
-                            - skew_symmetric_mat3(angular_momentum));
+            collider.previous_position = collider.position;
+            collider.previous_velocity = collider.velocity;
+
+            match &mut collider.body {
+                ColliderBody::Particle(_) => collider.position = translate,
+                ColliderBody::Rigid(rb) => {
+                    rb.previous_rotation = rb.rotation;
+                    rb.previous_angular_velocity = rb.angular_velocity;
+                    rb.rotation = rotate;
+                    collider.position = translate + rotate * rb.center_of_mass;
+                },
+            }
+        }
+    }
 
-                    let f = self.dt * local_ang_vel.cross(angular_momentum);
+    /// Puts colliders which have been at rest for the whole sleep window to sleep, and wakes
+    /// sleeping colliders which have started moving again.
+    fn update_sleeping(&mut self) {
+        if self.sleep_window == 0 {
+            return;
+        }
 
-                    let delta_ang_vel = -jacobian.inverse() * f;
+        for collider in self.colliders.iter_mut().filter(|c| c.is_dynamic()) {
+            let at_rest = collider.velocity.length() < self.sleep_linear_velocity
+                && collider.angular_velocity().length() < self.sleep_angular_velocity;
 
-                    rb.rotation * delta_ang_vel
-                };
+            if !at_rest {
+                collider.wake();
+                continue;
+            }
 
-                rb.angular_velocity += delta_ang_vel;
+            collider.sleep_frames += 1;
 
-                let delta_rot = Quat::from_scaled_axis(self.dt * rb.angular_velocity);
-                rb.rotation = (delta_rot * rb.rotation).normalize();
+            if !collider.asleep && collider.sleep_frames >= self.sleep_window {
+                collider.asleep = true;
+                collider.velocity = Vec3::ZERO;
 
-                rb.previous_angular_velocity = rb.angular_velocity;
+                if let ColliderBody::Rigid(rb) = &mut collider.body {
+                    rb.angular_velocity = Vec3::ZERO;
+                }
             }
         }
+    }
 
-        // TODO: Narrow phase
+    /// Keeps every dynamic rigid body from moving or rotating along its locked axes.
+    fn lock_axes(&mut self) {
+        for collider in self.colliders.iter_mut().filter(|c| c.is_dynamic()) {
+            collider.lock_axes();
+        }
+    }
 
-        self.create_collisions();
-        self.lagrange = vec![0.0; self.constraints.len() + self.collision_constraints.len()];
-        self.solve_constraints();
+    /// Limits the speeds of dynamic rigid bodies to their maximum velocities once every velocity
+    /// constraint has been applied.
+    fn clamp_velocities(&mut self) {
+        for collider in self.colliders.iter_mut().filter(|c| c.is_dynamic()) {
+            if let ColliderBody::Rigid(rb) = &mut collider.body {
+                collider.velocity = collider.velocity.clamp_length_max(rb.max_linear_velocity);
+                rb.angular_velocity = rb.angular_velocity.clamp_length_max(rb.max_angular_velocity);
+            }
+        }
+    }
 
-        // Update velocities
+    fn update_velocities(&mut self, h: f32) {
         for collider in self.colliders.iter_mut() {
-            collider.velocity = (collider.position - collider.previous_position) / self.dt;
+            collider.velocity = (collider.position - collider.previous_position) / h;
 
             if let ColliderBody::Rigid(rb) = &mut collider.body {
                 let delta_rot = rb.rotation * rb.previous_rotation.inverse();
-                rb.angular_velocity = 2.0 * delta_rot.xyz() / self.dt;
+                rb.angular_velocity = 2.0 * delta_rot.xyz() / h;
                 rb.angular_velocity = if delta_rot.w >= 0.0 { rb.angular_velocity } else { -rb.angular_velocity };
             }
         }
+    }
 
-        self.solve_velocities();
+    fn solve_constraints(&mut self, h: f32) {
+        let (lagrange, collision_lagrange) = self.lagrange.split_at_mut(self.constraints.len());
+        let (angular_lagrange, collision_angular_lagrange) = self.angular_lagrange.split_at_mut(self.constraints.len());
 
-        self.sync_transforms();
-    }
+        for ((constraint, lagrange), angular_lagrange) in self.constraints.iter().zip(lagrange.iter_mut()).zip(angular_lagrange.iter_mut()) {
+            solve_constraint(&mut self.colliders, constraint.as_ref(), lagrange, angular_lagrange, h);
+        }
 
-    fn solve_constraints(&mut self) {
-        for (constraint, lagrange) in self.constraints.iter().chain(self.collision_constraints.iter()).zip(self.lagrange.iter_mut()) {
+        let collisions = self.collision_constraints.iter()
+            .zip(collision_lagrange.iter_mut())
+            .zip(collision_angular_lagrange.iter_mut())
+            .zip(self.friction_lagrange.iter_mut())
+            .zip(self.warm_started.iter());
+
+        for ((((constraint, lagrange), _), friction_lagrange), &warm_started) in collisions {
+            solve_collision(&mut self.colliders, constraint.as_ref(), lagrange, h);
+
+            // Static friction is only applied while the tangential force stays within the friction
+            // cone of the normal force.
+            let friction = StaticFrictionConstraint { contact: constraint.as_ref() };
+            let mut delta_lagrange = positional_delta_lagrange(&self.colliders, &friction, *friction_lagrange, h);
+            let limit = constraint.static_friction() * lagrange.abs();
+
+            if warm_started {
+                // A warm started contact shares the normal force with the rest of its manifold from
+                // the first iteration, so it can rarely hold the whole tangential displacement on
+                // its own and instead takes as much of it as its friction cone allows.
+                delta_lagrange = (*friction_lagrange + delta_lagrange).clamp(-limit, limit) - *friction_lagrange;
+            } else if (*friction_lagrange + delta_lagrange).abs() >= limit {
+                continue;
+            }
+
+            *friction_lagrange += delta_lagrange;
+            apply_positional_correction(&mut self.colliders, &friction, delta_lagrange);
+        }
+    }
+    
+    fn solve_velocities(&mut self, h: f32) {
+        for constraint in self.velocity_constraints.iter().chain(self.velocity_collision_constraints.iter()) {
             let bodies: Vec<_> = unsafe {
                 constraint.bodies().into_iter()
-                    .map(|id| self.colliders.get_unchecked(id.0 as usize))
+                    .map(|id| &mut *(self.colliders.get_unchecked_mut(id.0 as usize) as *mut Collider))
                     .collect()
             };
 
-            let c = constraint.c(&bodies);
-            let gradients = constraint.c_gradients(&bodies);
-            let inverse_masses = constraint.inverse_masses(&bodies);
+            constraint.solve(bodies.into_iter(), h);
+        }
+    }
 
-            let w_sum = inverse_masses
-                .iter()
-                .zip(gradients.iter())
-                .fold(0.0, |acc, (&w, &g)| acc + w * g.length_squared());
+    /// Opposes the relative rolling and spinning of the colliders of each contact with angular
+    /// impulses proportional to the normal impulse the contact applied this substep, clamped so
+    /// that they can stop the relative rotation but never reverse it.
+    fn apply_rolling_friction(&mut self, h: f32) {
+        let collision_lagrange = &self.lagrange[self.constraints.len()..];
 
-            let delta_lagrange = if w_sum > f32::EPSILON {
-                let tilde_compliance = constraint.compliance() / (self.dt * self.dt);
-                (-c - tilde_compliance * *lagrange) / (w_sum + tilde_compliance)
-            } else {
-                0.0
+        for (constraint, lagrange) in self.collision_constraints.iter().zip(collision_lagrange) {
+            let &[id_a, id_b] = constraint.bodies().as_slice() else {
+                continue;
             };
 
-            *lagrange += delta_lagrange;
+            let normal_impulse = lagrange.abs() / h;
 
-            let anchors = constraint.anchors();
+            if normal_impulse == 0.0 {
+                continue;
+            }
 
-            let bodies = unsafe {
-                constraint.bodies().into_iter()
-                    .map(|id| &mut *(self.colliders.get_unchecked_mut(id.0 as usize) as *mut Collider))
-                    .zip(gradients.into_iter())
-                    .zip(inverse_masses.into_iter())
-                    .zip(anchors.into_iter())
-            };
+            let a = &self.colliders[id_a.0 as usize];
+            let b = &self.colliders[id_b.0 as usize];
 
-            for (((body, gradient), inv_mass), anchor) in bodies {
-                let p = delta_lagrange * gradient;
-                body.position += p * inv_mass;
+            let rolling_friction = self.friction_combine.combine(a.body.rolling_friction(), b.body.rolling_friction());
+            let spinning_friction = self.friction_combine.combine(a.body.spinning_friction(), b.body.spinning_friction());
 
-                if let ColliderBody::Rigid(rb) = &mut body.body {
-                    rb.rotation = rb.rotation +
-                        Quat::from_vec4(0.5 * (rb.global_inverse_inertia() * anchor.cross(p)).extend(0.0)) * rb.rotation;
+            if rolling_friction == 0.0 && spinning_friction == 0.0 {
+                continue;
+            }
+
+            let n = constraint.contact().normal;
+            let relative = a.angular_velocity() - b.angular_velocity();
+            let spinning = n * n.dot(relative);
+            let rolling = relative - spinning;
+
+            let (inverse_inertia_a, inverse_inertia_b) = (a.global_inverse_inertia(), b.global_inverse_inertia());
+            let mut impulses = Vec::with_capacity(2);
+
+            for (omega, coefficient) in [(rolling, rolling_friction), (spinning, spinning_friction)] {
+                let speed = omega.length();
+
+                if coefficient == 0.0 || speed <= f32::EPSILON {
+                    continue;
                 }
+
+                let u = omega / speed;
+                let w_sum = u.dot(inverse_inertia_a * u) + u.dot(inverse_inertia_b * u);
+
+                if w_sum <= f32::EPSILON {
+                    continue;
+                }
+
+                impulses.push(-u * (coefficient * normal_impulse).min(speed / w_sum));
             }
-        }
-    }
-    
-    fn solve_velocities(&mut self) {
-        for constraint in self.velocity_constraints.iter().chain(self.velocity_collision_constraints.iter()) {
-            let bodies: Vec<_> = unsafe {
-                constraint.bodies().into_iter()
-                    .map(|id| &mut *(self.colliders.get_unchecked_mut(id.0 as usize) as *mut Collider))
-                    .collect()
-            };
 
-            constraint.solve(bodies.into_iter());
+            for impulse in impulses {
+                self.colliders[id_a.0 as usize].apply_angular_impulse(impulse);
+                self.colliders[id_b.0 as usize].apply_angular_impulse(-impulse);
+            }
         }
     }
 
@@ -169,103 +835,561 @@ impl World {
         self.collision_constraints.clear();
         self.velocity_collision_constraints.clear();
 
-        for i in 0..self.colliders.len() {
-            for j in 0..self.colliders.len() {
-                if i >= j {
-                    continue;
+        self.sweep_particles();
+
+        let previous_manifolds = std::mem::take(&mut self.manifolds);
+
+        for (id_a, id_b) in self.broadphase.candidate_pairs(&self.colliders, &mut self.broadphase_tree) {
+            // Particles always come first so their contacts can be resolved by a
+            // `ParticleCollisionConstraint`.
+            let (id_a, id_b) = match (&self.colliders[id_a.0 as usize].body, &self.colliders[id_b.0 as usize].body) {
+                (ColliderBody::Rigid(_), ColliderBody::Particle(_)) => (id_b, id_a),
+                _ => (id_a, id_b),
+            };
+
+            let a = unsafe { self.colliders.get_unchecked(id_a.0 as usize) };
+            let b = unsafe { self.colliders.get_unchecked(id_b.0 as usize) };
+
+            if !a.can_collide(b) {
+                continue;
+            }
+
+            if a.is_sensor || b.is_sensor {
+                if !a.collide(b).is_empty() {
+                    self.overlapping.insert((id_a.min(id_b), id_a.max(id_b)));
                 }
 
-                let id_a = ColliderId::new(i);
-                let id_b = ColliderId::new(j);
-
-                let a = unsafe { self.colliders.get_unchecked(i) };
-                let b = unsafe { self.colliders.get_unchecked(j) };
-
-                match (&a.body, &b.body) {
-                    (ColliderBody::Particle(_), ColliderBody::Particle(_)) => (),
-                    (ColliderBody::Particle(_), ColliderBody::Rigid(_)) => {
-                        let Some(contact) = Contact::from_particle_rigid_body(a, b) else {
-                            continue;
-                        };
-
-                        let collision = ParticleCollisionConstraint {
-                            particle: id_a,
-                            rb: id_b,
-                            contact: contact.clone(),
-                            compliance: 0.0,
-                        };
-
-                        let restitution = ParticleRestitutionConstraint {
-                            particle: id_a,
-                            rb: id_b,
-                            contact,
-                            coefficient: 1.0,
-                        };
-
-                        self.collision_constraints.push(Box::new(collision));
-                        self.velocity_collision_constraints.push(Box::new(restitution));
-                    },
-                    (ColliderBody::Rigid(_), ColliderBody::Particle(_)) => {
-                        let Some(contact) = Contact::from_particle_rigid_body(b, a) else {
-                            continue;
-                        };
-
-                        let collision = ParticleCollisionConstraint {
-                            particle: id_b,
-                            rb: id_a,
-                            contact: contact.clone(),
-                            compliance: 0.0,
-                        };
-
-                        let restitution = ParticleRestitutionConstraint {
-                            particle: id_b,
-                            rb: id_a,
-                            contact,
-                            coefficient: 1.0,
-                        };
-
-                        self.collision_constraints.push(Box::new(collision));
-                        self.velocity_collision_constraints.push(Box::new(restitution));
-                    },
-                    (ColliderBody::Rigid(_rb1), ColliderBody::Rigid(_rb2)) => {
-                        // todo!();
-                    },
+                continue;
+            }
+
+            let mut contacts = a.collide(b);
+            let previous = previous_manifolds.get(&(id_a, id_b)).map_or(&[][..], Vec::as_slice);
+
+            for contact in contacts.iter_mut() {
+                contact.persist(previous, a, b);
+            }
+
+            let manifold: Vec<_> = contacts.iter().filter_map(|contact| contact.persistent(previous)).collect();
+
+            if !manifold.is_empty() {
+                self.manifolds.insert((id_a, id_b), manifold);
+            }
+
+            for contact in contacts {
+                self.add_contact(id_a, id_b, contact);
+            }
+        }
+
+        self.wake_colliding();
+    }
+
+    /// Creates the constraints resolving `contact` between the colliders `id_a` and `id_b`.
+    fn add_contact(&mut self, id_a: ColliderId, id_b: ColliderId, contact: Contact) {
+        let a = unsafe { self.colliders.get_unchecked(id_a.0 as usize) };
+        let b = unsafe { self.colliders.get_unchecked(id_b.0 as usize) };
+
+        let static_friction = self.friction_combine.combine(a.body.static_friction(), b.body.static_friction());
+        let dynamic_friction = self.friction_combine.combine(a.body.dynamic_friction(), b.body.dynamic_friction());
+        let restitution = self.restitution_combine.combine(a.body.restitution(), b.body.restitution());
+
+        let friction = DynamicFrictionConstraint {
+            a: id_a,
+            b: id_b,
+            contact: contact.clone(),
+            coefficient: dynamic_friction,
+        };
+
+        let restitution = RestitutionConstraint::new((id_a, a), (id_b, b), contact.clone(), restitution, self.restitution_velocity_threshold);
+
+        let collision: Box<dyn ContactConstraint> = match (&a.body, &b.body) {
+            (ColliderBody::Particle(_), ColliderBody::Particle(_)) => Box::new(ParticleParticleCollisionConstraint {
+                a: id_a,
+                b: id_b,
+                contact,
+                compliance: 0.0,
+                static_friction,
+            }),
+            (ColliderBody::Particle(_), _) => Box::new(ParticleCollisionConstraint {
+                particle: id_a,
+                rb: id_b,
+                contact,
+                compliance: 0.0,
+                static_friction,
+            }),
+            _ => Box::new(RigidBodyCollisionConstraint {
+                a: id_a,
+                b: id_b,
+                contact,
+                compliance: 0.0,
+                static_friction,
+            }),
+        };
+
+        self.collision_constraints.push(collision);
+        self.velocity_collision_constraints.push(Box::new(friction));
+        self.velocity_collision_constraints.push(Box::new(restitution));
+    }
+
+    /// Sweeps every particle which moved further than its radius this substep against the rigid
+    /// bodies, or further than `ccd_threshold` for particles smaller than that, moving it back to
+    /// the first surface it would have passed through and adding a contact there.
+    fn sweep_particles(&mut self) {
+        for i in 0..self.colliders.len() {
+            let particle = &self.colliders[i];
+
+            let ColliderBody::Particle(particle_body) = &particle.body else {
+                continue;
+            };
+
+            if !particle.is_dynamic()
+                || particle.is_sensor
+                || particle.asleep
+                || particle.position.distance(particle.previous_position) <= particle_body.radius.max(self.ccd_threshold)
+            {
+                continue;
+            }
+
+            let sweep = Aabb::from_point(particle.previous_position).include(particle.position);
+
+            let hit = self.colliders.iter()
+                .enumerate()
+                .filter(|(_, rb)| matches!(rb.body, ColliderBody::Rigid(_)) && !rb.removed && !rb.is_sensor && particle.can_collide(rb) && rb.aabb().intersects(&sweep))
+                .filter_map(|(j, rb)| Contact::from_particle_sweep(particle, rb).map(|hit| (j, hit)))
+                .min_by(|(_, (a, _)), (_, (b, _))| {
+                    a.distance_squared(particle.previous_position).total_cmp(&b.distance_squared(particle.previous_position))
+                });
+
+            if let Some((j, (position, contact))) = hit {
+                self.colliders[i].position = position;
+                self.add_contact(ColliderId::new(i), ColliderId::new(j), contact);
+            }
+        }
+    }
+
+    /// Wakes every sleeping collider which is penetrated by a collider that is awake.
+    ///
+    /// Locked colliders never wake others, so bodies resting on static geometry stay asleep, while
+    /// kinematic colliders always do.
+    fn wake_colliding(&mut self) {
+        let mut wake = Vec::new();
+
+        for constraint in self.collision_constraints.iter() {
+            let ids = constraint.bodies();
+            let bodies: Vec<_> = ids.iter().map(|id| &self.colliders[id.0 as usize]).collect();
+
+            if constraint.c(&bodies) <= 0.0 {
+                continue;
+            }
+
+            for (&id, (body, other)) in ids.iter().zip([(bodies[0], bodies[1]), (bodies[1], bodies[0])]) {
+                if body.asleep && !other.asleep && !matches!(other.motion, ColliderMotion::Locked) {
+                    wake.push(id);
                 }
             }
         }
+
+        for id in wake {
+            self.colliders[id.0 as usize].wake();
+        }
     }
 
-    fn sync_transforms(&mut self) {
-        for collider in self.colliders.iter_mut() {
+    /// The colliders whose bounding boxes overlap `aabb`, in ascending order of their IDs.
+    pub fn query_aabb(&self, aabb: Aabb) -> Vec<ColliderId> {
+        self.broadphase.query(&self.colliders, aabb)
+    }
+
+    /// Casts the ray `origin + t * direction` into this world, returning the first collider it
+    /// hits along with the distance to the hit along the normalized `direction` and the outward
+    /// surface normal there.
+    ///
+    /// See [`Collider::raycast`].
+    pub fn raycast(&self, origin: Vec3, direction: Vec3) -> Option<(ColliderId, f32, Vec3)> {
+        self.colliders.iter()
+            .enumerate()
+            .filter(|(_, collider)| !collider.removed)
+            .filter_map(|(i, collider)| collider.raycast(origin, direction).map(|(t, normal)| (ColliderId::new(i), t, normal)))
+            .min_by(|(_, a, _), (_, b, _)| a.total_cmp(b))
+    }
+
+    /// The full state of this world, from which the simulation can be resumed with
+    /// [`World::restore`].
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            step: self.step,
+            colliders: self.colliders.iter().map(|collider| CheckpointCollider {
+                id: collider.id,
+                position: collider.position,
+                velocity: collider.velocity,
+                rotation: collider.rotation(),
+                angular_velocity: collider.angular_velocity(),
+                asleep: collider.asleep,
+                sleep_frames: collider.sleep_frames,
+            }).collect(),
+            touching: self.touching.iter().map(|(a, b)| [a.0, b.0]).collect(),
+            manifolds: self.manifolds.iter().map(|((a, b), contacts)| CheckpointManifold {
+                a: a.0,
+                b: b.0,
+                contacts: contacts.iter().map(|contact| CheckpointContact {
+                    feature: contact.feature,
+                    local_anchor1: contact.local_anchor1,
+                    local_anchor2: contact.local_anchor2,
+                    lagrange: contact.lagrange,
+                }).collect(),
+            }).collect(),
+        }
+    }
+
+    /// Restores the state of this world from a `checkpoint` of the same world.
+    ///
+    /// The particles spawned and removed by emitters only depend on the step, so those of the
+    /// steps up to the `checkpoint` are replayed before the colliders are restored.
+    pub fn restore(&mut self, checkpoint: &Checkpoint) {
+        while self.step < checkpoint.step {
+            self.emit();
+            self.step += 1;
+        }
+
+        self.step = checkpoint.step;
+        self.restore_contacts(&checkpoint.touching, &checkpoint.manifolds);
+
+        for saved in checkpoint.colliders.iter() {
+            let Some(collider) = self.colliders.get_mut(saved.id as usize) else {
+                continue;
+            };
+
+            collider.position = saved.position;
+            collider.previous_position = saved.position;
+            collider.velocity = saved.velocity;
+            collider.previous_velocity = saved.velocity;
+            collider.asleep = saved.asleep;
+            collider.sleep_frames = saved.sleep_frames;
+
             if let ColliderBody::Rigid(rb) = &mut collider.body {
-                // rb.transform.translate = collider.position;
+                rb.rotation = saved.rotation;
+                rb.previous_rotation = saved.rotation;
+                rb.angular_velocity = saved.angular_velocity;
+                rb.previous_angular_velocity = saved.angular_velocity;
             }
         }
     }
 
+    /// Restores the pairs of colliders which were `touching` in the last step and the contact
+    /// `manifolds` of its last substep, as stored in a [`Checkpoint`] or [`Snapshot`].
+    fn restore_contacts(&mut self, touching: &[[u32; 2]], manifolds: &[CheckpointManifold]) {
+        self.touching = touching.iter().map(|&[a, b]| (ColliderId(a), ColliderId(b))).collect();
+        self.manifolds = manifolds.iter().map(|manifold| (
+            (ColliderId(manifold.a), ColliderId(manifold.b)),
+            manifold.contacts.iter().map(|contact| PersistentContact {
+                feature: contact.feature,
+                local_anchor1: contact.local_anchor1,
+                local_anchor2: contact.local_anchor2,
+                lagrange: contact.lagrange,
+            }).collect(),
+        )).collect();
+    }
+
+    /// Stores this world and its full state in a [`Snapshot`], which holds its settings,
+    /// colliders, emitters and constraints themselves, so the world can be built again from it
+    /// alone.
+    ///
+    /// Fails if any constraint of this world cannot be given in a world file.
+    pub fn snapshot(&self) -> Result<Snapshot, SnapshotError> {
+        let constraints = self.constraints.iter().enumerate()
+            .map(|(index, constraint)| constraint.to_parsed().ok_or(SnapshotError::UnsupportedConstraint { index }))
+            .collect::<Result<_, _>>()?;
+        let velocity_constraints = self.velocity_constraints.iter().enumerate()
+            .map(|(index, constraint)| constraint.to_parsed().ok_or(SnapshotError::UnsupportedVelocityConstraint { index }))
+            .collect::<Result<_, _>>()?;
+        let Checkpoint { touching, manifolds, .. } = self.checkpoint();
+
+        Ok(Snapshot {
+            version: SNAPSHOT_VERSION,
+            steps: self.steps,
+            dt: self.dt,
+            step: self.step,
+            substeps: self.substeps,
+            iterations: self.iterations,
+            friction_combine: self.friction_combine.into(),
+            restitution_combine: self.restitution_combine.into(),
+            gravity: self.gravity,
+            broadphase: self.broadphase.into(),
+            water: self.water.map(Water::into),
+            sleep_linear_velocity: self.sleep_linear_velocity,
+            sleep_angular_velocity: self.sleep_angular_velocity,
+            sleep_window: self.sleep_window,
+            ccd_threshold: self.ccd_threshold,
+            restitution_velocity_threshold: self.restitution_velocity_threshold,
+            warm_start: self.warm_start,
+            max_vertex_resolution: self.max_vertex_resolution,
+            max_correction_per_step: self.max_correction_per_step,
+            colliders: self.colliders.iter().map(SnapshotCollider::from).collect(),
+            emitters: self.emitters.iter().map(SnapshotEmitter::from).collect(),
+            script: self.script.clone(),
+            constraints,
+            velocity_constraints,
+            touching,
+            manifolds,
+        })
+    }
+
+    /// Writes this world and its full state to a single [`Snapshot`] file at `path`, from which it
+    /// can be loaded again with [`World::load_snapshot`] without any other file.
+    pub fn save_snapshot<P>(&self, path: P) -> Result<(), SnapshotError>
+    where
+        P: AsRef<path::Path>
+    {
+        Ok(self.snapshot()?.write(path)?)
+    }
+
+    /// Loads a world written with [`World::save_snapshot`] from `path`, which continues to
+    /// simulate exactly as the saved world would have.
+    pub fn load_snapshot<P>(path: P) -> Result<World, SnapshotError>
+    where
+        P: AsRef<path::Path>
+    {
+        Ok(Snapshot::read(path)?.into())
+    }
+
     pub fn state(&self) -> WriteWorldState {
         WriteWorldState {
-            colliders: self.colliders.iter().map(WriteCollider::from).collect(),
+            step: self.step,
+            dt: self.dt,
+            delta: false,
+            colliders: self.colliders.iter().filter(|c| !c.removed).map(WriteCollider::from).collect(),
+            removed: Vec::new(),
             inspector: self.inspector.clone(),
         }
     }
+
+    /// The current state of this world, with every collider also recording the colliders it
+    /// touched during the last step and the impulses between them.
+    pub fn verbose_state(&self) -> WriteWorldState {
+        let mut state = self.state();
+
+        // The events are ordered by their pairs, so the contacts of each collider are ordered by
+        // the other collider.
+        for event in self.events.iter().filter(|event| event.kind != CollisionEventKind::End) {
+            for (collider, other) in [(event.a, event.b), (event.b, event.a)] {
+                // Removed colliders are missing from the state, so the colliders are looked up by
+                // their IDs rather than indexed by them.
+                if let Ok(index) = state.colliders.binary_search_by_key(&collider, |c| c.id) {
+                    state.colliders[index].contacts.push(WriteContact { other, impulse: event.impulse });
+                }
+            }
+        }
+
+        state
+    }
+}
+
+/// Projects a single `constraint`, first correcting the positions and then the relative rotation
+/// of its bodies.
+fn solve_constraint(colliders: &mut [Collider], constraint: &dyn Constraint, lagrange: &mut f32, angular_lagrange: &mut f32, h: f32) {
+    let delta_lagrange = positional_delta_lagrange(colliders, constraint, *lagrange, h);
+    *lagrange += delta_lagrange;
+    apply_positional_correction(colliders, constraint, delta_lagrange);
+
+    let bodies: Vec<_> = unsafe {
+        constraint.bodies().into_iter()
+            .map(|id| colliders.get_unchecked(id.0 as usize))
+            .collect()
+    };
+
+    let Some(error) = constraint.angular_error(&bodies) else {
+        return;
+    };
+
+    let angle = error.length();
+
+    if angle <= f32::EPSILON {
+        return;
+    }
+
+    let n = error / angle;
+    let inverse_inertias = [bodies[0].global_inverse_inertia(), bodies[1].global_inverse_inertia()];
+    let w_sum = inverse_inertias.iter().fold(0.0, |acc, inv_inertia| acc + n.dot(*inv_inertia * n));
+
+    if w_sum <= f32::EPSILON {
+        return;
+    }
+
+    let tilde_compliance = constraint.compliance() / (h * h);
+    let delta_lagrange = (-angle - tilde_compliance * *angular_lagrange) / (w_sum + tilde_compliance);
+    *angular_lagrange += delta_lagrange;
+
+    // The error is the rotation to apply to the first body, so it is rotated against the
+    // (negative) impulse while the second body is rotated along it.
+    let p = delta_lagrange * n;
+    let bodies = constraint.bodies();
+
+    for (id, inv_inertia, sign) in [(bodies[0], inverse_inertias[0], -1.0), (bodies[1], inverse_inertias[1], 1.0)] {
+        let body = unsafe { colliders.get_unchecked_mut(id.0 as usize) };
+
+        if let ColliderBody::Rigid(rb) = &mut body.body {
+            rb.rotate(sign * (inv_inertia * p));
+        }
+    }
+}
+
+/// The persistent contact in `manifolds` resolved by a collision `constraint`, if its contact is
+/// part of a manifold.
+fn persistent_contact<'a>(
+    manifolds: &'a BTreeMap<(ColliderId, ColliderId), Vec<PersistentContact>>,
+    constraint: &dyn ContactConstraint,
+) -> Option<&'a PersistentContact> {
+    let feature = constraint.contact().feature?;
+    let &[a, b] = constraint.bodies().as_slice() else {
+        return None;
+    };
+
+    manifolds.get(&(a, b))?.iter().find(|contact| contact.feature == feature)
+}
+
+/// Resolves a collision `constraint` with the [`SeparatingCollisionConstraint`] of its contact,
+/// clamping the accumulated Lagrange multiplier so the contact never pulls its bodies together.
+///
+/// Only the penetration the bodies moved into during the substep is resolved, since the
+/// penetration they already had before it is left to [`World::depenetrate`].
+fn solve_collision(colliders: &mut [Collider], constraint: &dyn ContactConstraint, lagrange: &mut f32, h: f32) {
+    let target_depth = existing_depth(colliders, constraint);
+    let separating = SeparatingCollisionConstraint { collision: constraint, target_depth };
+    let delta_lagrange = positional_delta_lagrange(colliders, &separating, *lagrange, h).min(-*lagrange);
+
+    *lagrange += delta_lagrange;
+    apply_positional_correction(colliders, &separating, delta_lagrange);
+}
+
+/// The penetration depth up to which the penetration a contact already had at the start of a
+/// substep is resolved like the rest of its penetration, which keeps [`World::depenetrate`] from
+/// affecting the small overlaps left over by resting contacts.
+const DEPENETRATION_SLOP: f32 = 0.005;
+
+/// The penetration depth the contact of a collision `constraint` already had at the start of the
+/// substep, before its bodies were integrated, up to its depth when it was generated, or zero if
+/// it is within [`DEPENETRATION_SLOP`].
+fn existing_depth(colliders: &[Collider], constraint: &dyn ContactConstraint) -> f32 {
+    let contact = constraint.contact();
+
+    let &[a, b, ..] = constraint.bodies().as_slice() else {
+        return 0.0;
+    };
+
+    let depth = contact.previous_signed_depth(&colliders[a.0 as usize], &colliders[b.0 as usize]).min(contact.depth);
+    if depth > DEPENETRATION_SLOP { depth } else { 0.0 }
+}
+
+/// Computes the change in the Lagrange multiplier of a positional `constraint` given its current
+/// value `lagrange`.
+fn positional_delta_lagrange(colliders: &[Collider], constraint: &dyn Constraint, lagrange: f32, h: f32) -> f32 {
+    let bodies: Vec<_> = unsafe {
+        constraint.bodies().into_iter()
+            .map(|id| colliders.get_unchecked(id.0 as usize))
+            .collect()
+    };
+
+    let c = constraint.c(&bodies);
+    let gradients = constraint.c_gradients(&bodies);
+    let inverse_masses = constraint.inverse_masses(&bodies);
+
+    let w_sum = inverse_masses
+        .iter()
+        .zip(gradients.iter())
+        .fold(0.0, |acc, (&w, &g)| acc + w * g.length_squared());
+
+    let tilde_compliance = constraint.compliance() / (h * h);
+
+    if w_sum > f32::EPSILON {
+        (-c - tilde_compliance * lagrange) / (w_sum + tilde_compliance)
+    } else {
+        0.0
+    }
+}
+
+/// Moves and rotates the bodies of a positional `constraint` by the impulse `delta_lagrange` along
+/// its gradients.
+fn apply_positional_correction(colliders: &mut [Collider], constraint: &dyn Constraint, delta_lagrange: f32) {
+    let (gradients, inverse_masses, anchors) = {
+        let bodies: Vec<_> = unsafe {
+            constraint.bodies().into_iter()
+                .map(|id| colliders.get_unchecked(id.0 as usize))
+                .collect()
+        };
+
+        (constraint.c_gradients(&bodies), constraint.inverse_masses(&bodies), constraint.anchors(&bodies))
+    };
+
+    let bodies = constraint.bodies().into_iter()
+        .zip(gradients)
+        .zip(inverse_masses)
+        .zip(anchors);
+
+    for (((id, gradient), inv_mass), anchor) in bodies {
+        if inv_mass == 0.0 {
+            continue;
+        }
+
+        let body = unsafe { colliders.get_unchecked_mut(id.0 as usize) };
+
+        let p = delta_lagrange * gradient;
+        body.position += p * body.body.inverse_mass();
+
+        if let ColliderBody::Rigid(rb) = &mut body.body {
+            rb.rotate(rb.global_inverse_inertia() * anchor.cross(p));
+        }
+    }
 }
 
 impl From<ParsedWorld> for World {
     fn from(value: ParsedWorld) -> Self {
         World {
             steps: value.steps,
-            dt: value.dt,
-            gravity: value.gravity,
+            substeps: value.substeps,
+            iterations: value.iterations,
+            friction_combine: value.friction_combine.into(),
+            restitution_combine: value.restitution_combine.into(),
+            restitution_velocity_threshold: value.restitution_velocity_threshold,
+            max_vertex_resolution: value.max_vertex_resolution,
+            max_correction_per_step: value.max_correction_per_step,
+            broadphase: value.broadphase.into(),
+            water: value.water.map(Water::from),
             colliders: value.colliders.into_iter().map(Collider::from).collect(),
+            emitters: value.emitters.into_iter().map(Emitter::from).collect(),
 
-            constraints: Vec::new(),
-            collision_constraints: Vec::new(),
-            velocity_constraints: Vec::new(),
-            velocity_collision_constraints: Vec::new(),
-            lagrange: Vec::new(),
-
-            inspector: InspectElements::default(),
+            velocity_constraints: value.constraints.iter().filter_map(velocity_constraint).collect(),
+            constraints: value.constraints.into_iter().map(Box::<dyn Constraint>::from).collect(),
+            ..World::new(value.dt, value.gravity)
         }
     }
 }
+
+impl From<Snapshot> for World {
+    fn from(value: Snapshot) -> Self {
+        let mut world = World {
+            steps: value.steps,
+            step: value.step,
+            substeps: value.substeps,
+            iterations: value.iterations,
+            friction_combine: value.friction_combine.into(),
+            restitution_combine: value.restitution_combine.into(),
+            broadphase: value.broadphase.into(),
+            water: value.water.map(Water::from),
+            sleep_linear_velocity: value.sleep_linear_velocity,
+            sleep_angular_velocity: value.sleep_angular_velocity,
+            sleep_window: value.sleep_window,
+            ccd_threshold: value.ccd_threshold,
+            restitution_velocity_threshold: value.restitution_velocity_threshold,
+            warm_start: value.warm_start,
+            max_vertex_resolution: value.max_vertex_resolution,
+            max_correction_per_step: value.max_correction_per_step,
+            colliders: value.colliders.into_iter().map(Collider::from).collect(),
+            emitters: value.emitters.into_iter().map(Emitter::from).collect(),
+            script: value.script,
+
+            velocity_constraints: value.velocity_constraints.iter().filter_map(velocity_constraint).collect(),
+            constraints: value.constraints.into_iter().map(Box::<dyn Constraint>::from).collect(),
+            ..World::new(value.dt, value.gravity)
+        };
+        world.restore_contacts(&value.touching, &value.manifolds);
+        world
+    }
+}
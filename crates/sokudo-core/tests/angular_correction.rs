@@ -0,0 +1,88 @@
+use glam::Vec3;
+use sokudo_core::{
+    collider::{Collider, ColliderBody},
+    rigid_body::RigidBody,
+    shape::{cuboid::CuboidShape, Shape},
+    world::World,
+};
+
+/// A world without gravity in which a small box moving along X at `height` is about to strike a
+/// unit box resting at the origin.
+fn strike(height: f32) -> World {
+    let mut world = World::new(0.01, Vec3::ZERO);
+    world.add_collider(Collider::new(ColliderBody::Rigid(RigidBody::new(Shape::Cuboid(CuboidShape), 1.0)), Vec3::ZERO));
+
+    let mut striker = RigidBody::new(Shape::Cuboid(CuboidShape), 0.5);
+    striker.scale = Vec3::splat(0.2);
+
+    let mut collider = Collider::new(ColliderBody::Rigid(striker), Vec3::new(-1.02, height, 0.0));
+    collider.velocity = Vec3::new(5.0, 0.0, 0.0);
+    collider.previous_velocity = collider.velocity;
+    world.add_collider(collider);
+
+    world.initialize();
+    world
+}
+
+/// Whether `v` points mostly along negative Z, which is the axis the struck box turns around.
+fn mostly_negative_z(v: Vec3) -> bool {
+    v.z < 0.0 && v.z.abs() > 3.0 * v.truncate().length()
+}
+
+#[test]
+fn box_struck_off_center_spins() {
+    let mut world = strike(0.35);
+
+    for _ in 0..40 {
+        world.step();
+    }
+
+    let cube = &world.colliders[0];
+
+    // The striker pushes the box along X above its center, which turns it clockwise around Z.
+    let angular_velocity = cube.angular_velocity();
+    assert!(angular_velocity.z < -0.5 && mostly_negative_z(angular_velocity), "angular velocity was {angular_velocity}");
+
+    let (axis, angle) = cube.rotation().to_axis_angle();
+    assert!(angle > 0.05 && mostly_negative_z(axis), "rotation was {angle} around {axis}");
+    assert!(cube.velocity.x > 0.5, "velocity was {}", cube.velocity);
+}
+
+#[test]
+fn positional_correction_rotates_box_struck_off_center() {
+    let mut world = strike(0.35);
+    world.substeps = 1;
+
+    // With a single substep, the rotation after the step in which the striker first touches the
+    // box is entirely due to the positional correction of the contact, since the angular velocity
+    // integrated into it was still zero.
+    for _ in 0..100 {
+        world.step();
+
+        if world.colliders[0].velocity != Vec3::ZERO {
+            break;
+        }
+    }
+
+    let (axis, angle) = world.colliders[0].rotation().to_axis_angle();
+    assert!(angle > 1e-3 && mostly_negative_z(axis), "rotation was {angle} around {axis}");
+}
+
+#[test]
+fn box_struck_through_its_center_barely_spins() {
+    let mut central = strike(0.0);
+    let mut off_center = strike(0.35);
+
+    for _ in 0..40 {
+        central.step();
+        off_center.step();
+    }
+
+    // The contacts of the striker are solved one after another, so the box is not turned by
+    // exactly the same amounts in opposite directions and picks up a little spin regardless.
+    let spin = central.colliders[0].angular_velocity().length();
+    let off_center_spin = off_center.colliders[0].angular_velocity().length();
+
+    assert!(spin < 0.25 * off_center_spin, "angular velocity was {spin} against {off_center_spin} off-center");
+    assert!(central.colliders[0].velocity.x > 0.5, "velocity was {}", central.colliders[0].velocity);
+}
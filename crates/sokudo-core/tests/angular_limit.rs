@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use glam::{EulerRot, Vec3};
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn twist_is_stopped_at_limit() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("angular-limit-history.ron");
+    run_simulation(PathBuf::from("tests/angular-limit.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let angle = |frame: usize| history.get(frame).colliders[1].transform.rotate.to_euler(EulerRot::ZYX).0;
+
+    // Within the range the link swings freely, accelerating downwards under gravity.
+    let first_reached = (0..history.len()).find(|&frame| angle(frame) < -0.45).expect("link never reached the limit");
+    assert!(first_reached > 5, "link reached the limit after only {first_reached} frames");
+
+    for frame in 1..first_reached {
+        assert!(angle(frame) < angle(frame - 1), "link stopped swinging at frame {frame} before the limit");
+    }
+
+    // Past the limit the link is held there instead of hanging straight down.
+    for frame in first_reached..history.len() {
+        assert!(angle(frame) > -0.55, "link swung {} radians at frame {frame}", angle(frame));
+    }
+
+    let last = history.get(history.len() - 1).colliders[1].transform.clone();
+    let end = last.translate + last.rotate * Vec3::new(-0.25, 0.0, 0.0);
+    assert!(end.length() < 1e-2, "link came loose from its pivot to {end}");
+}
@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use glam::Quat;
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn locked_axes_stay_fixed() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("axis-locks-history.ron");
+    run_simulation(PathBuf::from("tests/axis-locks.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let last = history.get(history.len() - 1);
+
+    // The free box lands on the frictionless slope and slides down it.
+    let free = &last.colliders[1].transform;
+    assert!(free.translate.x < -1.0, "free box stopped at {}", free.translate);
+
+    // The box locked to the y-axis can only fall straight down onto the slope.
+    for frame in 0..history.len() {
+        let translate = history.get(frame).colliders[2].transform.translate;
+        assert!(translate.x == 0.0 && translate.z == 0.0, "vertical box moved to {translate} at frame {frame}");
+    }
+
+    let vertical = &last.colliders[2].transform;
+    assert!(vertical.translate.y < 1.5, "vertical box stopped at {}", vertical.translate);
+
+    // The box which cannot rotate lands on its corner and slides without turning.
+    let upright = &last.colliders[3].transform;
+    assert!(upright.rotate.abs_diff_eq(Quat::IDENTITY, 1e-5), "upright box turned to {}", upright.rotate);
+    assert!(upright.translate.x < -1.0, "upright box stopped at {}", upright.translate);
+}
@@ -0,0 +1,25 @@
+use sokudo_core::run::bench_simulation;
+
+#[test]
+fn bench_reports_steps_and_constraints() {
+    let report = bench_simulation("tests/resting-box.ron", Some(50)).unwrap();
+
+    assert_eq!(report.steps, 50);
+    assert!(report.wall_time > 0.0 && report.steps_per_second > 0.0, "report was {report:?}");
+
+    // The box lands on the floor, and the world has no other constraints.
+    assert!(report.average_collision_constraints > 0.0, "report was {report:?}");
+    assert_eq!(report.average_constraints, report.average_collision_constraints);
+}
+
+#[test]
+fn bench_report_fields_keep_their_order() {
+    let report = bench_simulation("tests/resting-box.ron", Some(1)).unwrap();
+    let json = report.to_json().unwrap();
+
+    let keys = ["\"steps\"", "\"wall_time\"", "\"steps_per_second\"", "\"average_constraints\"", "\"average_collision_constraints\"", "\"max_residual\"", "\"average_residual\""];
+    let positions: Vec<_> = keys.iter().map(|key| json.find(key).unwrap_or_else(|| panic!("{key} missing from {json}"))).collect();
+
+    assert!(positions.windows(2).all(|pair| pair[0] < pair[1]), "fields were out of order in {json}");
+    assert!(!json.contains('\n'));
+}
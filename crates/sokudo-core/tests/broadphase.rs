@@ -0,0 +1,47 @@
+use std::{fs, path::PathBuf};
+
+use glam::Vec3;
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+/// Runs `tests/falling-particles.ron` with the given broadphase and returns the final positions.
+fn run_with_broadphase(name: &str, broadphase: &str) -> Vec<Vec3> {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let world_path = dir.join(format!("falling-particles-{name}.ron"));
+    let history_path = dir.join(format!("falling-particles-{name}-history.ron"));
+
+    let world = fs::read_to_string("tests/falling-particles.ron").unwrap();
+    fs::write(&world_path, world.replace("broadphase: BruteForce", &format!("broadphase: {broadphase}"))).unwrap();
+
+    run_simulation(world_path, history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    history.get(history.len() - 1).colliders.iter().map(|c| c.transform.translate).collect()
+}
+
+#[test]
+fn broadphases_agree() {
+    let brute_force = run_with_broadphase("brute-force", "BruteForce");
+    let sweep_and_prune = run_with_broadphase("sweep-and-prune", "SweepAndPrune");
+    let spatial_hash = run_with_broadphase("spatial-hash", "SpatialHash(cell_size: 1.0)");
+    let dynamic_bvh = run_with_broadphase("dynamic-bvh", "DynamicBvh(rebuild_threshold: 1.5)");
+
+    assert_eq!(brute_force, sweep_and_prune);
+    assert_eq!(brute_force, spatial_hash);
+    assert_eq!(brute_force, dynamic_bvh);
+
+    // Every particle should have landed on the ground rather than falling through it.
+    assert!(brute_force[2..].iter().all(|p| p.y > 0.4), "{brute_force:?}");
+}
+
+#[test]
+fn invalid_cell_sizes_fall_back_to_sweep_and_prune() {
+    let sweep_and_prune = run_with_broadphase("fallback-sweep-and-prune", "SweepAndPrune");
+
+    // Each of these would otherwise bucket the colliders into infinitely many cells, or so many
+    // that the simulation never finishes.
+    for (name, cell_size) in [("zero", "0.0"), ("negative", "-1.0"), ("infinite", "inf")] {
+        let positions = run_with_broadphase(&format!("{name}-cell-size"), &format!("SpatialHash(cell_size: {cell_size})"));
+        assert_eq!(positions, sweep_and_prune, "cell size {cell_size}");
+    }
+}
@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn cork_floats_and_dense_cube_sinks() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("buoyancy-history.ron");
+    run_simulation(PathBuf::from("tests/buoyancy.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let last = history.get(history.len() - 1);
+
+    // The cork floats with a quarter of its height below the surface, so its center settles a
+    // quarter of the way above it.
+    let cork = last.colliders[1].transform.translate;
+    assert!((cork.y - 0.25).abs() < 2e-2, "cork floated at {cork}");
+
+    // The cube sinks to the bottom, where it rests on the floor.
+    let cube = last.colliders[2].transform.translate;
+    assert!((cube.y + 4.5).abs() < 2e-2, "cube came to rest at {cube}");
+}
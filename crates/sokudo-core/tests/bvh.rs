@@ -0,0 +1,98 @@
+use glam::Vec3;
+use sokudo_core::{aabb::Aabb, bvh::Bvh, world::World};
+use sokudo_io::read::ParsedWorld;
+
+/// A deterministic stream of pseudorandom numbers within `-1.0..1.0`.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+
+    fn vec3(&mut self) -> Vec3 {
+        Vec3::new(self.next(), self.next(), self.next())
+    }
+}
+
+fn random_triangles(random: &mut Random, count: usize) -> Vec<Aabb> {
+    (0..count).map(|_| {
+        let center = 10.0 * random.vec3();
+        let [a, b, c] = [(); 3].map(|_| center + random.vec3());
+        Aabb::from_point(a).include(b).include(c)
+    }).collect()
+}
+
+#[test]
+fn queries_match_brute_force() {
+    let mut random = Random(7);
+    let aabbs = random_triangles(&mut random, 500);
+    let bvh = Bvh::new(&aabbs);
+
+    for _ in 0..200 {
+        let center = 10.0 * random.vec3();
+        let query = Aabb::new(center - 2.0 * random.vec3().abs(), center + 2.0 * random.vec3().abs());
+
+        let expected: Vec<_> = (0..aabbs.len() as u32).filter(|&i| aabbs[i as usize].intersects(&query)).collect();
+        assert_eq!(bvh.aabb_query(query), expected);
+
+        let origin = 12.0 * random.vec3();
+        let direction = random.vec3();
+        let max_t = 30.0 * random.next().abs();
+
+        let expected: Vec<_> = (0..aabbs.len() as u32)
+            .filter(|&i| aabbs[i as usize].ray_distance(origin, direction, max_t).is_some())
+            .collect();
+        assert_eq!(bvh.ray_query(origin, direction, max_t), expected);
+    }
+}
+
+#[test]
+fn refit_hierarchy_matches_brute_force() {
+    let mut random = Random(11);
+    let mut aabbs = random_triangles(&mut random, 300);
+    let mut bvh = Bvh::new(&aabbs);
+    let built_cost = bvh.cost();
+
+    for _ in 0..20 {
+        for aabb in aabbs.iter_mut() {
+            let offset = 0.5 * random.vec3();
+            *aabb = Aabb::new(aabb.min + offset, aabb.max + offset);
+        }
+
+        bvh.refit(&aabbs);
+
+        let center = 10.0 * random.vec3();
+        let query = Aabb::new(center - 3.0 * random.vec3().abs(), center + 3.0 * random.vec3().abs());
+
+        let expected: Vec<_> = (0..aabbs.len() as u32).filter(|&i| aabbs[i as usize].intersects(&query)).collect();
+        assert_eq!(bvh.aabb_query(query), expected);
+    }
+
+    // The items have drifted apart from the others in their nodes, so the refit nodes are looser
+    // than those of a new hierarchy.
+    assert!(bvh.cost() > built_cost);
+    assert!(bvh.cost() > Bvh::new(&aabbs).cost());
+}
+
+#[test]
+fn empty_hierarchy_finds_nothing() {
+    let bvh = Bvh::new(&[]);
+
+    assert!(bvh.aabb_query(Aabb::INFINITE).is_empty());
+    assert!(bvh.ray_query(Vec3::ZERO, Vec3::X, f32::INFINITY).is_empty());
+}
+
+#[test]
+fn rays_hit_mesh_triangles() {
+    let mut world: World = ParsedWorld::read("tests/bowl.ron").unwrap().into();
+    world.initialize();
+
+    // The ray falls into the bowl and hits its lowest vertex.
+    let (hit, t, normal) = world.raycast(Vec3::new(0.0, 5.0, 0.0), Vec3::NEG_Y).expect("ray missed the bowl");
+
+    assert_eq!(hit.0, 0);
+    assert!((t - 7.0).abs() < 1e-4, "ray hit at {t}");
+    assert!(normal.y.abs() > 0.9, "normal was {normal}");
+}
@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn weight_falls_freely_until_cable_is_taut() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("cable-history.ron");
+    run_simulation(PathBuf::from("tests/cable.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let weight = |frame: usize| history.get(frame).colliders[1].clone();
+
+    // The cable is slack until the weight has fallen far enough to pull it straight.
+    let taut = (0..history.len()).find(|&frame| weight(frame).transform.translate.length() > 0.99).expect("cable never went taut");
+    assert!(taut > 10, "cable went taut after only {taut} frames");
+
+    for frame in 0..taut {
+        let weight = weight(frame);
+        let t = frame as f32 * 0.01;
+
+        assert!((weight.transform.translate.x - 0.5).abs() < 1e-4, "cable pulled the slack weight to {}", weight.transform.translate);
+        assert!((weight.velocity.y + 9.81 * t).abs() < 0.1, "weight fell at {} after {t}s", weight.velocity);
+    }
+
+    // Once taut, the cable holds the weight at its full length.
+    for frame in taut..history.len() {
+        let distance = weight(frame).transform.translate.length();
+        assert!(distance < 1.01, "cable stretched to {distance} at frame {frame}");
+    }
+}
@@ -0,0 +1,128 @@
+use glam::{Quat, Vec3};
+use sokudo_core::narrowphase::{
+    capsule::{closest_points_on_segments, collide_capsule_box, collide_capsules, Capsule},
+    manifold::OrientedBox,
+};
+
+fn floor() -> OrientedBox {
+    OrientedBox { center: Vec3::ZERO, rotation: Quat::IDENTITY, half_extents: Vec3::new(2.0, 0.5, 2.0) }
+}
+
+#[test]
+fn closest_points_of_skew_segments_are_found() {
+    let (a, b) = closest_points_on_segments(
+        Vec3::new(-1.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.5, 2.0, -1.0),
+        Vec3::new(0.5, 2.0, 1.0),
+    );
+
+    assert!(a.abs_diff_eq(Vec3::new(0.5, 0.0, 0.0), 1e-5), "point on a was {a}");
+    assert!(b.abs_diff_eq(Vec3::new(0.5, 2.0, 0.0), 1e-5), "point on b was {b}");
+
+    // The closest point of the second segment is beyond the end of the first.
+    let (a, b) = closest_points_on_segments(
+        Vec3::new(-1.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(3.0, 1.0, -1.0),
+        Vec3::new(3.0, 1.0, 1.0),
+    );
+
+    assert!(a.abs_diff_eq(Vec3::new(1.0, 0.0, 0.0), 1e-5), "point on a was {a}");
+    assert!(b.abs_diff_eq(Vec3::new(3.0, 1.0, 0.0), 1e-5), "point on b was {b}");
+}
+
+#[test]
+fn parallel_capsules_penetrate_by_the_overlap_of_their_radii() {
+    let a = Capsule { start: Vec3::new(0.0, -1.0, 0.0), end: Vec3::new(0.0, 1.0, 0.0), radius: 0.5 };
+    let b = Capsule { start: Vec3::new(0.8, -0.5, 0.0), end: Vec3::new(0.8, 2.0, 0.0), radius: 0.5 };
+
+    let penetration = collide_capsules(&a, &b).expect("capsules should overlap");
+
+    assert!(penetration.normal.abs_diff_eq(Vec3::X, 1e-5), "normal was {}", penetration.normal);
+    assert!((penetration.depth - 0.2).abs() < 1e-5, "depth was {}", penetration.depth);
+    assert!((penetration.point_a.x - 0.5).abs() < 1e-5, "point on a was {}", penetration.point_a);
+    assert!((penetration.point_b.x - 0.3).abs() < 1e-5, "point on b was {}", penetration.point_b);
+}
+
+#[test]
+fn crossed_capsules_penetrate_between_their_segments() {
+    let a = Capsule { start: Vec3::new(-1.0, 0.0, 0.0), end: Vec3::new(1.0, 0.0, 0.0), radius: 0.3 };
+    let b = Capsule { start: Vec3::new(0.2, 0.5, -1.0), end: Vec3::new(0.2, 0.5, 1.0), radius: 0.3 };
+
+    let penetration = collide_capsules(&a, &b).expect("capsules should overlap");
+
+    assert!(penetration.normal.abs_diff_eq(Vec3::Y, 1e-5), "normal was {}", penetration.normal);
+    assert!((penetration.depth - 0.1).abs() < 1e-5, "depth was {}", penetration.depth);
+    assert!(penetration.point_a.abs_diff_eq(Vec3::new(0.2, 0.3, 0.0), 1e-5), "point on a was {}", penetration.point_a);
+    assert!(penetration.point_b.abs_diff_eq(Vec3::new(0.2, 0.2, 0.0), 1e-5), "point on b was {}", penetration.point_b);
+}
+
+#[test]
+fn separated_capsules_do_not_collide() {
+    let a = Capsule { start: Vec3::new(-1.0, 0.0, 0.0), end: Vec3::new(1.0, 0.0, 0.0), radius: 0.3 };
+    let b = Capsule { start: Vec3::new(1.5, 0.0, 0.0), end: Vec3::new(3.0, 0.0, 0.0), radius: 0.19 };
+
+    assert_eq!(collide_capsules(&a, &b), None);
+}
+
+#[test]
+fn capsule_lying_on_a_box_penetrates_its_top_face() {
+    let capsule = Capsule { start: Vec3::new(-1.0, 0.9, 0.3), end: Vec3::new(1.0, 0.9, 0.3), radius: 0.5 };
+
+    let penetration = collide_capsule_box(&capsule, &floor()).expect("capsule should overlap the box");
+
+    assert!(penetration.normal.abs_diff_eq(Vec3::NEG_Y, 1e-4), "normal was {}", penetration.normal);
+    assert!((penetration.depth - 0.1).abs() < 1e-4, "depth was {}", penetration.depth);
+    assert!((penetration.point_a.y - 0.4).abs() < 1e-4, "point on capsule was {}", penetration.point_a);
+    assert!((penetration.point_b.y - 0.5).abs() < 1e-4, "point on box was {}", penetration.point_b);
+}
+
+#[test]
+fn tilted_capsule_touches_a_box_with_its_lower_end() {
+    // Only the lower end of the capsule reaches below the top face, at a distance of 0.4 from it.
+    let capsule = Capsule { start: Vec3::new(0.0, 0.9, 0.0), end: Vec3::new(1.0, 2.0, 0.0), radius: 0.45 };
+
+    let penetration = collide_capsule_box(&capsule, &floor()).expect("capsule should overlap the box");
+
+    assert!(penetration.normal.abs_diff_eq(Vec3::NEG_Y, 1e-3), "normal was {}", penetration.normal);
+    assert!((penetration.depth - 0.05).abs() < 1e-3, "depth was {}", penetration.depth);
+    assert!(penetration.point_b.abs_diff_eq(Vec3::new(0.0, 0.5, 0.0), 1e-3), "point on box was {}", penetration.point_b);
+}
+
+#[test]
+fn capsule_beside_a_rotated_box_is_pushed_off_its_edge() {
+    // The box is turned by 45 degrees around Z, so its edge along Z is at a distance of
+    // `sqrt(2) / 2` from its center along X.
+    let r#box = OrientedBox {
+        center: Vec3::ZERO,
+        rotation: Quat::from_rotation_z(std::f32::consts::FRAC_PI_4),
+        half_extents: Vec3::splat(0.5),
+    };
+    let capsule = Capsule { start: Vec3::new(1.0, -1.0, 0.0), end: Vec3::new(1.0, 1.0, 0.0), radius: 0.4 };
+
+    let penetration = collide_capsule_box(&capsule, &r#box).expect("capsule should overlap the box");
+    let expected = 0.4 - (1.0 - std::f32::consts::FRAC_1_SQRT_2);
+
+    assert!(penetration.normal.abs_diff_eq(Vec3::NEG_X, 1e-3), "normal was {}", penetration.normal);
+    assert!((penetration.depth - expected).abs() < 1e-3, "depth was {}", penetration.depth);
+}
+
+#[test]
+fn capsule_away_from_a_box_does_not_collide() {
+    let capsule = Capsule { start: Vec3::new(-1.0, 1.2, 0.0), end: Vec3::new(1.0, 1.5, 0.0), radius: 0.5 };
+
+    assert_eq!(collide_capsule_box(&capsule, &floor()), None);
+}
+
+#[test]
+fn capsule_inside_a_box_is_pushed_out_of_the_nearest_face() {
+    // The segment lies within the box, so the capsule is pushed out through the top face, which
+    // is 0.2 above the segment.
+    let capsule = Capsule { start: Vec3::new(0.0, 0.3, -0.5), end: Vec3::new(0.0, 0.3, 0.5), radius: 0.3 };
+
+    let penetration = collide_capsule_box(&capsule, &floor()).expect("capsule should overlap the box");
+
+    assert!(penetration.normal.abs_diff_eq(Vec3::NEG_Y, 1e-3), "normal was {}", penetration.normal);
+    assert!((penetration.depth - 0.5).abs() < 1e-2, "depth was {}", penetration.depth);
+}
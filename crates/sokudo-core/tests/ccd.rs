@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use sokudo_core::{collider::ColliderBody, run::run_simulation, world::World};
+use sokudo_io::{read::ParsedWorld, write::ReadWorldStateHistory};
+
+#[test]
+fn bullet_stops_at_thin_wall() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("bullet-history.ron");
+    run_simulation(PathBuf::from("tests/bullet.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+
+    // The particle moves far further than the wall is thick each substep, but should never end
+    // up on the other side of it.
+    for frame in 0..history.len() {
+        let x = history.get(frame).colliders[1].transform.translate.x;
+        assert!(x <= 1.975 + 1e-3, "bullet passed through the wall at frame {frame}: x = {x}");
+    }
+}
+
+#[test]
+fn ball_stops_with_its_surface_at_thin_wall() {
+    let mut world: World = ParsedWorld::read("tests/bullet.ron").unwrap().into();
+
+    if let ColliderBody::Particle(particle) = &mut world.colliders[1].body {
+        particle.radius = 0.2;
+    }
+
+    world.initialize();
+
+    for step in 0..world.steps {
+        world.step();
+
+        let x = world.colliders[1].position.x;
+        assert!(x <= 1.775 + 1e-3, "ball sank into the wall after step {step}: x = {x}");
+    }
+}
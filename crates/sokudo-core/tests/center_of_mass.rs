@@ -0,0 +1,24 @@
+use std::{f32::consts::FRAC_PI_4, path::PathBuf};
+
+use glam::{EulerRot, Vec3};
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn weighted_box_tips_towards_heavy_side() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("tipping-history.ron");
+    run_simulation(PathBuf::from("tests/tipping.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let state = history.get(history.len() - 1);
+    let angle = |i: usize| state.colliders[i].transform.rotate.to_euler(EulerRot::XYZ).2;
+
+    // The center of mass lies to the right of the edge, so the weighted box falls clockwise onto
+    // its face while the balanced box stays on its edge.
+    let weighted = &state.colliders[1].transform;
+    assert!(angle(1).abs() < 0.05, "weighted box came to rest at {} radians", angle(1));
+    assert!(weighted.translate.x > 0.1, "weighted box came to rest at {}", weighted.translate);
+    assert!(weighted.translate.abs_diff_eq(Vec3::new(weighted.translate.x, 0.5, 0.0), 1e-2));
+
+    assert!((angle(2) - FRAC_PI_4).abs() < 0.05, "balanced box fell to {} radians", angle(2));
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use sokudo_core::{
+    run::{checkpoint_path, resume_simulation, run_simulation_with_options, RunOptions},
+    world::World,
+};
+use sokudo_io::{
+    read::ParsedWorld,
+    write::{HistoryOptions, HistoryWriter, ReadWorldStateHistory},
+};
+
+/// Simulates the chain for `steps` steps, writing a checkpoint once at least `checkpoint` of them
+/// are in the history, and then stops without finishing it as if it had been interrupted.
+fn interrupted_run(history_path: &PathBuf, options: HistoryOptions, checkpoint: u32, steps: u32) {
+    let mut world: World = ParsedWorld::read("tests/chain.ron").unwrap().into();
+    world.initialize();
+
+    let mut history = HistoryWriter::create(history_path, world.dt, options).unwrap();
+    history.push(&world.state()).unwrap();
+
+    let mut written = false;
+
+    for _ in 0..steps {
+        world.step();
+        history.push(&world.state()).unwrap();
+
+        if !written && world.step >= checkpoint && history.flush().unwrap() == history.len() {
+            world.checkpoint().write(checkpoint_path(history_path)).unwrap();
+            written = true;
+        }
+    }
+}
+
+fn resumed_run_is_identical(name: &str, options: HistoryOptions) {
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let uninterrupted_path = directory.join(format!("{name}-uninterrupted-history.ron"));
+    let resumed_path = directory.join(format!("{name}-resumed-history.ron"));
+
+    let run_options = RunOptions { history: options, ..Default::default() };
+    run_simulation_with_options(PathBuf::from("tests/chain.ron"), uninterrupted_path.clone(), run_options).unwrap();
+
+    // The states written after the checkpoint and before the interruption are discarded.
+    interrupted_run(&resumed_path, options, 64, 120);
+    resume_simulation(PathBuf::from("tests/chain.ron"), resumed_path.clone(), checkpoint_path(&resumed_path), RunOptions::default()).unwrap();
+
+    let uninterrupted = ReadWorldStateHistory::read(uninterrupted_path).unwrap();
+    let resumed = ReadWorldStateHistory::read(resumed_path.clone()).unwrap();
+
+    assert_eq!(resumed.len(), uninterrupted.len());
+    assert!(!checkpoint_path(&resumed_path).exists(), "checkpoint was not removed");
+
+    for frame in 0..uninterrupted.len() {
+        let expected = uninterrupted.get(frame);
+        let actual = resumed.get(frame);
+
+        assert_eq!(actual.step, expected.step);
+
+        for (actual, expected) in actual.colliders.iter().zip(expected.colliders.iter()) {
+            assert_eq!(actual.transform.translate, expected.transform.translate, "frame {frame}");
+            assert_eq!(actual.transform.rotate, expected.transform.rotate, "frame {frame}");
+            assert_eq!(actual.velocity, expected.velocity, "frame {frame}");
+            assert_eq!(actual.angular_velocity, expected.angular_velocity, "frame {frame}");
+        }
+    }
+}
+
+#[test]
+fn resumed_run_matches_uninterrupted_run() {
+    resumed_run_is_identical("chain", HistoryOptions::default());
+}
+
+#[test]
+fn resumed_compressed_run_matches_uninterrupted_run() {
+    let options = HistoryOptions { compress: true, keyframe_interval: 8, ..Default::default() };
+    resumed_run_is_identical("chain-compressed", options);
+}
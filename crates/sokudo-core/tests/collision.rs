@@ -0,0 +1,21 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn pressed_boxes_separate() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("pressed-boxes-history.ron");
+    run_simulation(PathBuf::from("tests/pressed-boxes.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let state = history.get(history.len() - 1);
+
+    let a = &state.colliders[0].transform;
+    let b = &state.colliders[1].transform;
+
+    // The boxes only overlap along the x axis, so they should be pushed apart along it.
+    let gap = (b.translate.x - 0.5 * b.scale.x) - (a.translate.x + 0.5 * a.scale.x);
+    assert!(gap >= -1e-3, "boxes still overlap by {}", -gap);
+    assert!(b.translate.x > a.translate.x);
+}
@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use glam::{Quat, Vec3};
+use sokudo_core::{run::run_simulation, shape::{compound::CompoundShape, cuboid::CuboidShape, AbstractShape, Shape}, transform::Transform};
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn compound_inertia_uses_parallel_axes() {
+    let compound = CompoundShape {
+        parts: vec![
+            (Transform::from_xyz(-1.0, 0.0, 0.0), Shape::Cuboid(CuboidShape)),
+            (Transform::from_xyz(1.0, 0.0, 0.0), Shape::Cuboid(CuboidShape)),
+        ],
+    };
+
+    assert!((compound.volume(Vec3::ONE) - 2.0).abs() < 1e-6);
+    assert!(compound.centroid(Vec3::ONE).length() < 1e-6);
+
+    // Each unit cube has a moment of 1/6 about its own axes, and is 1 away from the y and z axes.
+    let moments = compound.moments(Vec3::ONE);
+    assert!((moments - Vec3::new(1.0 / 6.0, 1.0 / 6.0 + 1.0, 1.0 / 6.0 + 1.0)).length() < 1e-5, "moments were {moments}");
+
+    // Rotating a part changes nothing for a cube, but scaling the compound stretches the offsets.
+    let rotated = CompoundShape {
+        parts: vec![
+            (Transform {
+                rotate: Quat::from_rotation_z(0.7),
+                ..Transform::from_xyz(-1.0, 0.0, 0.0)
+            }, Shape::Cuboid(CuboidShape)),
+            (Transform::from_xyz(1.0, 0.0, 0.0), Shape::Cuboid(CuboidShape)),
+        ],
+    };
+    let stretched = rotated.moments(Vec3::new(2.0, 1.0, 1.0));
+    assert!((stretched - Vec3::new(1.0 / 6.0, 5.0 / 12.0 + 4.0, 5.0 / 12.0 + 4.0)).length() < 1e-4, "moments were {stretched}");
+}
+
+#[test]
+fn compound_rests_on_its_parts() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("compound-history.ron");
+    run_simulation(PathBuf::from("tests/compound.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let last = &history.get(history.len() - 1).colliders[2];
+
+    // The weights have a radius of 0.5 and rest on the floor, holding the bar above the post.
+    assert!((last.transform.translate.y - 1.0).abs() < 0.05, "dumbbell rested at {}", last.transform.translate);
+    assert!(last.transform.rotate.angle_between(Quat::IDENTITY) < 0.05, "dumbbell tipped to {:?}", last.transform.rotate);
+    assert!(last.velocity.length() < 0.1, "dumbbell still moved at {}", last.velocity);
+}
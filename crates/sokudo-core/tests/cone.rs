@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use glam::Vec3;
+use sokudo_core::{run::run_simulation, shape::{cone::ConeShape, AbstractShape}};
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn cone_is_centered_at_its_centroid() {
+    let cone = ConeShape { radius: 0.5, height: 2.0 };
+
+    assert!(cone.sd(Vec3::ZERO) < 0.0);
+    assert!(cone.sd(Vec3::new(0.0, 1.5, 0.0)).abs() < 1e-6);
+    assert!(cone.sd(Vec3::new(0.0, -0.5, 0.0)).abs() < 1e-6);
+    assert!((cone.sd(Vec3::new(0.0, -1.0, 0.0)) - 0.5).abs() < 1e-6);
+    assert_eq!(cone.support(Vec3::Y), Some(Vec3::new(0.0, 1.5, 0.0)));
+    assert_eq!(cone.support(Vec3::new(1.0, -1.0, 0.0)), Some(Vec3::new(0.5, -0.5, 0.0)));
+}
+
+#[test]
+fn cones_stand_or_tip_about_their_center_of_mass() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("cone-history.ron");
+    run_simulation(PathBuf::from("tests/cone.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let last = history.get(history.len() - 1);
+    let tilt = |i: usize| (last.colliders[i].transform.rotate * Vec3::Y).angle_between(Vec3::Y).to_degrees();
+
+    assert!(tilt(1) < 5.0, "upright cone tilted to {} degrees", tilt(1));
+    assert!(tilt(2) < 5.0, "cone tilted by 35 degrees did not stand back up, ending at {} degrees", tilt(2));
+
+    // Lying on its side leaves the axis of a cone with a half angle of 14 degrees tilted by 104.
+    assert!((tilt(3) - 104.0).abs() < 5.0, "cone tilted by 55 degrees ended at {} degrees", tilt(3));
+}
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use glam::Vec3;
+use sokudo_core::{run::run_simulation, shape::{cylinder::CylinderShape, AbstractShape}};
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn cylinder_inertia_matches_solid_cylinder() {
+    let cylinder = CylinderShape { radius: 0.5, half_height: 1.0 };
+    let moments = cylinder.moments(Vec3::ONE);
+
+    assert!((moments.y - 0.5 * 0.25).abs() < 1e-6);
+    assert!((moments.x - (3.0 * 0.25 + 4.0) / 12.0).abs() < 1e-6);
+    assert!((moments.z - moments.x).abs() < 1e-6);
+}
+
+#[test]
+fn cylinder_on_its_side_rolls() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("cylinder-history.ron");
+    run_simulation(PathBuf::from("tests/cylinder.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let last = &history.get(history.len() - 1).colliders[1];
+
+    // Rolling without slipping, the cylinder turns about the z axis at `v / r` while staying on
+    // the floor.
+    let speed = last.velocity.x;
+    let spin = -last.angular_velocity.z;
+
+    assert!(speed > 0.5, "cylinder moved at {}", last.velocity);
+    assert!((spin * 0.5 - speed).abs() < 0.2 * speed, "cylinder moved at {speed} while spinning at {spin}");
+    assert!((last.transform.translate.y - 1.0).abs() < 0.05, "cylinder left the floor to {}", last.transform.translate);
+}
@@ -0,0 +1,21 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn linear_damping_slows_bodies() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("damping-history.ron");
+    run_simulation(PathBuf::from("tests/damping.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let last = history.get(history.len() - 1);
+
+    // After one second, the damped box has lost roughly `1 - e^(-0.5)` of its velocity while the
+    // undamped box keeps moving at the same speed.
+    let damped = last.colliders[0].velocity.x;
+    let undamped = last.colliders[1].velocity.x;
+
+    assert!((damped - (-0.5f32).exp()).abs() < 1e-2, "damped box had velocity {damped}");
+    assert!((undamped - 1.0).abs() < 1e-4, "undamped box had velocity {undamped}");
+}
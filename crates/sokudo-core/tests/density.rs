@@ -0,0 +1,11 @@
+use sokudo_core::world::World;
+use sokudo_io::read::ParsedWorld;
+
+#[test]
+fn mass_is_computed_from_density() {
+    let world: World = ParsedWorld::read("tests/density.ron").unwrap().into();
+
+    // A 2 x 1 x 0.5 box and a unit tetrahedron scaled by two, with a volume of 8 / 6.
+    assert!((world.colliders[0].body.mass() - 3.0).abs() < 1e-5);
+    assert!((world.colliders[1].body.mass() - 4.0).abs() < 1e-5);
+}
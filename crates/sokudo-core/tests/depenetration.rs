@@ -0,0 +1,55 @@
+use sokudo_core::world::World;
+use sokudo_io::read::ParsedWorld;
+
+/// Two unit boxes overlapping by 90% along X, without gravity.
+fn overlapping_boxes(max_correction_per_step: Option<f32>) -> World {
+    let mut world: World = ParsedWorld::read("tests/overlapping-boxes.ron").unwrap().into();
+
+    if let Some(max_correction_per_step) = max_correction_per_step {
+        world.max_correction_per_step = max_correction_per_step;
+    }
+
+    world.initialize();
+    world
+}
+
+fn gap(world: &World) -> f32 {
+    world.colliders[1].position.x - world.colliders[0].position.x
+}
+
+#[test]
+fn overlapping_boxes_separate_without_a_velocity_spike() {
+    let mut world = overlapping_boxes(None);
+
+    for step in 0..world.steps {
+        world.step();
+
+        // Resolving the whole overlap within a substep launches the boxes apart at over 15 m/s.
+        for collider in world.colliders.iter() {
+            let speed = collider.velocity.length();
+            assert!(speed < 0.5, "collider {} moved at {speed} after step {step}", collider.id);
+        }
+    }
+
+    assert!(gap(&world) > 0.995, "boxes were separated by {}", gap(&world));
+}
+
+#[test]
+fn each_step_resolves_its_fraction_of_the_overlap() {
+    for max_correction_per_step in [0.05, 0.2, 0.5] {
+        let mut world = overlapping_boxes(Some(max_correction_per_step));
+
+        for step in 0..5 {
+            let overlap = 1.0 - gap(&world);
+            world.step();
+
+            // The fraction is spread over the substeps, but adds up to the same share of the
+            // overlap every step.
+            let resolved = (overlap - (1.0 - gap(&world))) / overlap;
+            assert!(
+                (resolved - max_correction_per_step).abs() < 0.01,
+                "{resolved} of the overlap was resolved in step {step} with a max_correction_per_step of {max_correction_per_step}",
+            );
+        }
+    }
+}
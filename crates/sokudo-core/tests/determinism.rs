@@ -0,0 +1,28 @@
+use std::{fs, path::PathBuf};
+
+use sokudo_core::run::{run_simulation_with_options, RunOptions};
+use sokudo_io::write::{HistoryFormat, HistoryOptions};
+
+fn assert_bakes_identical(name: &str, history: HistoryOptions) {
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let options = RunOptions { history, ..Default::default() };
+
+    let first = directory.join(format!("{name}-first"));
+    let second = directory.join(format!("{name}-second"));
+
+    run_simulation_with_options(PathBuf::from("tests/determinism.ron"), first.clone(), options.clone()).unwrap();
+    run_simulation_with_options(PathBuf::from("tests/determinism.ron"), second.clone(), options).unwrap();
+
+    assert!(fs::read(first).unwrap() == fs::read(second).unwrap(), "two bakes of the same world differed");
+}
+
+#[test]
+fn bakes_are_byte_identical() {
+    assert_bakes_identical("determinism-ron", HistoryOptions::default());
+}
+
+#[test]
+fn compressed_bakes_are_byte_identical() {
+    let options = HistoryOptions { format: HistoryFormat::Json, compress: true, ..Default::default() };
+    assert_bakes_identical("determinism-compressed", options);
+}
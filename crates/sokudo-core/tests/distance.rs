@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+use glam::Vec3;
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn pendulum_hangs_at_rest_length() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("pendulum-history.ron");
+    run_simulation(PathBuf::from("tests/pendulum.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let state = history.get(history.len() - 1);
+
+    let anchor = state.colliders[0].transform.translate;
+    let bob = state.colliders[1].transform.translate;
+
+    assert!(anchor.abs_diff_eq(Vec3::ZERO, 1e-6));
+    assert!(bob.abs_diff_eq(Vec3::new(0.0, -1.0, 0.0), 1e-3), "bob settled at {bob}");
+}
@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn quadratic_drag_slows_bodies() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("drag-history.ron");
+    run_simulation(PathBuf::from("tests/drag.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let last = history.get(history.len() - 1);
+
+    // With `k = 0.5 * drag_coefficient * area / mass`, a body launched at `v0` slows to
+    // `v0 / (1 + k * v0 * t)`, so after one second the unit box moves at a third of its initial
+    // speed. The wide box has four times the cross-section and four times the mass, so it slows
+    // just the same.
+    let expected = 4.0 / (1.0 + 0.5 * 4.0);
+
+    for i in 0..2 {
+        let velocity = last.colliders[i].velocity.x;
+        assert!((velocity - expected).abs() < 2e-2, "box {i} had velocity {velocity}");
+    }
+
+    let undragged = last.colliders[2].velocity.x;
+    assert!((undragged - 4.0).abs() < 1e-4, "undragged box had velocity {undragged}");
+}
@@ -0,0 +1,81 @@
+use glam::Vec3;
+use sokudo_core::world::World;
+use sokudo_io::{read::ParsedWorld, write::WriteWorldState};
+
+fn fountain() -> World {
+    let mut world: World = ParsedWorld::read("tests/fountain.ron").unwrap().into();
+    world.initialize();
+    world
+}
+
+/// The ID, translation and velocity of each collider in `state`.
+fn colliders(state: &WriteWorldState) -> Vec<(u32, Vec3, Vec3)> {
+    state.colliders.iter().map(|collider| (collider.id, collider.transform.translate, collider.velocity)).collect()
+}
+
+fn run(mut world: World) -> Vec<Vec<(u32, Vec3, Vec3)>> {
+    let mut frames = Vec::new();
+
+    while world.step < world.steps {
+        frames.push(colliders(&world.step()));
+    }
+
+    frames
+}
+
+#[test]
+fn emitter_spawns_and_removes_particles() {
+    let mut world = fountain();
+    let mut most = 0;
+
+    while world.step < world.steps {
+        let state = world.step();
+        most = most.max(state.colliders.len());
+
+        for collider in world.colliders.iter().filter(|collider| collider.removed) {
+            assert!(state.colliders.iter().all(|c| c.id != collider.id), "removed particle {} is in the state", collider.id);
+        }
+    }
+
+    // Twenty particles are spawned every second and each lives for a second, so the emitter is
+    // held at its cap once it has filled up.
+    assert_eq!(most, 15);
+    assert!(world.colliders.len() > 40, "only {} particles were spawned", world.colliders.len());
+    assert_eq!(world.emitters[0].particles.len(), 15);
+}
+
+#[test]
+fn emitted_particles_follow_an_arc() {
+    let heights: Vec<_> = run(fountain()).iter()
+        .filter_map(|frame| frame.iter().find(|(id, _, _)| *id == 0))
+        .map(|&(_, translate, _)| translate)
+        .collect();
+
+    let apex = heights.iter().enumerate().max_by(|(_, a), (_, b)| a.y.total_cmp(&b.y)).unwrap().0;
+
+    assert!(apex > 0 && apex < heights.len() - 1, "particle peaked at frame {apex}");
+    assert!(heights[..apex].windows(2).all(|pair| pair[1].y > pair[0].y), "particle did not rise to its apex");
+    assert!(heights[apex..].windows(2).all(|pair| pair[1].y < pair[0].y), "particle did not fall from its apex");
+    assert!(heights.windows(2).all(|pair| pair[1].x > pair[0].x), "particle did not move away from the emitter");
+}
+
+#[test]
+fn emitters_are_deterministic() {
+    assert_eq!(run(fountain()), run(fountain()));
+}
+
+#[test]
+fn restored_world_respawns_particles() {
+    let mut world = fountain();
+
+    for _ in 0..150 {
+        world.advance();
+    }
+
+    let checkpoint = world.checkpoint();
+    let mut restored = fountain();
+    restored.restore(&checkpoint);
+
+    assert_eq!(colliders(&restored.state()), colliders(&world.state()));
+    assert_eq!(run(restored), run(world));
+}
@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use sokudo_core::{
+    run::{checkpoint_path, resume_simulation, run_simulation_with_options, RunOptions},
+    world::World,
+};
+use sokudo_io::{
+    read::ParsedWorld,
+    write::{events::{CollisionEventKind, CollisionEventLog}, HistoryOptions, HistoryWriter},
+};
+
+fn bake_events(name: &str) -> CollisionEventLog {
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let events_path = directory.join(format!("{name}-events.ron"));
+
+    let options = RunOptions { events: Some(events_path.clone()), ..Default::default() };
+    run_simulation_with_options(PathBuf::from("tests/bounce.ron"), directory.join(format!("{name}-history.ron")), options).unwrap();
+
+    CollisionEventLog::read(events_path).unwrap()
+}
+
+#[test]
+fn events_follow_contacts() {
+    let log = bake_events("bounce");
+
+    for pair in [(0, 1), (2, 3)] {
+        let mut touching = false;
+
+        for event in log.events.iter().filter(|event| (event.a, event.b) == pair) {
+            match event.kind {
+                CollisionEventKind::Begin => assert!(!touching, "{pair:?} began touching twice in step {}", event.step),
+                _ => assert!(touching, "{pair:?} was not touching in step {}", event.step),
+            }
+
+            // Resting contacts can momentarily need no impulse, but impacts always do.
+            if event.kind == CollisionEventKind::Begin {
+                assert!(event.impulse > 0.0, "{pair:?} began touching without an impulse in step {}", event.step);
+            }
+
+            touching = event.kind != CollisionEventKind::End;
+        }
+    }
+
+    let count = |pair, kind| log.events.iter().filter(|event| (event.a, event.b) == pair && event.kind == kind).count();
+
+    // The elastic particle bounces off the floor repeatedly, while the inelastic one stays on it.
+    assert!(count((0, 1), CollisionEventKind::Begin) > 1);
+    assert!(count((0, 1), CollisionEventKind::End) > 1);
+    assert_eq!(count((2, 3), CollisionEventKind::Begin), 1);
+    assert_eq!(count((2, 3), CollisionEventKind::End), 0);
+    assert!(count((2, 3), CollisionEventKind::Stay) > 1);
+}
+
+#[test]
+fn resumed_events_match_uninterrupted_events() {
+    let expected = bake_events("bounce-uninterrupted");
+
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let history_path = directory.join("bounce-resumed-history.ron");
+    let events_path = directory.join("bounce-resumed-events.ron");
+
+    // Simulate past a checkpoint in the middle of the bounces and stop as if interrupted, leaving
+    // events after the checkpoint behind like a log written at a later checkpoint would.
+    let mut world: World = ParsedWorld::read("tests/bounce.ron").unwrap().into();
+    world.initialize();
+
+    let mut history = HistoryWriter::create(&history_path, world.dt, HistoryOptions::default()).unwrap();
+    history.push(&world.state()).unwrap();
+
+    let mut log = CollisionEventLog::default();
+
+    for _ in 0..90 {
+        world.step();
+        history.push(&world.state()).unwrap();
+        log.events.extend_from_slice(&world.events);
+
+        if world.step == 60 {
+            history.flush().unwrap();
+            world.checkpoint().write(checkpoint_path(&history_path)).unwrap();
+        }
+    }
+
+    log.write(&events_path).unwrap();
+
+    let options = RunOptions { events: Some(events_path.clone()), ..Default::default() };
+    resume_simulation(PathBuf::from("tests/bounce.ron"), history_path.clone(), checkpoint_path(&history_path), options).unwrap();
+
+    assert_eq!(CollisionEventLog::read(events_path).unwrap(), expected);
+}
@@ -0,0 +1,81 @@
+use std::{collections::BTreeSet, fs, path::PathBuf};
+
+use gltf_json::{animation::Property, validation::{Checked, Validate}, Path, Root};
+use sokudo_core::{export::export_gltf, run::run_simulation};
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn export_animates_every_collider() {
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let history_path = directory.join("export-bounce-history.ron");
+    let output_path = directory.join("export-bounce.gltf");
+
+    run_simulation(PathBuf::from("tests/bounce.ron"), history_path.clone()).unwrap();
+    export_gltf(PathBuf::from("tests/bounce.ron"), history_path.clone(), output_path.clone()).unwrap();
+
+    let root = Root::from_slice(&fs::read(&output_path).unwrap()).unwrap();
+    let data = fs::read(output_path.with_extension("bin")).unwrap();
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+
+    let mut errors = Vec::new();
+    root.validate(&root, Path::new, &mut |path, err| errors.push(format!("{}: {err}", path())));
+    assert!(errors.is_empty(), "{errors:?}");
+
+    assert_eq!(root.buffers[0].byte_length.0 as usize, data.len());
+    assert_eq!(root.nodes.len(), 4);
+
+    // Nothing changes scale, so only translation and rotation are animated.
+    let animation = &root.animations[0];
+    assert_eq!(animation.channels.len(), 8);
+
+    let channel = animation.channels.iter()
+        .find(|channel| channel.target.node.value() == 1 && channel.target.path == Checked::Valid(Property::Translation))
+        .unwrap();
+    let sampler = &animation.samplers[channel.sampler.value()];
+    let output = &root.accessors[sampler.output.value()];
+    let view = &root.buffer_views[output.buffer_view.unwrap().value()];
+
+    assert_eq!(output.count.0 as usize, history.len());
+    assert_eq!(root.accessors[sampler.input.value()].count, output.count);
+
+    // The last keyframe is where the ball ends up.
+    let last = view.byte_offset.unwrap().0 as usize + view.byte_length.0 as usize - 12;
+    let translation: Vec<f32> = data[last..last + 12]
+        .chunks(4)
+        .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect();
+
+    let state = history.get(history.len() - 1);
+    assert_eq!(translation, state.colliders[1].transform.translate.to_array());
+}
+
+#[test]
+fn export_has_a_node_for_every_emitted_particle() {
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let history_path = directory.join("export-fountain-history.ron");
+    let output_path = directory.join("export-fountain.gltf");
+
+    run_simulation(PathBuf::from("tests/fountain.ron"), history_path.clone()).unwrap();
+    export_gltf(PathBuf::from("tests/fountain.ron"), history_path.clone(), output_path.clone()).unwrap();
+
+    let root = Root::from_slice(&fs::read(&output_path).unwrap()).unwrap();
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+
+    let mut errors = Vec::new();
+    root.validate(&root, Path::new, &mut |path, err| errors.push(format!("{}: {err}", path())));
+    assert!(errors.is_empty(), "{errors:?}");
+
+    let ids: BTreeSet<u32> = (0..history.len())
+        .flat_map(|step| history.get(step).colliders.iter().map(|collider| collider.id).collect::<Vec<_>>())
+        .collect();
+    let names: BTreeSet<String> = root.nodes.iter().filter_map(|node| node.name.clone()).collect();
+
+    assert!(!ids.is_empty());
+    assert_eq!(root.nodes.len(), ids.len());
+    assert_eq!(names, ids.iter().map(|id| format!("collider {id}")).collect());
+
+    // The particles are hidden before they are spawned and once they expire.
+    let animation = &root.animations[0];
+    assert_eq!(animation.channels.len(), 3 * ids.len());
+    assert!(root.nodes.iter().all(|node| node.scale == Some([0.0; 3])));
+}
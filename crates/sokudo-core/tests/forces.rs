@@ -0,0 +1,44 @@
+use glam::Vec3;
+use sokudo_core::{collider::ColliderBody, rigid_body::RigidBody, world::World};
+use sokudo_io::read::ParsedWorld;
+
+fn world() -> World {
+    let mut world: World = ParsedWorld::read("tests/forces.ron").unwrap().into();
+    world.initialize();
+    world
+}
+
+fn apply(world: &mut World, f: impl FnOnce(&mut RigidBody)) {
+    let ColliderBody::Rigid(rb) = &mut world.colliders[0].body else {
+        panic!("expected a rigid body");
+    };
+
+    f(rb);
+}
+
+#[test]
+fn forces_accelerate_bodies_for_one_step() {
+    let mut world = world();
+    apply(&mut world, |rb| rb.apply_force(Vec3::new(10.0, 0.0, 0.0)));
+
+    world.step();
+    let velocity = world.colliders[0].velocity;
+    assert!((velocity.x - 0.1).abs() < 1e-4, "box had velocity {velocity}");
+
+    // The force is cleared after the step, so the box keeps moving at the same speed.
+    world.step();
+    assert!((world.colliders[0].velocity - velocity).length() < 1e-6);
+}
+
+#[test]
+fn off_center_forces_spin_bodies() {
+    let mut world = world();
+    apply(&mut world, |rb| rb.apply_force_at_point(Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.5, 0.0, 0.0)));
+
+    world.step();
+    let angular_velocity = world.colliders[0].angular_velocity();
+
+    // A push on the +X face along +Z spins the box clockwise about Y.
+    assert!(angular_velocity.y < 0.0, "box had angular velocity {angular_velocity}");
+    assert!(world.colliders[0].velocity.z > 0.0);
+}
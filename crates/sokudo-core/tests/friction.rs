@@ -0,0 +1,19 @@
+use std::path::PathBuf;
+
+use glam::Vec3;
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn box_rests_on_slope() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("slope-history.ron");
+    run_simulation(PathBuf::from("tests/slope.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let start = history.get(0).colliders[1].transform.translate;
+    let end = history.get(history.len() - 1).colliders[1].transform.translate;
+
+    // The slope is 15 degrees, well below the friction angle of the box, so it should not slide.
+    assert!(end.abs_diff_eq(start, 2e-2), "box slid from {start} to {end}");
+    assert!(end.abs_diff_eq(Vec3::new(-0.2588, 0.9659, 0.0), 2e-2));
+}
@@ -0,0 +1,54 @@
+use glam::Vec3;
+use sokudo_core::{collider::ColliderBody, world::World};
+use sokudo_io::read::ParsedWorld;
+
+fn spinning_box(angular_velocity: Vec3) -> World {
+    let mut world: World = ParsedWorld::read("tests/gyroscopic.ron").unwrap().into();
+    world.initialize();
+
+    let ColliderBody::Rigid(rb) = &mut world.colliders[0].body else {
+        panic!("expected a rigid body");
+    };
+
+    rb.angular_velocity = angular_velocity;
+    world
+}
+
+/// The angular velocity of the box in its own local coordinates.
+fn local_angular_velocity(world: &World) -> Vec3 {
+    let collider = &world.colliders[0];
+    collider.rotation().inverse() * collider.angular_velocity()
+}
+
+#[test]
+fn spin_about_intermediate_axis_flips() {
+    // The y axis has the intermediate moment of inertia, so a slightly perturbed spin about it is
+    // unstable and the box periodically turns over.
+    let mut world = spinning_box(Vec3::new(0.01, 5.0, 0.0));
+    let mut flips = 0;
+    let mut previous = local_angular_velocity(&world).y;
+
+    for _ in 0..world.steps {
+        world.step();
+
+        let current = local_angular_velocity(&world).y;
+        if current.signum() != previous.signum() {
+            flips += 1;
+        }
+        previous = current;
+    }
+
+    assert!(flips >= 3, "the box flipped {flips} times");
+}
+
+#[test]
+fn spin_about_principal_axis_is_stable() {
+    let mut world = spinning_box(Vec3::new(0.01, 0.0, 5.0));
+
+    for _ in 0..world.steps {
+        world.step();
+
+        let local = local_angular_velocity(&world);
+        assert!(local.z > 4.9, "the box drifted off its axis of spin to {local}");
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use glam::Vec3;
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::{inspect::InspectFeature, ReadWorldStateHistory};
+
+#[test]
+fn colliders_rest_on_half_space() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("half-space-history.ron");
+    run_simulation(PathBuf::from("tests/half-space.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let state = history.get(history.len() - 1);
+
+    let ground = state.colliders[0].transform.translate;
+    let cube = state.colliders[1].transform.translate;
+    let particle = state.colliders[2].transform.translate;
+
+    assert_eq!(ground, Vec3::ZERO);
+    assert!((cube.y - 0.5).abs() < 2e-2, "cube came to rest at {cube}");
+    assert!(particle.y.abs() < 2e-2, "particle came to rest at {particle}");
+}
+
+#[test]
+fn resting_contacts_are_inspected() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("half-space-contacts-history.ron");
+    run_simulation(PathBuf::from("tests/half-space.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let state = history.get(history.len() - 1);
+
+    let contacts: Vec<_> = state.inspector.elements.values()
+        .filter_map(|feature| match feature {
+            InspectFeature::Contact { point, normal, .. } => Some((*point, *normal)),
+            _ => None,
+        })
+        .collect();
+
+    assert!(!contacts.is_empty(), "no contacts were recorded");
+
+    for (point, normal) in contacts {
+        assert!(point.y.abs() < 2e-2, "contact at {point} is not on the ground");
+        // The normal points towards whichever collider comes first in the pair.
+        assert!(normal.abs().abs_diff_eq(Vec3::Y, 1e-3), "contact normal {normal} is not along the ground normal");
+    }
+}
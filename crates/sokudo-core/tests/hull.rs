@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use glam::Vec3;
+use sokudo_core::{aabb::Aabb, run::run_simulation, shape::{convex_hull::ConvexHullShape, AbstractShape}};
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn convex_hulls_land_on_ground() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("hull-history.ron");
+    run_simulation(PathBuf::from("tests/hull.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let state = history.get(history.len() - 1);
+
+    let cube = state.colliders[1].transform.translate;
+    let plate = state.colliders[2].transform.translate;
+
+    assert!((cube.y - 1.0).abs() < 2e-2, "cube hull came to rest at {cube}");
+    assert!((plate.y - 0.5).abs() < 2e-2, "flat hull came to rest at {plate}");
+}
+
+#[test]
+fn quickhull_discards_interior_points() {
+    let mut points = Aabb::new(Vec3::splat(-0.5), Vec3::splat(0.5)).corners().to_vec();
+    points.extend([Vec3::ZERO, Vec3::new(0.1, -0.2, 0.3), Vec3::new(0.0, 0.5, 0.0)]);
+
+    let hull = ConvexHullShape::new(&points);
+
+    assert_eq!(hull.vertices.len(), 8);
+    assert_eq!(hull.indices.len(), 12);
+    assert!((hull.sd(Vec3::ZERO) + 0.5).abs() < 1e-5);
+    assert!((hull.sd(Vec3::new(1.5, 0.0, 0.0)) - 1.0).abs() < 1e-5);
+}
@@ -0,0 +1,58 @@
+use glam::{Quat, Vec3};
+use sokudo_core::{collider::{Collider, ColliderBody}, rigid_body::RigidBody, shape::{cuboid::CuboidShape, Shape}, world::World};
+
+/// A world without gravity holding a single box of `mass`, scaled and rotated so that its inertia
+/// tensor is not diagonal.
+fn box_world(mass: f32) -> World {
+    let mut world = World::new(0.01, Vec3::ZERO);
+
+    let mut cube = RigidBody::new(Shape::Cuboid(CuboidShape), mass);
+    cube.scale = Vec3::new(1.0, 2.0, 3.0);
+    cube.rotation = Quat::from_euler(glam::EulerRot::XYZ, 0.3, -0.5, 0.8);
+
+    world.add_collider(Collider::new(ColliderBody::Rigid(cube), Vec3::new(1.0, 2.0, 3.0)));
+    world
+}
+
+#[test]
+fn off_center_impulse_spins_body() {
+    let mut world = box_world(2.0);
+    let collider = &mut world.colliders[0];
+
+    let impulse = Vec3::new(0.0, 0.0, 4.0);
+    let point = collider.position + Vec3::new(0.5, 0.2, 0.0);
+    let expected = collider.global_inverse_inertia() * (point - collider.position).cross(impulse);
+
+    collider.apply_impulse_at_point(impulse, point);
+
+    assert!(collider.velocity.abs_diff_eq(Vec3::new(0.0, 0.0, 2.0), 1e-6), "velocity was {}", collider.velocity);
+    assert!(collider.angular_velocity().abs_diff_eq(expected, 1e-6), "angular velocity was {}", collider.angular_velocity());
+    assert!(expected.length() > 0.1);
+}
+
+#[test]
+fn central_impulse_only_moves_body() {
+    let mut world = box_world(4.0);
+    world.colliders[0].apply_impulse(Vec3::new(2.0, 0.0, -1.0));
+
+    assert_eq!(world.colliders[0].velocity, Vec3::new(0.5, 0.0, -0.25));
+    assert_eq!(world.colliders[0].angular_velocity(), Vec3::ZERO);
+
+    let start = world.colliders[0].position;
+    world.advance();
+
+    let moved = world.colliders[0].position - start;
+    assert!(moved.abs_diff_eq(Vec3::new(0.005, 0.0, -0.0025), 1e-6), "body moved by {moved}");
+}
+
+#[test]
+fn impulse_wakes_sleeping_body() {
+    let mut world = box_world(1.0);
+    world.colliders[0].asleep = true;
+    world.colliders[0].apply_impulse(Vec3::X);
+
+    assert!(!world.colliders[0].asleep);
+
+    world.advance();
+    assert!(world.colliders[0].position.x > 1.0, "body stayed at {}", world.colliders[0].position);
+}
@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn moving_platform_carries_box() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("kinematic-history.ron");
+    run_simulation(PathBuf::from("tests/kinematic.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+
+    // Halfway along its path, the platform moves at one meter per second regardless of the box
+    // resting on it.
+    let halfway = &history.get(150).colliders;
+    assert!((halfway[0].transform.translate.x - 1.0).abs() < 1e-3, "platform was at {}", halfway[0].transform.translate);
+    assert!((halfway[0].velocity.x - 1.0).abs() < 1e-3, "platform moved at {}", halfway[0].velocity);
+    assert!(halfway[0].transform.translate.y.abs() < 1e-6, "platform was pushed to {}", halfway[0].transform.translate);
+    assert!((halfway[1].velocity.x - 1.0).abs() < 0.05, "box moved at {}", halfway[1].velocity);
+
+    let last = &history.get(history.len() - 1).colliders;
+    let platform = last[0].transform.translate;
+    let cube = last[1].transform.translate;
+
+    assert!((platform.x - 2.0).abs() < 1e-3, "platform stopped at {platform}");
+    assert!(last[0].velocity.length() < 1e-3, "platform still moved at {}", last[0].velocity);
+    assert!(cube.x > 1.7, "box was only carried to {cube}");
+    assert!((cube.y - 0.75).abs() < 0.02, "box left the platform to {cube}");
+}
@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn masked_colliders_pass_through_each_other() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("layers-history.ron");
+    run_simulation(PathBuf::from("tests/layers.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let last = history.get(history.len() - 1);
+    let height = |i: usize| last.colliders[i].transform.translate.y;
+
+    // Both pieces of debris fall onto the floor through each other, and the box above lands on
+    // top of them since it collides with every layer.
+    for i in 1..3 {
+        assert!((height(i) - 0.5).abs() < 1e-2, "debris {i} rested at {}", height(i));
+    }
+
+    assert!((height(3) - 1.5).abs() < 1e-2, "box rested at {}", height(3));
+}
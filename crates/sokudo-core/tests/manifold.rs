@@ -0,0 +1,111 @@
+use std::{collections::BTreeSet, f32::consts::FRAC_PI_4, path::PathBuf};
+
+use glam::{Quat, Vec3};
+use sokudo_core::{narrowphase::manifold::{collide_boxes, OrientedBox}, run::run_simulation};
+use sokudo_io::write::ReadWorldStateHistory;
+
+fn unit_box(center: Vec3, rotation: Quat) -> OrientedBox {
+    OrientedBox { center, rotation, half_extents: Vec3::splat(0.5) }
+}
+
+fn floor() -> OrientedBox {
+    OrientedBox { center: Vec3::ZERO, rotation: Quat::IDENTITY, half_extents: Vec3::new(2.0, 0.5, 2.0) }
+}
+
+#[test]
+fn resting_box_is_supported_at_its_corners() {
+    let manifold = collide_boxes(&floor(), &unit_box(Vec3::new(0.2, 0.99, -0.1), Quat::IDENTITY))
+        .expect("boxes should overlap");
+
+    assert!(manifold.normal.abs_diff_eq(Vec3::Y, 1e-5), "normal was {}", manifold.normal);
+    assert_eq!(manifold.points.len(), 4);
+
+    for point in manifold.points.iter() {
+        assert!((point.depth - 0.01).abs() < 1e-4, "depth was {}", point.depth);
+        assert!((point.point_a.y - 0.5).abs() < 1e-4, "point on floor was {}", point.point_a);
+        assert!((point.point_b.y - 0.49).abs() < 1e-4, "point on box was {}", point.point_b);
+    }
+
+    let features: BTreeSet<_> = manifold.points.iter().map(|point| point.feature).collect();
+    assert_eq!(features.len(), 4, "features were not unique: {:?}", manifold.points);
+}
+
+#[test]
+fn box_overhanging_an_edge_is_clipped() {
+    // The box hangs halfway over the edge of the floor, so two of its corners are replaced by
+    // points along the edge.
+    let manifold = collide_boxes(&floor(), &unit_box(Vec3::new(2.0, 0.99, 0.0), Quat::IDENTITY))
+        .expect("boxes should overlap");
+
+    assert_eq!(manifold.points.len(), 4);
+    assert!(manifold.points.iter().all(|point| point.point_b.x <= 2.0 + 1e-5), "{:?}", manifold.points);
+    assert_eq!(manifold.points.iter().filter(|point| (point.point_b.x - 2.0).abs() < 1e-5).count(), 2);
+}
+
+#[test]
+fn tilted_box_touches_along_its_lowest_edge() {
+    let rotation = Quat::from_rotation_z(0.1);
+    let lowest = 0.5 * (0.1f32.cos() + 0.1f32.sin());
+    let manifold = collide_boxes(&floor(), &unit_box(Vec3::new(0.0, 0.49 + lowest, 0.0), rotation))
+        .expect("boxes should overlap");
+
+    assert!(manifold.normal.abs_diff_eq(Vec3::Y, 1e-5), "normal was {}", manifold.normal);
+    assert_eq!(manifold.points.len(), 2);
+
+    for point in manifold.points.iter() {
+        assert!((point.depth - 0.01).abs() < 1e-4, "depth was {}", point.depth);
+    }
+}
+
+#[test]
+fn crossed_edges_touch_at_one_point() {
+    let a = unit_box(Vec3::ZERO, Quat::from_rotation_x(FRAC_PI_4));
+    let b = unit_box(Vec3::new(0.1, 1.4, 0.1), Quat::from_rotation_z(FRAC_PI_4));
+    let manifold = collide_boxes(&a, &b).expect("boxes should overlap");
+
+    assert!(manifold.normal.abs_diff_eq(Vec3::Y, 1e-5), "normal was {}", manifold.normal);
+    assert_eq!(manifold.points.len(), 1);
+
+    let point = manifold.points[0];
+    let expected = 2.0 * std::f32::consts::FRAC_1_SQRT_2 - 1.4;
+
+    assert!((point.depth - expected).abs() < 1e-4, "depth was {}", point.depth);
+    assert!(point.point_a.abs_diff_eq(Vec3::new(0.1, 0.5 * 2f32.sqrt(), 0.0), 1e-4), "point on a was {}", point.point_a);
+    assert!(point.point_b.abs_diff_eq(Vec3::new(0.1, 1.4 - 0.5 * 2f32.sqrt(), 0.0), 1e-4), "point on b was {}", point.point_b);
+}
+
+#[test]
+fn features_persist_while_sliding() {
+    let features = |x: f32| {
+        let manifold = collide_boxes(&floor(), &unit_box(Vec3::new(x, 0.99, 0.0), Quat::IDENTITY)).unwrap();
+        manifold.points.iter().map(|point| point.feature).collect::<BTreeSet<_>>()
+    };
+
+    assert_eq!(features(0.0), features(0.3));
+    assert_eq!(features(1.8), features(1.9));
+}
+
+#[test]
+fn separated_boxes_have_no_manifold() {
+    assert!(collide_boxes(&floor(), &unit_box(Vec3::new(0.0, 1.01, 0.0), Quat::IDENTITY)).is_none());
+    assert!(collide_boxes(&floor(), &unit_box(Vec3::new(2.7, 0.9, 0.0), Quat::from_rotation_y(0.3))).is_none());
+}
+
+#[test]
+fn box_comes_to_rest_on_box() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("resting-box-history.ron");
+    run_simulation(PathBuf::from("tests/resting-box.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let settled = &history.get(100).colliders[1].transform;
+
+    assert!((settled.translate.y - 0.75).abs() < 1e-3, "box rested at {}", settled.translate);
+    assert!(settled.rotate.x.abs() < 1e-3 && settled.rotate.z.abs() < 1e-3, "box tipped to {}", settled.rotate);
+
+    for frame in 100..history.len() {
+        let transform = &history.get(frame).colliders[1].transform;
+
+        assert!(transform.translate.abs_diff_eq(settled.translate, 1e-5), "box jittered to {} at frame {frame}", transform.translate);
+        assert!(transform.rotate.abs_diff_eq(settled.rotate, 1e-5), "box jittered to {} at frame {frame}", transform.rotate);
+    }
+}
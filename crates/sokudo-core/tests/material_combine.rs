@@ -0,0 +1,54 @@
+use sokudo_core::{material::MaterialCombine, world::World};
+use sokudo_io::read::ParsedWorld;
+
+/// The ratio of the speed the ball rebounds at to the speed it lands at when the restitution
+/// coefficients are combined by `combine`.
+fn rebound(combine: MaterialCombine) -> f32 {
+    let parsed = ParsedWorld::read("tests/material-combine.ron").unwrap();
+
+    let mut world: World = parsed.into();
+    world.restitution_combine = combine;
+    world.initialize();
+
+    let mut landing: f32 = 0.0;
+    let mut rebound: f32 = 0.0;
+
+    for _ in 0..world.steps {
+        world.step();
+
+        let velocity = world.colliders[1].velocity.y;
+        landing = landing.min(velocity);
+        rebound = rebound.max(velocity);
+    }
+
+    rebound / -landing
+}
+
+#[test]
+fn combine_rules_pick_coefficients() {
+    assert_eq!(MaterialCombine::Min.combine(0.8, 0.5), 0.5);
+    assert_eq!(MaterialCombine::Max.combine(0.8, 0.5), 0.8);
+    assert!((MaterialCombine::Multiply.combine(0.8, 0.5) - 0.4).abs() < 1e-6);
+    assert!((MaterialCombine::Average.combine(0.8, 0.5) - 0.65).abs() < 1e-6);
+}
+
+#[test]
+fn combine_rules_are_read_from_world() {
+    let world: World = ParsedWorld::read("tests/material-combine.ron").unwrap().into();
+
+    assert_eq!(world.friction_combine, MaterialCombine::Multiply);
+    assert_eq!(world.restitution_combine, MaterialCombine::Average);
+}
+
+#[test]
+fn restitution_combine_controls_bounce() {
+    for (combine, expected) in [
+        (MaterialCombine::Min, 0.5),
+        (MaterialCombine::Max, 0.8),
+        (MaterialCombine::Multiply, 0.4),
+        (MaterialCombine::Average, 0.65),
+    ] {
+        let ratio = rebound(combine);
+        assert!((ratio - expected).abs() < 0.05, "{combine:?} rebounded at {ratio} of the landing speed");
+    }
+}
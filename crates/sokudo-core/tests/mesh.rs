@@ -0,0 +1,23 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::{run_simulation, RunSimulationError};
+use sokudo_io::{read::ParseError, write::ReadWorldStateHistory};
+
+#[test]
+fn mesh_cube_lands_on_ground() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("mesh-drop-history.ron");
+    run_simulation(PathBuf::from("tests/mesh-drop.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let cube = history.get(history.len() - 1).colliders[1].transform.translate;
+
+    assert!((cube.y - 1.0).abs() < 2e-2, "cube came to rest at {cube}");
+}
+
+#[test]
+fn missing_mesh_is_reported() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("missing-mesh-history.ron");
+    let result = run_simulation(PathBuf::from("tests/missing-mesh.ron"), history_path);
+
+    assert!(matches!(result, Err(RunSimulationError::Parse(ParseError::Mesh { .. }))));
+}
@@ -0,0 +1,24 @@
+use std::path::PathBuf;
+
+use glam::{EulerRot, Vec3};
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn hinge_motor_raises_arm_to_target_angle() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("motor-history.ron");
+    run_simulation(PathBuf::from("tests/motor.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let angle = |frame: usize| history.get(frame).colliders[1].transform.rotate.to_euler(EulerRot::ZYX).0;
+
+    // The motor lifts the arm above the horizontal against gravity and holds it there.
+    for frame in history.len() - 50..history.len() {
+        assert!((angle(frame) - 0.5).abs() < 0.02, "arm was at {} radians at frame {frame}", angle(frame));
+    }
+
+    let last = history.get(history.len() - 1).colliders[1].clone();
+
+    let pivot = last.transform.translate + last.transform.rotate * Vec3::new(-0.5, 0.0, 0.0);
+    assert!(pivot.length() < 1e-2, "arm came loose from its pivot to {pivot}");
+}
@@ -0,0 +1,59 @@
+use glam::{Quat, Vec3};
+use sokudo_core::{
+    narrowphase::{penetration, ConvexShape, Sphere},
+    shape::{cuboid::CuboidShape, Shape},
+};
+
+fn cuboid(shape: &Shape, position: Vec3, rotation: Quat) -> ConvexShape<'_> {
+    ConvexShape { shape, position, rotation, scale: Vec3::ONE }
+}
+
+#[test]
+fn overlapping_boxes_penetrate_along_shallowest_axis() {
+    let shape = Shape::Cuboid(CuboidShape);
+    let a = cuboid(&shape, Vec3::ZERO, Quat::IDENTITY);
+    let b = cuboid(&shape, Vec3::new(0.8, 0.1, -0.05), Quat::IDENTITY);
+
+    let penetration = penetration(&a, &b).expect("boxes should overlap");
+
+    assert!((penetration.depth - 0.2).abs() < 1e-4, "depth was {}", penetration.depth);
+    assert!(penetration.normal.abs_diff_eq(Vec3::X, 1e-4), "normal was {}", penetration.normal);
+    assert!((penetration.point_a.x - 0.5).abs() < 1e-4, "point on a was {}", penetration.point_a);
+    assert!((penetration.point_b.x - 0.3).abs() < 1e-4, "point on b was {}", penetration.point_b);
+}
+
+#[test]
+fn rotated_box_penetrates_with_its_corner() {
+    let shape = Shape::Cuboid(CuboidShape);
+    let a = cuboid(&shape, Vec3::ZERO, Quat::IDENTITY);
+    // Resting on a corner, the lowest point of the rotated box is 0.5 * sqrt(2) below its center.
+    let b = cuboid(&shape, Vec3::new(0.0, 1.1, 0.0), Quat::from_rotation_z(std::f32::consts::FRAC_PI_4));
+
+    let penetration = penetration(&a, &b).expect("boxes should overlap");
+    let expected = 0.5 + std::f32::consts::FRAC_1_SQRT_2 - 1.1;
+
+    assert!((penetration.depth - expected).abs() < 1e-3, "depth was {}", penetration.depth);
+    assert!(penetration.normal.abs_diff_eq(Vec3::Y, 1e-3), "normal was {}", penetration.normal);
+}
+
+#[test]
+fn sphere_penetrates_box_face() {
+    let shape = Shape::Cuboid(CuboidShape);
+    let a = cuboid(&shape, Vec3::ZERO, Quat::IDENTITY);
+    let b = Sphere { center: Vec3::new(0.0, 0.0, 0.9), radius: 0.5 };
+
+    let penetration = penetration(&a, &b).expect("box and sphere should overlap");
+
+    assert!((penetration.depth - 0.1).abs() < 1e-3, "depth was {}", penetration.depth);
+    assert!(penetration.normal.abs_diff_eq(Vec3::Z, 1e-3), "normal was {}", penetration.normal);
+    assert!(penetration.point_b.abs_diff_eq(Vec3::new(0.0, 0.0, 0.4), 1e-2), "point on sphere was {}", penetration.point_b);
+}
+
+#[test]
+fn separated_shapes_do_not_penetrate() {
+    let shape = Shape::Cuboid(CuboidShape);
+    let a = cuboid(&shape, Vec3::ZERO, Quat::IDENTITY);
+
+    assert!(penetration(&a, &cuboid(&shape, Vec3::new(1.2, 0.0, 0.0), Quat::IDENTITY)).is_none());
+    assert!(penetration(&a, &Sphere { center: Vec3::new(0.8, 0.8, 0.8), radius: 0.5 }).is_none());
+}
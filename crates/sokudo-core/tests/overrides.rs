@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::{run_simulation_with_options, RunOptions, RunSimulationError};
+use sokudo_io::write::ReadWorldStateHistory;
+
+fn history_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{name}-history.ron"))
+}
+
+#[test]
+fn overrides_replace_the_world_file_values() {
+    let path = history_path("overridden-stack");
+    let options = RunOptions { dt: Some(0.02), steps: Some(20), ..Default::default() };
+    run_simulation_with_options(PathBuf::from("tests/stack.ron"), path.clone(), options).unwrap();
+
+    let history = ReadWorldStateHistory::read(path).unwrap();
+
+    assert_eq!(history.dt(), 0.02);
+    assert_eq!(history.len(), 21);
+}
+
+#[test]
+fn omitted_overrides_keep_the_world_file_values() {
+    let path = history_path("unchanged-stack");
+    run_simulation_with_options(PathBuf::from("tests/stack.ron"), path.clone(), RunOptions::default()).unwrap();
+
+    let history = ReadWorldStateHistory::read(path).unwrap();
+
+    assert_eq!(history.dt(), 0.01);
+    assert_eq!(history.len(), 101);
+}
+
+#[test]
+fn non_positive_overrides_are_rejected() {
+    let run = |options| run_simulation_with_options(PathBuf::from("tests/stack.ron"), history_path("rejected-stack"), options);
+
+    let result = run(RunOptions { dt: Some(0.0), ..Default::default() });
+    assert!(matches!(result, Err(RunSimulationError::NonPositiveDt(dt)) if dt == 0.0), "ran with {result:?}");
+
+    let result = run(RunOptions { dt: Some(-0.01), ..Default::default() });
+    assert!(matches!(result, Err(RunSimulationError::NonPositiveDt(_))), "ran with {result:?}");
+
+    let result = run(RunOptions { steps: Some(0), ..Default::default() });
+    assert!(matches!(result, Err(RunSimulationError::ZeroSteps)), "ran with {result:?}");
+}
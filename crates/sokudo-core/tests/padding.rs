@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::{run_simulation_with_options, RunOptions};
+use sokudo_io::write::ReadWorldStateHistory;
+
+fn padded_history(name: &str, pad_to: u64) -> ReadWorldStateHistory {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let options = RunOptions { pad_to: Some(pad_to), ..Default::default() };
+    run_simulation_with_options(PathBuf::from("tests/sleeping.ron"), history_path.clone(), options).unwrap();
+
+    ReadWorldStateHistory::read(history_path).unwrap()
+}
+
+#[test]
+fn settled_history_holds_its_last_frame() {
+    let history = padded_history("padded-history.ron", 400);
+
+    assert_eq!(history.len(), 400);
+    assert_eq!(history.header().frames, 400);
+
+    // Everything is asleep well before the 200 steps of the world are simulated, and nothing
+    // moves from then on.
+    let last = history.get(399);
+    let held = (1..400).find(|&frame| history.get(frame).colliders.iter().zip(last.colliders.iter())
+        .all(|(a, b)| a.transform.translate == b.transform.translate && a.transform.rotate == b.transform.rotate))
+        .unwrap();
+
+    assert!(held < 200, "history was only held from frame {held}");
+
+    for frame in 0..400 {
+        assert_eq!(history.get(frame).step, frame as u32);
+    }
+}
+
+#[test]
+fn long_history_is_cut_short() {
+    let history = padded_history("cut-history.ron", 50);
+
+    assert_eq!(history.len(), 50);
+    assert_eq!(history.header().frames, 50);
+    assert_eq!(history.get(49).step, 49);
+}
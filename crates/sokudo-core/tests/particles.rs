@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn particles_collide_with_each_other() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("particle-stack-history.ron");
+    run_simulation(PathBuf::from("tests/particle-stack.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+
+    for i in 0..history.len() {
+        let state = history.get(i);
+        let position = |j: usize| state.colliders[j].transform.translate;
+
+        // Neither the stacked particles nor the pair moving towards each other may sink into one
+        // another by more than a small tolerance.
+        let stacked = position(1).distance(position(2));
+        let head_on = position(3).distance(position(4));
+
+        assert!(stacked > 0.99, "stacked particles were {stacked} apart in frame {i}");
+        assert!(head_on > 0.99, "colliding particles were {head_on} apart in frame {i}");
+    }
+
+    let last = history.get(history.len() - 1);
+    let lower = last.colliders[1].transform.translate;
+    let upper = last.colliders[2].transform.translate;
+
+    // The particles rest with their surfaces, rather than their centers, on the floor.
+    assert!((lower.y - 1.0).abs() < 1e-2, "lower particle rested at {lower}");
+    assert!((upper.y - 2.0).abs() < 1e-2, "upper particle rested at {upper}");
+}
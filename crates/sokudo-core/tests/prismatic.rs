@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+use glam::{Quat, Vec3};
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn piston_slides_to_its_travel_limit() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("piston-history.ron");
+    run_simulation(PathBuf::from("tests/piston.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let axis = Vec3::new(1.0, -1.0, 0.0).normalize();
+
+    for frame in 10..history.len() {
+        let piston = &history.get(frame).colliders[1].transform;
+        let offset = piston.translate - piston.translate.dot(axis) * axis;
+
+        assert!(offset.length() < 1e-2, "piston left its axis by {offset} at frame {frame}");
+        assert!(piston.rotate.abs_diff_eq(Quat::IDENTITY, 1e-3), "piston rotated at frame {frame}");
+        assert!(piston.translate.dot(axis) < 1.51, "piston passed its limit at frame {frame}");
+    }
+
+    // Gravity pulls the piston down the slide until it rests against the upper limit.
+    let piston = history.get(history.len() - 1).colliders[1].transform.translate;
+    assert!(piston.abs_diff_eq(1.5 * axis, 1e-2), "piston came to rest at {piston}");
+}
@@ -0,0 +1,47 @@
+use glam::Vec3;
+use sokudo_core::{aabb::Aabb, collider::ColliderId, world::World};
+use sokudo_io::read::ParsedWorld;
+
+fn world() -> World {
+    let mut world: World = ParsedWorld::read("tests/queries.ron").unwrap().into();
+    world.initialize();
+    world
+}
+
+fn assert_hit(world: &World, origin: Vec3, direction: Vec3, id: u32, distance: f32, normal: Vec3) {
+    let Some((hit, t, hit_normal)) = world.raycast(origin, direction) else {
+        panic!("ray from {origin} along {direction} missed");
+    };
+
+    assert_eq!(hit, ColliderId(id), "ray from {origin} along {direction} hit the wrong collider");
+    assert!((t - distance).abs() < 1e-3, "ray from {origin} along {direction} hit at a distance of {t}");
+    assert!(hit_normal.distance(normal) < 1e-2, "ray from {origin} along {direction} hit with normal {hit_normal}");
+}
+
+#[test]
+fn rays_hit_the_nearest_collider() {
+    let world = world();
+
+    // The first box is stretched along x before being turned to lie along z.
+    assert_hit(&world, Vec3::new(-5.0, 0.0, 0.0), Vec3::X, 0, 4.5, Vec3::NEG_X);
+    assert_hit(&world, Vec3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 2.0), 0, 4.0, Vec3::NEG_Z);
+    assert_hit(&world, Vec3::new(5.0, 5.0, 0.0), Vec3::NEG_Y, 1, 4.5, Vec3::Y);
+    assert_hit(&world, Vec3::new(0.0, 10.0, 0.0), Vec3::NEG_Y, 2, 6.5, Vec3::Y);
+    assert_hit(&world, Vec3::new(10.0, 0.0, 10.0), Vec3::NEG_Y, 3, 2.0, Vec3::Y);
+
+    assert!(world.raycast(Vec3::new(-5.0, 0.0, 0.0), Vec3::Y).is_none());
+}
+
+#[test]
+fn aabb_queries_find_overlapping_colliders() {
+    let world = world();
+
+    let around_second_box = Aabb::new(Vec3::new(4.0, -0.2, -0.2), Vec3::new(4.6, 0.2, 0.2));
+    assert_eq!(world.query_aabb(around_second_box), vec![ColliderId(1), ColliderId(3)]);
+
+    let between_boxes = Aabb::new(Vec3::new(1.0, 0.0, 0.0), Vec3::new(4.0, 1.0, 1.0));
+    assert_eq!(world.query_aabb(between_boxes), vec![ColliderId(3)]);
+
+    let everything = Aabb::new(Vec3::splat(-10.0), Vec3::splat(10.0));
+    assert_eq!(world.query_aabb(everything), (0..4).map(ColliderId).collect::<Vec<_>>());
+}
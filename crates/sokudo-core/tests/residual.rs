@@ -0,0 +1,42 @@
+use sokudo_core::world::World;
+use sokudo_io::read::ParsedWorld;
+
+/// The largest constraint error left in any of the first 100 steps of the swinging chain when it
+/// is simulated with `substeps` substeps.
+fn max_chain_residual(substeps: u32) -> f32 {
+    let mut world: World = ParsedWorld::read("tests/chain.ron").unwrap().into();
+    world.substeps = substeps;
+    world.initialize();
+
+    let mut max = 0.0f32;
+
+    for _ in 0..100 {
+        world.advance();
+
+        let residual = world.last_residual();
+        assert_eq!(residual.constraints, 3);
+        assert!(residual.rms <= residual.max);
+
+        max = max.max(residual.max);
+    }
+
+    max
+}
+
+#[test]
+fn too_few_substeps_leave_large_residual() {
+    let coarse = max_chain_residual(1);
+    let fine = max_chain_residual(16);
+
+    assert!(coarse > 1e-3, "residual with one substep was only {coarse}");
+    assert!(coarse > 10.0 * fine, "residual with one substep was {coarse}, with 16 it was {fine}");
+}
+
+#[test]
+fn empty_world_has_no_residual() {
+    let mut world = World::new(0.01, glam::Vec3::ZERO);
+    world.advance();
+
+    assert_eq!(world.last_residual().constraints, 0);
+    assert_eq!(world.last_residual().max, 0.0);
+}
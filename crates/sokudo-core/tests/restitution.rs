@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn restitution_controls_bounce_height() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("bounce-history.ron");
+    run_simulation(PathBuf::from("tests/bounce.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let max_height = |i: usize| (60..history.len())
+        .map(|frame| history.get(frame).colliders[i].transform.translate.y)
+        .fold(f32::MIN, f32::max);
+
+    // Both balls are released one unit above the ground and land after roughly 45 frames.
+    let elastic = max_height(1);
+    let inelastic = max_height(3);
+
+    assert!(elastic > 1.4, "elastic ball only bounced to {elastic}");
+    assert!(inelastic < 0.52, "inelastic ball bounced to {inelastic}");
+}
+
+#[test]
+fn velocities_are_written_to_history() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("bounce-velocity-history.ron");
+    run_simulation(PathBuf::from("tests/bounce.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+
+    // Falling freely, the ball has gained 20 steps worth of gravity.
+    let falling = history.get(20).colliders[1].velocity;
+    assert!((falling.y + 9.81 * 0.2).abs() < 1e-2, "falling ball had velocity {falling}");
+
+    // Having bounced elastically, it is moving upwards again.
+    let rising = history.get(60).colliders[1].velocity;
+    assert!(rising.y > 0.0, "bounced ball had velocity {rising}");
+}
+
+#[test]
+fn bouncy_ball_comes_to_rest() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("bouncy-ball-history.ron");
+    run_simulation(PathBuf::from("tests/bouncy-ball.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+
+    // Each bounce is lower than the last until the ball lands slower than the restitution
+    // threshold, after which it stays on the ground for the last second and a half.
+    for frame in history.len() - 150..history.len() {
+        let ball = &history.get(frame).colliders[1];
+
+        assert!((ball.transform.translate.y - 0.5).abs() < 1e-3, "ball was at {} in frame {frame}", ball.transform.translate);
+        assert!(ball.velocity.length() < 1e-3, "ball was moving at {} in frame {frame}", ball.velocity);
+    }
+}
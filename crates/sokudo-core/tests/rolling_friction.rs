@@ -0,0 +1,64 @@
+use glam::{Quat, UVec3, Vec3};
+use sokudo_core::{collider::{Collider, ColliderBody}, rigid_body::RigidBody, shape::{cylinder::CylinderShape, half_space::HalfSpaceShape, Shape}, world::World};
+
+/// A world of a cylinder lying on its side and rolling across the floor at 2 m/s, with the given
+/// rolling friction coefficient.
+fn rolling_cylinder(rolling_friction: f32) -> World {
+    let mut world = World::new(0.01, Vec3::new(0.0, -9.81, 0.0));
+
+    let mut floor = RigidBody::new(Shape::HalfSpace(HalfSpaceShape { normal: Vec3::Y }), 1.0);
+    floor.static_friction = 1.0;
+    floor.dynamic_friction = 1.0;
+    world.add_collider(Collider::new(ColliderBody::Rigid(floor), Vec3::ZERO));
+
+    let mut cylinder = RigidBody::new(Shape::Cylinder(CylinderShape { radius: 0.5, half_height: 1.0 }), 1.0);
+    cylinder.rotation = Quat::from_rotation_x(std::f32::consts::FRAC_PI_2);
+    // The cylinder collides through the vertices around its caps, so they are sampled finely
+    // enough that it rolls smoothly instead of tumbling over the edges between them.
+    cylinder.vertex_resolution = UVec3::new(16, 1, 1);
+    cylinder.static_friction = 1.0;
+    cylinder.dynamic_friction = 1.0;
+    cylinder.rolling_friction = rolling_friction;
+    cylinder.angular_velocity = Vec3::new(0.0, 0.0, -4.0);
+
+    let mut collider = Collider::new(ColliderBody::Rigid(cylinder), Vec3::new(0.0, 0.5, 0.0));
+    collider.velocity = Vec3::new(2.0, 0.0, 0.0);
+    world.add_collider(collider);
+
+    world.sleep_window = 0;
+    world
+}
+
+#[test]
+fn ideal_cylinder_keeps_rolling() {
+    let mut world = rolling_cylinder(0.0);
+
+    for _ in 0..300 {
+        world.advance();
+    }
+
+    assert!(world.colliders[1].velocity.x > 1.5, "cylinder slowed to {}", world.colliders[1].velocity);
+}
+
+#[test]
+fn rolling_friction_brings_cylinder_to_rest() {
+    let mut world = rolling_cylinder(0.1);
+    let mut stopped = None;
+
+    for step in 0..300 {
+        world.advance();
+
+        let spin = world.colliders[1].angular_velocity().z;
+        assert!(spin <= 1e-3, "spin reversed to {spin} in step {step}");
+
+        if stopped.is_none() && world.colliders[1].velocity.length() < 1e-2 {
+            stopped = Some(step);
+        }
+    }
+
+    // The cylinder slows down gradually rather than stopping at once.
+    let stopped = stopped.expect("cylinder never came to rest");
+    assert!(stopped > 100, "cylinder stopped abruptly in step {stopped}");
+    assert!(world.colliders[1].velocity.length() < 1e-2, "cylinder kept moving at {}", world.colliders[1].velocity);
+    assert!(world.colliders[1].angular_velocity().length() < 2e-2, "cylinder kept spinning at {}", world.colliders[1].angular_velocity());
+}
@@ -0,0 +1,62 @@
+use std::{fs, path::PathBuf};
+
+use glam::Vec3;
+use sokudo_core::run::{run_simulation_with_options, RunOptions};
+use sokudo_io::{read::script::{ParsedScript, ScriptAction, ScriptEntry}, write::ReadWorldStateHistory};
+
+fn scripted_run(name: &str, script: &str) -> PathBuf {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let options = RunOptions { script: Some(PathBuf::from(script)), ..Default::default() };
+    run_simulation_with_options(PathBuf::from("tests/resting-box.ron"), history_path.clone(), options).unwrap();
+
+    history_path
+}
+
+#[test]
+fn script_throws_box_at_its_step() {
+    let unscripted_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("unscripted-history.ron");
+    run_simulation_with_options(PathBuf::from("tests/resting-box.ron"), unscripted_path.clone(), RunOptions::default()).unwrap();
+
+    let unscripted = ReadWorldStateHistory::read(unscripted_path).unwrap();
+    let scripted = ReadWorldStateHistory::read(scripted_run("scripted-history.ron", "tests/throw-script.ron")).unwrap();
+
+    let translate = |history: &ReadWorldStateHistory, frame: usize| history.get(frame).colliders[1].transform.translate;
+    let velocity = |history: &ReadWorldStateHistory, frame: usize| history.get(frame).colliders[1].velocity;
+
+    for frame in 0..=30 {
+        assert_eq!(translate(&scripted, frame), translate(&unscripted, frame), "box moved early at frame {frame}");
+    }
+
+    assert!(velocity(&scripted, 31).x > 2.0, "box was not thrown: {}", velocity(&scripted, 31));
+    assert!(velocity(&scripted, 81).z - velocity(&scripted, 80).z > 0.1, "box was not pushed: {}", velocity(&scripted, 81));
+    assert_eq!(scripted.get(100).colliders[0].transform.translate, Vec3::ZERO);
+}
+
+#[test]
+fn scripted_bakes_are_byte_identical() {
+    let first = scripted_run("scripted-first.ron", "tests/throw-script.ron");
+    let second = scripted_run("scripted-second.ron", "tests/throw-script.ron");
+
+    assert!(fs::read(first).unwrap() == fs::read(second).unwrap(), "two bakes of the same script differed");
+}
+
+#[test]
+fn recorded_script_replays_identically() {
+    let recorded = ParsedScript {
+        entries: vec![
+            ScriptEntry { step: 80, collider: 1, action: ScriptAction::Force(Vec3::new(0.0, 0.0, 40.0)) },
+            ScriptEntry { step: 30, collider: 1, action: ScriptAction::Impulse(Vec3::new(3.0, 0.5, 0.0)) },
+        ],
+    };
+
+    let script_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("recorded-script.ron");
+    recorded.write(&script_path).unwrap();
+
+    let read = ParsedScript::read(&script_path).unwrap();
+    assert_eq!(read.entries, [recorded.entries[1], recorded.entries[0]]);
+
+    let replayed = scripted_run("replayed-history.ron", script_path.to_str().unwrap());
+    let original = scripted_run("original-history.ron", "tests/throw-script.ron");
+
+    assert!(fs::read(replayed).unwrap() == fs::read(original).unwrap(), "recorded script replayed differently");
+}
@@ -0,0 +1,58 @@
+use std::{f32::consts::PI, path::PathBuf};
+
+use glam::{UVec3, Vec3};
+use sokudo_core::{run::{run_simulation, RunSimulationError}, shape::{sdf::SdfShape, AbstractShape}};
+use sokudo_io::{read::{sdf::ParsedSdf, ParseError}, write::ReadWorldStateHistory};
+
+fn sphere() -> SdfShape {
+    ParsedSdf::read_sdf("tests/fields/sphere.sdf").unwrap().into()
+}
+
+#[test]
+fn sampled_sphere_matches_analytic_sphere() {
+    let sphere = sphere();
+
+    for point in [Vec3::ZERO, Vec3::new(0.25, 0.1, -0.3), Vec3::new(0.45, 0.0, 0.0), Vec3::new(2.0, 0.0, 0.0)] {
+        let sd = sphere.sd(point);
+        assert!((sd - (point.length() - 0.5)).abs() < 0.02, "distance at {point} was {sd}");
+    }
+
+    let volume = sphere.volume(Vec3::ONE);
+    assert!((volume - 4.0 / 3.0 * PI * 0.125).abs() < 0.05, "volume was {volume}");
+
+    let moments = sphere.moments(Vec3::ONE);
+    assert!((moments - Vec3::splat(0.4 * 0.25)).abs().max_element() < 0.01, "moments were {moments}");
+}
+
+#[test]
+fn triangulated_surface_lies_on_the_sphere() {
+    let (vertices, indices) = sphere().triangulate(UVec3::splat(2));
+
+    assert!(!indices.is_empty());
+    assert!(vertices.iter().all(|v| (v.length() - 0.5).abs() < 0.02));
+
+    // Every triangle faces away from the center.
+    for &[a, b, c] in indices.iter() {
+        let [a, b, c] = [a, b, c].map(|i| vertices[i as usize]);
+        assert!((b - a).cross(c - a).dot(a + b + c) > 0.0);
+    }
+}
+
+#[test]
+fn sdf_sphere_lands_on_ground() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("sdf-drop-history.ron");
+    run_simulation(PathBuf::from("tests/sdf-drop.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let sphere = history.get(history.len() - 1).colliders[1].transform.translate;
+
+    assert!((sphere.y - 1.0).abs() < 2e-2, "sphere came to rest at {sphere}");
+}
+
+#[test]
+fn truncated_sdf_is_reported() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("truncated-sdf-history.ron");
+    let result = run_simulation(PathBuf::from("tests/truncated-sdf.ron"), history_path);
+
+    assert!(matches!(result, Err(RunSimulationError::Parse(ParseError::SdfGrid { line: 3, .. }))), "result was {result:?}");
+}
@@ -0,0 +1,29 @@
+use sokudo_core::world::World;
+use sokudo_io::{read::ParsedWorld, write::events::CollisionEventKind};
+
+#[test]
+fn bodies_pass_through_sensors() {
+    let mut world: World = ParsedWorld::read("tests/sensor.ron").unwrap().into();
+    world.initialize();
+
+    let mut events = Vec::new();
+
+    while world.step < world.steps {
+        world.advance();
+        events.extend(world.events.iter().map(|event| (event.a, event.b, event.kind, event.impulse)));
+
+        assert!(world.collision_constraints.is_empty(), "sensor was resolved in step {}", world.step);
+    }
+
+    // The box falls through the sensor exactly like the one beside it falls through nothing.
+    assert_eq!(world.colliders[1].velocity, world.colliders[2].velocity);
+    assert!(world.colliders[1].position.y < -1.5, "box stopped at {}", world.colliders[1].position);
+
+    let kinds: Vec<_> = events.iter()
+        .filter(|(_, _, kind, _)| *kind != CollisionEventKind::Stay)
+        .map(|&(a, b, kind, _)| (a, b, kind))
+        .collect();
+
+    assert_eq!(kinds, [(0, 1, CollisionEventKind::Begin), (0, 1, CollisionEventKind::End)]);
+    assert!(events.iter().all(|&(_, _, _, impulse)| impulse == 0.0));
+}
@@ -0,0 +1,19 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn resting_box_sleeps_until_hit() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("sleeping-history.ron");
+    run_simulation(PathBuf::from("tests/sleeping.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let position = |frame: usize| history.get(frame).colliders[1].transform.translate;
+
+    // The box settles on the ground within the first half second and then stays perfectly still
+    // until the particle lands on it at around frame 75.
+    let asleep = position(50);
+    assert!((50..=70).all(|frame| position(frame) == asleep));
+    assert!(position(90).distance(asleep) > 1e-2, "box was not woken up by the particle");
+}
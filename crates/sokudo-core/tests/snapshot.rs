@@ -0,0 +1,182 @@
+use std::{fs, path::PathBuf};
+
+use glam::Vec3;
+use sokudo_core::{
+    collider::{Collider, ColliderBody, ColliderId},
+    constraint::{
+        hinge::{HingeConstraint, HingeMotor},
+        spring::SpringConstraint,
+        Constraint,
+    },
+    material::MaterialCombine,
+    particle::Particle,
+    rigid_body::RigidBody,
+    shape::{convex_hull::ConvexHullShape, cuboid::CuboidShape, half_space::HalfSpaceShape, Shape},
+    water::Water,
+    world::{SnapshotError, World},
+};
+use sokudo_io::{read::ParsedWorld, write::ReadStateError};
+
+fn read_world(path: &str) -> World {
+    let mut world: World = ParsedWorld::read(path).unwrap().into();
+    world.initialize();
+    world
+}
+
+/// Simulates `world` for `steps` more steps, saving a snapshot after `saved` of them, and checks
+/// that the world loaded from the snapshot continues exactly as the original one.
+fn loaded_world_is_identical(name: &str, mut world: World, saved: u32, steps: u32) {
+    let snapshot_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{name}-snapshot.ron"));
+
+    for _ in 0..saved {
+        world.step();
+    }
+
+    world.save_snapshot(&snapshot_path).unwrap();
+    let mut loaded = World::load_snapshot(&snapshot_path).unwrap();
+
+    assert_eq!(loaded.step, world.step);
+    assert_eq!(loaded.colliders.len(), world.colliders.len());
+    assert_eq!(loaded.constraints.len(), world.constraints.len());
+    assert_eq!(loaded.velocity_constraints.len(), world.velocity_constraints.len());
+
+    for _ in saved..steps {
+        world.step();
+        loaded.step();
+
+        let (expected, actual) = (world.state(), loaded.state());
+
+        assert_eq!(actual.colliders.len(), expected.colliders.len(), "step {}", world.step);
+
+        for (actual, expected) in actual.colliders.iter().zip(expected.colliders.iter()) {
+            assert_eq!(actual.transform.translate, expected.transform.translate, "step {}", world.step);
+            assert_eq!(actual.transform.rotate, expected.transform.rotate, "step {}", world.step);
+        }
+    }
+}
+
+#[test]
+fn loaded_chain_continues_identically() {
+    loaded_world_is_identical("chain", read_world("tests/chain.ron"), 64, 120);
+}
+
+#[test]
+fn loaded_fountain_continues_identically() {
+    loaded_world_is_identical("fountain", read_world("tests/fountain.ron"), 40, 80);
+}
+
+#[test]
+fn snapshot_loads_without_mesh_files() {
+    // The mesh is stored in the snapshot itself, so its file is removed before the snapshot is
+    // loaded.
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("snapshot-mesh-drop");
+    fs::create_dir_all(directory.join("meshes")).unwrap();
+    fs::copy("tests/mesh-drop.ron", directory.join("mesh-drop.ron")).unwrap();
+    fs::copy("tests/meshes/cube.obj", directory.join("meshes/cube.obj")).unwrap();
+
+    let world = read_world(directory.join("mesh-drop.ron").to_str().unwrap());
+    fs::remove_dir_all(directory.join("meshes")).unwrap();
+
+    loaded_world_is_identical("mesh-drop", world, 20, 40);
+}
+
+#[test]
+fn loaded_world_built_in_code_continues_identically() {
+    let mut world = World::new(1.0 / 60.0, Vec3::new(0.0, -9.81, 0.0));
+    world.substeps = 8;
+    world.friction_combine = MaterialCombine::Max;
+    world.water = Some(Water { height: 0.5, density: 500.0, drag: 0.2 });
+
+    let floor = RigidBody::new(Shape::HalfSpace(HalfSpaceShape { normal: Vec3::Y }), 1.0);
+    world.add_collider(Collider::new(ColliderBody::Rigid(floor), Vec3::ZERO));
+
+    let [a, b] = [0.5, 2.0].map(|x| {
+        let cube = RigidBody::new(Shape::Cuboid(CuboidShape), 1.0);
+        world.add_collider(Collider::new(ColliderBody::Rigid(cube), Vec3::new(x, 1.0, 0.0)))
+    });
+
+    let points = [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::Z];
+    let hull = RigidBody::new(Shape::ConvexHull(ConvexHullShape::new(&points)), 2.0);
+    let c = world.add_collider(Collider::new(ColliderBody::Rigid(hull), Vec3::new(-1.5, 2.0, 0.0)));
+
+    let particle = Particle { mass: 1.0, radius: 0.1, static_friction: 0.5, dynamic_friction: 0.3, restitution: 0.0 };
+    let removed = world.add_collider(Collider::new(ColliderBody::Particle(particle), Vec3::new(0.0, 3.0, 0.0)));
+    world.remove_collider(removed);
+
+    let hinge = || HingeConstraint {
+        a,
+        b,
+        anchor_a: Vec3::new(0.75, 0.0, 0.0),
+        anchor_b: Vec3::new(-0.75, 0.0, 0.0),
+        axis_a: Vec3::Z,
+        axis_b: Vec3::Z,
+        min_angle: None,
+        max_angle: None,
+        compliance: 0.0,
+        motor: Some(HingeMotor::Velocity { target_velocity: 1.0, max_force: 10.0 }),
+    };
+    let spring = || SpringConstraint { a, b: c, rest_length: 2.0, stiffness: 50.0, damping: 1.0 };
+
+    world.add_constraint(Box::new(hinge()));
+    world.add_constraint(Box::new(spring()));
+    world.velocity_constraints.push(Box::new(hinge()));
+    world.velocity_constraints.push(Box::new(spring()));
+
+    for _ in 0..10 {
+        world.step();
+    }
+
+    // Anything added to the world after it started is stored as well.
+    let cube = RigidBody::new(Shape::Cuboid(CuboidShape), 1.0);
+    world.add_collider(Collider::new(ColliderBody::Rigid(cube), Vec3::new(0.5, 3.0, 0.0)));
+
+    loaded_world_is_identical("built-in-code", world, 20, 80);
+}
+
+/// A constraint which cannot be given in a world file.
+struct Unsupported;
+
+impl Constraint for Unsupported {
+    fn bodies(&self) -> Vec<ColliderId> {
+        Vec::new()
+    }
+
+    fn c(&self, _bodies: &[&Collider]) -> f32 {
+        0.0
+    }
+
+    fn c_gradients(&self, _bodies: &[&Collider]) -> Vec<Vec3> {
+        Vec::new()
+    }
+
+    fn inverse_masses(&self, _bodies: &[&Collider]) -> Vec<f32> {
+        Vec::new()
+    }
+
+    fn compliance(&self) -> f32 {
+        0.0
+    }
+}
+
+#[test]
+fn world_with_unsupported_constraint_cannot_be_saved() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("unsupported-snapshot.ron");
+    let mut world = World::new(0.01, Vec3::new(0.0, -9.81, 0.0));
+    world.add_constraint(Box::new(Unsupported));
+
+    assert!(matches!(world.save_snapshot(path), Err(SnapshotError::UnsupportedConstraint { index: 0 })));
+}
+
+#[test]
+fn snapshot_of_other_version_is_rejected() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("future-snapshot.ron");
+    read_world("tests/chain.ron").save_snapshot(&path).unwrap();
+
+    let text = fs::read_to_string(&path).unwrap().replacen("version:1", "version:99", 1);
+    fs::write(&path, text).unwrap();
+
+    assert!(matches!(
+        World::load_snapshot(&path),
+        Err(SnapshotError::ReadState(ReadStateError::UnsupportedSnapshotVersion { found: 99, supported: 1 })),
+    ));
+}
@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use glam::Vec3;
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn chain_links_stay_connected() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("chain-history.ron");
+    run_simulation(PathBuf::from("tests/chain.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let end = |frame: usize, link: usize, side: f32| {
+        let transform = &history.get(frame).colliders[link].transform;
+        transform.translate + transform.rotate * Vec3::new(0.25 * side, 0.0, 0.0)
+    };
+
+    for frame in (0..history.len()).step_by(10) {
+        assert!(end(frame, 1, -1.0).length() < 1e-2, "first link came loose at frame {frame}");
+
+        for link in 1..3 {
+            let gap = end(frame, link, 1.0).distance(end(frame, link + 1, -1.0));
+            assert!(gap < 1e-2, "links {link} and {} were {gap} apart at frame {frame}", link + 1);
+        }
+    }
+
+    // Released horizontally, the chain swings down below its pivot.
+    let lowest = (0..history.len()).map(|frame| end(frame, 3, 1.0).y).fold(f32::MAX, f32::min);
+    assert!(lowest < -1.0, "chain only swung down to {lowest}");
+}
+
+#[test]
+fn swing_is_limited_to_cone() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("swing-limit-history.ron");
+    run_simulation(PathBuf::from("tests/swing-limit.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+
+    // Without the limit the link would hang straight down, but it is held within half a radian of
+    // the horizontal.
+    for frame in 0..history.len() {
+        let axis = history.get(frame).colliders[1].transform.rotate * Vec3::X;
+        let angle = axis.angle_between(Vec3::X);
+        assert!(angle < 0.55, "link swung {angle} radians at frame {frame}");
+    }
+
+    let axis = history.get(history.len() - 1).colliders[1].transform.rotate * Vec3::X;
+    assert!(axis.angle_between(Vec3::X) > 0.4, "link did not swing down to the limit");
+}
@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn overdamped_spring_returns_to_rest_length() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("spring-history.ron");
+    run_simulation(PathBuf::from("tests/spring.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let length = |frame: usize| history.get(frame).colliders[1].transform.translate.x;
+
+    // Released at twice its rest length, the spring contracts without ever overshooting.
+    for frame in 1..history.len() {
+        assert!(length(frame) <= length(frame - 1) + 1e-5, "spring extended again at frame {frame}");
+        assert!(length(frame) > 1.0 - 1e-3, "spring overshot to {} at frame {frame}", length(frame));
+    }
+
+    let rest = length(history.len() - 1);
+    assert!((rest - 1.0).abs() < 1e-2, "spring came to rest at length {rest}");
+}
@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use sokudo_core::{run::run_simulation, world::World};
+use sokudo_io::{read::ParsedWorld, write::{inspect::InspectFeature, ReadWorldStateHistory}};
+
+/// The furthest any box of the stack may drift from the axis it started on.
+const DRIFT_TOLERANCE: f32 = 0.05;
+/// The furthest any box may sink into the box it rests on.
+const SINK_TOLERANCE: f32 = 0.01;
+
+#[test]
+fn ten_box_stack_stays_upright() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("ten-stack-history.ron");
+    run_simulation(PathBuf::from("tests/ten-stack.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    assert_eq!(history.len(), 301, "the initial state and every step should be baked");
+
+    let mut drift = 0.0f32;
+    let mut sink = 0.0f32;
+
+    for frame in 0..history.len() {
+        let colliders = history.get(frame).colliders;
+
+        for pair in colliders[1..].windows(2) {
+            let (below, above) = (pair[0].transform.translate, pair[1].transform.translate);
+            sink = sink.max(1.0 - (above.y - below.y));
+        }
+
+        for collider in colliders[1..].iter() {
+            let translate = collider.transform.translate;
+            drift = drift.max(translate.x.hypot(translate.z));
+        }
+    }
+
+    assert!(drift < DRIFT_TOLERANCE, "stack drifted by {drift}");
+    assert!(sink < SINK_TOLERANCE, "boxes sank into each other by {sink}");
+
+    // The stack rests on the ground rather than sinking through it.
+    let bottom = history.get(history.len() - 1).colliders[1].transform.translate;
+    assert!((bottom.y - 1.0).abs() < SINK_TOLERANCE, "bottom box rested at {bottom}");
+}
+
+#[test]
+fn lower_contacts_carry_the_weight_above_them() {
+    let mut world: World = ParsedWorld::read("tests/ten-stack.ron").unwrap().into();
+    world.initialize();
+
+    // The mean total impulse through the contacts beneath each box, from the ground up, over the
+    // steps after the stack has settled.
+    let mut impulses = [0.0f32; 10];
+    let steps = 100;
+
+    for _ in 0..100 {
+        world.advance();
+    }
+
+    for _ in 0..steps {
+        world.advance();
+
+        for feature in world.inspector.elements.values() {
+            // The top of the box beneath each contact is half a box below its center.
+            if let InspectFeature::Contact { point, impulse, .. } = feature {
+                impulses[((point.y - 0.5).round() as usize).min(9)] += impulse / steps as f32;
+            }
+        }
+    }
+
+    // Every box weighs the same, so the contacts lower in the stack carry more of its weight.
+    let (lower, upper) = impulses.split_at(5);
+
+    assert!(impulses[0] > 5.0 * impulses[9], "impulses were {impulses:?}");
+    assert!(lower.iter().sum::<f32>() > 2.0 * upper.iter().sum::<f32>(), "impulses were {impulses:?}");
+}
@@ -0,0 +1,76 @@
+use glam::Vec3;
+use sokudo_core::{collider::{Collider, ColliderBody}, particle::Particle, world::World};
+use sokudo_io::read::ParsedWorld;
+
+/// The deepest overlap between any two neighbouring colliders of the stack while simulating it
+/// with `substeps` substeps.
+fn max_overlap(substeps: u32) -> f32 {
+    let parsed = ParsedWorld::read("tests/stack.ron").unwrap();
+    assert_eq!(parsed.substeps, 1);
+
+    let mut world: World = parsed.into();
+    world.substeps = substeps;
+    world.initialize();
+
+    let mut overlap: f32 = 0.0;
+
+    for _ in 0..world.steps {
+        world.step();
+
+        for pair in world.colliders.windows(2) {
+            let gap = pair[1].position.y - pair[0].position.y;
+            overlap = overlap.max(1.0 - gap);
+        }
+    }
+
+    overlap
+}
+
+#[test]
+fn more_substeps_reduce_constraint_error() {
+    let coarse = max_overlap(1);
+    let fine = max_overlap(16);
+
+    assert!(fine < 0.75 * coarse, "16 substeps overlapped by {fine}, while one overlapped by {coarse}");
+}
+
+/// The horizontal distance a projectile launched from the origin travels before falling back to
+/// its height, when simulated with `substeps` substeps of each step.
+fn projectile_range(substeps: u32) -> f32 {
+    let mut world = World::new(0.02, Vec3::new(0.0, -9.81, 0.0));
+    world.substeps = substeps;
+
+    let particle = Particle { mass: 1.0, radius: 0.0, static_friction: 0.0, dynamic_friction: 0.0, restitution: 0.0 };
+    let mut projectile = Collider::new(ColliderBody::Particle(particle), Vec3::ZERO);
+    projectile.velocity = Vec3::new(4.0, 4.905, 0.0);
+    world.add_collider(projectile);
+
+    let mut previous = Vec3::ZERO;
+
+    loop {
+        world.advance();
+        let position = world.colliders[0].position;
+
+        if position.y < 0.0 {
+            // Interpolate between the steps on either side of the landing.
+            let s = previous.y / (previous.y - position.y);
+            return previous.lerp(position, s).x;
+        }
+
+        previous = position;
+    }
+}
+
+#[test]
+fn projectile_range_is_independent_of_substeps() {
+    // Launched at 4.905 units per second upwards, the projectile lands after a second, less the
+    // length of one substep since each substep moves it with the velocity at its end.
+    for substeps in [1, 4, 16] {
+        let range = projectile_range(substeps);
+        assert!((range - 4.0).abs() < 0.1, "projectile with {substeps} substeps travelled {range}");
+    }
+
+    let coarse = projectile_range(1);
+    let fine = projectile_range(16);
+    assert!((coarse - fine).abs() < 0.1, "projectile travelled {coarse} with one substep but {fine} with 16");
+}
@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use glam::{UVec3, Vec3};
+use sokudo_core::{run::run_simulation, shape::{torus::TorusShape, AbstractShape}};
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn torus_inertia_matches_solid_torus() {
+    let torus = TorusShape { major_radius: 1.0, minor_radius: 0.5 };
+    let moments = torus.moments(Vec3::ONE);
+
+    assert!((moments.y - (1.0 + 0.75 * 0.25)).abs() < 1e-6);
+    assert!((moments.x - (0.5 + 0.625 * 0.25)).abs() < 1e-6);
+    assert!((moments.z - moments.x).abs() < 1e-6);
+}
+
+#[test]
+fn torus_vertices_lie_on_its_surface() {
+    let torus = TorusShape { major_radius: 1.0, minor_radius: 0.25 };
+    let vertices = torus.vertices(UVec3::new(2, 1, 1));
+
+    assert_eq!(vertices.len() as u32, 2 * TorusShape::SEGMENTS * TorusShape::TUBE_SEGMENTS);
+    assert!(vertices.iter().all(|&v| torus.sd(v).abs() < 1e-5));
+}
+
+#[test]
+fn tilted_torus_settles_flat() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("torus-history.ron");
+    run_simulation(PathBuf::from("tests/torus.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+    let last = &history.get(history.len() - 1).colliders[1];
+
+    // Lying flat, the center of the ring sits one tube radius above the floor.
+    let axis = last.transform.rotate * Vec3::Y;
+
+    assert!(axis.y > 0.99, "torus came to rest with its axis along {axis}");
+    assert!((last.transform.translate.y - 0.125).abs() < 0.02, "torus came to rest at {}", last.transform.translate);
+}
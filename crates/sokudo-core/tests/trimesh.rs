@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn sphere_rests_at_the_bottom_of_a_bowl() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("bowl-history.ron");
+    run_simulation(PathBuf::from("tests/bowl.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+
+    for i in 0..history.len() {
+        let position = history.get(i).colliders[1].transform.translate;
+        assert!(position.length() < 2.0 - 0.2, "sphere passed through the bowl to {position} in frame {i}");
+    }
+
+    // The bowl is faceted, so its lowest point is slightly above that of a true hemisphere.
+    let last = history.get(history.len() - 1).colliders[1].transform.translate;
+    assert!(last.x.abs() < 0.1 && last.z.abs() < 0.1, "sphere rested at {last}");
+    assert!((last.y + 1.75).abs() < 0.05, "sphere rested at {last}");
+}
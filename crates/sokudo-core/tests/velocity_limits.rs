@@ -0,0 +1,29 @@
+use glam::Vec3;
+use sokudo_core::{collider::ColliderBody, world::World};
+use sokudo_io::read::ParsedWorld;
+
+#[test]
+fn velocities_are_clamped_to_their_limits() {
+    let mut world: World = ParsedWorld::read("tests/velocity-limits.ron").unwrap().into();
+    world.initialize();
+
+    for collider in world.colliders.iter_mut() {
+        if let ColliderBody::Rigid(rb) = &mut collider.body {
+            rb.angular_velocity = Vec3::new(0.0, 5.0, 0.0);
+        }
+    }
+
+    for _ in 0..world.steps {
+        world.step();
+
+        let limited = &world.colliders[0];
+        assert!(limited.velocity.length() <= 2.0 + 1e-4, "limited box moved at {}", limited.velocity);
+        assert!(limited.angular_velocity().length() <= 1.0 + 1e-4, "limited box rotated at {}", limited.angular_velocity());
+    }
+
+    // Falling only ever speeds the bodies up, so the limited one stays at its limit while the
+    // unlimited one keeps its initial speed and more.
+    assert!((world.colliders[0].velocity.length() - 2.0).abs() < 1e-4);
+    assert!(world.colliders[1].velocity.length() > 10.0);
+    assert!((world.colliders[1].angular_velocity().length() - 5.0).abs() < 1e-2);
+}
@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::{run_simulation_with_options, RunOptions};
+use sokudo_io::write::{collider::WriteContact, HistoryOptions, ReadWorldStateHistory};
+
+fn bake(name: &str, verbose: bool) -> ReadWorldStateHistory {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{name}-history.ron"));
+    let options = RunOptions { history: HistoryOptions { verbose, ..Default::default() }, ..Default::default() };
+    run_simulation_with_options(PathBuf::from("tests/bounce.ron"), history_path.clone(), options).unwrap();
+
+    ReadWorldStateHistory::read(history_path).unwrap()
+}
+
+#[test]
+fn verbose_history_records_resting_contacts() {
+    let history = bake("verbose-bounce", true);
+    assert!(history.header().verbose);
+
+    // The inelastic ball lands on its floor and stays there.
+    for step in history.len() - 20..history.len() {
+        let state = history.get(step);
+        let contacts = &state.colliders[3].contacts;
+
+        assert!(matches!(contacts.as_slice(), [WriteContact { other: 2, .. }]), "ball touched {contacts:?} in step {step}");
+        assert_eq!(state.colliders[2].contacts, vec![WriteContact { other: 3, impulse: contacts[0].impulse }]);
+    }
+
+    // Stopping the ball takes at least the momentum it landed with.
+    let states: Vec<_> = (0..history.len()).map(|step| history.get(step)).collect();
+    let momentum = -states.iter().map(|state| state.colliders[3].velocity.y).fold(0.0, f32::min);
+    let impulse: f32 = states.iter().flat_map(|state| state.colliders[3].contacts.iter()).map(|c| c.impulse).sum();
+
+    assert!(impulse > 0.95 * momentum, "floor applied {impulse} to stop a momentum of {momentum}");
+}
+
+#[test]
+fn quiet_history_omits_contacts() {
+    let history = bake("quiet-bounce", false);
+
+    assert!(!history.header().verbose);
+    assert!((0..history.len()).all(|step| history.get(step).colliders.iter().all(|c| c.contacts.is_empty())));
+}
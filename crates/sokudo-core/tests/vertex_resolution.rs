@@ -0,0 +1,29 @@
+use glam::UVec3;
+use sokudo_core::{collider::ColliderBody, world::World};
+use sokudo_io::read::{ParseWarning, ParsedWorld};
+
+#[test]
+fn excessive_resolutions_are_clamped() {
+    let parsed = ParsedWorld::read("tests/dense-box.ron").unwrap();
+
+    assert!(matches!(
+        parsed.warnings.as_slice(),
+        [ParseWarning::VertexResolutionClamped { id: 1, resolution: UVec3 { x: 1000, y: 1000, z: 2 }, max: 8 }],
+    ), "warnings were {:?}", parsed.warnings);
+
+    let mut world: World = parsed.into();
+    world.initialize();
+
+    let ColliderBody::Rigid(rb) = &world.colliders[1].body else { panic!("collider 1 is not a rigid body") };
+
+    assert_eq!(rb.vertex_resolution, UVec3::new(8, 8, 2));
+    // Every face of the box is sampled on a grid of at most 9 by 9 points.
+    assert!(rb.vertices.len() <= 6 * 9 * 9, "box has {} vertices", rb.vertices.len());
+
+    // The box still rests on the ground with its clamped vertices.
+    for _ in 0..world.steps {
+        world.advance();
+    }
+
+    assert!(world.colliders[1].position.y > 0.45, "box sank to {}", world.colliders[1].position);
+}
@@ -0,0 +1,25 @@
+use std::path::PathBuf;
+
+use sokudo_core::run::run_simulation;
+use sokudo_io::write::ReadWorldStateHistory;
+
+#[test]
+fn dropped_tetrahedron_keeps_its_volume() {
+    let history_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("jelly-history.ron");
+    run_simulation(PathBuf::from("tests/jelly.ron"), history_path.clone()).unwrap();
+
+    let history = ReadWorldStateHistory::read(history_path).unwrap();
+
+    for frame in 0..history.len() {
+        let state = history.get(frame);
+        let [a, b, c, d] = [1, 2, 3, 4].map(|i| state.colliders[i].transform.translate);
+        let volume = (b - a).cross(c - a).dot(d - a) / 6.0;
+
+        assert!((volume - 1.0 / 6.0).abs() < 0.01 / 6.0, "tetrahedron had a volume of {volume} at frame {frame}");
+    }
+
+    // The soft edges squash under the impact, so the volume is held by the volume constraint.
+    let state = history.get(history.len() - 1);
+    let lowest = [1, 2, 3, 4].map(|i| state.colliders[i].transform.translate.y).into_iter().fold(f32::MAX, f32::min);
+    assert!(lowest.abs() < 0.05, "tetrahedron came to rest at a height of {lowest}");
+}
@@ -0,0 +1,54 @@
+use glam::Vec3;
+use sokudo_core::{collider::ColliderId, world::World};
+use sokudo_io::read::ParsedWorld;
+
+fn resting_box() -> World {
+    let mut world: World = ParsedWorld::read("tests/resting-box.ron").unwrap().into();
+    world.initialize();
+
+    for _ in 0..50 {
+        world.step();
+    }
+
+    world
+}
+
+#[test]
+fn resting_contacts_cache_their_multipliers() {
+    let world = resting_box();
+    let manifold = &world.manifolds[&(ColliderId(0), ColliderId(1))];
+
+    assert_eq!(manifold.len(), 4);
+    assert!(manifold.iter().all(|contact| contact.lagrange <= 0.0), "multipliers were {manifold:?}");
+    assert!(manifold.iter().map(|contact| contact.lagrange).sum::<f32>() < 0.0, "multipliers were {manifold:?}");
+}
+
+#[test]
+fn separated_contacts_drop_their_multipliers() {
+    let mut world = resting_box();
+    world.colliders[1].velocity = Vec3::new(0.0, 10.0, 0.0);
+    world.step();
+
+    assert!(world.manifolds.is_empty(), "manifolds were {:?}", world.manifolds);
+}
+
+#[test]
+fn warm_started_box_rests_at_the_same_height() {
+    let mut cold: World = ParsedWorld::read("tests/resting-box.ron").unwrap().into();
+    cold.warm_start = false;
+    cold.initialize();
+
+    let mut warm = resting_box();
+
+    for _ in 0..150 {
+        cold.step();
+    }
+
+    for _ in 0..100 {
+        warm.step();
+    }
+
+    let (cold, warm) = (&cold.colliders[1], &warm.colliders[1]);
+
+    assert!((cold.position.y - warm.position.y).abs() < 1e-4, "cold box rested at {}, warm at {}", cold.position, warm.position);
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+use super::defaults::DefaultOptions;
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename = "Broadphase")]
+pub enum ParsedBroadphase {
+    BruteForce,
+    #[default]
+    SweepAndPrune,
+    SpatialHash {
+        cell_size: f32,
+    },
+    DynamicBvh {
+        #[serde(default = "DefaultOptions::rebuild_threshold")]
+        rebuild_threshold: f32,
+    },
+}
+
+impl ParsedBroadphase {
+    /// Whether the cells of a [`ParsedBroadphase::SpatialHash`] have a positive and finite size,
+    /// without which colliders would be bucketed into infinitely many cells. Every other
+    /// broadphase is always valid.
+    pub fn has_valid_cell_size(&self) -> bool {
+        match *self {
+            ParsedBroadphase::SpatialHash { cell_size } => cell_size > 0.0 && cell_size.is_finite(),
+            _ => true,
+        }
+    }
+}
@@ -1,24 +1,55 @@
-use glam::{UVec3, Vec3};
-use serde::{de::{EnumAccess, Error, MapAccess, VariantAccess, Visitor}, Deserialize, Deserializer};
+use std::path::PathBuf;
 
-use crate::read::{defaults::DefaultOptions, transform::ParsedTransform};
+use glam::{BVec3, UVec3, Vec3};
+use serde::{Deserialize, Deserializer};
+
+use crate::read::{defaults::DefaultOptions, mesh::ParsedMesh, sdf::ParsedSdf, transform::ParsedTransform};
 
 #[derive(Debug)]
 pub struct ParsedCollider {
     pub id: u32,
     pub body: ParsedColliderBody,
-    pub locked: bool,
-    
+    pub motion: ParsedColliderMotion,
+    /// Whether the collider only reports overlaps with other colliders instead of resolving them.
+    pub is_sensor: bool,
+    /// The layers the collider belongs to, as a bitfield.
+    pub collision_layer: u32,
+    /// The layers the collider collides with, as a bitfield.
+    pub collision_mask: u32,
+
     pub position: Vec3,
     pub velocity: Vec3,
+
+    /// The sRGBA color the collider is displayed with, if it overrides the default.
+    pub color: Option<[f32; 4]>,
+    /// How metallic the collider appears, from zero to one.
+    pub metallic: f32,
+    /// How rough the collider appears, from zero to one.
+    pub roughness: f32,
+}
+
+/// How a collider is moved.
+#[derive(Debug)]
+pub enum ParsedColliderMotion {
+    /// The collider is moved by gravity, forces and collisions.
+    Dynamic,
+    /// The collider never moves and has infinite mass.
+    Locked,
+    /// The collider follows a path of `(time, transform)` keyframes with infinite mass, ordered by
+    /// their times in seconds. The scales of the keyframes are ignored.
+    Kinematic(Vec<(f32, ParsedTransform)>),
 }
 
+// Most colliders of a world are rigid bodies, so boxing them would only add indirection.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum ParsedColliderBody {
     Particle(ParsedParticle),
     RigidBody(ParsedRigidBody),
 }
 
+// Raw colliders only exist while a world file is being parsed, so boxing would gain nothing.
+#[allow(clippy::large_enum_variant)]
 #[derive(Deserialize, Debug)]
 #[serde(rename = "Collider")]
 pub(crate) enum RawCollider {
@@ -26,28 +57,93 @@ pub(crate) enum RawCollider {
         #[serde(default)]
         locked: bool,
         #[serde(default)]
+        is_sensor: bool,
+        #[serde(default = "DefaultOptions::collision_layers")]
+        collision_layer: u32,
+        #[serde(default = "DefaultOptions::collision_layers")]
+        collision_mask: u32,
+        #[serde(default)]
         position: Vec3,
         #[serde(default)]
         velocity: Vec3,
 
         #[serde(default = "DefaultOptions::mass")]
         mass: f32,
+        #[serde(default)]
+        radius: f32,
+        #[serde(default = "DefaultOptions::static_friction")]
+        static_friction: f32,
+        #[serde(default = "DefaultOptions::dynamic_friction")]
+        dynamic_friction: f32,
+        #[serde(default = "DefaultOptions::restitution")]
+        restitution: f32,
+
+        #[serde(default, deserialize_with = "deserialize_some")]
+        color: Option<[f32; 4]>,
+        #[serde(default = "DefaultOptions::metallic")]
+        metallic: f32,
+        #[serde(default = "DefaultOptions::roughness")]
+        roughness: f32,
     },
     RigidBody {
         #[serde(default)]
         locked: bool,
         #[serde(default)]
+        is_sensor: bool,
+        #[serde(default = "DefaultOptions::collision_layers")]
+        collision_layer: u32,
+        #[serde(default = "DefaultOptions::collision_layers")]
+        collision_mask: u32,
+        #[serde(default)]
         transform: ParsedTransform,
         #[serde(default)]
         velocity: Vec3,
+        /// The keyframes of a kinematic rigid body, which take precedence over `locked`.
+        #[serde(default)]
+        keyframes: Vec<(f32, ParsedTransform)>,
 
         shape: ParsedShape,
-        #[serde(default = "DefaultOptions::mass")]
-        mass: f32,
+        #[serde(default, deserialize_with = "deserialize_some")]
+        mass: Option<f32>,
+        #[serde(default, deserialize_with = "deserialize_some")]
+        density: Option<f32>,
+        #[serde(default)]
+        center_of_mass: Vec3,
         #[serde(default = "DefaultOptions::vertex_resolution")]
         vertex_resolution: UVec3,
         #[serde(default)]
         vertices: Vec<Vec3>,
+        #[serde(default = "DefaultOptions::static_friction")]
+        static_friction: f32,
+        #[serde(default = "DefaultOptions::dynamic_friction")]
+        dynamic_friction: f32,
+        #[serde(default = "DefaultOptions::restitution")]
+        restitution: f32,
+        #[serde(default)]
+        linear_damping: f32,
+        #[serde(default)]
+        angular_damping: f32,
+        #[serde(default)]
+        drag_coefficient: f32,
+        #[serde(default)]
+        rolling_friction: f32,
+        #[serde(default)]
+        spinning_friction: f32,
+        #[serde(default = "DefaultOptions::max_velocity")]
+        max_linear_velocity: f32,
+        #[serde(default = "DefaultOptions::max_velocity")]
+        max_angular_velocity: f32,
+        #[serde(default)]
+        lock_translation: BVec3,
+        #[serde(default)]
+        lock_rotation: BVec3,
+
+        #[serde(default, deserialize_with = "deserialize_some")]
+        color: Option<[f32; 4]>,
+        #[serde(default = "DefaultOptions::metallic")]
+        metallic: f32,
+        #[serde(default = "DefaultOptions::roughness")]
+        roughness: f32,
     },
 }
 
@@ -59,8 +155,17 @@ impl From<RawCollider> for ParsedColliderBody {
                 position: _,
                 velocity: _,
                 mass,
+                radius,
+                static_friction,
+                dynamic_friction,
+                restitution,
+                ..
             } => ParsedColliderBody::Particle(ParsedParticle {
                 mass,
+                radius,
+                static_friction,
+                dynamic_friction,
+                restitution,
             }),
             RawCollider::RigidBody {
                 locked: _,
@@ -68,14 +173,44 @@ impl From<RawCollider> for ParsedColliderBody {
                 velocity: _,
                 shape,
                 mass,
+                density,
+                center_of_mass,
                 vertex_resolution,
                 vertices,
+                static_friction,
+                dynamic_friction,
+                restitution,
+                linear_damping,
+                angular_damping,
+                drag_coefficient,
+                rolling_friction,
+                spinning_friction,
+                max_linear_velocity,
+                max_angular_velocity,
+                lock_translation,
+                lock_rotation,
+                ..
             } => ParsedColliderBody::RigidBody(ParsedRigidBody {
                 shape,
                 transform,
-                mass,
+                mass: mass.unwrap_or(DefaultOptions::mass()),
+                // An explicit mass always takes precedence over the density.
+                density: if mass.is_some() { None } else { density },
+                center_of_mass,
                 vertex_resolution,
                 vertices,
+                static_friction,
+                dynamic_friction,
+                restitution,
+                linear_damping,
+                angular_damping,
+                drag_coefficient,
+                rolling_friction,
+                spinning_friction,
+                max_linear_velocity,
+                max_angular_velocity,
+                lock_translation,
+                lock_rotation,
             }),
         }
     }
@@ -84,6 +219,11 @@ impl From<RawCollider> for ParsedColliderBody {
 #[derive(Debug)]
 pub struct ParsedParticle {
     pub mass: f32,
+    /// The radius of the particle, which is zero for point particles.
+    pub radius: f32,
+    pub static_friction: f32,
+    pub dynamic_friction: f32,
+    pub restitution: f32,
 }
 
 #[derive(Debug)]
@@ -91,12 +231,107 @@ pub struct ParsedRigidBody {
     pub shape: ParsedShape,
     pub transform: ParsedTransform,
     pub mass: f32,
+    /// The density to compute the mass from instead of using `mass`, if any.
+    pub density: Option<f32>,
+    /// The center of mass in the rigid body's local coordinates, relative to the origin of its
+    /// shape.
+    pub center_of_mass: Vec3,
     pub vertex_resolution: UVec3,
     pub vertices: Vec<Vec3>,
+    pub static_friction: f32,
+    pub dynamic_friction: f32,
+    pub restitution: f32,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    /// The drag coefficient of the rigid body, which slows it in proportion to the square of its
+    /// speed.
+    pub drag_coefficient: f32,
+    /// The torque opposing the rolling of the rigid body at each contact per unit of normal force.
+    pub rolling_friction: f32,
+    /// The torque opposing the spinning of the rigid body about the normal of each contact per
+    /// unit of normal force.
+    pub spinning_friction: f32,
+    /// The largest linear speed of the rigid body, which is infinite when unset.
+    pub max_linear_velocity: f32,
+    /// The largest angular speed of the rigid body, which is infinite when unset.
+    pub max_angular_velocity: f32,
+    /// The global axes along which the rigid body cannot move.
+    pub lock_translation: BVec3,
+    /// The local axes about which the rigid body cannot rotate.
+    pub lock_rotation: BVec3,
 }
 
 #[derive(Deserialize, Debug)]
 #[serde(rename = "Shape")]
 pub enum ParsedShape {
     Cuboid,
+    /// A cylinder whose axis lies along y.
+    Cylinder {
+        #[serde(default = "DefaultOptions::radius")]
+        radius: f32,
+        #[serde(default = "DefaultOptions::half_height")]
+        half_height: f32,
+    },
+    /// A cone whose axis lies along y with its apex pointing up, centered at its centroid a
+    /// quarter of its height above its base.
+    Cone {
+        #[serde(default = "DefaultOptions::radius")]
+        radius: f32,
+        #[serde(default = "DefaultOptions::height")]
+        height: f32,
+    },
+    /// A torus centered at the origin whose tube circles the y-axis.
+    Torus {
+        /// The distance from the center to the middle of the tube.
+        #[serde(default = "DefaultOptions::major_radius")]
+        major_radius: f32,
+        /// The radius of the tube.
+        #[serde(default = "DefaultOptions::minor_radius")]
+        minor_radius: f32,
+    },
+    Mesh {
+        /// The OBJ file to load, relative to the world file.
+        path: PathBuf,
+        /// The mesh loaded from `path` by [`ParsedWorld::read`](super::ParsedWorld::read).
+        #[serde(skip)]
+        mesh: ParsedMesh,
+    },
+    ConvexHull {
+        points: Vec<Vec3>,
+    },
+    /// A shape whose surface is the zero level set of a sampled signed distance field.
+    Sdf {
+        /// The `.sdf` grid file to load, relative to the world file.
+        path: PathBuf,
+        /// The grid loaded from `path` by [`ParsedWorld::read`](super::ParsedWorld::read).
+        #[serde(skip)]
+        grid: Box<ParsedSdf>,
+    },
+    HalfSpace {
+        #[serde(default = "DefaultOptions::half_space_normal")]
+        normal: Vec3,
+    },
+    /// A union of other shapes, none of which may be compounds or half-spaces.
+    Compound {
+        parts: Vec<ParsedCompoundPart>,
+    },
+}
+
+/// A shape within a [`ParsedShape::Compound`], placed relative to the origin of the compound.
+#[derive(Deserialize, Debug)]
+#[serde(rename = "Part")]
+pub struct ParsedCompoundPart {
+    #[serde(default)]
+    pub transform: ParsedTransform,
+    pub shape: ParsedShape,
+}
+
+/// Deserializes a value which is present into `Some`, so that optional fields can be written
+/// without wrapping them in `Some(...)`.
+pub(crate) fn deserialize_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
 }
@@ -0,0 +1,144 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::read::defaults::DefaultOptions;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Constraint")]
+pub enum ParsedConstraint {
+    Distance {
+        a: u32,
+        b: u32,
+        rest_length: f32,
+        #[serde(default)]
+        compliance: f32,
+    },
+    /// Keeps two colliders at most `max_length` apart, while leaving them free to move closer.
+    Cable {
+        a: u32,
+        b: u32,
+        max_length: f32,
+        #[serde(default)]
+        compliance: f32,
+    },
+    Hinge {
+        a: u32,
+        b: u32,
+        #[serde(default)]
+        anchor_a: Vec3,
+        #[serde(default)]
+        anchor_b: Vec3,
+        axis_a: Vec3,
+        axis_b: Vec3,
+        #[serde(default)]
+        min_angle: Option<f32>,
+        #[serde(default)]
+        max_angle: Option<f32>,
+        #[serde(default)]
+        compliance: f32,
+        /// Drives the hinge about its axis, if set.
+        #[serde(default)]
+        motor: Option<ParsedHingeMotor>,
+    },
+    Spherical {
+        a: u32,
+        b: u32,
+        #[serde(default)]
+        anchor_a: Vec3,
+        #[serde(default)]
+        anchor_b: Vec3,
+        #[serde(default = "DefaultOptions::joint_axis")]
+        cone_axis_a: Vec3,
+        #[serde(default = "DefaultOptions::joint_axis")]
+        cone_axis_b: Vec3,
+        #[serde(default)]
+        max_swing_angle: Option<f32>,
+        #[serde(default)]
+        compliance: f32,
+    },
+    Prismatic {
+        a: u32,
+        b: u32,
+        #[serde(default)]
+        anchor_a: Vec3,
+        #[serde(default)]
+        anchor_b: Vec3,
+        axis: Vec3,
+        #[serde(default)]
+        min_distance: Option<f32>,
+        #[serde(default)]
+        max_distance: Option<f32>,
+        #[serde(default)]
+        compliance: f32,
+    },
+    Spring {
+        a: u32,
+        b: u32,
+        rest_length: f32,
+        stiffness: f32,
+        #[serde(default)]
+        damping: f32,
+    },
+    /// Keeps the signed volume of the tetrahedron between four colliders at `rest_volume`, which is
+    /// positive when `d` lies on the side of the triangle `a`, `b`, `c` which it faces
+    /// counter-clockwise.
+    Volume {
+        a: u32,
+        b: u32,
+        c: u32,
+        d: u32,
+        rest_volume: f32,
+        #[serde(default)]
+        compliance: f32,
+    },
+    /// Limits the twist of `b` relative to `a` about `axis`, given in the local coordinates of
+    /// `a`, without otherwise coupling the two bodies.
+    AngularLimit {
+        a: u32,
+        b: u32,
+        axis: Vec3,
+        min_angle: f32,
+        max_angle: f32,
+        #[serde(default)]
+        compliance: f32,
+    },
+}
+
+/// How a hinge is driven about its axis.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename = "Motor")]
+pub enum ParsedHingeMotor {
+    /// Drives the relative angular velocity of the bodies about the axis towards
+    /// `target_velocity`, in radians per second.
+    Velocity {
+        target_velocity: f32,
+        /// The largest torque the motor applies.
+        #[serde(default = "DefaultOptions::max_force")]
+        max_force: f32,
+    },
+    /// Drives the hinge angle towards `target_angle`, in radians, closing `stiffness` times the
+    /// remaining angle per second.
+    Angle {
+        target_angle: f32,
+        stiffness: f32,
+        /// The largest torque the motor applies.
+        #[serde(default = "DefaultOptions::max_force")]
+        max_force: f32,
+    },
+}
+
+impl ParsedConstraint {
+    /// The IDs of the colliders this constraint acts on.
+    pub fn bodies(&self) -> Vec<u32> {
+        match *self {
+            ParsedConstraint::Distance { a, b, .. }
+            | ParsedConstraint::Cable { a, b, .. }
+            | ParsedConstraint::Hinge { a, b, .. }
+            | ParsedConstraint::Spherical { a, b, .. }
+            | ParsedConstraint::Prismatic { a, b, .. }
+            | ParsedConstraint::Spring { a, b, .. }
+            | ParsedConstraint::AngularLimit { a, b, .. } => vec![a, b],
+            ParsedConstraint::Volume { a, b, c, d, .. } => vec![a, b, c, d],
+        }
+    }
+}
@@ -1,30 +1,126 @@
-use glam::UVec3;
+use glam::{UVec3, Vec3};
 
+/// The values of the options which are omitted from a world file.
 pub struct DefaultOptions;
 
 impl DefaultOptions {
+    #[inline(always)]
+    pub const fn gravity() -> Vec3 {
+        Vec3::new(0.0, -9.81, 0.0)
+    }
+
+    #[inline(always)]
+    pub const fn substeps() -> u32 {
+        8
+    }
+
+    #[inline(always)]
+    pub const fn iterations() -> u32 {
+        1
+    }
+
     #[inline(always)]
     pub const fn vertex_resolution() -> UVec3 {
         UVec3::ONE
     }
 
     #[inline(always)]
-    pub const fn mass() -> f32 {
+    pub const fn max_vertex_resolution() -> u32 {
+        64
+    }
+
+    #[inline(always)]
+    pub const fn max_correction_per_step() -> f32 {
+        0.2
+    }
+
+    #[inline(always)]
+    pub const fn major_radius() -> f32 {
+        0.5
+    }
+
+    #[inline(always)]
+    pub const fn minor_radius() -> f32 {
+        0.25
+    }
+
+    #[inline(always)]
+    pub const fn half_space_normal() -> Vec3 {
+        Vec3::Y
+    }
+
+    #[inline(always)]
+    pub const fn radius() -> f32 {
+        0.5
+    }
+
+    #[inline(always)]
+    pub const fn half_height() -> f32 {
+        0.5
+    }
+
+    #[inline(always)]
+    pub const fn height() -> f32 {
         1.0
     }
 
     #[inline(always)]
-    pub const fn material_roughness() -> f32 {
+    pub const fn joint_axis() -> Vec3 {
+        Vec3::NEG_Y
+    }
+
+    #[inline(always)]
+    pub const fn mass() -> f32 {
         1.0
     }
 
     #[inline(always)]
-    pub const fn material_resilience() -> f32 {
+    pub const fn static_friction() -> f32 {
+        0.5
+    }
+
+    #[inline(always)]
+    pub const fn dynamic_friction() -> f32 {
+        0.3
+    }
+
+    #[inline(always)]
+    pub const fn restitution() -> f32 {
         0.2
     }
 
     #[inline(always)]
-    pub const fn material_hardness() -> f32 {
+    pub const fn max_velocity() -> f32 {
+        f32::INFINITY
+    }
+
+    #[inline(always)]
+    pub const fn max_force() -> f32 {
+        f32::INFINITY
+    }
+
+    #[inline(always)]
+    pub const fn metallic() -> f32 {
+        0.0
+    }
+
+    #[inline(always)]
+    pub const fn roughness() -> f32 {
+        0.5
+    }
+
+    #[inline(always)]
+    pub const fn collision_layers() -> u32 {
+        u32::MAX
+    }
+
+    #[inline(always)]
+    pub const fn restitution_velocity_threshold() -> f32 {
         1.0
     }
+
+    #[inline(always)]
+    pub const fn rebuild_threshold() -> f32 {
+        1.25
+    }
 }
@@ -0,0 +1,37 @@
+use glam::Vec3;
+use serde::Deserialize;
+
+use crate::read::defaults::DefaultOptions;
+
+/// A source which spawns particles at a steady rate while the world is simulated.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename = "Emitter")]
+pub struct ParsedEmitter {
+    /// The position every particle is spawned at.
+    #[serde(default)]
+    pub position: Vec3,
+    /// The number of particles spawned per second.
+    pub rate: f32,
+    /// The mean initial velocity of the particles.
+    #[serde(default)]
+    pub velocity: Vec3,
+    /// The largest amount by which each component of the initial velocity of a particle differs
+    /// from `velocity`, which is drawn uniformly.
+    #[serde(default)]
+    pub velocity_spread: Vec3,
+    /// The number of seconds each particle exists for before it is removed.
+    pub lifetime: f32,
+    /// The radius of the particles.
+    #[serde(default)]
+    pub radius: f32,
+    /// The mass of the particles.
+    #[serde(default = "DefaultOptions::mass")]
+    pub mass: f32,
+    /// The largest number of particles of this emitter which exist at once. No particles are
+    /// spawned while there are this many.
+    pub max_particles: u32,
+    /// The seed of the initial velocities, so that the same world always spawns the same
+    /// particles.
+    #[serde(default)]
+    pub seed: u64,
+}
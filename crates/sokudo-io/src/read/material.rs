@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename = "MaterialCombine")]
+pub enum ParsedMaterialCombine {
+    Min,
+    Max,
+    Multiply,
+    #[default]
+    Average,
+}
@@ -0,0 +1,81 @@
+use std::{fs, path::Path};
+
+use glam::Vec3;
+
+use super::ParseError;
+
+/// A triangle mesh loaded from a file.
+#[derive(Debug, Default, Clone)]
+pub struct ParsedMesh {
+    pub vertices: Vec<Vec3>,
+    /// The vertex indices of each triangle.
+    pub indices: Vec<[u32; 3]>,
+}
+
+impl ParsedMesh {
+    /// Reads the vertices and faces of the Wavefront OBJ file at `path`, triangulating polygonal
+    /// faces as fans. All other statements are ignored.
+    pub fn read_obj<P>(path: P) -> Result<ParsedMesh, ParseError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|source| ParseError::Mesh {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let error = |line: usize, message: &str| ParseError::Obj {
+            path: path.to_path_buf(),
+            line: line + 1,
+            message: message.to_string(),
+        };
+
+        let mut mesh = ParsedMesh::default();
+
+        for (line_number, line) in source.lines().enumerate() {
+            let mut tokens = line.split_whitespace();
+
+            match tokens.next() {
+                Some("v") => {
+                    let coords: Vec<f32> = tokens
+                        .take(3)
+                        .map(str::parse)
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| error(line_number, "invalid vertex coordinate"))?;
+
+                    let [x, y, z] = coords[..] else {
+                        return Err(error(line_number, "vertex must have three coordinates"));
+                    };
+
+                    mesh.vertices.push(Vec3::new(x, y, z));
+                },
+                Some("f") => {
+                    let face: Vec<u32> = tokens
+                        .map(|token| {
+                            // Only the vertex index of `v/vt/vn` is needed. Negative indices are
+                            // relative to the most recently defined vertex.
+                            let index: i64 = token.split('/').next()?.parse().ok()?;
+                            let count = mesh.vertices.len() as i64;
+                            let index = if index < 0 { count + index } else { index - 1 };
+
+                            (0..count).contains(&index).then_some(index as u32)
+                        })
+                        .collect::<Option<_>>()
+                        .ok_or_else(|| error(line_number, "invalid face index"))?;
+
+                    if face.len() < 3 {
+                        return Err(error(line_number, "face must have at least three vertices"));
+                    }
+
+                    for i in 1..face.len() - 1 {
+                        mesh.indices.push([face[0], face[i], face[i + 1]]);
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        Ok(mesh)
+    }
+}
@@ -1,13 +1,30 @@
-use std::{fs, io, path};
+use std::{fs, io, path::{self, PathBuf}};
 
-use collider::{ParsedCollider, ParsedColliderBody, RawCollider};
-use glam::Vec3;
+use broadphase::ParsedBroadphase;
+use collider::{deserialize_some, ParsedCollider, ParsedColliderBody, ParsedColliderMotion, ParsedShape, RawCollider};
+use constraint::ParsedConstraint;
+use defaults::DefaultOptions;
+use emitter::ParsedEmitter;
+use glam::{UVec3, Vec3};
+use material::ParsedMaterialCombine;
+use mesh::ParsedMesh;
+use sdf::ParsedSdf;
 use serde::Deserialize;
 use thiserror::Error;
+use water::ParsedWater;
 
 pub mod transform;
+pub mod broadphase;
 pub mod collider;
-mod defaults;
+pub mod constraint;
+pub mod material;
+pub mod mesh;
+pub mod sdf;
+pub mod validate;
+pub mod water;
+pub mod emitter;
+pub mod script;
+pub mod defaults;
 
 #[derive(Error, Debug)]
 pub enum ParseError {
@@ -17,6 +34,65 @@ pub enum ParseError {
     /// A RON error.
     #[error(transparent)]
     Ron(#[from] ron::de::SpannedError),
+    /// A YAML error.
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+    /// A mesh file could not be read.
+    #[error("failed to read mesh `{}`: {source}", path.display())]
+    Mesh {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// A compound shape contains another compound shape.
+    #[error("compound shapes cannot contain other compound shapes")]
+    NestedCompound,
+    /// A compound shape contains a half-space.
+    #[error("compound shapes cannot contain half-spaces")]
+    UnboundedCompoundPart,
+    /// A mesh file is not a valid OBJ file.
+    #[error("invalid OBJ file `{}` on line {line}: {message}", path.display())]
+    Obj {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    /// A signed distance field file could not be read.
+    #[error("failed to read signed distance field `{}`: {source}", path.display())]
+    Sdf {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// A signed distance field file is not a valid grid.
+    #[error("invalid SDF file `{}` on line {line}: {message}", path.display())]
+    SdfGrid {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+}
+
+/// A problem with a world file which does not prevent it from being simulated.
+#[derive(Error, Debug)]
+pub enum ParseWarning {
+    /// A rigid body specifies both a mass and a density, in which case the mass is used.
+    #[error("collider {id} specifies both a mass and a density, so the density is ignored")]
+    MassAndDensity {
+        id: u32,
+    },
+    /// A rigid body has a vertex resolution above the maximum of the world along at least one
+    /// axis, so it is clamped to the maximum.
+    #[error("collider {id} has a vertex resolution of {resolution}, which is clamped to {max}")]
+    VertexResolutionClamped {
+        id: u32,
+        resolution: UVec3,
+        max: u32,
+    },
+    /// The spatial hash broadphase has a cell size which is not positive and finite, so the sweep
+    /// and prune broadphase is used instead.
+    #[error("the spatial hash has a cell size of {cell_size}, so sweep and prune is used instead")]
+    InvalidCellSize {
+        cell_size: f32,
+    },
 }
 
 #[derive(Deserialize, Debug)]
@@ -24,10 +100,33 @@ pub enum ParseError {
 pub(crate) struct RawWorld {
     steps: u32,
     dt: f32,
+    #[serde(default = "DefaultOptions::gravity")]
     gravity: Vec3,
+    #[serde(default)]
+    broadphase: ParsedBroadphase,
+    #[serde(default = "DefaultOptions::substeps")]
+    substeps: u32,
+    #[serde(default = "DefaultOptions::iterations")]
+    iterations: u32,
+    #[serde(default)]
+    friction_combine: ParsedMaterialCombine,
+    #[serde(default)]
+    restitution_combine: ParsedMaterialCombine,
+    #[serde(default = "DefaultOptions::restitution_velocity_threshold")]
+    restitution_velocity_threshold: f32,
+    #[serde(default = "DefaultOptions::max_vertex_resolution")]
+    max_vertex_resolution: u32,
+    #[serde(default = "DefaultOptions::max_correction_per_step")]
+    max_correction_per_step: f32,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    water: Option<ParsedWater>,
+    #[serde(default)]
+    emitters: Vec<ParsedEmitter>,
 
     #[serde(default)]
     colliders: Vec<RawCollider>,
+    #[serde(default)]
+    constraints: Vec<ParsedConstraint>,
 }
 
 #[derive(Debug)]
@@ -35,29 +134,131 @@ pub struct ParsedWorld {
     pub steps: u32,
     pub dt: f32,
     pub gravity: Vec3,
+    pub broadphase: ParsedBroadphase,
+    /// The number of substeps each step is divided into. Every substep integrates the colliders
+    /// over a fraction of `dt` and resolves their contacts and constraints again, so more
+    /// substeps make stiff constraints and stacks converge at the cost of more work.
+    pub substeps: u32,
+    /// The number of times the constraints are projected within each substep. Extra iterations
+    /// refine the positions without integrating again, which is usually less effective than the
+    /// same number of extra substeps.
+    pub iterations: u32,
+    /// How the friction coefficients of two colliders are combined when they touch.
+    pub friction_combine: ParsedMaterialCombine,
+    /// How the restitution coefficients of two colliders are combined when they touch.
+    pub restitution_combine: ParsedMaterialCombine,
+    /// The relative normal speed below which contacts do not bounce, whatever the restitution of
+    /// their colliders.
+    pub restitution_velocity_threshold: f32,
+    /// The largest vertex resolution of a rigid body along each axis, above which it is clamped
+    /// to keep pathological resolutions from generating millions of vertices.
+    pub max_vertex_resolution: u32,
+    /// The largest fraction of the penetration bodies already had at the start of a step which is
+    /// resolved during it, so that bodies placed overlapping each other are eased apart over
+    /// several steps.
+    pub max_correction_per_step: f32,
+    /// The water the colliders float in, if any.
+    pub water: Option<ParsedWater>,
+    /// The emitters which spawn particles into the world while it is simulated.
+    pub emitters: Vec<ParsedEmitter>,
     pub colliders: Vec<ParsedCollider>,
+    pub constraints: Vec<ParsedConstraint>,
+    /// The problems found while parsing the world.
+    pub warnings: Vec<ParseWarning>,
 }
 
 impl ParsedWorld {
+    /// Reads the world file at `path`, which is written in YAML if its extension is `.yaml` or
+    /// `.yml` and in RON otherwise, and loads the mesh and signed distance field files it refers
+    /// to.
+    ///
+    /// Enum variants with fields are written in YAML as a tag naming the variant, such as
+    /// `shape: !Cylinder { radius: 0.5 }`, and variants without fields as just their name.
     pub fn read<P>(path: P) -> Result<ParsedWorld, ParseError>
     where
         P: AsRef<path::Path>,
     {
-        let file = fs::File::open(path)?;
-        let raw_world: RawWorld = ron::de::from_reader(file)?;
-        let world = ParsedWorld::from(raw_world);
+        let file = fs::File::open(&path)?;
+        let raw_world: RawWorld = match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => serde_yaml::from_reader(file)?,
+            _ => ron::de::from_reader(file)?,
+        };
+        let mut world = ParsedWorld::from(raw_world);
+
+        // Mesh paths are relative to the world file.
+        let directory = path.as_ref().parent().unwrap_or(path::Path::new(""));
+
+        for collider in world.colliders.iter_mut() {
+            if let ParsedColliderBody::RigidBody(rb) = &mut collider.body {
+                load_shape(&mut rb.shape, directory, false)?;
+            }
+        }
 
         Ok(world)
     }
 }
 
+/// Loads the meshes and signed distance fields of `shape` relative to `directory`, rejecting the
+/// shapes a compound cannot contain if `in_compound` is set.
+fn load_shape(shape: &mut ParsedShape, directory: &path::Path, in_compound: bool) -> Result<(), ParseError> {
+    match shape {
+        ParsedShape::Mesh { path: mesh_path, mesh } => {
+            *mesh = ParsedMesh::read_obj(directory.join(mesh_path))?;
+        },
+        ParsedShape::Sdf { path: sdf_path, grid } => {
+            **grid = ParsedSdf::read_sdf(directory.join(sdf_path))?;
+        },
+        ParsedShape::Compound { .. } if in_compound => return Err(ParseError::NestedCompound),
+        ParsedShape::Compound { parts } => {
+            for part in parts.iter_mut() {
+                load_shape(&mut part.shape, directory, true)?;
+            }
+        },
+        ParsedShape::HalfSpace { .. } if in_compound => return Err(ParseError::UnboundedCompoundPart),
+        _ => (),
+    }
+
+    Ok(())
+}
+
 impl From<RawWorld> for ParsedWorld {
     fn from(raw: RawWorld) -> Self {
+        let mut warnings = Vec::new();
+
+        if let ParsedBroadphase::SpatialHash { cell_size } = raw.broadphase {
+            if !raw.broadphase.has_valid_cell_size() {
+                warnings.push(ParseWarning::InvalidCellSize { cell_size });
+            }
+        }
+
+        for (i, collider) in raw.colliders.iter().enumerate() {
+            let RawCollider::RigidBody { mass, density, vertex_resolution, .. } = collider else { continue };
+            let id = i as u32;
+
+            if mass.is_some() && density.is_some() {
+                warnings.push(ParseWarning::MassAndDensity { id });
+            }
+
+            if vertex_resolution.max_element() > raw.max_vertex_resolution {
+                warnings.push(ParseWarning::VertexResolutionClamped { id, resolution: *vertex_resolution, max: raw.max_vertex_resolution });
+            }
+        }
+
         ParsedWorld {
             steps: raw.steps,
             dt: raw.dt,
             gravity: raw.gravity,
-            colliders: raw.colliders.into_iter().enumerate().map(|(i, collider)| {
+            broadphase: raw.broadphase,
+            substeps: raw.substeps,
+            iterations: raw.iterations,
+            friction_combine: raw.friction_combine,
+            restitution_combine: raw.restitution_combine,
+            restitution_velocity_threshold: raw.restitution_velocity_threshold,
+            max_vertex_resolution: raw.max_vertex_resolution,
+            max_correction_per_step: raw.max_correction_per_step,
+            water: raw.water,
+            emitters: raw.emitters,
+            colliders: raw.colliders.into_iter().enumerate().map(|(i, mut collider)| {
                 ParsedCollider {
                     id: i as u32,
                     position: match collider {
@@ -68,13 +269,42 @@ impl From<RawWorld> for ParsedWorld {
                         RawCollider::Particle { velocity, .. } => velocity,
                         RawCollider::RigidBody { velocity, .. } => velocity,
                     },
-                    locked: match collider {
-                        RawCollider::Particle { locked, .. } => locked,
-                        RawCollider::RigidBody { locked, .. } => locked,
+                    is_sensor: match collider {
+                        RawCollider::Particle { is_sensor, .. } => is_sensor,
+                        RawCollider::RigidBody { is_sensor, .. } => is_sensor,
+                    },
+                    collision_layer: match collider {
+                        RawCollider::Particle { collision_layer, .. } => collision_layer,
+                        RawCollider::RigidBody { collision_layer, .. } => collision_layer,
+                    },
+                    collision_mask: match collider {
+                        RawCollider::Particle { collision_mask, .. } => collision_mask,
+                        RawCollider::RigidBody { collision_mask, .. } => collision_mask,
+                    },
+                    motion: match collider {
+                        RawCollider::RigidBody { ref mut keyframes, .. } if !keyframes.is_empty() => {
+                            ParsedColliderMotion::Kinematic(std::mem::take(keyframes))
+                        },
+                        RawCollider::Particle { locked: true, .. } | RawCollider::RigidBody { locked: true, .. } => ParsedColliderMotion::Locked,
+                        _ => ParsedColliderMotion::Dynamic,
+                    },
+                    color: match collider {
+                        RawCollider::Particle { color, .. } => color,
+                        RawCollider::RigidBody { color, .. } => color,
+                    },
+                    metallic: match collider {
+                        RawCollider::Particle { metallic, .. } => metallic,
+                        RawCollider::RigidBody { metallic, .. } => metallic,
+                    },
+                    roughness: match collider {
+                        RawCollider::Particle { roughness, .. } => roughness,
+                        RawCollider::RigidBody { roughness, .. } => roughness,
                     },
                     body: ParsedColliderBody::from(collider),
                 }
             }).collect(),
+            constraints: raw.constraints,
+            warnings,
         }
     }
 }
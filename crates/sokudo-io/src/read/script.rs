@@ -0,0 +1,62 @@
+use std::{fs, path};
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::write::WriteStateError;
+
+use super::ParseError;
+
+/// A list of impulses and forces applied to colliders at given steps, which reproduces an
+/// interactive session when replayed alongside the world it was recorded in.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename = "Script")]
+pub struct ParsedScript {
+    /// The entries of the script. Entries of the same step are applied in the order they are
+    /// listed in.
+    pub entries: Vec<ScriptEntry>,
+}
+
+/// An impulse or force applied to a single collider during a single step.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename = "Entry")]
+pub struct ScriptEntry {
+    /// The number of steps simulated before the step the entry is applied in, so that an entry
+    /// of step `30` changes the state between frames 30 and 31 of the history.
+    pub step: u32,
+    /// The ID of the collider.
+    pub collider: u32,
+    pub action: ScriptAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ScriptAction {
+    /// An impulse applied at the center of mass of the collider at the start of the step.
+    Impulse(Vec3),
+    /// A force applied at the center of mass of the collider throughout the step.
+    Force(Vec3),
+}
+
+impl ParsedScript {
+    /// Reads a [`ParsedScript`] from file `path`, ordering its entries by their steps.
+    pub fn read<P>(path: P) -> Result<ParsedScript, ParseError>
+    where
+        P: AsRef<path::Path>
+    {
+        let mut script: ParsedScript = ron::de::from_str(&fs::read_to_string(path)?)?;
+        script.entries.sort_by_key(|entry| entry.step);
+
+        Ok(script)
+    }
+
+    /// Writes this [`ParsedScript`] to file `path`, from which it can be read back with
+    /// [`ParsedScript::read`].
+    pub fn write<P>(&self, path: P) -> Result<(), WriteStateError>
+    where
+        P: AsRef<path::Path>
+    {
+        fs::write(path, ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use std::{fs, path::Path};
+
+use glam::{UVec3, Vec3};
+
+use super::ParseError;
+
+/// A signed distance field sampled on a regular grid, loaded from a file.
+#[derive(Debug, Default, Clone)]
+pub struct ParsedSdf {
+    /// The number of samples along each axis, which is at least two.
+    pub size: UVec3,
+    /// The position of the first sample.
+    pub min: Vec3,
+    /// The position of the last sample.
+    pub max: Vec3,
+    /// The samples ordered with x varying fastest, then y, then z.
+    pub values: Vec<f32>,
+}
+
+impl ParsedSdf {
+    /// Reads the `.sdf` grid file at `path`.
+    ///
+    /// The file contains a `size nx ny nz` statement giving the number of samples along each axis,
+    /// a `bounds minx miny minz maxx maxy maxz` statement giving the positions of the first and
+    /// last samples, and then the samples themselves as whitespace separated numbers in the order
+    /// of [`ParsedSdf::values`]. Lines starting with `#` are ignored.
+    pub fn read_sdf<P>(path: P) -> Result<ParsedSdf, ParseError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|source| ParseError::Sdf {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let error = |line: usize, message: &str| ParseError::SdfGrid {
+            path: path.to_path_buf(),
+            line: line + 1,
+            message: message.to_string(),
+        };
+
+        let mut size = None;
+        let mut bounds = None;
+        let mut values = Vec::new();
+        let mut last_line = 0;
+
+        for (line_number, line) in source.lines().enumerate() {
+            let mut tokens = line.split_whitespace().peekable();
+            last_line = line_number;
+
+            match tokens.peek().copied() {
+                None => (),
+                Some(token) if token.starts_with('#') => (),
+                Some("size") => {
+                    tokens.next();
+
+                    let counts: Vec<u32> = tokens
+                        .map(str::parse)
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| error(line_number, "invalid sample count"))?;
+
+                    let [x, y, z] = counts[..] else {
+                        return Err(error(line_number, "size must have three sample counts"));
+                    };
+
+                    if x < 2 || y < 2 || z < 2 {
+                        return Err(error(line_number, "size must have at least two samples along each axis"));
+                    }
+
+                    size = Some(UVec3::new(x, y, z));
+                },
+                Some("bounds") => {
+                    tokens.next();
+
+                    let coords: Vec<f32> = tokens
+                        .map(str::parse)
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| error(line_number, "invalid bounds coordinate"))?;
+
+                    let [min_x, min_y, min_z, max_x, max_y, max_z] = coords[..] else {
+                        return Err(error(line_number, "bounds must have six coordinates"));
+                    };
+
+                    let (min, max) = (Vec3::new(min_x, min_y, min_z), Vec3::new(max_x, max_y, max_z));
+
+                    if min.cmpge(max).any() {
+                        return Err(error(line_number, "bounds must have a positive extent along each axis"));
+                    }
+
+                    bounds = Some((min, max));
+                },
+                Some(_) => {
+                    for token in tokens {
+                        values.push(token.parse().map_err(|_| error(line_number, "invalid sample"))?);
+                    }
+                },
+            }
+        }
+
+        let size = size.ok_or_else(|| error(last_line, "missing size"))?;
+        let (min, max) = bounds.ok_or_else(|| error(last_line, "missing bounds"))?;
+
+        if values.len() != (size.x * size.y * size.z) as usize {
+            return Err(error(last_line, "number of samples does not match size"));
+        }
+
+        Ok(ParsedSdf { size, min, max, values })
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+
+use glam::Vec3;
+use thiserror::Error;
+
+use crate::read::{broadphase::ParsedBroadphase, collider::{ParsedColliderBody, ParsedColliderMotion, ParsedShape}, ParsedWorld};
+
+/// A problem with a world file which would make its simulation meaningless.
+#[derive(Error, Debug, PartialEq)]
+pub enum ValidationError {
+    /// The world is not divided into any substeps, so it would never be integrated.
+    #[error("the world has zero substeps")]
+    ZeroSubsteps,
+    /// The fraction of existing penetration resolved each substep is not positive, so bodies
+    /// placed overlapping would never be separated, or is more than the whole penetration.
+    #[error("the world has a max_correction_per_step of {max_correction}, which is not within (0, 1]")]
+    MaxCorrectionOutOfRange {
+        max_correction: f32,
+    },
+    /// The spatial hash broadphase has cells whose size is not positive and finite.
+    #[error("the spatial hash has a cell size of {cell_size}, which is not positive and finite")]
+    InvalidCellSize {
+        cell_size: f32,
+    },
+    /// The water has a density which is not positive.
+    #[error("the water has a density of {density}, which is not positive")]
+    NonPositiveWaterDensity {
+        density: f32,
+    },
+    /// An emitter spawns particles which are removed as soon as they are spawned.
+    #[error("emitter {index} has a lifetime of {lifetime}, which is not positive")]
+    NonPositiveEmitterLifetime {
+        index: usize,
+        lifetime: f32,
+    },
+    /// An emitter spawns particles with a mass which is not positive.
+    #[error("emitter {index} has a mass of {mass}, which is not positive")]
+    NonPositiveEmitterMass {
+        index: usize,
+        mass: f32,
+    },
+    /// An emitter spawns particles with a negative radius.
+    #[error("emitter {index} has a radius of {radius}, which is negative")]
+    NegativeEmitterRadius {
+        index: usize,
+        radius: f32,
+    },
+    /// A collider has a mass which is not positive.
+    #[error("collider {id} has a mass of {mass}, which is not positive")]
+    NonPositiveMass {
+        id: u32,
+        mass: f32,
+    },
+    /// A particle has a negative radius.
+    #[error("collider {id} has a radius of {radius}, which is negative")]
+    NegativeRadius {
+        id: u32,
+        radius: f32,
+    },
+    /// A rigid body has a density which is not positive.
+    #[error("collider {id} has a density of {density}, which is not positive")]
+    NonPositiveDensity {
+        id: u32,
+        density: f32,
+    },
+    /// A collider's position, rotation, scale or velocity is not finite.
+    #[error("collider {id} has a transform or velocity which is not finite")]
+    NonFiniteTransform {
+        id: u32,
+    },
+    /// A rigid body is scaled by zero along at least one axis.
+    #[error("collider {id} has a scale of zero along at least one axis")]
+    ZeroScale {
+        id: u32,
+    },
+    /// A rigid body whose shape is not sampled at a resolution, such as a mesh or convex hull,
+    /// has a vertex resolution which differs between axes.
+    #[error("collider {id} has a non-uniform vertex resolution, which its shape ignores")]
+    NonUniformVertexResolution {
+        id: u32,
+    },
+    /// A kinematic collider has keyframes which are not ordered by their times.
+    #[error("collider {id} has keyframes which are not ordered by their times")]
+    UnorderedKeyframes {
+        id: u32,
+    },
+    /// More than one collider has the same ID.
+    #[error("more than one collider has the ID {id}")]
+    DuplicateId {
+        id: u32,
+    },
+    /// A constraint refers to a collider which does not exist.
+    #[error("constraint {index} refers to collider {id}, which does not exist")]
+    UnknownCollider {
+        index: usize,
+        id: u32,
+    },
+}
+
+impl ParsedWorld {
+    /// Checks this world for problems which are not caught while parsing it, returning every
+    /// problem found.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let mut ids = HashSet::new();
+
+        if self.substeps == 0 {
+            errors.push(ValidationError::ZeroSubsteps);
+        }
+
+        if !(self.max_correction_per_step > 0.0 && self.max_correction_per_step <= 1.0) {
+            errors.push(ValidationError::MaxCorrectionOutOfRange { max_correction: self.max_correction_per_step });
+        }
+
+        if let ParsedBroadphase::SpatialHash { cell_size } = self.broadphase {
+            if !self.broadphase.has_valid_cell_size() {
+                errors.push(ValidationError::InvalidCellSize { cell_size });
+            }
+        }
+
+        if let Some(water) = &self.water {
+            if water.density <= 0.0 {
+                errors.push(ValidationError::NonPositiveWaterDensity { density: water.density });
+            }
+        }
+
+        for (index, emitter) in self.emitters.iter().enumerate() {
+            if emitter.lifetime <= 0.0 {
+                errors.push(ValidationError::NonPositiveEmitterLifetime { index, lifetime: emitter.lifetime });
+            }
+
+            if emitter.mass <= 0.0 {
+                errors.push(ValidationError::NonPositiveEmitterMass { index, mass: emitter.mass });
+            }
+
+            if emitter.radius < 0.0 {
+                errors.push(ValidationError::NegativeEmitterRadius { index, radius: emitter.radius });
+            }
+        }
+
+        for collider in self.colliders.iter() {
+            let id = collider.id;
+
+            if !ids.insert(id) {
+                errors.push(ValidationError::DuplicateId { id });
+            }
+
+            if !collider.position.is_finite() || !collider.velocity.is_finite() {
+                errors.push(ValidationError::NonFiniteTransform { id });
+            }
+
+            if let ParsedColliderMotion::Kinematic(keyframes) = &collider.motion {
+                if keyframes.windows(2).any(|pair| pair[0].0 > pair[1].0) {
+                    errors.push(ValidationError::UnorderedKeyframes { id });
+                }
+            }
+
+            match &collider.body {
+                ParsedColliderBody::Particle(particle) => {
+                    if particle.mass <= 0.0 {
+                        errors.push(ValidationError::NonPositiveMass { id, mass: particle.mass });
+                    }
+
+                    if particle.radius < 0.0 {
+                        errors.push(ValidationError::NegativeRadius { id, radius: particle.radius });
+                    }
+                },
+                ParsedColliderBody::RigidBody(rb) => {
+                    match rb.density {
+                        Some(density) if density <= 0.0 => errors.push(ValidationError::NonPositiveDensity { id, density }),
+                        Some(_) => (),
+                        None if rb.mass <= 0.0 => errors.push(ValidationError::NonPositiveMass { id, mass: rb.mass }),
+                        None => (),
+                    }
+
+                    let transform = &rb.transform;
+                    if !transform.rotate.is_finite() || !transform.scale.is_finite() {
+                        errors.push(ValidationError::NonFiniteTransform { id });
+                    }
+
+                    if transform.scale.cmpeq(Vec3::ZERO).any() {
+                        errors.push(ValidationError::ZeroScale { id });
+                    }
+
+                    let resolution = rb.vertex_resolution;
+                    let ignores_resolution = matches!(rb.shape, ParsedShape::Mesh { .. } | ParsedShape::ConvexHull { .. } | ParsedShape::HalfSpace { .. });
+
+                    if ignores_resolution && (resolution.x != resolution.y || resolution.y != resolution.z) {
+                        errors.push(ValidationError::NonUniformVertexResolution { id });
+                    }
+                },
+            }
+        }
+
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            for id in constraint.bodies() {
+                if !ids.contains(&id) {
+                    errors.push(ValidationError::UnknownCollider { index, id });
+                }
+            }
+        }
+
+        errors
+    }
+}
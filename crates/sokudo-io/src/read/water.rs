@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// A body of water filling everything below a horizontal plane.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename = "Water")]
+pub struct ParsedWater {
+    /// The height of the surface of the water.
+    #[serde(default)]
+    pub height: f32,
+    /// The density of the water, which bodies less dense than it float on.
+    pub density: f32,
+    /// The fraction of linear velocity a fully submerged body loses per second.
+    #[serde(default)]
+    pub drag: f32,
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use super::WriteStateError;
+
+/// The performance of a simulation run without writing its history.
+///
+/// Its fields are written in this order by [`BenchReport::to_json`], so reports from different
+/// builds can be compared line by line.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    /// The number of steps simulated.
+    pub steps: u32,
+    /// The wall time taken to simulate every step, in seconds, excluding reading the world.
+    pub wall_time: f64,
+    /// The number of steps simulated per second of wall time.
+    pub steps_per_second: f64,
+    /// The mean number of constraints solved in the last substep of each step, including the
+    /// collision constraints of its contacts.
+    pub average_constraints: f64,
+    /// The mean number of collision constraints in the last substep of each step.
+    pub average_collision_constraints: f64,
+    /// The largest constraint error left after solving the last substep of any step.
+    pub max_residual: f32,
+    /// The mean of the root mean square constraint error left after solving the last substep of
+    /// each step.
+    pub average_residual: f64,
+}
+
+impl BenchReport {
+    /// Serializes this [`BenchReport`] as a single line of JSON.
+    pub fn to_json(&self) -> Result<String, WriteStateError> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
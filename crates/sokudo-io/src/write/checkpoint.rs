@@ -0,0 +1,92 @@
+use std::{fs, path};
+
+use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
+
+use super::{ReadStateError, WriteStateError};
+
+/// The full state of a simulation part way through, from which it can be resumed exactly as if
+/// it had never stopped.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Checkpoint")]
+pub struct Checkpoint {
+    /// The number of steps simulated before this checkpoint.
+    pub step: u32,
+    pub colliders: Vec<CheckpointCollider>,
+    /// The pairs of colliders which were touching in the last step, from which the
+    /// [`CollisionEvent`](super::events::CollisionEvent)s of the next step are classified.
+    #[serde(default)]
+    pub touching: Vec<[u32; 2]>,
+    /// The contact manifolds generated by each pair of colliders in the last substep, from which
+    /// the contacts of the next step are kept in place.
+    #[serde(default)]
+    pub manifolds: Vec<CheckpointManifold>,
+}
+
+/// The state of a single collider in a [`Checkpoint`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Collider")]
+pub struct CheckpointCollider {
+    pub id: u32,
+    pub position: Vec3,
+    pub velocity: Vec3,
+    /// The rotation of the collider, which is always the identity for particles.
+    pub rotation: Quat,
+    /// The angular velocity of the collider, which is always zero for particles.
+    pub angular_velocity: Vec3,
+
+    pub asleep: bool,
+    pub sleep_frames: u32,
+}
+
+/// The contact manifold of a pair of colliders in a [`Checkpoint`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Manifold")]
+pub struct CheckpointManifold {
+    pub a: u32,
+    pub b: u32,
+    pub contacts: Vec<CheckpointContact>,
+}
+
+/// A contact of a [`CheckpointManifold`], identified by the features of the colliders which
+/// generated it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Contact")]
+pub struct CheckpointContact {
+    pub feature: u32,
+    /// The contact point in the first collider's local coordinates relative to its center of mass.
+    pub local_anchor1: Vec3,
+    /// The contact point in the second collider's local coordinates relative to its center of mass.
+    pub local_anchor2: Vec3,
+    /// The Lagrange multiplier accumulated by the collision constraint of the contact.
+    #[serde(default)]
+    pub lagrange: f32,
+}
+
+impl Checkpoint {
+    /// Writes this [`Checkpoint`] to file `path`.
+    ///
+    /// The checkpoint is first written next to `path` and then moved over it, so that an
+    /// interruption never leaves a partially written checkpoint behind.
+    pub fn write<P>(&self, path: P) -> Result<(), WriteStateError>
+    where
+        P: AsRef<path::Path>
+    {
+        let path = path.as_ref();
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+
+        fs::write(&partial, ron::to_string(self)?)?;
+        fs::rename(&partial, path)?;
+
+        Ok(())
+    }
+
+    /// Reads a [`Checkpoint`] from file `path`.
+    pub fn read<P>(path: P) -> Result<Checkpoint, ReadStateError>
+    where
+        P: AsRef<path::Path>
+    {
+        Ok(ron::de::from_str(&fs::read_to_string(path)?)?)
+    }
+}
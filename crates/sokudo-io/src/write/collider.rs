@@ -1,9 +1,29 @@
+use glam::Vec3;
 use serde::{Deserialize, Serialize};
 
 use super::transform::WriteTransform;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct WriteCollider {
     pub id: u32,
     pub transform: WriteTransform,
+    /// The linear velocity of the collider.
+    #[serde(default)]
+    pub velocity: Vec3,
+    /// The angular velocity of the collider, which is always zero for particles.
+    #[serde(default)]
+    pub angular_velocity: Vec3,
+    /// The colliders this collider was touching during the step, ordered by their IDs. Only
+    /// recorded in verbose histories.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contacts: Vec<WriteContact>,
+}
+
+/// The contact between a collider and another collider during a step.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WriteContact {
+    /// The ID of the other collider.
+    pub other: u32,
+    /// The total normal impulse the contact applied over the step.
+    pub impulse: f32,
 }
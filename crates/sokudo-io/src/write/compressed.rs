@@ -0,0 +1,213 @@
+//! Compressed histories, which are stored as a binary header followed by blocks of states each
+//! compressed with zstd.
+//!
+//! Every block but the last holds [`STATES_PER_BLOCK`] states separated by newlines, so a single
+//! state can be read by decompressing only the block containing it.
+
+use std::{
+    fs,
+    io::{self, BufReader, Read, Seek, SeekFrom, Write},
+    path,
+};
+
+use super::{HistoryFormat, HistoryHeader, ReadStateError};
+
+/// The bytes every compressed history starts with.
+pub const MAGIC: [u8; 4] = *b"SKDZ";
+
+/// The number of states compressed together in each block.
+pub const STATES_PER_BLOCK: usize = 32;
+
+/// The byte offset of the frame count within the header.
+pub(super) const FRAMES_OFFSET: u64 = MAGIC.len() as u64 + 1;
+
+/// The length of the header, which is followed by the first block.
+const HEADER_LEN: u64 = FRAMES_OFFSET + 8 + 4 + 4 + 4;
+
+/// The bit of the format byte which is set in verbose histories, so that histories written
+/// before it existed are read as not verbose.
+const VERBOSE_FLAG: u8 = 0x80;
+
+const LEVEL: i32 = 3;
+
+/// Returns whether or not the file at `path` is a compressed history.
+pub(super) fn is_compressed<P>(path: P) -> io::Result<bool>
+where
+    P: AsRef<path::Path>
+{
+    let mut magic = [0; MAGIC.len()];
+    let mut file = fs::File::open(path)?;
+
+    match file.read_exact(&mut magic) {
+        Ok(()) => Ok(magic == MAGIC),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+pub(super) fn write_header(
+    file: &mut impl Write,
+    format: HistoryFormat,
+    frames: u64,
+    dt: f32,
+    keyframe_interval: u32,
+    verbose: bool,
+) -> io::Result<()> {
+    file.write_all(&MAGIC)?;
+    file.write_all(&[format_byte(format) | if verbose { VERBOSE_FLAG } else { 0 }])?;
+    file.write_all(&frames.to_le_bytes())?;
+    file.write_all(&dt.to_le_bytes())?;
+    file.write_all(&keyframe_interval.to_le_bytes())?;
+    file.write_all(&(STATES_PER_BLOCK as u32).to_le_bytes())
+}
+
+/// Compresses the serialized `states` into a single block.
+pub(super) fn write_block(file: &mut impl Write, states: &[String]) -> io::Result<()> {
+    let data = zstd::encode_all(states.join("\n").as_bytes(), LEVEL)?;
+
+    file.write_all(&(data.len() as u32).to_le_bytes())?;
+    file.write_all(&(states.len() as u32).to_le_bytes())?;
+    file.write_all(&data)
+}
+
+struct Block {
+    offset: u64,
+    len: u32,
+    states: u32,
+}
+
+/// Reads the states of a compressed history, keeping the most recently used block decompressed.
+pub(super) struct BlockReader {
+    file: BufReader<fs::File>,
+    states_per_block: usize,
+    blocks: Vec<Block>,
+    cache: Option<(usize, Vec<String>)>,
+}
+
+impl BlockReader {
+    /// Reads the header of the compressed history at `path` and the location of every block.
+    pub(super) fn open<P>(path: P) -> Result<(HistoryFormat, HistoryHeader, BlockReader), ReadStateError>
+    where
+        P: AsRef<path::Path>
+    {
+        let mut file = BufReader::new(fs::File::open(path)?);
+
+        let mut magic = [0; MAGIC.len()];
+        file.read_exact(&mut magic)?;
+
+        if magic != MAGIC {
+            return Err(ReadStateError::InvalidHeader);
+        }
+
+        let [format_byte] = read_array::<1>(&mut file)?;
+        let verbose = format_byte & VERBOSE_FLAG != 0;
+        let format = match format_byte & !VERBOSE_FLAG {
+            0 => HistoryFormat::Ron,
+            1 => HistoryFormat::Json,
+            _ => return Err(ReadStateError::InvalidHeader),
+        };
+        let frames = u64::from_le_bytes(read_array(&mut file)?);
+        let dt = f32::from_le_bytes(read_array(&mut file)?);
+        let keyframe_interval = u32::from_le_bytes(read_array(&mut file)?);
+        let states_per_block = u32::from_le_bytes(read_array(&mut file)?) as usize;
+
+        // Every state is found by dividing its step by the number of states per block.
+        if states_per_block == 0 {
+            return Err(ReadStateError::InvalidHeader);
+        }
+
+        let mut blocks = Vec::new();
+        let mut offset = file.stream_position()?;
+        let file_len = file.get_ref().metadata()?.len();
+
+        loop {
+            let block_header = match read_array::<8>(&mut file) {
+                Ok(bytes) => bytes,
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err.into()),
+            };
+
+            let len = u32::from_le_bytes(block_header[..4].try_into().unwrap());
+            let states = u32::from_le_bytes(block_header[4..].try_into().unwrap());
+
+            // The last block of an interrupted history may not have been written in full.
+            if offset + 8 + len as u64 > file_len {
+                break;
+            }
+
+            blocks.push(Block { offset: offset + 8, len, states });
+
+            offset = file.seek(SeekFrom::Current(len as i64))?;
+        }
+
+        let reader = BlockReader { file, states_per_block, blocks, cache: None };
+
+        Ok((format, HistoryHeader { frames, dt, keyframe_interval, verbose }, reader))
+    }
+
+    /// Finds where the history should be cut to keep only its first `frames` states, returning
+    /// the byte offset to truncate the file at and the serialized states of the last, partially
+    /// kept block.
+    pub(super) fn truncation(&mut self, frames: usize) -> Result<(u64, Vec<String>), ReadStateError> {
+        let index = frames / self.states_per_block;
+        let kept = frames % self.states_per_block;
+
+        let end = match self.blocks.get(index) {
+            // Block offsets point past the length and state count preceding each block.
+            Some(block) => block.offset - 8,
+            None => self.blocks.last().map_or(HEADER_LEN, |block| block.offset + block.len as u64),
+        };
+
+        let block = if kept > 0 {
+            self.decompress(index)?.into_iter().take(kept).collect()
+        } else {
+            Vec::new()
+        };
+
+        Ok((end, block))
+    }
+
+    /// The number of states in the history.
+    pub(super) fn len(&self) -> usize {
+        self.blocks.iter().map(|block| block.states as usize).sum()
+    }
+
+    /// The serialized state at index `step`.
+    pub(super) fn state(&mut self, step: usize) -> Result<&str, ReadStateError> {
+        let index = step / self.states_per_block;
+
+        if self.cache.as_ref().is_none_or(|(cached, _)| *cached != index) {
+            let states = self.decompress(index)?;
+            self.cache = Some((index, states));
+        }
+
+        let (_, states) = self.cache.as_ref().unwrap();
+        Ok(&states[step % self.states_per_block])
+    }
+
+    fn decompress(&mut self, index: usize) -> Result<Vec<String>, ReadStateError> {
+        let block = &self.blocks[index];
+        let mut data = vec![0; block.len as usize];
+
+        self.file.seek(SeekFrom::Start(block.offset))?;
+        self.file.read_exact(&mut data)?;
+
+        let text = String::from_utf8(zstd::decode_all(data.as_slice())?)?;
+
+        Ok(text.split('\n').map(str::to_string).collect())
+    }
+}
+
+fn format_byte(format: HistoryFormat) -> u8 {
+    match format {
+        HistoryFormat::Ron => 0,
+        HistoryFormat::Json => 1,
+    }
+}
+
+fn read_array<const N: usize>(file: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    file.read_exact(&mut bytes)?;
+
+    Ok(bytes)
+}
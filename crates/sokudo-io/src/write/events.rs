@@ -0,0 +1,67 @@
+use std::{fs, path};
+
+use serde::{Deserialize, Serialize};
+
+use super::{ReadStateError, WriteStateError};
+
+/// A change in whether or not two colliders are touching, or their continued contact.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Event")]
+pub struct CollisionEvent {
+    /// The step during which the event occurred, counting from one so that it matches the index
+    /// of the first state in the history showing it.
+    pub step: u32,
+    /// The collider with the smaller ID.
+    pub a: u32,
+    /// The collider with the larger ID.
+    pub b: u32,
+    pub kind: CollisionEventKind,
+    /// The magnitude of the normal impulse between the colliders over the step, which is zero
+    /// once they have stopped touching.
+    pub impulse: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionEventKind {
+    /// The colliders started touching.
+    Begin,
+    /// The colliders were already touching in the previous step.
+    Stay,
+    /// The colliders stopped touching.
+    End,
+}
+
+/// Every [`CollisionEvent`] of a simulation, written next to its history.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename = "CollisionEvents")]
+pub struct CollisionEventLog {
+    pub events: Vec<CollisionEvent>,
+}
+
+impl CollisionEventLog {
+    /// Writes this [`CollisionEventLog`] to file `path`.
+    ///
+    /// Like [`Checkpoint::write`](super::checkpoint::Checkpoint::write), the log is first written
+    /// next to `path` and then moved over it.
+    pub fn write<P>(&self, path: P) -> Result<(), WriteStateError>
+    where
+        P: AsRef<path::Path>
+    {
+        let path = path.as_ref();
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+
+        fs::write(&partial, ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?)?;
+        fs::rename(&partial, path)?;
+
+        Ok(())
+    }
+
+    /// Reads a [`CollisionEventLog`] from file `path`.
+    pub fn read<P>(path: P) -> Result<CollisionEventLog, ReadStateError>
+    where
+        P: AsRef<path::Path>
+    {
+        Ok(ron::de::from_str(&fs::read_to_string(path)?)?)
+    }
+}
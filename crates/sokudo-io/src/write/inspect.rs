@@ -1,11 +1,12 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use glam::Vec3;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct InspectElements {
-    pub elements: HashMap<String, InspectFeature>,
+    /// The elements by name, ordered so that they are always written in the same order.
+    pub elements: BTreeMap<String, InspectFeature>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -15,6 +16,16 @@ pub enum InspectFeature {
         origin: Vec3,
         direction: Vec3,
     },
+    /// A contact between two colliders.
+    Contact {
+        /// The contact point on the surface of the second collider.
+        point: Vec3,
+        /// The contact normal, pointing from the second collider towards the first.
+        normal: Vec3,
+        /// The magnitude of the normal impulse the contact applied in the last substep.
+        #[serde(default)]
+        impulse: f32,
+    },
 }
 
 impl InspectElements {
@@ -29,4 +40,18 @@ impl InspectElements {
     pub fn add_ray<S: ToString>(&mut self, name: S, origin: Vec3, direction: Vec3) {
         self.elements.insert(name.to_string(), InspectFeature::Ray { origin, direction });
     }
+
+    pub fn add_contact<S: ToString>(&mut self, name: S, point: Vec3, normal: Vec3, impulse: f32) {
+        self.elements.insert(name.to_string(), InspectFeature::Contact { point, normal, impulse });
+    }
+
+    /// The largest normal impulse of the contacts, or zero if there are none.
+    pub fn max_contact_impulse(&self) -> f32 {
+        self.elements.values()
+            .filter_map(|feature| match feature {
+                InspectFeature::Contact { impulse, .. } => Some(*impulse),
+                _ => None,
+            })
+            .fold(0.0, f32::max)
+    }
 }
@@ -1,13 +1,26 @@
-use std::{fs, path};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write},
+    path,
+    str::FromStr,
+    sync::Mutex,
+};
 
 use collider::WriteCollider;
 use inspect::InspectElements;
+use transform::WriteTransform;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 pub mod collider;
 pub mod transform;
 pub mod inspect;
+pub mod compressed;
+pub mod checkpoint;
+pub mod snapshot;
+pub mod events;
+pub mod bench;
 
 #[derive(Error, Debug)]
 pub enum WriteStateError {
@@ -17,6 +30,12 @@ pub enum WriteStateError {
     /// A RON error.
     #[error(transparent)]
     Ron(#[from] ron::Error),
+    /// A JSON error.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// An existing history could not be read to continue writing it.
+    #[error(transparent)]
+    Read(#[from] ReadStateError),
 }
 
 #[derive(Error, Debug)]
@@ -27,16 +46,457 @@ pub enum ReadStateError {
     /// A RON error.
     #[error(transparent)]
     Ron(#[from] ron::de::SpannedError),
+    /// A JSON error.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// A compressed block which is not valid UTF-8.
+    #[error(transparent)]
+    Utf8(#[from] std::string::FromUtf8Error),
+    /// A compressed history with an unrecognized header.
+    #[error("invalid compressed history header")]
+    InvalidHeader,
+    /// A history which holds fewer states than are needed.
+    #[error("expected at least {expected} states in the history, but found {found}")]
+    MissingStates {
+        expected: u64,
+        found: u64,
+    },
+    /// A snapshot written in a version of the format which cannot be read.
+    #[error("snapshot version {found} is not supported, expected version {supported}")]
+    UnsupportedSnapshotVersion {
+        found: u32,
+        supported: u32,
+    },
+}
+
+/// How a simulation history is written to file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HistoryOptions {
+    /// The encoding of each state.
+    pub format: HistoryFormat,
+    /// Whether or not to compress the states with zstd, see [`compressed`].
+    pub compress: bool,
+    /// The number of states from one keyframe to the next. The states in between only store the
+    /// colliders whose transforms changed, relative to the previous state. Zero or one store
+    /// every state in full.
+    pub keyframe_interval: u32,
+    /// Whether or not each collider also records its contacts, see [`WriteCollider::contacts`].
+    pub verbose: bool,
+}
+
+/// The encoding used to write a simulation history to file.
+///
+/// Either way, the file starts with a header line recording the number of frames and the time
+/// step, followed by one state per line so that states can be read individually.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HistoryFormat {
+    /// A RON `History` struct whose `states` contain every state.
+    #[default]
+    Ron,
+    /// A JSON object whose `states` array contains every state.
+    Json,
+}
+
+impl HistoryFormat {
+    /// Detects the format of a history file from its first line.
+    fn detect(header: &str) -> HistoryFormat {
+        if header.trim_start().starts_with('{') {
+            HistoryFormat::Json
+        } else {
+            HistoryFormat::Ron
+        }
+    }
+
+    /// The text preceding the frame count in the header.
+    fn header_prefix(&self) -> &'static str {
+        match self {
+            HistoryFormat::Ron => "History(frames: ",
+            HistoryFormat::Json => "{\"frames\": ",
+        }
+    }
+
+    /// The text closing the document after the last state.
+    fn footer(&self) -> &'static str {
+        match self {
+            HistoryFormat::Ron => "])",
+            HistoryFormat::Json => "]}",
+        }
+    }
+
+    fn header(&self, frames: u64, dt: f32, keyframe_interval: u32, verbose: bool) -> Result<String, WriteStateError> {
+        // The frame count is padded so it can be overwritten in place once every frame has been
+        // written.
+        Ok(match self {
+            HistoryFormat::Ron => format!(
+                "{}{frames:<20}, dt: {}, keyframe_interval: {keyframe_interval}, verbose: {verbose}, states: [",
+                self.header_prefix(),
+                ron::to_string(&dt)?,
+            ),
+            HistoryFormat::Json => format!(
+                "{}{frames:<20}, \"dt\": {}, \"keyframe_interval\": {keyframe_interval}, \"verbose\": {verbose}, \"states\": [",
+                self.header_prefix(),
+                serde_json::to_string(&dt)?,
+            ),
+        })
+    }
+
+    fn serialize_state(&self, state: &WriteWorldState) -> Result<String, WriteStateError> {
+        Ok(match self {
+            HistoryFormat::Ron => ron::to_string(state)?,
+            HistoryFormat::Json => serde_json::to_string(state)?,
+        })
+    }
+
+    fn deserialize_header(&self, header: &str) -> Result<HistoryHeader, ReadStateError> {
+        let document = format!("{}{}", header.trim_end(), self.footer());
+
+        Ok(match self {
+            HistoryFormat::Ron => ron::de::from_str(&document)?,
+            HistoryFormat::Json => serde_json::from_str(&document)?,
+        })
+    }
+
+    fn deserialize_state(&self, line: &str) -> Result<WriteWorldState, ReadStateError> {
+        let line = line.trim_end().trim_end_matches(',');
+
+        Ok(match self {
+            HistoryFormat::Ron => ron::de::from_str(line)?,
+            HistoryFormat::Json => serde_json::from_str(line)?,
+        })
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Error, Debug)]
+#[error("unknown history format `{0}`, expected `ron` or `json`")]
+pub struct UnknownHistoryFormat(pub String);
+
+impl FromStr for HistoryFormat {
+    type Err = UnknownHistoryFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ron" => Ok(HistoryFormat::Ron),
+            "json" => Ok(HistoryFormat::Json),
+            _ => Err(UnknownHistoryFormat(s.to_string())),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct WriteWorldState {
+    /// The number of steps simulated before this state.
+    #[serde(default)]
+    pub step: u32,
+    /// The time step between this state and the next.
+    #[serde(default)]
+    pub dt: f32,
+    /// Whether or not this state only stores the colliders whose transforms or velocities changed
+    /// since the previous state, with their translations and rotations relative to it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub delta: bool,
     pub colliders: Vec<WriteCollider>,
+    /// The IDs of the colliders of the previous state which no longer exist in this one. Only
+    /// recorded in delta states, since full states simply omit them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<u32>,
     pub inspector: InspectElements,
 }
 
-#[derive(Serialize, Default)]
+impl WriteWorldState {
+    /// The changes from the `previous` state to this one.
+    ///
+    /// Colliders which did not exist in the `previous` state are stored relative to the
+    /// identity transform, and those which no longer exist are listed by their IDs.
+    pub fn delta_from(&self, previous: &WriteWorldState) -> WriteWorldState {
+        let previous_colliders: HashMap<u32, &WriteCollider> = previous.colliders.iter()
+            .map(|collider| (collider.id, collider))
+            .collect();
+
+        let colliders = self.colliders.iter()
+            .filter_map(|collider| {
+                let transform = &collider.transform;
+                let Some(previous) = previous_colliders.get(&collider.id) else {
+                    return Some(collider.clone());
+                };
+
+                if transform.translate == previous.transform.translate
+                    && transform.rotate == previous.transform.rotate
+                    && transform.scale == previous.transform.scale
+                    && collider.velocity == previous.velocity
+                    && collider.angular_velocity == previous.angular_velocity
+                    && collider.contacts == previous.contacts
+                {
+                    return None;
+                }
+
+                Some(WriteCollider {
+                    id: collider.id,
+                    transform: WriteTransform {
+                        translate: transform.translate - previous.transform.translate,
+                        rotate: transform.rotate * previous.transform.rotate.inverse(),
+                        scale: transform.scale,
+                    },
+                    velocity: collider.velocity,
+                    angular_velocity: collider.angular_velocity,
+                    contacts: collider.contacts.clone(),
+                })
+            })
+            .collect();
+
+        let ids: HashSet<u32> = self.colliders.iter().map(|collider| collider.id).collect();
+        let removed = previous.colliders.iter()
+            .map(|collider| collider.id)
+            .filter(|id| !ids.contains(id))
+            .collect();
+
+        WriteWorldState {
+            step: self.step,
+            dt: self.dt,
+            delta: true,
+            colliders,
+            removed,
+            inspector: self.inspector.clone(),
+        }
+    }
+
+    /// Applies the changes stored in the `delta` state to this one, turning it into the state
+    /// the delta was computed from.
+    pub fn apply_delta(&mut self, delta: &WriteWorldState) {
+        if !delta.removed.is_empty() {
+            self.colliders.retain(|collider| !delta.removed.contains(&collider.id));
+        }
+
+        let indices: HashMap<u32, usize> = self.colliders.iter()
+            .enumerate()
+            .map(|(index, collider)| (collider.id, index))
+            .collect();
+
+        for collider in delta.colliders.iter() {
+            let Some(&index) = indices.get(&collider.id) else {
+                self.colliders.push(collider.clone());
+                continue;
+            };
+
+            let current = &mut self.colliders[index];
+            current.transform.translate += collider.transform.translate;
+            current.transform.rotate = (collider.transform.rotate * current.transform.rotate).normalize();
+            current.transform.scale = collider.transform.scale;
+            current.velocity = collider.velocity;
+            current.angular_velocity = collider.angular_velocity;
+            current.contacts = collider.contacts.clone();
+        }
+
+        self.step = delta.step;
+        self.dt = delta.dt;
+        self.inspector = delta.inspector.clone();
+    }
+}
+
+/// The header of a history file.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 #[serde(rename = "History")]
+pub struct HistoryHeader {
+    /// The number of frames in the history, which is zero if it was never finished.
+    pub frames: u64,
+    /// The time step between frames.
+    pub dt: f32,
+    /// The number of states from one keyframe to the next.
+    #[serde(default = "HistoryHeader::default_keyframe_interval")]
+    pub keyframe_interval: u32,
+    /// Whether or not the colliders record their contacts, which is never the case for histories
+    /// written before verbose histories existed.
+    #[serde(default)]
+    pub verbose: bool,
+}
+
+impl HistoryHeader {
+    const fn default_keyframe_interval() -> u32 {
+        1
+    }
+
+    /// The index of the last keyframe at or before `step`.
+    fn keyframe(&self, step: usize) -> usize {
+        let interval = self.keyframe_interval.max(1) as usize;
+        step - step % interval
+    }
+}
+
+/// Writes a history to file one state at a time, so that it never has to be held in memory.
+pub struct HistoryWriter {
+    file: BufWriter<fs::File>,
+    options: HistoryOptions,
+    frames: u64,
+    /// The serialized states waiting to be compressed, if the history is compressed.
+    block: Vec<String>,
+    /// The most recent state, as it will be reconstructed when read.
+    previous: Option<WriteWorldState>,
+}
+
+impl HistoryWriter {
+    /// The number of states written between each flush to disk.
+    pub const FLUSH_INTERVAL: u64 = 64;
+
+    /// Creates the file `path` and writes the header of a history with time step `dt` to it.
+    pub fn create<P>(path: P, dt: f32, options: HistoryOptions) -> Result<HistoryWriter, WriteStateError>
+    where
+        P: AsRef<path::Path>
+    {
+        let mut file = BufWriter::new(fs::File::create(path)?);
+
+        let keyframe_interval = options.keyframe_interval.max(1);
+
+        if options.compress {
+            compressed::write_header(&mut file, options.format, 0, dt, keyframe_interval, options.verbose)?;
+        } else {
+            write!(file, "{}", options.format.header(0, dt, keyframe_interval, options.verbose)?)?;
+        }
+
+        Ok(HistoryWriter { file, options, frames: 0, block: Vec::new(), previous: None })
+    }
+
+    /// Reopens the history at `path` to append states to it, discarding every state after the
+    /// first `frames`.
+    ///
+    /// The format, compression, keyframe interval and verbosity are those of the existing
+    /// history. Histories which were never finished, such as those of an interrupted simulation,
+    /// can be resumed as long as their first `frames` states were written in full.
+    pub fn resume<P>(path: P, frames: u64) -> Result<HistoryWriter, WriteStateError>
+    where
+        P: AsRef<path::Path>
+    {
+        let path = path.as_ref();
+        let history = ReadWorldStateHistory::open(path)?;
+        let found = history.len() as u64;
+
+        if frames == 0 || found < frames {
+            return Err(ReadStateError::MissingStates { expected: frames.max(1), found }.into());
+        }
+
+        let previous = Some(history.try_get(frames as usize - 1)?);
+        let header = history.header();
+
+        let (format, compress, end, block) = if compressed::is_compressed(path)? {
+            let (format, _, mut reader) = compressed::BlockReader::open(path)?;
+            let (end, block) = reader.truncation(frames as usize)?;
+
+            (format, true, end, block)
+        } else {
+            // Every state is kept up to the end of its contents, so the separator before the
+            // next state is written again by `push`.
+            let mut ends = Vec::new();
+            let (_, format) = ReadWorldStateHistory::scan(path, |offset, line, _| {
+                ends.push(offset + line.trim_end().trim_end_matches(',').len() as u64);
+                Ok(())
+            })?;
+
+            (format, false, ends[frames as usize - 1], Vec::new())
+        };
+
+        let mut file = fs::OpenOptions::new().write(true).open(path)?;
+        file.set_len(end)?;
+        file.seek(SeekFrom::End(0))?;
+
+        Ok(HistoryWriter {
+            file: BufWriter::new(file),
+            options: HistoryOptions { format, compress, keyframe_interval: header.keyframe_interval, verbose: header.verbose },
+            frames,
+            block,
+            previous,
+        })
+    }
+
+    /// Appends a `state` to the history.
+    pub fn push(&mut self, state: &WriteWorldState) -> Result<(), WriteStateError> {
+        let keyframe = self.frames.is_multiple_of(self.options.keyframe_interval.max(1) as u64);
+
+        let state = match self.previous.as_mut() {
+            Some(previous) if !keyframe => {
+                // The delta is taken from the reconstructed state rather than the true previous
+                // one, so that rounding errors do not accumulate between keyframes.
+                let delta = state.delta_from(previous);
+                previous.apply_delta(&delta);
+
+                self.options.format.serialize_state(&delta)?
+            },
+            _ => {
+                self.previous = Some(state.clone());
+                self.options.format.serialize_state(state)?
+            },
+        };
+
+        self.frames += 1;
+
+        if self.options.compress {
+            self.block.push(state);
+
+            if self.block.len() == compressed::STATES_PER_BLOCK {
+                compressed::write_block(&mut self.file, &self.block)?;
+                self.block.clear();
+                self.file.flush()?;
+            }
+
+            return Ok(());
+        }
+
+        let separator = if self.frames == 1 { "" } else { "," };
+        write!(self.file, "{separator}\n{state}")?;
+
+        if self.frames.is_multiple_of(Self::FLUSH_INTERVAL) {
+            self.file.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Flushes the states written so far to disk, returning how many of them are now in the file.
+    ///
+    /// This is fewer than [`HistoryWriter::len`] when the history is compressed and the states
+    /// since the last full block are still waiting to be compressed.
+    pub fn flush(&mut self) -> Result<u64, WriteStateError> {
+        self.file.flush()?;
+
+        Ok(self.frames - self.block.len() as u64)
+    }
+
+    /// Whether or not the states should record the contacts of their colliders.
+    pub fn is_verbose(&self) -> bool {
+        self.options.verbose
+    }
+
+    /// The number of states written so far.
+    pub fn len(&self) -> u64 {
+        self.frames
+    }
+
+    /// Returns whether or not no states have been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.frames == 0
+    }
+
+    /// Closes the history, recording the final number of frames in its header.
+    pub fn finish(mut self) -> Result<(), WriteStateError> {
+        if self.options.compress {
+            if !self.block.is_empty() {
+                compressed::write_block(&mut self.file, &self.block)?;
+            }
+
+            self.file.seek(SeekFrom::Start(compressed::FRAMES_OFFSET))?;
+            self.file.write_all(&self.frames.to_le_bytes())?;
+        } else {
+            write!(self.file, "\n{}", self.options.format.footer())?;
+
+            self.file.seek(SeekFrom::Start(self.options.format.header_prefix().len() as u64))?;
+            write!(self.file, "{:<20}", self.frames)?;
+        }
+
+        self.file.flush()?;
+
+        Ok(())
+    }
+}
+
+/// A history which is held in memory until it is written.
+#[derive(Default)]
 pub struct WriteWorldStateHistory {
     states: Vec<WriteWorldState>,
 }
@@ -47,54 +507,234 @@ impl WriteWorldStateHistory {
         self.states.push(state)
     }
 
-    /// Write this [`WriteWorldStateHistory`] to file `path`.
-    // TODO: Make async or Box::pin(async move ||)
-    pub fn write<P>(&self, path: P) -> Result<(), WriteStateError>
+    /// Write this [`WriteWorldStateHistory`] to file `path` with the given `options`.
+    pub fn write<P>(&self, path: P, options: HistoryOptions) -> Result<(), WriteStateError>
     where
         P: AsRef<path::Path>
     {
-        let file = fs::File::create(path)?;
-        ron::ser::to_writer(file, self)?;
+        let dt = self.states.first().map_or(0.0, |state| state.dt);
+        let mut writer = HistoryWriter::create(path, dt, options)?;
 
-        Ok(())
+        for state in self.states.iter() {
+            writer.push(state)?;
+        }
+
+        writer.finish()
     }
 }
 
-#[derive(Deserialize)]
-#[serde(rename = "History")]
+enum HistoryStates {
+    /// Every state, read into memory.
+    Loaded(Vec<WriteWorldState>),
+    /// The byte offset of every state within an uncompressed file, which are read on demand.
+    Indexed {
+        format: HistoryFormat,
+        file: Mutex<BufReader<fs::File>>,
+        offsets: Vec<u64>,
+    },
+    /// The blocks of a compressed file, which are decompressed on demand.
+    Compressed {
+        format: HistoryFormat,
+        reader: Mutex<compressed::BlockReader>,
+        len: usize,
+    },
+}
+
 pub struct ReadWorldStateHistory {
-    states: Vec<WriteWorldState>,
+    header: HistoryHeader,
+    states: HistoryStates,
+    /// The most recently reconstructed state, from which the next one can be found without
+    /// starting over from its keyframe.
+    cache: Mutex<Option<(usize, WriteWorldState)>>,
 }
 
 impl ReadWorldStateHistory {
-    /// Pops a state from this [`ReadWorldStateHistory`], returning it.
-    pub fn pop(&mut self) -> WriteWorldState {
-        self.states.remove(0)
+    /// Gets a state from this [`ReadWorldStateHistory`], given its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is out of bounds or the state cannot be read.
+    pub fn get(&self, step: usize) -> WriteWorldState {
+        self.try_get(step).unwrap()
     }
 
-    /// Gets a state from this [`ReadWorldStateHistory`], given its index.
-    pub fn get(&self, step: usize) -> &WriteWorldState {
-        self.states.get(step).as_ref().unwrap()
+    /// Gets a state from this [`ReadWorldStateHistory`], given its index, reading it from file if
+    /// the history was opened with [`ReadWorldStateHistory::open`].
+    ///
+    /// Delta-encoded states are reconstructed from the nearest keyframe before them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is out of bounds.
+    pub fn try_get(&self, step: usize) -> Result<WriteWorldState, ReadStateError> {
+        if let HistoryStates::Loaded(states) = &self.states {
+            return Ok(states[step].clone());
+        }
+
+        let keyframe = self.header.keyframe(step);
+        let mut cache = self.cache.lock().unwrap();
+
+        let (mut current, mut state) = match cache.take() {
+            Some((cached, state)) if (keyframe..=step).contains(&cached) => (cached, state),
+            _ => (keyframe, self.stored(keyframe)?),
+        };
+
+        while current < step {
+            current += 1;
+            state.apply_delta(&self.stored(current)?);
+        }
+
+        *cache = Some((step, state.clone()));
+
+        Ok(state)
+    }
+
+    /// The state at index `step` as it is stored in file, which may be delta-encoded.
+    fn stored(&self, step: usize) -> Result<WriteWorldState, ReadStateError> {
+        match &self.states {
+            HistoryStates::Loaded(states) => Ok(states[step].clone()),
+            HistoryStates::Indexed { format, file, offsets } => {
+                let mut file = file.lock().unwrap();
+                let mut line = String::new();
+
+                file.seek(SeekFrom::Start(offsets[step]))?;
+                file.read_line(&mut line)?;
+
+                format.deserialize_state(&line)
+            },
+            HistoryStates::Compressed { format, reader, len } => {
+                assert!(step < *len, "state {step} is out of bounds for a history of {len} states");
+                format.deserialize_state(reader.lock().unwrap().state(step)?)
+            },
+        }
     }
 
     /// The number of states in this [`ReadWorldStateHistory`].
     pub fn len(&self) -> usize {
-        self.states.len()
+        match &self.states {
+            HistoryStates::Loaded(states) => states.len(),
+            HistoryStates::Indexed { offsets, .. } => offsets.len(),
+            HistoryStates::Compressed { len, .. } => *len,
+        }
     }
 
     /// Returns whether or not this [`ReadWorldStateHistory`] is empty.
     pub fn is_empty(&self) -> bool {
-        self.states.is_empty()
+        self.len() == 0
+    }
+
+    /// The header of this [`ReadWorldStateHistory`].
+    pub fn header(&self) -> HistoryHeader {
+        self.header
     }
 
-    /// Read the given `path` into a [`ReadWorldStateHistory`].
+    /// The time step between states in this [`ReadWorldStateHistory`].
+    pub fn dt(&self) -> f32 {
+        self.header.dt
+    }
+
+    /// Read every state in the given `path` into a [`ReadWorldStateHistory`].
+    ///
+    /// The format and compression are detected from the contents of the file.
     pub fn read<P>(path: P) -> Result<ReadWorldStateHistory, ReadStateError>
     where
         P: AsRef<path::Path>
     {
-        let file = fs::File::open(path)?;
-        let de: ReadWorldStateHistory = ron::de::from_reader(file)?;
+        if compressed::is_compressed(path.as_ref())? {
+            let history = Self::open(path)?;
+            let states = (0..history.len()).map(|step| history.try_get(step)).collect::<Result<_, _>>()?;
+
+            return Ok(ReadWorldStateHistory::loaded(history.header, states));
+        }
+
+        let mut states: Vec<WriteWorldState> = Vec::new();
+        let (header, _) = Self::scan(path, |_, line, format| {
+            let state = format.deserialize_state(line)?;
+
+            match states.last() {
+                Some(previous) if state.delta => {
+                    let mut previous = previous.clone();
+                    previous.apply_delta(&state);
+                    states.push(previous);
+                },
+                _ => states.push(state),
+            }
+
+            Ok(())
+        })?;
+
+        Ok(ReadWorldStateHistory::loaded(header, states))
+    }
+
+    /// Open the given `path` as a [`ReadWorldStateHistory`] which only reads states from file
+    /// when they are requested.
+    ///
+    /// The format and compression are detected from the contents of the file.
+    pub fn open<P>(path: P) -> Result<ReadWorldStateHistory, ReadStateError>
+    where
+        P: AsRef<path::Path>
+    {
+        if compressed::is_compressed(path.as_ref())? {
+            let (format, header, reader) = compressed::BlockReader::open(path)?;
+            let len = reader.len();
+
+            return Ok(ReadWorldStateHistory {
+                header,
+                states: HistoryStates::Compressed { format, reader: Mutex::new(reader), len },
+                cache: Mutex::new(None),
+            });
+        }
+
+        let mut offsets = Vec::new();
+        let (header, format) = Self::scan(path.as_ref(), |offset, _, _| {
+            offsets.push(offset);
+            Ok(())
+        })?;
+
+        let file = Mutex::new(BufReader::new(fs::File::open(path)?));
+
+        Ok(ReadWorldStateHistory {
+            header,
+            states: HistoryStates::Indexed { format, file, offsets },
+            cache: Mutex::new(None),
+        })
+    }
+
+    fn loaded(header: HistoryHeader, states: Vec<WriteWorldState>) -> ReadWorldStateHistory {
+        ReadWorldStateHistory { header, states: HistoryStates::Loaded(states), cache: Mutex::new(None) }
+    }
+
+    /// Reads the header of the history at `path`, then calls `on_state` with the byte offset and
+    /// contents of every line holding a state.
+    fn scan<P, F>(path: P, mut on_state: F) -> Result<(HistoryHeader, HistoryFormat), ReadStateError>
+    where
+        P: AsRef<path::Path>,
+        F: FnMut(u64, &str, HistoryFormat) -> Result<(), ReadStateError>,
+    {
+        let mut file = BufReader::new(fs::File::open(path)?);
+        let mut line = String::new();
+
+        let mut offset = file.read_line(&mut line)? as u64;
+        let format = HistoryFormat::detect(&line);
+        let header = format.deserialize_header(&line)?;
+
+        loop {
+            line.clear();
+            let read = file.read_line(&mut line)? as u64;
+
+            if read == 0 {
+                break;
+            }
+
+            let trimmed = line.trim();
+
+            if !trimmed.is_empty() && trimmed != format.footer() {
+                on_state(offset, &line, format)?;
+            }
+
+            offset += read;
+        }
 
-        Ok(de)
+        Ok((header, format))
     }
 }
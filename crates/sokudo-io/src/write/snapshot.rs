@@ -0,0 +1,250 @@
+use std::{fs, path};
+
+use glam::{BVec3, Mat3, Quat, UVec3, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::read::{broadphase::ParsedBroadphase, constraint::ParsedConstraint, material::ParsedMaterialCombine, script::ScriptEntry, water::ParsedWater};
+
+use super::{checkpoint::CheckpointManifold, transform::WriteTransform, ReadStateError, WriteStateError};
+
+/// The version of the snapshot format written by [`Snapshot::write`]. It is increased whenever the
+/// format changes in a way older snapshots cannot be read in.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A world along with its full state part way through a simulation, stored in a single file from
+/// which the simulation can be continued without the world file or any of the files it refers
+/// to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Snapshot")]
+pub struct Snapshot {
+    /// The version of the format this snapshot was written in.
+    pub version: u32,
+
+    pub steps: u32,
+    pub dt: f32,
+    /// The number of steps simulated before this snapshot.
+    pub step: u32,
+    pub substeps: u32,
+    pub iterations: u32,
+    pub friction_combine: ParsedMaterialCombine,
+    pub restitution_combine: ParsedMaterialCombine,
+    pub gravity: Vec3,
+    pub broadphase: ParsedBroadphase,
+    pub water: Option<ParsedWater>,
+    pub sleep_linear_velocity: f32,
+    pub sleep_angular_velocity: f32,
+    pub sleep_window: u32,
+    pub ccd_threshold: f32,
+    pub restitution_velocity_threshold: f32,
+    pub warm_start: bool,
+    pub max_vertex_resolution: u32,
+    pub max_correction_per_step: f32,
+
+    /// Every collider of the world, including removed ones, ordered by their IDs.
+    pub colliders: Vec<SnapshotCollider>,
+    pub emitters: Vec<SnapshotEmitter>,
+    pub script: Vec<ScriptEntry>,
+    /// The constraints solved on positions.
+    pub constraints: Vec<ParsedConstraint>,
+    /// The constraints solved on velocities, such as the damping of springs and the motors of
+    /// hinges.
+    pub velocity_constraints: Vec<ParsedConstraint>,
+
+    /// The pairs of colliders which were touching in the last step.
+    pub touching: Vec<[u32; 2]>,
+    /// The contact manifolds generated by each pair of colliders in the last substep.
+    pub manifolds: Vec<CheckpointManifold>,
+}
+
+/// A collider of a [`Snapshot`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Collider")]
+pub struct SnapshotCollider {
+    pub id: u32,
+    pub body: SnapshotColliderBody,
+    pub motion: SnapshotColliderMotion,
+    pub is_sensor: bool,
+    pub collision_layer: u32,
+    pub collision_mask: u32,
+    pub removed: bool,
+
+    pub position: Vec3,
+    pub previous_position: Vec3,
+    pub velocity: Vec3,
+    pub previous_velocity: Vec3,
+
+    pub asleep: bool,
+    pub sleep_frames: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Motion")]
+pub enum SnapshotColliderMotion {
+    Dynamic,
+    Locked,
+    /// The `(time, transform)` keyframes of the path of the collider.
+    Kinematic(Vec<(f32, WriteTransform)>),
+}
+
+// Snapshots only exist while a world is being saved or loaded, so boxing would gain nothing.
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Body")]
+pub enum SnapshotColliderBody {
+    Particle(SnapshotParticle),
+    RigidBody(SnapshotRigidBody),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Particle")]
+pub struct SnapshotParticle {
+    pub mass: f32,
+    pub radius: f32,
+    pub static_friction: f32,
+    pub dynamic_friction: f32,
+    pub restitution: f32,
+}
+
+/// A rigid body of a [`Snapshot`], along with the vertices and inertia tensor computed for it
+/// when it was added to the world.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "RigidBody")]
+pub struct SnapshotRigidBody {
+    pub shape: SnapshotShape,
+    pub scale: Vec3,
+    pub mass: f32,
+    pub center_of_mass: Vec3,
+    pub vertex_resolution: UVec3,
+    pub vertices: Vec<Vec3>,
+    pub static_friction: f32,
+    pub dynamic_friction: f32,
+    pub restitution: f32,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub drag_coefficient: f32,
+    pub rolling_friction: f32,
+    pub spinning_friction: f32,
+    pub max_linear_velocity: f32,
+    pub max_angular_velocity: f32,
+    pub lock_translation: BVec3,
+    pub lock_rotation: BVec3,
+
+    pub force: Vec3,
+    pub torque: Vec3,
+    /// The inverse of the inertia tensor, in local coordinates.
+    pub inverse_inertia_tensor: Mat3,
+
+    pub rotation: Quat,
+    pub previous_rotation: Quat,
+    pub angular_velocity: Vec3,
+    pub previous_angular_velocity: Vec3,
+}
+
+/// The shape of a [`SnapshotRigidBody`], which holds the triangles and samples of meshes and
+/// signed distance fields themselves rather than the files they were loaded from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Shape")]
+pub enum SnapshotShape {
+    Cuboid,
+    Cylinder {
+        radius: f32,
+        half_height: f32,
+    },
+    Cone {
+        radius: f32,
+        height: f32,
+    },
+    Torus {
+        major_radius: f32,
+        minor_radius: f32,
+    },
+    TriangleMesh {
+        vertices: Vec<Vec3>,
+        indices: Vec<[u32; 3]>,
+    },
+    /// A convex hull, along with the planes of its faces so that it is not rebuilt on load.
+    ConvexHull {
+        vertices: Vec<Vec3>,
+        indices: Vec<[u32; 3]>,
+        planes: Vec<(Vec3, f32)>,
+    },
+    Sdf {
+        size: UVec3,
+        min: Vec3,
+        max: Vec3,
+        values: Vec<f32>,
+    },
+    HalfSpace {
+        normal: Vec3,
+    },
+    Compound {
+        parts: Vec<(WriteTransform, SnapshotShape)>,
+    },
+}
+
+/// An emitter of a [`Snapshot`], along with the particles it has spawned which still exist.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Emitter")]
+pub struct SnapshotEmitter {
+    pub position: Vec3,
+    pub rate: f32,
+    pub velocity: Vec3,
+    pub velocity_spread: Vec3,
+    pub lifetime: f32,
+    pub radius: f32,
+    pub mass: f32,
+    pub max_particles: u32,
+
+    /// The state of the generator the initial velocities are drawn from.
+    pub random: u64,
+    pub accumulated: f32,
+    /// The IDs of the particles which still exist, oldest first, along with the time in seconds
+    /// at which each of them is removed.
+    pub particles: Vec<(u32, f32)>,
+}
+
+/// Just the version of a [`Snapshot`].
+#[derive(Deserialize)]
+#[serde(rename = "Snapshot")]
+struct SnapshotVersion {
+    version: u32,
+}
+
+impl Snapshot {
+    /// Writes this [`Snapshot`] to file `path`.
+    ///
+    /// Like [`Checkpoint::write`](super::checkpoint::Checkpoint::write), the snapshot is first
+    /// written next to `path` and then moved over it.
+    pub fn write<P>(&self, path: P) -> Result<(), WriteStateError>
+    where
+        P: AsRef<path::Path>
+    {
+        let path = path.as_ref();
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+
+        fs::write(&partial, ron::to_string(self)?)?;
+        fs::rename(&partial, path)?;
+
+        Ok(())
+    }
+
+    /// Reads a [`Snapshot`] from file `path`, rejecting snapshots of any version other than
+    /// [`SNAPSHOT_VERSION`].
+    pub fn read<P>(path: P) -> Result<Snapshot, ReadStateError>
+    where
+        P: AsRef<path::Path>
+    {
+        let text = fs::read_to_string(path)?;
+
+        // The version is read on its own first, since the rest of a snapshot of another version
+        // may not be readable at all.
+        let SnapshotVersion { version } = ron::de::from_str(&text)?;
+
+        if version != SNAPSHOT_VERSION {
+            return Err(ReadStateError::UnsupportedSnapshotVersion { found: version, supported: SNAPSHOT_VERSION });
+        }
+
+        Ok(ron::de::from_str(&text)?)
+    }
+}
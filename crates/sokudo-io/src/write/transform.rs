@@ -1,14 +1,20 @@
 use glam::{Quat, Vec3};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct WriteTransform {
     pub translate: Vec3,
     pub rotate: Quat,
+    #[serde(default = "WriteTransform::default_scale")]
+    pub scale: Vec3,
 }
 
 impl WriteTransform {
     pub fn from_translate(translate: Vec3) -> WriteTransform {
-        WriteTransform { translate, rotate: Quat::IDENTITY }
+        WriteTransform { translate, rotate: Quat::IDENTITY, scale: Vec3::ONE }
+    }
+
+    const fn default_scale() -> Vec3 {
+        Vec3::ONE
     }
 }
@@ -0,0 +1,291 @@
+use std::path::PathBuf;
+
+use glam::{Quat, Vec3};
+use sokudo_io::write::{
+    collider::{WriteCollider, WriteContact}, compressed::MAGIC, inspect::InspectElements, transform::WriteTransform,
+    HistoryFormat, HistoryHeader, HistoryOptions, HistoryWriter, ReadStateError, ReadWorldStateHistory, WriteWorldState,
+    WriteWorldStateHistory,
+};
+
+fn frame(step: u32) -> WriteWorldState {
+    WriteWorldState {
+        step,
+        dt: 0.01,
+        delta: false,
+        colliders: vec![WriteCollider {
+            id: 0,
+            transform: WriteTransform::from_translate(Vec3::new(0.0, step as f32, 0.0)),
+            velocity: Vec3::Y,
+            angular_velocity: Vec3::ZERO,
+            contacts: Vec::new(),
+        }],
+        removed: Vec::new(),
+        inspector: InspectElements::default(),
+    }
+}
+
+fn history() -> WriteWorldStateHistory {
+    let mut history = WriteWorldStateHistory::default();
+
+    for step in 0..3 {
+        history.push(frame(step));
+    }
+
+    history
+}
+
+fn round_trip(format: HistoryFormat, name: &str) -> ReadWorldStateHistory {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    history().write(&path, HistoryOptions { format, ..Default::default() }).unwrap();
+
+    ReadWorldStateHistory::read(path).unwrap()
+}
+
+#[test]
+fn json_history_round_trips() {
+    let history = round_trip(HistoryFormat::Json, "history.json");
+
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.get(2).step, 2);
+    assert_eq!(history.get(2).dt, 0.01);
+    assert_eq!(history.get(2).colliders[0].transform.translate, Vec3::new(0.0, 2.0, 0.0));
+}
+
+#[test]
+fn json_history_is_a_json_document() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("history-schema.json");
+    history().write(&path, HistoryOptions { format: HistoryFormat::Json, ..Default::default() }).unwrap();
+
+    let contents = std::fs::read_to_string(path).unwrap();
+    let frame = contents.lines().nth(1).unwrap();
+
+    assert!(frame.starts_with("{\"step\":0,\"dt\":0.01,\"colliders\":[{\"id\":0,\"transform\":"));
+    assert!(contents.contains("\"translate\":[0.0,1.0,0.0]"));
+    assert!(contents.contains("\"rotate\":[0.0,0.0,0.0,1.0]"));
+    assert!(contents.contains("\"scale\":[1.0,1.0,1.0]"));
+}
+
+#[test]
+fn streamed_history_reads_states_on_demand() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("history-streamed.ron");
+    let mut writer = HistoryWriter::create(&path, 0.01, HistoryOptions::default()).unwrap();
+
+    for step in 0..200 {
+        writer.push(&frame(step)).unwrap();
+    }
+
+    writer.finish().unwrap();
+
+    let history = ReadWorldStateHistory::open(&path).unwrap();
+
+    assert_eq!(history.header(), HistoryHeader { frames: 200, dt: 0.01, keyframe_interval: 1, verbose: false });
+    assert_eq!(history.len(), 200);
+    assert_eq!(history.get(150).step, 150);
+    assert_eq!(history.get(3).colliders[0].transform.translate, Vec3::new(0.0, 3.0, 0.0));
+}
+
+#[test]
+fn ron_history_round_trips() {
+    let history = round_trip(HistoryFormat::Ron, "history.ron");
+
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.get(1).colliders[0].transform.translate, Vec3::new(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn compressed_history_reads_states_on_demand() {
+    let options = |compress| HistoryOptions { format: HistoryFormat::Json, compress, ..Default::default() };
+    let compressed_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("history-compressed.json.zst");
+    let plain_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("history-uncompressed.json");
+
+    for (path, compress) in [(&compressed_path, true), (&plain_path, false)] {
+        let mut writer = HistoryWriter::create(path, 0.01, options(compress)).unwrap();
+
+        for step in 0..100 {
+            writer.push(&frame(step)).unwrap();
+        }
+
+        writer.finish().unwrap();
+    }
+
+    let compressed = std::fs::metadata(&compressed_path).unwrap().len();
+    let plain = std::fs::metadata(&plain_path).unwrap().len();
+    assert!(compressed < plain / 4, "compressed history was {compressed} bytes, uncompressed was {plain}");
+
+    let history = ReadWorldStateHistory::open(&compressed_path).unwrap();
+
+    assert_eq!(history.header(), HistoryHeader { frames: 100, dt: 0.01, keyframe_interval: 1, verbose: false });
+    assert_eq!(history.len(), 100);
+    assert_eq!(history.get(99).step, 99);
+    assert_eq!(history.get(40).colliders[0].transform.translate, Vec3::new(0.0, 40.0, 0.0));
+    assert_eq!(history.get(3).step, 3);
+
+    assert_eq!(ReadWorldStateHistory::read(&compressed_path).unwrap().get(70).step, 70);
+}
+
+#[test]
+fn compressed_history_without_states_per_block_is_rejected() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("history-zero-states-per-block.json.zst");
+    let options = HistoryOptions { format: HistoryFormat::Json, compress: true, ..Default::default() };
+
+    let mut writer = HistoryWriter::create(&path, 0.01, options).unwrap();
+    writer.push(&frame(0)).unwrap();
+    writer.finish().unwrap();
+
+    // The number of states per block follows the magic, the format, the number of frames, the time
+    // step and the keyframe interval.
+    let mut bytes = std::fs::read(&path).unwrap();
+    let offset = MAGIC.len() + 1 + 8 + 4 + 4;
+    bytes[offset..offset + 4].copy_from_slice(&0u32.to_le_bytes());
+    std::fs::write(&path, bytes).unwrap();
+
+    assert!(matches!(ReadWorldStateHistory::open(&path), Err(ReadStateError::InvalidHeader)));
+}
+
+#[test]
+fn verbose_history_records_contacts() {
+    // The contacts change every other step, so delta-encoded states only carry some of them.
+    let contact_frame = |step: u32| {
+        let mut state = frame(step);
+        state.colliders[0].contacts = vec![WriteContact { other: 1, impulse: (step / 2) as f32 }];
+        state
+    };
+
+    for (format, compress, keyframe_interval) in [(HistoryFormat::Ron, false, 1), (HistoryFormat::Json, false, 4), (HistoryFormat::Ron, true, 4)] {
+        let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("verbose-history-{format:?}-{compress}"));
+        let options = HistoryOptions { format, compress, keyframe_interval, verbose: true };
+        let mut writer = HistoryWriter::create(&path, 0.01, options).unwrap();
+
+        for step in 0..10 {
+            writer.push(&contact_frame(step)).unwrap();
+        }
+
+        writer.finish().unwrap();
+
+        let history = ReadWorldStateHistory::open(&path).unwrap();
+        assert!(history.header().verbose);
+        assert_eq!(history.get(7).colliders[0].contacts, vec![WriteContact { other: 1, impulse: 3.0 }]);
+    }
+
+    // Histories which are not verbose are flagged as such.
+    let history = round_trip(HistoryFormat::Json, "quiet-history.json");
+    assert!(!history.header().verbose);
+    assert!(history.get(1).colliders[0].contacts.is_empty());
+}
+
+#[test]
+fn delta_encoded_history_reconstructs_transforms() {
+    // One collider spins and falls while the other stays still.
+    let moving = |step: u32| WriteWorldState {
+        step,
+        dt: 0.01,
+        delta: false,
+        colliders: vec![
+            WriteCollider {
+                id: 0,
+                transform: WriteTransform {
+                    translate: Vec3::new(0.1 * step as f32, -0.05 * (step * step) as f32, 0.0),
+                    rotate: Quat::from_rotation_y(0.37 * step as f32),
+                    scale: Vec3::ONE,
+                },
+                velocity: Vec3::new(0.1, -0.1 * step as f32, 0.0),
+                angular_velocity: Vec3::new(0.0, 0.37, 0.0),
+                contacts: Vec::new(),
+            },
+            WriteCollider {
+                id: 1,
+                transform: WriteTransform::from_translate(Vec3::new(1.234567, 2.345678, 3.456789)),
+                velocity: Vec3::ZERO,
+                angular_velocity: Vec3::ZERO,
+                contacts: Vec::new(),
+            },
+        ],
+        removed: Vec::new(),
+        inspector: InspectElements::default(),
+    };
+
+    let delta_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("history-delta.ron");
+    let full_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("history-full.ron");
+
+    for (path, keyframe_interval) in [(&delta_path, 16), (&full_path, 1)] {
+        let options = HistoryOptions { keyframe_interval, ..Default::default() };
+        let mut writer = HistoryWriter::create(path, 0.01, options).unwrap();
+
+        for step in 0..100 {
+            writer.push(&moving(step)).unwrap();
+        }
+
+        writer.finish().unwrap();
+    }
+
+    let delta_size = std::fs::metadata(&delta_path).unwrap().len();
+    let full_size = std::fs::metadata(&full_path).unwrap().len();
+    assert!(delta_size < full_size, "delta-encoded history was {delta_size} bytes, full history was {full_size}");
+
+    let opened = ReadWorldStateHistory::open(&delta_path).unwrap();
+    let read = ReadWorldStateHistory::read(&delta_path).unwrap();
+
+    assert_eq!(opened.len(), 100);
+    assert_eq!(read.len(), 100);
+
+    // Read out of order to exercise both the keyframe lookup and the cached state.
+    for step in [57, 58, 99, 3, 16, 15, 0] {
+        let expected = moving(step as u32);
+
+        for state in [opened.get(step), read.get(step)] {
+            assert_eq!(state.step, step as u32);
+            assert!(!state.delta);
+
+            for (collider, expected) in state.colliders.iter().zip(expected.colliders.iter()) {
+                assert_eq!(collider.id, expected.id);
+                assert!(collider.transform.translate.abs_diff_eq(expected.transform.translate, 1e-4));
+                assert!(collider.transform.rotate.abs_diff_eq(expected.transform.rotate, 1e-4));
+                assert_eq!(collider.velocity, expected.velocity);
+            }
+        }
+    }
+}
+
+#[test]
+fn delta_encoded_history_spawns_and_removes_colliders() {
+    // A new collider appears every step and each one is removed three steps after it appears.
+    let frame = |step: u32| WriteWorldState {
+        step,
+        dt: 0.01,
+        delta: false,
+        colliders: (step.saturating_sub(2)..=step).map(|id| WriteCollider {
+            id,
+            transform: WriteTransform::from_translate(Vec3::new(id as f32, (step - id) as f32, 0.0)),
+            velocity: Vec3::Y,
+            angular_velocity: Vec3::ZERO,
+            contacts: Vec::new(),
+        }).collect(),
+        removed: Vec::new(),
+        inspector: InspectElements::default(),
+    };
+
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("history-spawn.ron");
+    let options = HistoryOptions { keyframe_interval: 8, ..Default::default() };
+    let mut writer = HistoryWriter::create(&path, 0.01, options).unwrap();
+
+    for step in 0..20 {
+        writer.push(&frame(step)).unwrap();
+    }
+
+    writer.finish().unwrap();
+
+    for history in [ReadWorldStateHistory::open(&path).unwrap(), ReadWorldStateHistory::read(&path).unwrap()] {
+        for step in [13, 14, 19, 2, 8, 7, 0] {
+            let state = history.get(step);
+            let expected = frame(step as u32);
+
+            let ids: Vec<_> = state.colliders.iter().map(|collider| collider.id).collect();
+            let expected_ids: Vec<_> = expected.colliders.iter().map(|collider| collider.id).collect();
+            assert_eq!(ids, expected_ids, "wrong colliders at step {step}");
+
+            for (collider, expected) in state.colliders.iter().zip(expected.colliders.iter()) {
+                assert!(collider.transform.translate.abs_diff_eq(expected.transform.translate, 1e-5));
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use sokudo_io::read::ParsedWorld;
+use glam::Vec3;
+use sokudo_io::read::{collider::ParsedColliderBody, validate::ValidationError, ParseError, ParsedWorld, ParseWarning};
 
 #[test]
 fn read() {
@@ -12,3 +13,95 @@ fn read() {
 
     println!("world: {:?}", world);
 }
+
+#[test]
+fn gravity_defaults_to_earth() {
+    let world = ParsedWorld::read("tests/default-world.ron").unwrap();
+
+    assert_eq!(world.gravity, Vec3::new(0.0, -9.81, 0.0));
+}
+
+#[test]
+fn solver_settings_are_optional() {
+    let world = ParsedWorld::read("tests/default-world.ron").unwrap();
+    assert_eq!((world.substeps, world.iterations), (8, 1));
+
+    let world = ParsedWorld::read("tests/solver-world.ron").unwrap();
+    assert_eq!((world.substeps, world.iterations), (4, 2));
+}
+
+#[test]
+fn nested_compounds_are_rejected() {
+    let result = ParsedWorld::read("tests/nested-compound-world.ron");
+
+    assert!(matches!(result, Err(ParseError::NestedCompound)), "read {result:?}");
+}
+
+#[test]
+fn collider_colors_are_optional() {
+    let world = ParsedWorld::read("tests/colored-world.ron").unwrap();
+
+    assert_eq!(world.colliders[0].color, Some([0.2, 0.4, 0.6, 1.0]));
+    assert_eq!(world.colliders[0].metallic, 0.8);
+    assert_eq!(world.colliders[0].roughness, 0.1);
+
+    assert_eq!(world.colliders[1].color, None);
+    assert_eq!(world.colliders[1].metallic, 0.0);
+    assert_eq!(world.colliders[1].roughness, 0.5);
+}
+
+#[test]
+fn mass_takes_precedence_over_density() {
+    let world = ParsedWorld::read("tests/density-world.ron").unwrap();
+    let rigid_body = |i: usize| match &world.colliders[i].body {
+        ParsedColliderBody::RigidBody(rb) => rb,
+        ParsedColliderBody::Particle(_) => panic!("collider {i} is not a rigid body"),
+    };
+
+    assert_eq!(rigid_body(0).density, Some(2.0));
+    assert_eq!(rigid_body(1).mass, 3.0);
+    assert_eq!(rigid_body(1).density, None);
+
+    assert!(matches!(world.warnings.as_slice(), [ParseWarning::MassAndDensity { id: 1 }]));
+}
+
+#[test]
+fn validation_finds_every_problem() {
+    let world = ParsedWorld::read("tests/invalid-world.ron").unwrap();
+
+    assert_eq!(world.validate(), vec![
+        ValidationError::ZeroSubsteps,
+        ValidationError::MaxCorrectionOutOfRange { max_correction: 1.5 },
+        ValidationError::InvalidCellSize { cell_size: 0.0 },
+        ValidationError::NonPositiveWaterDensity { density: 0.0 },
+        ValidationError::NonPositiveEmitterLifetime { index: 0, lifetime: 0.0 },
+        ValidationError::NonPositiveEmitterMass { index: 0, mass: -1.0 },
+        ValidationError::NegativeEmitterRadius { index: 0, radius: -0.1 },
+        ValidationError::NonPositiveMass { id: 0, mass: 0.0 },
+        ValidationError::NegativeRadius { id: 0, radius: -0.5 },
+        ValidationError::UnorderedKeyframes { id: 1 },
+        ValidationError::NonPositiveDensity { id: 1, density: -1.0 },
+        ValidationError::ZeroScale { id: 1 },
+        ValidationError::NonUniformVertexResolution { id: 2 },
+        ValidationError::UnknownCollider { index: 0, id: 3 },
+    ]);
+
+    assert!(world.warnings.iter().any(|warning| matches!(warning, ParseWarning::InvalidCellSize { cell_size } if *cell_size == 0.0)));
+
+    assert!(ParsedWorld::read("tests/colored-world.ron").unwrap().validate().is_empty());
+}
+
+#[test]
+fn tori_may_sample_their_ring_and_tube_differently() {
+    assert!(ParsedWorld::read("tests/torus-world.ron").unwrap().validate().is_empty());
+}
+
+#[test]
+fn yaml_worlds_are_read() {
+    let world = ParsedWorld::read("tests/yaml-world.yaml").unwrap();
+
+    assert_eq!((world.steps, world.dt), (120, 0.005));
+    assert_eq!(world.colliders.len(), 4);
+    assert!(matches!(world.colliders[3].body, ParsedColliderBody::Particle(_)));
+    assert_eq!(world.colliders[1].position, Vec3::new(0.0, 1.0, 0.0));
+}
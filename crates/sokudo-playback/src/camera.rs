@@ -1,6 +1,6 @@
 use std::f32::consts::{FRAC_PI_2, PI, TAU};
 
-use bevy::{input::mouse::MouseMotion, prelude::*};
+use bevy::{input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel}, prelude::*};
 use bevy_mod_picking::selection::PickSelection;
 
 pub struct PanOrbitPlugin;
@@ -40,9 +40,15 @@ pub struct PanOrbitSettings {
     pub pan_sensitivity: f32,
     pub orbit_sensitivity: f32,
     pub zoom_sensitivity: f32,
+    /// The zoom per line scrolled with the mouse wheel.
+    pub scroll_zoom_sensitivity: f32,
+    /// Whether the mouse wheel zooms, which is turned off while it moves the slice plane instead.
+    pub scroll_zoom: bool,
     pub pan_key: Option<KeyCode>,
     pub orbit_key: Option<KeyCode>,
     pub zoom_key: Option<KeyCode>,
+    /// The mouse button which pans when dragged, regardless of `pan_key`.
+    pub pan_button: MouseButton,
 }
 
 impl Default for PanOrbitState {
@@ -63,9 +69,12 @@ impl Default for PanOrbitSettings {
             pan_sensitivity: 0.001, // 1000 pixels per world unit
             orbit_sensitivity: 0.1f32.to_radians(), // 0.1 degree per pixel
             zoom_sensitivity: 0.01,
+            scroll_zoom_sensitivity: 0.1,
+            scroll_zoom: true,
             pan_key: Some(KeyCode::ShiftLeft),
             orbit_key: None,
             zoom_key: Some(KeyCode::ControlLeft),
+            pan_button: MouseButton::Middle,
         }
     }
 }
@@ -80,6 +89,7 @@ fn pan_orbit_camera(
     keys: Res<ButtonInput<KeyCode>>,
     mouse: Res<ButtonInput<MouseButton>>,
     mut evr_motion: EventReader<MouseMotion>,
+    mut evr_scroll: EventReader<MouseWheel>,
     mut cameras: Query<(&PanOrbitSettings, &mut PanOrbitState, &mut Transform)>,
 ) {
     let mut total_motion: Vec2 = evr_motion.read()
@@ -87,6 +97,14 @@ fn pan_orbit_camera(
 
     total_motion.y = -total_motion.y;
 
+    // Pixel scrolling (e.g. from touchpads) is much finer grained than line scrolling.
+    let total_scroll: f32 = evr_scroll.read()
+        .map(|ev| match ev.unit {
+            MouseScrollUnit::Line => ev.y,
+            MouseScrollUnit::Pixel => ev.y * 0.01,
+        })
+        .sum();
+
     let left_pressed = mouse.pressed(MouseButton::Left);
 
     for (settings, mut state, mut transform) in &mut cameras {
@@ -95,7 +113,7 @@ fn pan_orbit_camera(
         let is_zooming = settings.zoom_key.map(|key| keys.pressed(key)).unwrap_or(true);
 
         let mut total_pan = Vec2::ZERO;
-        if is_panning && left_pressed {
+        if (is_panning && left_pressed) || mouse.pressed(settings.pan_button) {
             total_pan -= total_motion * settings.pan_sensitivity;
         }
 
@@ -108,6 +126,9 @@ fn pan_orbit_camera(
         if is_zooming && left_pressed {
             total_zoom -= total_motion * settings.zoom_sensitivity;
         }
+        if settings.scroll_zoom {
+            total_zoom.y -= total_scroll * settings.scroll_zoom_sensitivity;
+        }
 
         if mouse.just_pressed(MouseButton::Left) {
             state.upside_down = state.pitch < -FRAC_PI_2 || state.pitch > FRAC_PI_2;
@@ -0,0 +1,128 @@
+use std::{path::PathBuf, sync::{atomic::{AtomicUsize, Ordering}, Arc}};
+
+use bevy::{prelude::*, render::view::screenshot::ScreenshotManager, window::PrimaryWindow};
+
+use crate::player::{WorldStateHistory, WorldStateIndex};
+
+pub struct CapturePlugin;
+
+/// The number of frames rendered before the first frame of a sequence is captured, giving the
+/// meshes and pipelines time to load.
+const WARMUP_FRAMES: usize = 10;
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Startup, setup_render_sequence.run_if(resource_exists::<RenderSequence>))
+            .add_systems(
+                PreUpdate,
+                (
+                    take_screenshot.run_if(not(resource_exists::<RenderSequence>)),
+                    render_sequence.run_if(resource_exists::<RenderSequence>),
+                ),
+            );
+    }
+}
+
+/// Renders every frame of the history to sequentially numbered images in `directory`, then exits.
+#[derive(Resource)]
+pub struct RenderSequence {
+    pub directory: PathBuf,
+    /// The number of frames waited for before capturing.
+    warmup: usize,
+    /// The number of frames requested to be captured.
+    requested: usize,
+    /// The number of captured frames which have been saved, shared with the screenshot callbacks.
+    saved: Arc<AtomicUsize>,
+}
+
+impl RenderSequence {
+    pub fn new(directory: PathBuf) -> Self {
+        RenderSequence {
+            directory,
+            warmup: 0,
+            requested: 0,
+            saved: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+fn setup_render_sequence(sequence: Res<RenderSequence>, mut exit: EventWriter<AppExit>) {
+    if let Err(err) = std::fs::create_dir_all(&sequence.directory) {
+        error!("cannot create {}: {}", sequence.directory.display(), err);
+        exit.send(AppExit::error());
+    }
+}
+
+/// Saves a screenshot of the current frame to the working directory when `P` is pressed.
+fn take_screenshot(
+    keys: Res<ButtonInput<KeyCode>>,
+    windows: Query<Entity, With<PrimaryWindow>>,
+    index: Res<WorldStateIndex>,
+    mut screenshots: ResMut<ScreenshotManager>,
+) {
+    if !keys.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    let path = format!("screenshot-{:05}.png", index.step);
+
+    if let Err(err) = screenshots.save_screenshot_to_disk(window, path) {
+        warn!("{}", err);
+    }
+}
+
+/// Advances to the next frame once the previous one has been saved, and requests a capture of it.
+///
+/// This runs before the colliders are moved, so the capture shows the frame it is named after.
+fn render_sequence(
+    mut sequence: ResMut<RenderSequence>,
+    mut index: ResMut<WorldStateIndex>,
+    history: Res<WorldStateHistory>,
+    windows: Query<Entity, With<PrimaryWindow>>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if sequence.warmup < WARMUP_FRAMES {
+        sequence.warmup += 1;
+        return;
+    }
+
+    if sequence.saved.load(Ordering::Acquire) < sequence.requested {
+        return;
+    }
+
+    if sequence.requested == history.history.len() {
+        exit.send(AppExit::Success);
+        return;
+    }
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    let step = sequence.requested;
+    let path = sequence.directory.join(format!("{:05}.png", step));
+    let saved = sequence.saved.clone();
+
+    let requested = screenshots.take_screenshot(window, move |image| {
+        match image.try_into_dynamic() {
+            // The alpha channel is discarded, as it stores brightness when HDR is enabled.
+            Ok(image) => if let Err(err) = image.to_rgb8().save(&path) {
+                error!("cannot save {}: {}", path.display(), err);
+            },
+            Err(err) => error!("cannot convert frame {}: {}", step, err),
+        }
+
+        saved.fetch_add(1, Ordering::Release);
+    });
+
+    if requested.is_ok() {
+        index.step = step;
+        sequence.requested += 1;
+    }
+}
@@ -1,13 +1,24 @@
 use std::path;
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    render::{settings::{RenderCreation, WgpuFeatures, WgpuSettings}, RenderPlugin},
+};
 use bevy_mod_picking::DefaultPickingPlugins;
 use camera::PanOrbitPlugin;
+use capture::{CapturePlugin, RenderSequence};
+use lighting::{Lighting, LightingPlugin};
 use player::{InitialWorld, PlayerPlugin, WorldStateHistory};
+use slice::SlicePlugin;
 use sokudo_io::{read::{ParseError, ParsedWorld}, write::{ReadStateError, ReadWorldStateHistory}};
 use thiserror::Error;
 
+pub use lighting::{LightingPreset, UnknownLightingPreset};
+
 mod player;
 mod camera;
+mod capture;
+mod lighting;
+mod slice;
 
 #[derive(Error, Debug)]
 pub enum PlaybackError {
@@ -19,17 +30,43 @@ pub enum PlaybackError {
     ReadState(#[from] ReadStateError),
 }
 
-pub fn play<P>(world_path: P, history_path: P) -> Result<(), PlaybackError>
+/// Play back the history in `history_path` of the world in `world_path`.
+///
+/// The scene is lit with `lighting` until another preset is picked. If `render_directory` is
+/// given, every frame is instead rendered to a numbered PNG in it, after which the player exits.
+pub fn play<P>(world_path: P, history_path: P, render_directory: Option<P>, lighting: LightingPreset) -> Result<(), PlaybackError>
 where
     P: AsRef<path::Path>,
 {
     let world = ParsedWorld::read(world_path)?;
-    let history = ReadWorldStateHistory::read(history_path)?;
+    let history = ReadWorldStateHistory::open(history_path)?;
+
+    let mut app = App::new();
+
+    if let Some(directory) = render_directory {
+        app.insert_resource(RenderSequence::new(directory.as_ref().to_path_buf()));
+    }
 
-    App::new()
-        .add_plugins((DefaultPlugins, DefaultPickingPlugins, PanOrbitPlugin, PlayerPlugin))
+    app
+        .add_plugins((
+            DefaultPlugins.set(RenderPlugin {
+                // Required to draw wireframes.
+                render_creation: RenderCreation::Automatic(WgpuSettings {
+                    features: WgpuFeatures::POLYGON_MODE_LINE,
+                    ..default()
+                }),
+                ..default()
+            }),
+            DefaultPickingPlugins,
+            PanOrbitPlugin,
+            PlayerPlugin,
+            CapturePlugin,
+            SlicePlugin,
+            LightingPlugin,
+        ))
         .insert_resource(WorldStateHistory { history })
         .insert_resource(InitialWorld { world })
+        .insert_resource(Lighting { preset: lighting })
         .run();
 
     Ok(())
@@ -0,0 +1,141 @@
+use std::{f32::consts::{FRAC_PI_3, FRAC_PI_4, FRAC_PI_6, PI}, str::FromStr};
+
+use bevy::prelude::*;
+use thiserror::Error;
+
+/// Lights the scene with one of the [`LightingPreset`]s, starting with the one in the
+/// [`Lighting`] resource and cycled with `I`.
+pub struct LightingPlugin;
+
+impl Plugin for LightingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Lighting>()
+            .add_systems(PreUpdate, cycle_lighting_preset)
+            // The lights of the initial preset are spawned on the first frame, since the resource
+            // counts as changed then.
+            .add_systems(Update, (despawn_lights, spawn_lights).chain().run_if(resource_changed::<Lighting>));
+    }
+}
+
+/// A set of directional lights and an ambient brightness suited to a kind of scene.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LightingPreset {
+    /// A bright key light with softer fill and rim lights.
+    #[default]
+    Studio,
+    /// A warm sun high overhead and a cool light from the sky opposite it.
+    Outdoor,
+    /// A single dim light with little ambient light, for bright or emissive colliders.
+    Dark,
+    /// Ambient light alone, which shows the colors of colliders without any shading.
+    Flat,
+}
+
+impl LightingPreset {
+    /// The presets in the order they are cycled through.
+    const ALL: [LightingPreset; 4] = [LightingPreset::Studio, LightingPreset::Outdoor, LightingPreset::Dark, LightingPreset::Flat];
+
+    fn next(self) -> LightingPreset {
+        let index = LightingPreset::ALL.iter().position(|&preset| preset == self).unwrap_or(0);
+        LightingPreset::ALL[(index + 1) % LightingPreset::ALL.len()]
+    }
+
+    fn ambient_brightness(self) -> f32 {
+        match self {
+            LightingPreset::Studio => 200.0,
+            LightingPreset::Outdoor => 400.0,
+            LightingPreset::Dark => 20.0,
+            LightingPreset::Flat => 1500.0,
+        }
+    }
+
+    /// The color, illuminance, yaw and pitch of each directional light.
+    fn lights(self) -> Vec<(Color, f32, f32, f32)> {
+        match self {
+            LightingPreset::Studio => vec![
+                (Color::WHITE, 5000.0, 2.0 * FRAC_PI_3, FRAC_PI_6),
+                (Color::WHITE, 2000.0, 4.0 * FRAC_PI_3, FRAC_PI_3),
+                (Color::WHITE, 500.0, 0.0, FRAC_PI_4),
+            ],
+            LightingPreset::Outdoor => vec![
+                (Color::srgb(1.0, 0.95, 0.85), 10000.0, FRAC_PI_4, -FRAC_PI_3),
+                (Color::srgb(0.7, 0.8, 1.0), 1500.0, FRAC_PI_4 + PI, -FRAC_PI_6),
+            ],
+            LightingPreset::Dark => vec![
+                (Color::WHITE, 1000.0, 2.0 * FRAC_PI_3, FRAC_PI_6),
+            ],
+            LightingPreset::Flat => vec![],
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("unknown lighting preset `{0}`, expected `studio`, `outdoor`, `dark` or `flat`")]
+pub struct UnknownLightingPreset(pub String);
+
+impl FromStr for LightingPreset {
+    type Err = UnknownLightingPreset;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "studio" => Ok(LightingPreset::Studio),
+            "outdoor" => Ok(LightingPreset::Outdoor),
+            "dark" => Ok(LightingPreset::Dark),
+            "flat" => Ok(LightingPreset::Flat),
+            _ => Err(UnknownLightingPreset(s.to_string())),
+        }
+    }
+}
+
+/// The lighting preset the scene is lit with.
+#[derive(Resource, Default)]
+pub struct Lighting {
+    pub preset: LightingPreset,
+}
+
+/// A directional light spawned for the current [`LightingPreset`].
+#[derive(Component)]
+struct PresetLight;
+
+fn spawn_lights(
+    mut commands: Commands,
+    mut ambient_light: ResMut<AmbientLight>,
+    lighting: Res<Lighting>,
+) {
+    ambient_light.brightness = lighting.preset.ambient_brightness();
+
+    for (color, illuminance, yaw, pitch) in lighting.preset.lights() {
+        commands.spawn((
+            DirectionalLightBundle {
+                directional_light: DirectionalLight {
+                    color,
+                    illuminance,
+                    ..default()
+                },
+                transform: Transform::from_rotation(Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0)),
+                ..default()
+            },
+            PresetLight,
+        ));
+    }
+}
+
+fn despawn_lights(
+    mut commands: Commands,
+    lights: Query<Entity, With<PresetLight>>,
+) {
+    for entity in &lights {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn cycle_lighting_preset(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut lighting: ResMut<Lighting>,
+) {
+    if keys.just_pressed(KeyCode::KeyI) {
+        lighting.preset = lighting.preset.next();
+        info!("lighting: {:?}", lighting.preset);
+    }
+}
@@ -1,13 +1,22 @@
-use std::f32::consts::{FRAC_PI_3, FRAC_PI_4, FRAC_PI_6};
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    pbr::wireframe::{WireframeConfig, WireframePlugin},
+    prelude::*,
+    render::{mesh::PrimitiveTopology, render_asset::RenderAssetUsages},
+    utils::{HashMap, HashSet},
+};
+use bevy_mod_picking::{selection::PickSelection, PickableBundle};
+use sokudo_core::{emitter::Emitter, shape::{convex_hull::ConvexHullShape, sdf::SdfShape}};
+use sokudo_io::{read::{collider::{ParsedCollider, ParsedColliderBody, ParsedShape}, transform::ParsedTransform, ParsedWorld}, write::{inspect::InspectFeature, transform::WriteTransform, ReadWorldStateHistory}};
 
-use bevy::{prelude::*, utils::HashMap};
-use bevy_mod_picking::PickableBundle;
-use sokudo_io::{read::{collider::{ParsedCollider, ParsedColliderBody, ParsedShape}, ParsedWorld}, write::{inspect::InspectFeature, ReadWorldStateHistory}};
-
-use crate::camera::PanOrbitState;
+use crate::{camera::PanOrbitState, capture::RenderSequence, slice::{ColliderMaterial, SliceExtension}};
 
 pub struct PlayerPlugin;
 
+/// Half the side length of the quad used to display half-spaces.
+const HALF_SPACE_HALF_SIZE: f32 = 100.0;
+const HALF_SPACE_SUBDIVISIONS: u32 = 200;
+
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app
@@ -15,8 +24,15 @@ impl Plugin for PlayerPlugin {
             .init_resource::<WorldStateIndex>()
             .init_resource::<DeltaTime>()
             .init_resource::<PlaybackTime>()
+            .init_resource::<Scrub>()
+            .init_resource::<GizmoVisibility>()
+            .init_resource::<Trails>()
+            .init_resource::<GridSettings>()
+            .init_resource::<PauseOnContact>()
             .init_state::<PlayerState>()
-            .add_systems(Startup, (setup_lights, setup_initial_state))
+            .add_plugins((WireframePlugin, FrameTimeDiagnosticsPlugin))
+            .add_systems(Startup, (setup_initial_state, setup_wireframe_mode, setup_info_panel, setup_frame_overlay))
+            .add_systems(PostStartup, focus_camera_on_colliders)
             .add_systems(
                 PreUpdate,
                 (
@@ -24,10 +40,18 @@ impl Plugin for PlayerPlugin {
                     set_player_state_paused.run_if(in_state(PlayerState::Playing)),
                     update_world_state.after(set_player_state_playing).run_if(in_state(PlayerState::Playing)),
                     step_state_on_pause.after(set_player_state_paused).run_if(in_state(PlayerState::Paused)),
-                    restart_player,
+                    // Restarting takes precedence over advancing the frame or toggling playback.
+                    restart_player.after(update_world_state).after(set_player_state_playing).after(step_state_on_pause),
+                    change_playback_speed,
+                    toggle_gizmos,
+                    toggle_pause_on_contact,
+                    change_grid_spacing,
+                    toggle_wireframe_mode,
                 )
+                    // Rendering a sequence steps through the frames on its own.
+                    .run_if(not(resource_exists::<RenderSequence>)),
             )
-            .add_systems(Update, (update_inspect_elements, update_colliders));
+            .add_systems(Update, (update_inspect_elements, update_collider_entities, update_colliders, update_collider_transforms.after(update_collider_entities), update_info_panel, update_frame_overlay, update_contact_legend, draw_reference_gizmos));
     }
 }
 
@@ -41,9 +65,26 @@ pub struct DeltaTime {
     pub dt: f32,
 }
 
-#[derive(Resource, Default)]
+#[derive(Resource)]
 pub struct PlaybackTime {
+    /// The simulated time which has been played back.
     pub time: f32,
+    /// The amount of simulated time played back per second of real time.
+    pub playback_speed: f32,
+}
+
+impl PlaybackTime {
+    const MIN_PLAYBACK_SPEED: f32 = 1.0 / 16.0;
+    const MAX_PLAYBACK_SPEED: f32 = 16.0;
+}
+
+impl Default for PlaybackTime {
+    fn default() -> Self {
+        PlaybackTime {
+            time: 0.0,
+            playback_speed: 1.0,
+        }
+    }
 }
 
 #[derive(Resource)]
@@ -63,149 +104,562 @@ pub enum PlayerState {
     Playing
 }
 
+/// Which debugging gizmos are drawn.
+#[derive(Resource)]
+pub struct GizmoVisibility {
+    /// Whether or not contact points and normals are drawn, toggled with `C`.
+    pub contacts: bool,
+    /// Whether or not the linear and angular velocity of each collider is drawn, toggled with
+    /// `V`.
+    pub velocities: bool,
+    /// Whether or not the recent path of each collider is drawn, toggled with `T`.
+    pub trails: bool,
+    /// Whether or not the ground grid is drawn, toggled with `G`.
+    pub grid: bool,
+    /// Whether or not the axes at the origin are drawn, toggled with `A`.
+    pub axes: bool,
+}
+
+impl Default for GizmoVisibility {
+    fn default() -> Self {
+        GizmoVisibility {
+            contacts: true,
+            velocities: false,
+            trails: false,
+            grid: true,
+            axes: true,
+        }
+    }
+}
+
+/// The radius of the spheres drawn at contact points.
+const CONTACT_GIZMO_RADIUS: f32 = 0.02;
+/// The length of the arrows drawn along contact normals.
+const CONTACT_GIZMO_NORMAL_LENGTH: f32 = 0.25;
+
+/// The length of the arrows drawn for velocities per unit of speed.
+const VELOCITY_GIZMO_SCALE: f32 = 0.1;
+
+/// How the ground grid is drawn.
+#[derive(Resource)]
+pub struct GridSettings {
+    /// The distance between neighbouring grid lines, halved with `-` and doubled with `=`.
+    pub spacing: f32,
+    /// The number of grid lines drawn on each side of the camera's center, beyond which the grid
+    /// has faded out.
+    pub lines: u32,
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        GridSettings { spacing: 1.0, lines: 20 }
+    }
+}
+
+/// Whether playback pauses on the first frame of each new contact, toggled with `B`.
+///
+/// Contacts are only recorded in verbose histories, so this does nothing otherwise.
+#[derive(Resource, Default)]
+pub struct PauseOnContact {
+    pub enabled: bool,
+}
+
+/// The length of each arrow of the axes drawn at the origin.
+const AXES_GIZMO_LENGTH: f32 = 1.0;
+
+/// The number of frames before the current one included in each collider's trail.
+const TRAIL_LENGTH: usize = 30;
+
+/// How long an arrow key must be held while paused before it scrubs continuously.
+const SCRUB_DELAY: f32 = 0.3;
+/// The number of frames scrubbed per second while an arrow key is held.
+const SCRUB_RATE: f32 = 30.0;
+
+/// The state of scrubbing through the history with the arrow keys.
+#[derive(Resource, Default)]
+struct Scrub {
+    /// How long the current arrow key has been held.
+    held: f32,
+    /// The fraction of a frame scrubbed but not yet stepped.
+    pending: f32,
+}
+
+/// The recent positions of each collider, kept so the history is only read when the frame changes.
+#[derive(Resource, Default)]
+struct Trails {
+    /// The frame the trails end at, or `None` if they have not been built yet.
+    step: Option<usize>,
+    positions: HashMap<u32, Vec<Vec3>>,
+}
+
+/// The entities displaying the colliders, which are spawned when their colliders first appear in
+/// a frame and hidden while they are absent.
 #[derive(Resource, Default)]
 struct ColliderEntities {
     map: HashMap<u32, Entity>,
+    /// The mesh and material of every collider which appears in the history.
+    prototypes: HashMap<u32, (Handle<Mesh>, Handle<ColliderMaterial>)>,
 }
 
+/// The ID of the collider an entity displays.
 #[derive(Component)]
-struct Collider;
+struct Collider(u32);
 
-fn setup_lights(
-    mut commands: Commands,
-    mut ambient_light: ResMut<AmbientLight>,
+/// The text panel showing the data of the selected collider.
+#[derive(Component)]
+struct InfoPanel;
+
+/// The text showing the current frame, time, playback state, and frame rate.
+#[derive(Component)]
+struct FrameOverlay;
+
+/// The material a collider is drawn with unless only its wireframe is.
+#[derive(Component)]
+struct SolidMaterial(Handle<ColliderMaterial>);
+
+/// How colliders are drawn, cycled with `W`.
+#[derive(Resource)]
+struct WireframeMode {
+    style: WireframeStyle,
+    /// The invisible material colliders are given so only their wireframes are drawn.
+    hidden_material: Handle<ColliderMaterial>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum WireframeStyle {
+    /// Colliders are drawn solid.
+    Off,
+    /// Only the wireframes of colliders are drawn.
+    Wireframe,
+    /// The wireframes of colliders are drawn over them.
+    Overlay,
+}
+
+impl WireframeStyle {
+    /// Whether the solid material of colliders is replaced with an invisible one.
+    fn hides_solid(self) -> bool {
+        self == WireframeStyle::Wireframe
+    }
+}
+
+fn setup_info_panel(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section("", TextStyle { font_size: 16.0, ..default() })
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(8.0),
+                left: Val::Px(8.0),
+                ..default()
+            }),
+        InfoPanel,
+    ));
+}
+
+fn setup_frame_overlay(mut commands: Commands) {
+    let style = TextStyle { font_size: 16.0, ..default() };
+
+    commands.spawn((
+        // The second section holds the legend of the contact gizmos.
+        TextBundle::from_sections([TextSection::new("", style.clone()), TextSection::new("", style)])
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(8.0),
+                right: Val::Px(8.0),
+                ..default()
+            }),
+        FrameOverlay,
+    ));
+}
+
+fn update_frame_overlay(
+    mut overlays: Query<&mut Text, With<FrameOverlay>>,
+    index: Res<WorldStateIndex>,
+    history: Res<WorldStateHistory>,
+    delta_time: Res<DeltaTime>,
+    state: Res<State<PlayerState>>,
+    pause_on_contact: Res<PauseOnContact>,
+    diagnostics: Res<DiagnosticsStore>,
 ) {
-    ambient_light.brightness = 200.0;
+    let Ok(mut text) = overlays.get_single_mut() else {
+        return;
+    };
 
-    commands.spawn(DirectionalLightBundle {
-        directional_light: DirectionalLight {
-            color: Color::WHITE,
-            illuminance: 5000.0,
-            ..default()
-        },
-        transform: Transform::from_rotation(Quat::from_euler(
-            EulerRot::YXZ,
-            2.0 * FRAC_PI_3,
-            FRAC_PI_6,
-            0.0,
-        )),
-        ..default()
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or(0.0);
+
+    text.sections[0].value = format!(
+        "frame {} / {}\ntime: {:.3} s\n{:?}\n{:.0} fps",
+        index.step,
+        history.history.len(),
+        index.step as f32 * delta_time.dt,
+        state.get(),
+        fps,
+    );
+
+    if pause_on_contact.enabled {
+        text.sections[0].value.push_str("\npausing on new contacts");
+    }
+}
+
+/// Shows the scale contact gizmos are colored on below the frame overlay while they are drawn.
+fn update_contact_legend(
+    mut overlays: Query<&mut Text, With<FrameOverlay>>,
+    index: Res<WorldStateIndex>,
+    history: Res<WorldStateHistory>,
+    visibility: Res<GizmoVisibility>,
+) {
+    let Ok(mut text) = overlays.get_single_mut() else {
+        return;
+    };
+
+    let max_impulse = history.history.get(index.step).inspector.max_contact_impulse();
+
+    text.sections[1].value = if visibility.contacts && max_impulse > 0.0 {
+        format!("\ncontact impulse: blue 0 to red {max_impulse:.3} N s")
+    } else {
+        String::new()
+    };
+}
+
+/// Shows the state of the selected collider at the current frame, or nothing if no collider is
+/// selected.
+fn update_info_panel(
+    mut panels: Query<&mut Text, With<InfoPanel>>,
+    colliders: Query<(&Collider, &PickSelection)>,
+    index: Res<WorldStateIndex>,
+    history: Res<WorldStateHistory>,
+) {
+    let Ok(mut text) = panels.get_single_mut() else {
+        return;
+    };
+
+    let selected = colliders.iter()
+        .find(|(_, selection)| selection.is_selected)
+        .map(|(collider, _)| collider.0);
+
+    let state = selected.and_then(|id| {
+        history.history.get(index.step).colliders.into_iter().find(|collider| collider.id == id)
     });
 
-    commands.spawn(DirectionalLightBundle {
-        directional_light: DirectionalLight {
-            color: Color::WHITE,
-            illuminance: 2000.0,
+    let Some(collider) = state else {
+        text.sections[0].value.clear();
+        return;
+    };
+
+    let translate = collider.transform.translate;
+    let rotate = collider.transform.rotate;
+
+    let mut info = format!(
+        "collider {}\nposition: ({:.3}, {:.3}, {:.3})\nrotation: ({:.3}, {:.3}, {:.3}, {:.3})",
+        collider.id,
+        translate.x, translate.y, translate.z,
+        rotate.x, rotate.y, rotate.z, rotate.w,
+    );
+
+    // Contacts are only recorded in verbose histories, so an empty list means nothing otherwise.
+    if history.history.header().verbose {
+        let velocity = collider.velocity;
+        let angular_velocity = collider.angular_velocity;
+
+        info.push_str(&format!(
+            "\nvelocity: ({:.3}, {:.3}, {:.3})\nangular velocity: ({:.3}, {:.3}, {:.3})\ncontacts: {}",
+            velocity.x, velocity.y, velocity.z,
+            angular_velocity.x, angular_velocity.y, angular_velocity.z,
+            collider.contacts.len(),
+        ));
+    }
+
+    text.sections[0].value = info;
+}
+
+/// Draws the ground grid on the XZ plane around the camera's center and the axes at the origin.
+fn draw_reference_gizmos(
+    mut gizmos: Gizmos,
+    visibility: Res<GizmoVisibility>,
+    grid: Res<GridSettings>,
+    cameras: Query<&PanOrbitState>,
+) {
+    if visibility.axes {
+        gizmos.axes(Transform::IDENTITY, AXES_GIZMO_LENGTH);
+    }
+
+    if !visibility.grid {
+        return;
+    }
+
+    let Ok(camera) = cameras.get_single() else {
+        return;
+    };
+
+    // The grid follows the camera in whole cells so it appears to extend forever.
+    let spacing = grid.spacing;
+    let lines = grid.lines as i32;
+    let extent = lines as f32 * spacing;
+    let center = (Vec2::new(camera.center.x, camera.center.z) / spacing).round() * spacing;
+
+    // Each line fades out towards its ends and the further it is from the center.
+    let color = |offset: Vec2| Color::srgba(0.6, 0.6, 0.6, (1.0 - offset.length() / extent).max(0.0) * 0.5);
+
+    for i in -lines..=lines {
+        let across = i as f32 * spacing;
+
+        for direction in [Vec2::X, Vec2::Y] {
+            let normal = direction.perp();
+
+            gizmos.linestrip_gradient((-lines..=lines).map(|j| {
+                let offset = across * normal + j as f32 * spacing * direction;
+                let point = center + offset;
+
+                (Vec3::new(point.x, 0.0, point.y), color(offset))
+            }));
+        }
+    }
+}
+
+/// Centers the camera on the centroid of the colliders in the first frame.
+fn focus_camera_on_colliders(
+    history: Res<WorldStateHistory>,
+    mut cameras: Query<&mut PanOrbitState>,
+) {
+    if history.history.is_empty() {
+        return;
+    }
+
+    let world_state = history.history.get(0);
+
+    if world_state.colliders.is_empty() {
+        return;
+    }
+
+    let centroid = world_state.colliders.iter()
+        .map(|collider| collider.transform.translate)
+        .sum::<glam::Vec3>() / world_state.colliders.len() as f32;
+
+    for mut camera in &mut cameras {
+        camera.center = Vec3::new(centroid.x, centroid.y, centroid.z);
+    }
+}
+
+/// The material of `collider`, using its color if it has one and `default_color` otherwise.
+fn collider_material(collider: &ParsedCollider, default_color: [f32; 4]) -> ColliderMaterial {
+    let [r, g, b, a] = collider.color.unwrap_or(default_color);
+
+    ColliderMaterial {
+        base: StandardMaterial {
+            base_color: Color::srgba(r, g, b, a),
+            metallic: collider.metallic,
+            perceptual_roughness: collider.roughness,
+            alpha_mode: if a < 1.0 { AlphaMode::Blend } else { AlphaMode::Opaque },
             ..default()
         },
-        transform: Transform::from_rotation(Quat::from_euler(
-            EulerRot::YXZ,
-            4.0 * FRAC_PI_3,
-            FRAC_PI_3,
-            0.0,
-        )),
-        ..default()
-    });
+        extension: SliceExtension::default(),
+    }
+}
 
-    commands.spawn(DirectionalLightBundle {
-        directional_light: DirectionalLight {
-            color: Color::WHITE,
-            illuminance: 500.0,
+fn setup_wireframe_mode(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColliderMaterial>>,
+) {
+    let hidden_material = materials.add(ColliderMaterial {
+        base: StandardMaterial {
+            base_color: Color::NONE,
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
             ..default()
         },
-        transform: Transform::from_rotation(Quat::from_euler(
-            EulerRot::YXZ,
-            0.0,
-            FRAC_PI_4,
-            0.0,
-        )),
-        ..default()
+        extension: SliceExtension::default(),
     });
+
+    commands.insert_resource(WireframeMode { style: WireframeStyle::Off, hidden_material });
+}
+
+fn toggle_wireframe_mode(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut mode: ResMut<WireframeMode>,
+    mut wireframe_config: ResMut<WireframeConfig>,
+    mut gizmo_config: ResMut<GizmoConfigStore>,
+    mut colliders: Query<(&mut Handle<ColliderMaterial>, &SolidMaterial), With<Collider>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyW) {
+        return;
+    }
+
+    mode.style = match mode.style {
+        WireframeStyle::Off => WireframeStyle::Wireframe,
+        WireframeStyle::Wireframe => WireframeStyle::Overlay,
+        WireframeStyle::Overlay => WireframeStyle::Off,
+    };
+
+    info!("wireframes: {:?}", mode.style);
+
+    wireframe_config.global = mode.style != WireframeStyle::Off;
+
+    // Gizmos are drawn in front of everything while only wireframes are so that contacts inside
+    // colliders stay visible.
+    let (config, _) = gizmo_config.config_mut::<DefaultGizmoConfigGroup>();
+    config.depth_bias = if mode.style.hides_solid() { -1.0 } else { 0.0 };
+
+    for (mut material, solid) in &mut colliders {
+        *material = if mode.style.hides_solid() {
+            mode.hidden_material.clone()
+        } else {
+            solid.0.clone()
+        };
+    }
 }
 
 fn setup_initial_state(
-    mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut materials: ResMut<Assets<ColliderMaterial>>,
     mut collider_entities: ResMut<ColliderEntities>,
     mut delta_time: ResMut<DeltaTime>,
     world: Res<InitialWorld>,
+    history: Res<WorldStateHistory>,
 ) {
-    delta_time.dt = world.world.dt;
+    delta_time.dt = history.history.dt();
 
     for collider in world.world.colliders.iter() {
-        match &collider.body {
-            ParsedColliderBody::Particle(_) => {
-                let mesh = Mesh::from(Sphere::new(0.1));
-                let material = StandardMaterial::from_color(Color::srgba(0.0, 0.0, 1.0, 1.0));
-
-                let entity = commands.spawn((
-                    PbrBundle {
-                        mesh: meshes.add(mesh),
-                        material: materials.add(material),
-                        transform: Transform::from_xyz(
-                            collider.position.x,
-                            collider.position.y,
-                            collider.position.z,
-                        ),
-                        ..default()
-                    },
-                    Collider,
-                    PickableBundle::default(),
-                )).id();
-
-                collider_entities.map.insert(collider.id, entity);
-            },
-            ParsedColliderBody::RigidBody(rb) => {
-                let mesh: Mesh = match rb.shape {
-                    ParsedShape::Cuboid => Cuboid::new(1.0, 1.0, 1.0).into(),
-                };
-
-                let material = StandardMaterial::from_color(Color::srgba(1.0, 0.0, 0.0, 1.0));
-
-                let entity = commands.spawn((
-                    PbrBundle {
-                        mesh: meshes.add(mesh),
-                        material: materials.add(material),
-                        transform: Transform {
-                            translation: Vec3::new(
-                                rb.transform.translate.x,
-                                rb.transform.translate.y,
-                                rb.transform.translate.z,
-                            ),
-                            rotation: Quat::from_xyzw(
-                                rb.transform.rotate.x,
-                                rb.transform.rotate.y,
-                                rb.transform.rotate.z,
-                                rb.transform.rotate.w,
-                            ),
-                            scale: Vec3::new(
-                                rb.transform.scale.x,
-                                rb.transform.scale.y,
-                                rb.transform.scale.z,
-                            ),
-                        },
-                        ..default()
-                    },
-                    Collider,
-                    PickableBundle::default(),
-                )).id();
-
-                collider_entities.map.insert(collider.id, entity);
-            },
+        let (mesh, material) = match &collider.body {
+            ParsedColliderBody::Particle(particle) => (
+                particle_mesh(particle.radius),
+                collider_material(collider, [0.0, 0.0, 1.0, 1.0]),
+            ),
+            ParsedColliderBody::RigidBody(rb) => (
+                shape_mesh(&rb.shape),
+                match rb.shape {
+                    ParsedShape::HalfSpace { .. } => collider_material(collider, [0.5, 0.5, 0.5, 1.0]),
+                    _ => collider_material(collider, [1.0, 0.0, 0.0, 1.0]),
+                },
+            ),
+        };
+
+        collider_entities.prototypes.insert(collider.id, (meshes.add(mesh), materials.add(material)));
+    }
+
+    // The particles spawned by emitters only depend on the step, so replaying the emitters over
+    // the history finds the IDs of all of them.
+    let mut emitters: Vec<_> = world.world.emitters.iter().map(|&emitter| Emitter::from(emitter)).collect();
+    let mut next_id = world.world.colliders.len() as u32;
+
+    let prototypes: Vec<_> = emitters.iter()
+        .map(|emitter| {
+            let material = ColliderMaterial {
+                base: StandardMaterial { base_color: Color::srgb(0.0, 0.0, 1.0), ..default() },
+                extension: SliceExtension::default(),
+            };
+
+            (meshes.add(particle_mesh(emitter.radius)), materials.add(material))
+        })
+        .collect();
+
+    // The world may have been simulated with a different time step than its file specifies.
+    for step in 0..history.history.len() {
+        let time = step as f32 * delta_time.dt;
+
+        for (emitter, prototype) in emitters.iter_mut().zip(prototypes.iter()) {
+            let (_, spawned) = emitter.update(time, delta_time.dt, &mut next_id);
+
+            for particle in spawned {
+                collider_entities.prototypes.insert(particle.id, prototype.clone());
+            }
         }
     }
 }
 
+/// Builds the [`Mesh`] displaying a particle of `radius`.
+fn particle_mesh(radius: f32) -> Mesh {
+    // Point particles are still drawn with a small size to keep them visible.
+    let radius = if radius > 0.0 { radius } else { 0.1 };
+    Mesh::from(Sphere::new(radius))
+}
+
+/// Builds the [`Mesh`] displaying a shape in its local coordinates.
+fn shape_mesh(shape: &ParsedShape) -> Mesh {
+    match shape {
+        ParsedShape::Cuboid => Cuboid::new(1.0, 1.0, 1.0).into(),
+        ParsedShape::Cylinder { radius, half_height } => Cylinder::new(*radius, 2.0 * half_height).into(),
+        // Bevy's cones are centered halfway up, while sokudo's are centered at their centroid.
+        ParsedShape::Cone { radius, height } => Mesh::from(Cone { radius: *radius, height: *height })
+            .translated_by(Vec3::new(0.0, 0.25 * height, 0.0)),
+        ParsedShape::Torus { major_radius, minor_radius } => Torus::new(major_radius - minor_radius, major_radius + minor_radius).into(),
+        ParsedShape::Mesh { mesh, .. } => triangle_mesh(&mesh.vertices, &mesh.indices),
+        ParsedShape::ConvexHull { points } => {
+            let hull = ConvexHullShape::new(points);
+            triangle_mesh(&hull.vertices, &hull.indices)
+        },
+        ParsedShape::Sdf { grid, .. } => {
+            let (vertices, indices) = SdfShape::from((**grid).clone()).triangulate(glam::UVec3::ONE);
+            triangle_mesh(&vertices, &indices)
+        },
+        ParsedShape::HalfSpace { normal } => {
+            let normal = Dir3::new(Vec3::new(normal.x, normal.y, normal.z)).unwrap_or(Dir3::Y);
+
+            Plane3d::new(*normal, Vec2::splat(HALF_SPACE_HALF_SIZE))
+                .mesh()
+                .subdivisions(HALF_SPACE_SUBDIVISIONS)
+                .build()
+        },
+        ParsedShape::Compound { parts } => {
+            // Only the attributes of the first mesh survive merging, so every part is reduced to
+            // unindexed positions and normals.
+            let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+                .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<[f32; 3]>::new())
+                .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, Vec::<[f32; 3]>::new());
+
+            for part in parts.iter() {
+                let mut part_mesh = shape_mesh(&part.shape).transformed_by(bevy_transform(&part.transform));
+                part_mesh.duplicate_vertices();
+                mesh.merge(&part_mesh);
+            }
+
+            mesh
+        },
+    }
+}
+
+/// Converts the transform of a collider in a frame into a Bevy [`Transform`].
+fn state_transform(transform: &WriteTransform) -> Transform {
+    Transform {
+        translation: Vec3::new(transform.translate.x, transform.translate.y, transform.translate.z),
+        rotation: Quat::from_xyzw(transform.rotate.x, transform.rotate.y, transform.rotate.z, transform.rotate.w),
+        scale: Vec3::new(transform.scale.x, transform.scale.y, transform.scale.z),
+    }
+}
+
+/// Converts a parsed transform into a Bevy [`Transform`].
+fn bevy_transform(transform: &ParsedTransform) -> Transform {
+    Transform {
+        translation: Vec3::new(transform.translate.x, transform.translate.y, transform.translate.z),
+        rotation: Quat::from_xyzw(transform.rotate.x, transform.rotate.y, transform.rotate.z, transform.rotate.w),
+        scale: Vec3::new(transform.scale.x, transform.scale.y, transform.scale.z),
+    }
+}
+
+/// Builds a flat-shaded [`Mesh`] from a list of triangles.
+fn triangle_mesh(vertices: &[glam::Vec3], indices: &[[u32; 3]]) -> Mesh {
+    let positions: Vec<[f32; 3]> = indices
+        .iter()
+        .flatten()
+        .map(|&i| vertices[i as usize].to_array())
+        .collect();
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_computed_flat_normals()
+}
+
 fn set_player_state_playing(
     keys: Res<ButtonInput<KeyCode>>,
     mut next_state: ResMut<NextState<PlayerState>>,
     mut playback_time: ResMut<PlaybackTime>,
-    time: Res<Time>,
+    index: Res<WorldStateIndex>,
+    delta_time: Res<DeltaTime>,
 ) {
     if keys.just_pressed(KeyCode::Space) {
         next_state.set(PlayerState::Playing);
-        playback_time.time = time.elapsed_seconds();
+        playback_time.time = index.step as f32 * delta_time.dt;
     }
 }
 
@@ -224,15 +678,35 @@ fn update_world_state(
     history: Res<WorldStateHistory>,
     delta_time: Res<DeltaTime>,
     mut playback_time: ResMut<PlaybackTime>,
+    pause_on_contact: Res<PauseOnContact>,
     time: Res<Time>,
 ) {
-    if time.elapsed_seconds() - playback_time.time <= delta_time.dt {
+    playback_time.time += time.delta_seconds() * playback_time.playback_speed;
+
+    let mut step = (playback_time.time / delta_time.dt) as usize;
+
+    // Slowed down playback shows every frame, even if rendering falls behind.
+    if playback_time.playback_speed <= 1.0 && step > index.step + 1 {
+        step = index.step + 1;
+        playback_time.time = step as f32 * delta_time.dt;
+    }
+
+    if step == index.step {
         return;
     }
 
-    playback_time.time += delta_time.dt;
+    if pause_on_contact.enabled && history.history.header().verbose {
+        let last = step.min(history.history.len().saturating_sub(1));
+
+        if let Some(contact) = (index.step + 1..=last).find(|&frame| contact_begins(&history.history, frame)) {
+            index.step = contact;
+            next_state.set(PlayerState::Paused);
+            info!("paused on new contact at frame {contact}");
+            return;
+        }
+    }
 
-    index.step += 1;
+    index.step = step;
 
     if history.history.len() <= index.step {
         next_state.set(PlayerState::Paused);
@@ -240,17 +714,211 @@ fn update_world_state(
     }
 }
 
+/// Whether a pair of colliders touches in `frame` of `history` but not in the frame before it.
+fn contact_begins(history: &ReadWorldStateHistory, frame: usize) -> bool {
+    let pairs = |frame: usize| -> HashSet<(u32, u32)> {
+        history.get(frame).colliders.iter()
+            .flat_map(|collider| collider.contacts.iter().map(move |contact| {
+                (collider.id.min(contact.other), collider.id.max(contact.other))
+            }))
+            .collect()
+    };
+
+    frame > 0 && !pairs(frame).is_subset(&pairs(frame - 1))
+}
+
+fn toggle_pause_on_contact(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut pause_on_contact: ResMut<PauseOnContact>,
+) {
+    if keys.just_pressed(KeyCode::KeyB) {
+        pause_on_contact.enabled = !pause_on_contact.enabled;
+        info!("pause on contact: {}", pause_on_contact.enabled);
+    }
+}
+
+fn change_playback_speed(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut playback_time: ResMut<PlaybackTime>,
+) {
+    let mut speed = playback_time.playback_speed;
+
+    if keys.just_pressed(KeyCode::BracketLeft) {
+        speed *= 0.5;
+    }
+
+    if keys.just_pressed(KeyCode::BracketRight) {
+        speed *= 2.0;
+    }
+
+    speed = speed.clamp(PlaybackTime::MIN_PLAYBACK_SPEED, PlaybackTime::MAX_PLAYBACK_SPEED);
+
+    if speed != playback_time.playback_speed {
+        playback_time.playback_speed = speed;
+        info!("playback speed: {speed}x");
+    }
+}
+
+/// Spawns the entities of the colliders which appear in the current frame for the first time and
+/// hides those of the colliders which are absent from it.
+fn update_collider_entities(
+    mut commands: Commands,
+    mut collider_entities: ResMut<ColliderEntities>,
+    mut colliders: Query<&mut Visibility, With<Collider>>,
+    wireframe_mode: Res<WireframeMode>,
+    index: Res<WorldStateIndex>,
+    history: Res<WorldStateHistory>,
+) {
+    if !index.is_changed() {
+        return;
+    }
+
+    let world_state = history.history.get(index.step);
+    let present: HashSet<u32> = world_state.colliders.iter().map(|collider| collider.id).collect();
+
+    for (id, &entity) in collider_entities.map.iter() {
+        if let Ok(mut visibility) = colliders.get_mut(entity) {
+            *visibility = if present.contains(id) { Visibility::Inherited } else { Visibility::Hidden };
+        }
+    }
+
+    for collider in world_state.colliders.iter() {
+        if collider_entities.map.contains_key(&collider.id) {
+            continue;
+        }
+
+        let Some((mesh, material)) = collider_entities.prototypes.get(&collider.id).cloned() else {
+            continue;
+        };
+
+        let entity = commands.spawn((
+            MaterialMeshBundle {
+                mesh,
+                material: if wireframe_mode.style.hides_solid() { wireframe_mode.hidden_material.clone() } else { material.clone() },
+                transform: state_transform(&collider.transform),
+                ..default()
+            },
+            Collider(collider.id),
+            SolidMaterial(material),
+            PickableBundle::default(),
+        )).id();
+
+        collider_entities.map.insert(collider.id, entity);
+    }
+}
+
 fn update_colliders(
+    mut gizmos: Gizmos,
+    index: Res<WorldStateIndex>,
+    history: Res<WorldStateHistory>,
+    visibility: Res<GizmoVisibility>,
+    mut trails: ResMut<Trails>,
+) {
+    if !index.is_changed() && !visibility.velocities && !visibility.trails {
+        return;
+    }
+
+    let world_state = history.history.get(index.step);
+
+    if visibility.trails {
+        if trails.step.is_some_and(|step| step + 1 == index.step) {
+            // Playing forwards only needs the newest frame, dropping the trails of the colliders
+            // which no longer exist.
+            trails.positions.retain(|id, _| world_state.colliders.iter().any(|collider| collider.id == *id));
+
+            for collider in world_state.colliders.iter() {
+                let positions = trails.positions.entry(collider.id).or_default();
+
+                if positions.len() > TRAIL_LENGTH {
+                    positions.remove(0);
+                }
+
+                positions.push(Vec3::new(
+                    collider.transform.translate.x,
+                    collider.transform.translate.y,
+                    collider.transform.translate.z,
+                ));
+            }
+        } else if trails.step != Some(index.step) {
+            trails.positions.clear();
+
+            for step in index.step.saturating_sub(TRAIL_LENGTH)..=index.step {
+                for collider in history.history.get(step).colliders {
+                    trails.positions.entry(collider.id).or_default().push(Vec3::new(
+                        collider.transform.translate.x,
+                        collider.transform.translate.y,
+                        collider.transform.translate.z,
+                    ));
+                }
+            }
+        }
+
+        trails.step = Some(index.step);
+
+        for positions in trails.positions.values() {
+            let len = positions.len() as f32;
+
+            gizmos.linestrip_gradient(positions.iter().enumerate().map(|(i, &position)| {
+                (position, Color::srgba(1.0, 1.0, 1.0, (i + 1) as f32 / len))
+            }));
+        }
+    }
+
+    if visibility.velocities {
+        for collider in world_state.colliders.iter() {
+            let position = Vec3::new(
+                collider.transform.translate.x,
+                collider.transform.translate.y,
+                collider.transform.translate.z,
+            );
+            let velocity = Vec3::new(collider.velocity.x, collider.velocity.y, collider.velocity.z);
+            let angular_velocity = Vec3::new(
+                collider.angular_velocity.x,
+                collider.angular_velocity.y,
+                collider.angular_velocity.z,
+            );
+
+            if velocity != Vec3::ZERO {
+                gizmos.arrow(position, position + velocity * VELOCITY_GIZMO_SCALE, Color::srgb(0.0, 0.0, 1.0));
+            }
+
+            if angular_velocity != Vec3::ZERO {
+                gizmos.arrow(position, position + angular_velocity * VELOCITY_GIZMO_SCALE, Color::srgb(1.0, 0.0, 0.0));
+            }
+        }
+    }
+}
+
+/// Places the entities of the colliders at their transforms in the current frame. While playing,
+/// each transform is interpolated towards the next frame by the time played back since the
+/// current frame, so playback stays smooth when frames are further apart than rendered ones.
+fn update_collider_transforms(
     collider_entities: Res<ColliderEntities>,
     mut colliders: Query<&mut Transform, With<Collider>>,
     index: Res<WorldStateIndex>,
     history: Res<WorldStateHistory>,
+    playback_time: Res<PlaybackTime>,
+    delta_time: Res<DeltaTime>,
+    state: Res<State<PlayerState>>,
 ) {
-    if !index.is_changed() {
+    // The last frame has nothing to interpolate towards.
+    let fraction = if *state.get() == PlayerState::Playing && index.step + 1 < history.history.len() {
+        (playback_time.time / delta_time.dt - index.step as f32).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    if fraction == 0.0 && !index.is_changed() && !state.is_changed() {
         return;
     }
 
     let world_state = history.history.get(index.step);
+    let next_state = (fraction > 0.0).then(|| history.history.get(index.step + 1));
+
+    let next: HashMap<u32, Transform> = next_state.iter()
+        .flat_map(|state| state.colliders.iter())
+        .map(|collider| (collider.id, state_transform(&collider.transform)))
+        .collect();
 
     for collider in world_state.colliders.iter() {
         let Some(&entity) = collider_entities.map.get(&collider.id) else {
@@ -261,18 +929,16 @@ fn update_colliders(
             continue;
         };
 
-        transform.translation = Vec3::new(
-            collider.transform.translate.x,
-            collider.transform.translate.y,
-            collider.transform.translate.z,
-        );
+        let current = state_transform(&collider.transform);
 
-        transform.rotation = Quat::from_xyzw(
-            collider.transform.rotate.x,
-            collider.transform.rotate.y,
-            collider.transform.rotate.z,
-            collider.transform.rotate.w,
-        );
+        *transform = match next.get(&collider.id) {
+            Some(next) => Transform {
+                translation: current.translation.lerp(next.translation, fraction),
+                rotation: current.rotation.slerp(next.rotation, fraction),
+                scale: current.scale.lerp(next.scale, fraction),
+            },
+            None => current,
+        };
     }
 }
 
@@ -280,11 +946,13 @@ fn update_inspect_elements(
     mut gizmos: Gizmos,
     index: Res<WorldStateIndex>,
     history: Res<WorldStateHistory>,
+    visibility: Res<GizmoVisibility>,
     cameras: Query<&Transform, With<PanOrbitState>>,
 ) {
     let world_state = history.history.get(index.step);
     let camera_transform = cameras.single();
     let camera = camera_transform.translation;
+    let max_impulse = world_state.inspector.max_contact_impulse();
 
     for elem in world_state.inspector.elements.values() {
         match elem {
@@ -312,29 +980,119 @@ fn update_inspect_elements(
 
                 gizmos.ray(origin, direction, Color::srgb(0.0, 0.0, 1.0));
             },
+            InspectFeature::Contact { point, normal, impulse } => {
+                if !visibility.contacts {
+                    continue;
+                }
+
+                let point = Vec3::new(point.x, point.y, point.z);
+                let normal = Vec3::new(normal.x, normal.y, normal.z);
+                let color = impulse_color(*impulse, max_impulse);
+
+                gizmos.sphere(point, Quat::IDENTITY, CONTACT_GIZMO_RADIUS, color);
+                gizmos.arrow(point, point + normal * CONTACT_GIZMO_NORMAL_LENGTH, color);
+            },
         }
     }
 }
 
+/// The color of a contact gizmo, from blue for no impulse to red for the largest impulse of the
+/// frame.
+fn impulse_color(impulse: f32, max_impulse: f32) -> Color {
+    let t = if max_impulse > 0.0 { (impulse / max_impulse).clamp(0.0, 1.0) } else { 0.0 };
+    Color::srgb(t, 0.0, 1.0 - t)
+}
+
+fn change_grid_spacing(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut grid: ResMut<GridSettings>,
+) {
+    if keys.just_pressed(KeyCode::Minus) {
+        grid.spacing *= 0.5;
+    }
+
+    if keys.just_pressed(KeyCode::Equal) {
+        grid.spacing *= 2.0;
+    }
+}
+
+fn toggle_gizmos(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut visibility: ResMut<GizmoVisibility>,
+) {
+    if keys.just_pressed(KeyCode::KeyC) {
+        visibility.contacts = !visibility.contacts;
+    }
+
+    if keys.just_pressed(KeyCode::KeyV) {
+        visibility.velocities = !visibility.velocities;
+    }
+
+    if keys.just_pressed(KeyCode::KeyT) {
+        visibility.trails = !visibility.trails;
+    }
+
+    if keys.just_pressed(KeyCode::KeyG) {
+        visibility.grid = !visibility.grid;
+    }
+
+    if keys.just_pressed(KeyCode::KeyA) {
+        visibility.axes = !visibility.axes;
+    }
+}
+
 fn step_state_on_pause(
     keys: Res<ButtonInput<KeyCode>>,
     mut index: ResMut<WorldStateIndex>,
+    mut scrub: ResMut<Scrub>,
     history: Res<WorldStateHistory>,
+    time: Res<Time>,
 ) {
-    if keys.just_pressed(KeyCode::KeyJ) && index.step > 0 {
-        index.step -= 1;
+    let mut delta = 0;
+
+    if keys.any_just_pressed([KeyCode::KeyJ, KeyCode::ArrowLeft]) {
+        delta -= 1;
+    }
+
+    if keys.any_just_pressed([KeyCode::KeyL, KeyCode::ArrowRight]) {
+        delta += 1;
+    }
+
+    let direction = keys.pressed(KeyCode::ArrowRight) as isize - keys.pressed(KeyCode::ArrowLeft) as isize;
+
+    if direction == 0 {
+        *scrub = Scrub::default();
+    } else {
+        scrub.held += time.delta_seconds();
+
+        if scrub.held > SCRUB_DELAY {
+            scrub.pending += SCRUB_RATE * time.delta_seconds();
+
+            let frames = scrub.pending.floor();
+            scrub.pending -= frames;
+            delta += direction * frames as isize;
+        }
     }
 
-    if keys.just_pressed(KeyCode::KeyL) && index.step < history.history.len() - 1 {
-        index.step += 1;
+    let last = history.history.len().saturating_sub(1) as isize;
+    let step = (index.step as isize + delta).clamp(0, last) as usize;
+
+    if step != index.step {
+        index.step = step;
     }
 }
 
+/// Rewinds to the first frame and pauses when `R` is pressed. Writing the index marks it as
+/// changed, so the colliders are redrawn at the first frame even if the player was already there.
 fn restart_player(
     keys: Res<ButtonInput<KeyCode>>,
     mut index: ResMut<WorldStateIndex>,
+    mut playback_time: ResMut<PlaybackTime>,
+    mut next_state: ResMut<NextState<PlayerState>>,
 ) {
     if keys.just_pressed(KeyCode::KeyR) {
         index.step = 0;
+        playback_time.time = 0.0;
+        next_state.set(PlayerState::Paused);
     }
 }
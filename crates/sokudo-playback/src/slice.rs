@@ -0,0 +1,185 @@
+use bevy::{
+    asset::embedded_asset,
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    pbr::{ExtendedMaterial, MaterialExtension},
+    prelude::*,
+    render::render_resource::{AsBindGroup, Face, ShaderRef},
+};
+
+use crate::camera::{PanOrbitSettings, PanOrbitState};
+
+/// Clips colliders against a plane along one of the axes so their interiors and the contacts
+/// between them can be seen.
+///
+/// `X` cycles the plane between the X, Y, and Z axes and off, and while it is on the mouse wheel
+/// moves it along its axis instead of zooming. Everything on the positive side of the plane is
+/// discarded, but still casts shadows.
+///
+/// `N` toggles drawing the back faces of colliders in [`BACK_FACE_COLOR`], so that a correctly
+/// wound mesh looks uniform while a flipped triangle flashes.
+pub struct SlicePlugin;
+
+impl Plugin for SlicePlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "slice.wgsl");
+
+        app
+            .init_resource::<SlicePlane>()
+            .init_resource::<BackFaceHighlight>()
+            .add_plugins(MaterialPlugin::<ColliderMaterial>::default())
+            .add_systems(PreUpdate, ((cycle_slice_axis, move_slice_plane).chain(), toggle_back_face_highlight))
+            .add_systems(
+                Update,
+                update_slice_materials.run_if(resource_changed::<SlicePlane>.or_else(resource_changed::<BackFaceHighlight>)),
+            );
+    }
+}
+
+/// The material colliders are drawn with, which clips them against the [`SlicePlane`].
+pub type ColliderMaterial = ExtendedMaterial<StandardMaterial, SliceExtension>;
+
+/// The distance the slice plane moves per line scrolled with the mouse wheel.
+const SLICE_SCROLL_STEP: f32 = 0.05;
+
+/// The color back faces are drawn in while they are highlighted.
+pub const BACK_FACE_COLOR: LinearRgba = LinearRgba::rgb(1.0, 0.0, 0.8);
+
+/// The axis-aligned plane colliders are clipped against.
+#[derive(Resource, Default)]
+pub struct SlicePlane {
+    /// The axis the plane is perpendicular to, or `None` if nothing is clipped.
+    pub axis: Option<SliceAxis>,
+    /// The position of the plane along its axis.
+    pub offset: f32,
+}
+
+/// Whether the back faces of colliders are drawn in [`BACK_FACE_COLOR`].
+#[derive(Resource, Default)]
+pub struct BackFaceHighlight {
+    pub enabled: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SliceAxis {
+    X,
+    Y,
+    Z,
+}
+
+impl SliceAxis {
+    fn direction(self) -> Vec3 {
+        match self {
+            SliceAxis::X => Vec3::X,
+            SliceAxis::Y => Vec3::Y,
+            SliceAxis::Z => Vec3::Z,
+        }
+    }
+}
+
+impl SlicePlane {
+    /// The normal of the plane and its offset along it, or zero if nothing is clipped.
+    fn uniform(&self) -> Vec4 {
+        self.axis.map_or(Vec4::ZERO, |axis| axis.direction().extend(self.offset))
+    }
+}
+
+/// The extension of the [`StandardMaterial`] of a collider which discards the fragments in front
+/// of the slice plane and highlights back faces.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone, Default)]
+pub struct SliceExtension {
+    /// The normal of the slice plane in `xyz` and its offset along it in `w`.
+    #[uniform(100)]
+    pub plane: Vec4,
+    /// The color back faces are drawn in, or transparent to shade them like front faces.
+    #[uniform(101)]
+    pub back_face_color: Vec4,
+}
+
+impl MaterialExtension for SliceExtension {
+    fn fragment_shader() -> ShaderRef {
+        "embedded://sokudo_playback/slice.wgsl".into()
+    }
+}
+
+fn cycle_slice_axis(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut slice: ResMut<SlicePlane>,
+    mut cameras: Query<(&PanOrbitState, &mut PanOrbitSettings)>,
+) {
+    if !keys.just_pressed(KeyCode::KeyX) {
+        return;
+    }
+
+    slice.axis = match slice.axis {
+        None => Some(SliceAxis::X),
+        Some(SliceAxis::X) => Some(SliceAxis::Y),
+        Some(SliceAxis::Y) => Some(SliceAxis::Z),
+        Some(SliceAxis::Z) => None,
+    };
+
+    // Each new plane starts through the center of the camera, which is usually the collider of
+    // interest.
+    if let (Some(axis), Ok((camera, _))) = (slice.axis, cameras.get_single()) {
+        slice.offset = camera.center.dot(axis.direction());
+    }
+
+    for (_, mut settings) in &mut cameras {
+        settings.scroll_zoom = slice.axis.is_none();
+    }
+
+    match slice.axis {
+        Some(axis) => info!("slicing along {axis:?} at {:.3}", slice.offset),
+        None => info!("slicing off"),
+    }
+}
+
+fn toggle_back_face_highlight(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut highlight: ResMut<BackFaceHighlight>,
+) {
+    if keys.just_pressed(KeyCode::KeyN) {
+        highlight.enabled = !highlight.enabled;
+        info!("highlight back faces: {}", highlight.enabled);
+    }
+}
+
+fn move_slice_plane(
+    mut evr_scroll: EventReader<MouseWheel>,
+    mut slice: ResMut<SlicePlane>,
+) {
+    if slice.axis.is_none() {
+        evr_scroll.clear();
+        return;
+    }
+
+    // Pixel scrolling (e.g. from touchpads) is much finer grained than line scrolling.
+    let total_scroll: f32 = evr_scroll.read()
+        .map(|ev| match ev.unit {
+            MouseScrollUnit::Line => ev.y,
+            MouseScrollUnit::Pixel => ev.y * 0.01,
+        })
+        .sum();
+
+    if total_scroll != 0.0 {
+        slice.offset += total_scroll * SLICE_SCROLL_STEP;
+    }
+}
+
+/// Copies the slice plane and back face color into every collider material, drawing their back
+/// faces while slicing so the cut colliders are not hollow, or while they are highlighted.
+fn update_slice_materials(
+    slice: Res<SlicePlane>,
+    highlight: Res<BackFaceHighlight>,
+    mut materials: ResMut<Assets<ColliderMaterial>>,
+) {
+    let plane = slice.uniform();
+    let back_face_color = if highlight.enabled { BACK_FACE_COLOR.to_vec4() } else { Vec4::ZERO };
+    let double_sided = slice.axis.is_some() || highlight.enabled;
+
+    for (_, material) in materials.iter_mut() {
+        material.extension.plane = plane;
+        material.extension.back_face_color = back_face_color;
+        material.base.cull_mode = if double_sided { None } else { Some(Face::Back) };
+        material.base.double_sided = double_sided;
+    }
+}
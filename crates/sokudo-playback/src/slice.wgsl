@@ -0,0 +1,59 @@
+// Discards the fragments of a collider on the far side of the slice plane before shading them as
+// a standard material, drawing its back faces in a flashing color if they are highlighted.
+
+#import bevy_pbr::{
+    mesh_view_bindings::globals,
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::alpha_discard,
+}
+
+#ifdef PREPASS_PIPELINE
+#import bevy_pbr::{
+    prepass_io::{VertexOutput, FragmentOutput},
+    pbr_deferred_functions::deferred_output,
+}
+#else
+#import bevy_pbr::{
+    forward_io::{VertexOutput, FragmentOutput},
+    pbr_functions::{apply_pbr_lighting, main_pass_post_lighting_processing},
+}
+#endif
+
+// The normal of the plane in `xyz` and its distance from the origin along it in `w`. Fragments
+// in front of the plane are discarded, and nothing is while the normal is zero.
+@group(2) @binding(100) var<uniform> slice_plane: vec4<f32>;
+// The color back faces are drawn in, which is transparent while they are shaded normally.
+@group(2) @binding(101) var<uniform> back_face_color: vec4<f32>;
+
+// The number of times highlighted back faces flash per second.
+const BACK_FACE_FLASH_RATE: f32 = 2.0;
+
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    if any(slice_plane.xyz != vec3<f32>(0.0)) && dot(slice_plane.xyz, in.world_position.xyz) > slice_plane.w {
+        discard;
+    }
+
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+    if !is_front && back_face_color.a > 0.0 {
+        // The color glows regardless of the lighting so that back faces in shadow stand out too.
+        let flash = 0.5 + 0.5 * cos(6.2831853 * BACK_FACE_FLASH_RATE * globals.time);
+        pbr_input.material.base_color = back_face_color;
+        pbr_input.material.emissive = vec4<f32>(back_face_color.rgb * flash, 1.0);
+    }
+
+#ifdef PREPASS_PIPELINE
+    let out = deferred_output(in, pbr_input);
+#else
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+#endif
+
+    return out;
+}
@@ -1,8 +1,9 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, process::ExitCode};
 
 use clap::Parser;
-use sokudo_core::run::run_simulation;
-use sokudo_playback::play;
+use sokudo_core::{export::export_gltf, run::{bench_simulation, resume_simulation, run_simulation_with_options, RunOptions}};
+use sokudo_io::{read::ParsedWorld, write::{HistoryFormat, HistoryOptions}};
+use sokudo_playback::{play, LightingPreset};
 
 #[derive(clap::Parser)]
 #[command(author, version, about)]
@@ -19,6 +20,22 @@ enum Commands {
 
         /// The file to output the simulation data to.
         history: PathBuf,
+
+        /// The format to write the simulation data in, either `ron` or `json`.
+        #[arg(long, default_value = "ron")]
+        format: HistoryFormat,
+
+        /// Do not show the progress of the simulation.
+        #[arg(long, short)]
+        quiet: bool,
+
+        /// The time step to simulate with, instead of the one in the world file.
+        #[arg(long)]
+        dt: Option<f32>,
+
+        /// The number of steps to simulate, instead of the number in the world file.
+        #[arg(long)]
+        steps: Option<u32>,
     },
     Bake {
         /// The file to read as the initial world state.
@@ -26,6 +43,53 @@ enum Commands {
 
         /// The file to output the simulation data to.
         history: PathBuf,
+
+        /// The format to write the simulation data in, either `ron` or `json`.
+        #[arg(long, default_value = "ron")]
+        format: HistoryFormat,
+
+        /// Compress the simulation data with zstd.
+        #[arg(long)]
+        compress: bool,
+
+        /// The number of steps between keyframes, with the steps in between only storing the
+        /// changes to each collider.
+        #[arg(long, default_value_t = 1)]
+        keyframe_interval: u32,
+
+        /// Also record the contacts of every collider and the impulses between them.
+        #[arg(long)]
+        verbose: bool,
+
+        /// Continue an interrupted bake from the checkpoint written next to its history, keeping
+        /// the format of the existing history.
+        #[arg(long)]
+        resume: Option<PathBuf>,
+
+        /// Do not show the progress of the bake.
+        #[arg(long, short)]
+        quiet: bool,
+
+        /// The file to write the collision events of the simulation to, if any.
+        #[arg(long)]
+        events: Option<PathBuf>,
+
+        /// Stop simulating once every collider is asleep, and repeat the last frame until the
+        /// history has exactly this many frames.
+        #[arg(long)]
+        pad_to: Option<u64>,
+
+        /// The script of impulses and forces to apply to the colliders while simulating.
+        #[arg(long)]
+        script: Option<PathBuf>,
+
+        /// The time step to simulate with, instead of the one in the world file.
+        #[arg(long)]
+        dt: Option<f32>,
+
+        /// The number of steps to simulate, instead of the number in the world file.
+        #[arg(long)]
+        steps: Option<u32>,
     },
     Play {
         /// The file to read as the initial world state.
@@ -33,25 +97,70 @@ enum Commands {
 
         /// The file to read as the computed simulation data.
         history: PathBuf,
+
+        /// Render every frame to numbered PNGs in this directory and exit, instead of playing.
+        #[arg(long)]
+        render: Option<PathBuf>,
+
+        /// The lighting to start with, either `studio`, `outdoor`, `dark` or `flat`.
+        #[arg(long, default_value = "studio")]
+        lighting: LightingPreset,
+    },
+    /// Simulate a world without writing its history, printing how long it took as JSON.
+    Bench {
+        /// The file to read as the initial world state.
+        world: PathBuf,
+
+        /// The number of steps to simulate, instead of the number in the world file.
+        #[arg(long)]
+        steps: Option<u32>,
+    },
+    /// Check a world file for problems without simulating it.
+    Validate {
+        /// The file to read as the initial world state.
+        world: PathBuf,
+    },
+    /// Export computed simulation data as a glTF animation.
+    Export {
+        /// The file to read as the initial world state.
+        world: PathBuf,
+
+        /// The file to read as the computed simulation data.
+        history: PathBuf,
+
+        /// The glTF file to write, alongside a `.bin` file of the same name.
+        output: PathBuf,
     },
 }
 
-fn main() {
+fn main() -> ExitCode {
     let cli = Cli::parse();
 
     match cli.command {
         Commands::Run {
             world,
             history,
+            format,
+            quiet,
+            dt,
+            steps,
         } => {
-            match run_simulation(world.clone(), history.clone()) {
+            let options = RunOptions {
+                history: HistoryOptions { format, ..Default::default() },
+                progress: !quiet,
+                dt,
+                steps,
+                ..Default::default()
+            };
+
+            match run_simulation_with_options(world.clone(), history.clone(), options) {
                 Ok(_) => (),
                 Err(err) => {
                     println!("{}", err);
                 },
             }
 
-            match play(world, history) {
+            match play(world, history, None, LightingPreset::default()) {
                 Ok(_) => (),
                 Err(err) => {
                     println!("{}", err);
@@ -61,8 +170,34 @@ fn main() {
         Commands::Bake {
             world,
             history,
+            format,
+            compress,
+            keyframe_interval,
+            verbose,
+            resume,
+            quiet,
+            events,
+            pad_to,
+            script,
+            dt,
+            steps,
         } => {
-            match run_simulation(world, history) {
+            let options = RunOptions {
+                history: HistoryOptions { format, compress, keyframe_interval, verbose },
+                progress: !quiet,
+                events,
+                pad_to,
+                script,
+                dt,
+                steps,
+            };
+
+            let result = match resume {
+                Some(checkpoint) => resume_simulation(world, history, checkpoint, options),
+                None => run_simulation_with_options(world, history, options),
+            };
+
+            match result {
                 Ok(_) => (),
                 Err(err) => {
                     println!("{}", err);
@@ -72,13 +207,73 @@ fn main() {
         Commands::Play {
             world,
             history,
+            render,
+            lighting,
         } => {
-            match play(world, history) {
+            match play(world, history, render, lighting) {
                 Ok(_) => (),
                 Err(err) => {
                     println!("{}", err);
                 }
             }
         },
+        Commands::Bench {
+            world,
+            steps,
+        } => {
+            match bench_simulation(world, steps).and_then(|report| Ok(report.to_json()?)) {
+                Ok(json) => println!("{}", json),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return ExitCode::FAILURE;
+                },
+            }
+        },
+        Commands::Validate {
+            world,
+        } => {
+            return validate(world);
+        },
+        Commands::Export {
+            world,
+            history,
+            output,
+        } => {
+            if let Err(err) = export_gltf(world, history, output) {
+                println!("{}", err);
+                return ExitCode::FAILURE;
+            }
+        },
     }
+
+    ExitCode::SUCCESS
+}
+
+/// Prints every problem found in the world file at `path`, failing if there are any.
+fn validate(path: PathBuf) -> ExitCode {
+    let world = match ParsedWorld::read(&path) {
+        Ok(world) => world,
+        Err(err) => {
+            println!("{}", err);
+            return ExitCode::FAILURE;
+        },
+    };
+
+    for warning in world.warnings.iter() {
+        println!("warning: {}", warning);
+    }
+
+    let errors = world.validate();
+
+    if errors.is_empty() {
+        println!("{} is valid", path.display());
+        return ExitCode::SUCCESS;
+    }
+
+    for error in errors.iter() {
+        println!("error: {}", error);
+    }
+
+    println!("found {} problem(s) in {}", errors.len(), path.display());
+    ExitCode::FAILURE
 }